@@ -131,6 +131,20 @@ function EventDetails({
         return "Data Received";
       case "dns_resolved":
         return e.overridden ? "DNS Override" : "DNS Resolution";
+      case "tcp_connected":
+        return "Connection";
+      case "tls_handshake_completed":
+        return "TLS Handshake";
+      case "first_byte_received":
+        return "Waiting";
+      case "download_completed":
+        return "Download";
+      case "tls_negotiated":
+        return "TLS Session";
+      case "tls_server_certificates":
+        return "Server Certificates";
+      case "tls_client_certificate":
+        return "Client Certificate";
       default:
         return label;
     }
@@ -247,6 +261,49 @@ function EventDetails({
       );
     }
 
+    // TLS session - show the negotiated parameters
+    if (e.type === "tls_negotiated") {
+      return (
+        <KeyValueRows>
+          <KeyValueRow label="Version">{e.protocol_version}</KeyValueRow>
+          <KeyValueRow label="Cipher Suite">{e.cipher_suite}</KeyValueRow>
+          <KeyValueRow label="ALPN">{e.alpn ?? "--"}</KeyValueRow>
+          <KeyValueRow label="Resumed">{e.resumed ? "Yes" : "No"}</KeyValueRow>
+        </KeyValueRows>
+      );
+    }
+
+    // Server certificates - one group per certificate in the chain, leaf first
+    if (e.type === "tls_server_certificates") {
+      return (
+        <div className="flex flex-col gap-4">
+          {e.certificates.map((c, i) => (
+            <KeyValueRows key={c.sha256_fingerprint || i}>
+              <KeyValueRow label="Subject">{c.subject}</KeyValueRow>
+              <KeyValueRow label="Issuer">{c.issuer}</KeyValueRow>
+              {c.subject_alt_names.length > 0 ? (
+                <KeyValueRow label="Alt Names">{c.subject_alt_names.join(", ")}</KeyValueRow>
+              ) : null}
+              <KeyValueRow label="Valid From">{c.not_before}</KeyValueRow>
+              <KeyValueRow label="Valid Until">{c.not_after}</KeyValueRow>
+              <KeyValueRow label="Serial">{c.serial_number}</KeyValueRow>
+              <KeyValueRow label="SHA-256">{c.sha256_fingerprint}</KeyValueRow>
+              <KeyValueRow label="SHA-1">{c.sha1_fingerprint}</KeyValueRow>
+            </KeyValueRows>
+          ))}
+        </div>
+      );
+    }
+
+    if (e.type === "tls_client_certificate") {
+      return (
+        <KeyValueRows>
+          <KeyValueRow label="Requested">{e.requested ? "Yes" : "No"}</KeyValueRow>
+          <KeyValueRow label="Presented">{e.presented ? "Yes" : "No"}</KeyValueRow>
+        </KeyValueRows>
+      );
+    }
+
     // Default - use summary
     const { summary } = getEventDisplay(event.event);
     return <div className="font-mono text-editor">{summary}</div>;
@@ -313,11 +370,44 @@ function getEventTextParts(event: HttpResponseEventData): EventTextParts {
         prefix: "*",
         text: `DNS resolved ${event.hostname} to ${event.addresses.join(", ")} (${event.duration}ms)`,
       };
+    case "tcp_connected":
+      return { prefix: "*", text: `Connected in ${event.duration}ms` };
+    case "tls_handshake_completed":
+      return { prefix: "*", text: `TLS handshake completed in ${event.duration}ms` };
+    case "first_byte_received":
+      return { prefix: "*", text: `First byte received after ${event.duration}ms` };
+    case "download_completed":
+      return { prefix: "*", text: `Body downloaded in ${event.duration}ms` };
+    case "tls_negotiated":
+      return {
+        prefix: "*",
+        text: `${event.protocol_version} with ${event.cipher_suite}${event.alpn ? `, ALPN ${event.alpn}` : ""}${event.resumed ? " (resumed)" : ""}`,
+      };
+    case "tls_server_certificates":
+      return {
+        prefix: "*",
+        text: event.certificates
+          .map(
+            (c) =>
+              `Server certificate ${c.subject}, issued by ${c.issuer}, valid until ${c.not_after}`,
+          )
+          .join("\n* "),
+      };
+    case "tls_client_certificate":
+      return { prefix: "*", text: clientCertificateText(event) };
     default:
       return { prefix: "*", text: "[unknown event]" };
   }
 }
 
+function clientCertificateText(
+  event: Extract<HttpResponseEventData, { type: "tls_client_certificate" }>,
+): string {
+  if (event.presented) return "Client certificate presented";
+  if (event.requested) return "Server requested a client certificate, none was presented";
+  return "Client certificate configured, server did not request it";
+}
+
 /** Format event as plaintext, optionally with curl-style prefix (> outgoing, < incoming, * info) */
 function formatEventText(event: HttpResponseEventData, includePrefix: boolean): string {
   const { prefix, text } = getEventTextParts(event);
@@ -455,6 +545,55 @@ function getEventDisplay(event: HttpResponseEventData): EventDisplay {
           ? `${event.hostname} → ${event.addresses.join(", ")} (overridden)`
           : `${event.hostname} → ${event.addresses.join(", ")} (${event.duration}ms)`,
       };
+    case "tcp_connected":
+      return {
+        icon: "plug",
+        color: "secondary",
+        label: "Connect",
+        summary: `Connected in ${event.duration}ms`,
+      };
+    case "tls_handshake_completed":
+      return {
+        icon: "lock",
+        color: "secondary",
+        label: "TLS",
+        summary: `Handshake completed in ${event.duration}ms`,
+      };
+    case "first_byte_received":
+      return {
+        icon: "clock",
+        color: "secondary",
+        label: "Waiting",
+        summary: `First byte after ${event.duration}ms`,
+      };
+    case "download_completed":
+      return {
+        icon: "arrow_down_to_line",
+        color: "secondary",
+        label: "Download",
+        summary: `Body downloaded in ${event.duration}ms`,
+      };
+    case "tls_negotiated":
+      return {
+        icon: "lock",
+        color: "secondary",
+        label: "TLS",
+        summary: `${event.protocol_version} with ${event.cipher_suite}${event.resumed ? " (resumed)" : ""}`,
+      };
+    case "tls_server_certificates":
+      return {
+        icon: "shield_check",
+        color: "secondary",
+        label: "Certificates",
+        summary: `${event.certificates[0]?.subject ?? "Server certificate"}${event.certificates.length > 1 ? ` (+${event.certificates.length - 1} in chain)` : ""}`,
+      };
+    case "tls_client_certificate":
+      return {
+        icon: "key_round",
+        color: event.requested && !event.presented ? "warning" : "secondary",
+        label: "Client Certificate",
+        summary: clientCertificateText(event),
+      };
     default:
       return {
         icon: "info",
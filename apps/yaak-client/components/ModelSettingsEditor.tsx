@@ -1,18 +1,27 @@
 import type {
   Folder,
+  GrpcCompression,
+  GrpcProtocol,
   GrpcRequest,
+  Http3Mode,
   HttpRequest,
   InheritedBoolSetting,
+  InheritedHttp3Setting,
   InheritedIntSetting,
+  InheritedProxySetting,
+  ProxySetting,
   WebsocketRequest,
   Workspace,
 } from "@yaakapp-internal/models";
-import { patchModel } from "@yaakapp-internal/models";
+import { patchModel, settingsAtom } from "@yaakapp-internal/models";
+import { useAtomValue } from "jotai";
 import { useModelAncestors } from "../hooks/useModelAncestors";
 import {
   modelSupportsSetting,
   type RequestSettingDefinition,
   SETTING_FOLLOW_REDIRECTS,
+  SETTING_HTTP3,
+  SETTING_PROXY,
   SETTING_REQUEST_MESSAGE_SIZE,
   SETTING_REQUEST_TIMEOUT,
   SETTING_SEND_COOKIES,
@@ -25,13 +34,36 @@ import {
   SettingOverrideRow,
   SettingRow,
   SettingRowBoolean,
+  SettingRowSelect,
+  SettingSelectControl,
   SettingsList,
   SettingsSection,
 } from "./core/SettingRow";
+import {
+  CustomProxySettings,
+  enabledProxyOrDefault,
+} from "./Settings/SettingsProxy";
 
 const BYTES_PER_MB = 1024 * 1024;
 const MAX_REQUEST_MESSAGE_SIZE_BYTES = 2_147_483_647;
 const MAX_MESSAGE_SIZE_MB = MAX_REQUEST_MESSAGE_SIZE_BYTES / BYTES_PER_MB;
+const HTTP3_OPTIONS: { label: string; value: Http3Mode }[] = [
+  { label: "Off", value: "off" },
+  { label: "When advertised", value: "alt_svc" },
+  { label: "Always", value: "force" },
+];
+const GRPC_PROTOCOL_OPTIONS: { label: string; value: GrpcProtocol }[] = [
+  { label: "gRPC", value: "grpc" },
+  { label: "gRPC-Web", value: "grpc_web" },
+  { label: "gRPC-Web (text)", value: "grpc_web_text" },
+  { label: "Connect (JSON)", value: "connect_json" },
+  { label: "Connect (protobuf)", value: "connect_proto" },
+];
+const GRPC_COMPRESSION_OPTIONS: { label: string; value: GrpcCompression }[] = [
+  { label: "None", value: "none" },
+  { label: "gzip", value: "gzip" },
+  { label: "zstd", value: "zstd" },
+];
 
 interface Props {
   showSectionTitles?: boolean;
@@ -45,6 +77,7 @@ type ModelWithSettings =
   | WebsocketRequest
   | GrpcRequest;
 type ModelWithHttpSettings = Workspace | Folder | HttpRequest;
+type ModelWithTimeoutSettings = Workspace | Folder | HttpRequest | GrpcRequest;
 type ModelWithTlsSettings =
   | Workspace
   | Folder
@@ -56,6 +89,7 @@ type ModelWithCookieSettings =
   | Folder
   | HttpRequest
   | WebsocketRequest;
+type ModelWithProxySettings = Workspace | Folder;
 type ModelWithMessageSizeSettings =
   | Workspace
   | Folder
@@ -63,17 +97,24 @@ type ModelWithMessageSizeSettings =
   | GrpcRequest;
 type BooleanSetting = boolean | InheritedBoolSetting;
 type IntegerSetting = number | InheritedIntSetting;
+type Http3Setting = Http3Mode | InheritedHttp3Setting;
 type CookieSettingsPatch = {
   settingSendCookies?: ModelWithCookieSettings["settingSendCookies"];
   settingStoreCookies?: ModelWithCookieSettings["settingStoreCookies"];
 };
 type HttpSettingsPatch = {
   settingFollowRedirects?: ModelWithHttpSettings["settingFollowRedirects"];
-  settingRequestTimeout?: ModelWithHttpSettings["settingRequestTimeout"];
+  settingHttp3?: ModelWithHttpSettings["settingHttp3"];
+};
+type TimeoutSettingsPatch = {
+  settingRequestTimeout?: ModelWithTimeoutSettings["settingRequestTimeout"];
 };
 type TlsSettingsPatch = {
   settingValidateCertificates?: ModelWithTlsSettings["settingValidateCertificates"];
 };
+type ProxySettingsPatch = {
+  settingProxy?: ModelWithProxySettings["settingProxy"];
+};
 type MessageSizeSettingsPatch = {
   settingRequestMessageSize?: ModelWithMessageSizeSettings["settingRequestMessageSize"];
 };
@@ -84,15 +125,17 @@ export function ModelSettingsEditor({
 }: Props) {
   const ancestors = useModelAncestors(model);
   const supportsHttpSettings = modelSupportsHttpSettings(model);
+  const supportsTimeoutSettings = modelSupportsTimeoutSettings(model);
   const supportsCookieSettings = modelSupportsCookieSettings(model);
   const supportsTlsSettings = modelSupportsTlsSettings(model);
   const supportsMessageSizeSettings = modelSupportsMessageSizeSettings(model);
+  const supportsProxySettings = modelSupportsProxySettings(model);
 
   return (
     <SettingsList className="space-y-8">
       {supportsTlsSettings && (
         <SettingsSection title={showSectionTitles ? "Requests" : null}>
-          {supportsHttpSettings && (
+          {supportsTimeoutSettings && (
             <IntegerSettingRow
               settingDefinition={SETTING_REQUEST_TIMEOUT}
               setting={model.settingRequestTimeout}
@@ -102,7 +145,7 @@ export function ModelSettingsEditor({
                 model.settingRequestTimeout,
               )}
               onChange={(settingRequestTimeout) =>
-                patchHttpSettings(model, {
+                patchTimeoutSettings(model, {
                   settingRequestTimeout,
                 })
               }
@@ -124,6 +167,26 @@ export function ModelSettingsEditor({
               }
             />
           )}
+          {model.model === "grpc_request" && (
+            <SettingRowSelect
+              name="protocol"
+              title="Protocol"
+              description="gRPC-Web and Connect also work over HTTP/1.1, for services behind browser-facing proxies."
+              value={model.protocol}
+              options={GRPC_PROTOCOL_OPTIONS}
+              onChange={(protocol) => patchModel(model, { protocol })}
+            />
+          )}
+          {model.model === "grpc_request" && (
+            <SettingRowSelect
+              name="compression"
+              title="Compression"
+              description="Compress sent messages. Compressed responses are always accepted. Connect requests are sent uncompressed."
+              value={model.compression}
+              options={GRPC_COMPRESSION_OPTIONS}
+              onChange={(compression) => patchModel(model, { compression })}
+            />
+          )}
           <BooleanSettingRow
             settingDefinition={SETTING_VALIDATE_CERTIFICATES}
             setting={model.settingValidateCertificates}
@@ -154,6 +217,22 @@ export function ModelSettingsEditor({
               }
             />
           )}
+          {supportsHttpSettings && (
+            <Http3SettingRow
+              settingDefinition={SETTING_HTTP3}
+              setting={model.settingHttp3}
+              inheritedValue={resolveInheritedValue(
+                ancestors,
+                SETTING_HTTP3.modelKey,
+                model.settingHttp3,
+              )}
+              onChange={(settingHttp3) =>
+                patchHttpSettings(model, {
+                  settingHttp3,
+                })
+              }
+            />
+          )}
         </SettingsSection>
       )}
       {supportsCookieSettings && (
@@ -190,12 +269,29 @@ export function ModelSettingsEditor({
           />
         </SettingsSection>
       )}
+      {supportsProxySettings && (
+        <ProxySettingSection
+          modelId={model.id}
+          ancestors={ancestors}
+          setting={model.settingProxy}
+          onChange={(settingProxy) =>
+            patchProxySettings(model, {
+              settingProxy,
+            })
+          }
+        />
+      )}
     </SettingsList>
   );
 }
 
 export function countOverriddenSettings(model: ModelWithSettings) {
-  const settings: (BooleanSetting | IntegerSetting)[] = [];
+  const settings: (
+    | BooleanSetting
+    | IntegerSetting
+    | Http3Setting
+    | InheritedProxySetting
+  )[] = [];
 
   if (modelSupportsCookieSettings(model)) {
     settings.push(model.settingSendCookies, model.settingStoreCookies);
@@ -203,14 +299,22 @@ export function countOverriddenSettings(model: ModelWithSettings) {
 
   settings.push(model.settingValidateCertificates);
 
+  if (modelSupportsTimeoutSettings(model)) {
+    settings.push(model.settingRequestTimeout);
+  }
+
   if (modelSupportsHttpSettings(model)) {
-    settings.push(model.settingFollowRedirects, model.settingRequestTimeout);
+    settings.push(model.settingFollowRedirects, model.settingHttp3);
   }
 
   if (modelSupportsMessageSizeSettings(model)) {
     settings.push(model.settingRequestMessageSize);
   }
 
+  if (modelSupportsProxySettings(model)) {
+    settings.push(model.settingProxy);
+  }
+
   return settings.filter(
     (setting) => isInheritedSetting(setting) && setting.enabled === true,
   ).length;
@@ -246,6 +350,22 @@ function patchHttpSettings(
   }
 }
 
+function patchTimeoutSettings(
+  model: ModelWithTimeoutSettings,
+  patch: Partial<TimeoutSettingsPatch>,
+) {
+  switch (model.model) {
+    case "workspace":
+      return patchModel(model, patch as Partial<Workspace>);
+    case "folder":
+      return patchModel(model, patch as Partial<Folder>);
+    case "http_request":
+      return patchModel(model, patch as Partial<HttpRequest>);
+    case "grpc_request":
+      return patchModel(model, patch as Partial<GrpcRequest>);
+  }
+}
+
 function patchTlsSettings(
   model: ModelWithTlsSettings,
   patch: Partial<TlsSettingsPatch>,
@@ -264,6 +384,18 @@ function patchTlsSettings(
   }
 }
 
+function patchProxySettings(
+  model: ModelWithProxySettings,
+  patch: Partial<ProxySettingsPatch>,
+) {
+  switch (model.model) {
+    case "workspace":
+      return patchModel(model, patch as Partial<Workspace>);
+    case "folder":
+      return patchModel(model, patch as Partial<Folder>);
+  }
+}
+
 function patchMessageSizeSettings(
   model: ModelWithMessageSizeSettings,
   patch: Partial<MessageSizeSettingsPatch>,
@@ -283,6 +415,12 @@ function patchMessageSizeSettings(
 function modelSupportsHttpSettings(
   model: ModelWithSettings,
 ): model is ModelWithHttpSettings {
+  return modelSupportsSetting(model, SETTING_FOLLOW_REDIRECTS);
+}
+
+function modelSupportsTimeoutSettings(
+  model: ModelWithSettings,
+): model is ModelWithTimeoutSettings {
   return modelSupportsSetting(model, SETTING_REQUEST_TIMEOUT);
 }
 
@@ -304,6 +442,12 @@ function modelSupportsMessageSizeSettings(
   return modelSupportsSetting(model, SETTING_REQUEST_MESSAGE_SIZE);
 }
 
+function modelSupportsProxySettings(
+  model: ModelWithSettings,
+): model is ModelWithProxySettings {
+  return modelSupportsSetting(model, SETTING_PROXY);
+}
+
 function BooleanSettingRow({
   inheritedValue,
   setting,
@@ -416,6 +560,116 @@ function IntegerSettingRow({
   );
 }
 
+function Http3SettingRow({
+  inheritedValue,
+  setting,
+  settingDefinition,
+  onChange,
+}: {
+  inheritedValue: Http3Mode;
+  setting: Http3Setting;
+  settingDefinition: RequestSettingDefinition<"settingHttp3">;
+  onChange: (setting: Http3Setting) => void;
+}) {
+  const inherited = isInheritedSetting(setting);
+  const overridden = inherited ? setting.enabled === true : false;
+  const value = inherited
+    ? overridden
+      ? setting.value
+      : inheritedValue
+    : setting;
+
+  if (!inherited) {
+    return (
+      <SettingRowSelect
+        name={settingDefinition.modelKey}
+        title={settingDefinition.title}
+        description={settingDefinition.description}
+        value={value}
+        options={HTTP3_OPTIONS}
+        onChange={(value) => onChange(value)}
+      />
+    );
+  }
+
+  return (
+    <SettingOverrideRow
+      title={settingDefinition.title}
+      description={settingDefinition.description}
+      overridden={overridden}
+      onResetOverride={() => onChange({ ...setting, enabled: false })}
+    >
+      <SettingSelectControl
+        name={settingDefinition.modelKey}
+        label={settingDefinition.title}
+        value={value}
+        options={HTTP3_OPTIONS}
+        onChange={(value) => onChange({ ...setting, enabled: true, value })}
+      />
+    </SettingOverrideRow>
+  );
+}
+
+function ProxySettingSection({
+  modelId,
+  ancestors,
+  setting,
+  onChange,
+}: {
+  modelId: string;
+  ancestors: (Folder | Workspace)[];
+  setting: InheritedProxySetting;
+  onChange: (setting: InheritedProxySetting) => void;
+}) {
+  const globalProxy = useAtomValue(settingsAtom).proxy;
+  const inheritedValue = resolveInheritedProxy(ancestors, globalProxy);
+  const overridden = setting.enabled === true;
+  const value = overridden ? setting.value : inheritedValue;
+
+  return (
+    <>
+      <SettingsSection title="Proxy">
+        <SettingOverrideRow
+          title={SETTING_PROXY.title}
+          description={SETTING_PROXY.description}
+          overridden={overridden}
+          onResetOverride={() => onChange({ ...setting, enabled: false })}
+        >
+          <SettingSelectControl
+            name={SETTING_PROXY.modelKey}
+            label={SETTING_PROXY.title}
+            value={value?.type ?? "automatic"}
+            selectClassName="w-64!"
+            options={[
+              { label: "Automatic proxy detection", value: "automatic" },
+              { label: "Custom proxy configuration", value: "enabled" },
+              { label: "No proxy", value: "disabled" },
+            ]}
+            onChange={(type) =>
+              onChange({
+                enabled: true,
+                value:
+                  type === "automatic"
+                    ? null
+                    : type === "enabled"
+                      ? enabledProxyOrDefault(value)
+                      : { type: "disabled" },
+              })
+            }
+          />
+        </SettingOverrideRow>
+      </SettingsSection>
+      {overridden && setting.value?.type === "enabled" && (
+        <CustomProxySettings
+          proxy={setting.value}
+          stateKey={`proxy.${modelId}`}
+          onChange={(value) => onChange({ ...setting, value })}
+        />
+      )}
+    </>
+  );
+}
+
 function MessageSizeSettingRow({
   inheritedValue,
   setting,
@@ -562,6 +816,11 @@ function resolveInheritedValue(
   key: "settingRequestTimeout" | "settingRequestMessageSize",
   fallback: IntegerSetting,
 ): number;
+function resolveInheritedValue(
+  ancestors: (Folder | Workspace)[],
+  key: "settingHttp3",
+  fallback: Http3Setting,
+): Http3Mode;
 function resolveInheritedValue(
   ancestors: (Folder | Workspace)[],
   key: BooleanWorkspaceSettingKey,
@@ -570,10 +829,13 @@ function resolveInheritedValue(
 function resolveInheritedValue(
   ancestors: (Folder | Workspace)[],
   key: keyof WorkspaceSettings,
-  fallback: BooleanSetting | IntegerSetting,
+  fallback: BooleanSetting | IntegerSetting | Http3Setting,
 ) {
   for (const ancestor of ancestors) {
-    const setting = ancestor[key] as BooleanSetting | IntegerSetting;
+    const setting = ancestor[key] as
+      | BooleanSetting
+      | IntegerSetting
+      | Http3Setting;
     if (isInheritedSetting(setting)) {
       if (setting.enabled === true) {
         return setting.value;
@@ -586,9 +848,23 @@ function resolveInheritedValue(
   return isInheritedSetting(fallback) ? fallback.value : fallback;
 }
 
+function resolveInheritedProxy(
+  ancestors: (Folder | Workspace)[],
+  globalProxy: ProxySetting | null,
+) {
+  for (const ancestor of ancestors) {
+    if (ancestor.settingProxy.enabled === true) {
+      return ancestor.settingProxy.value;
+    }
+  }
+
+  return globalProxy;
+}
+
 type WorkspaceSettings = Pick<
   Workspace,
   | "settingFollowRedirects"
+  | "settingHttp3"
   | "settingRequestMessageSize"
   | "settingRequestTimeout"
   | "settingSendCookies"
@@ -598,7 +874,7 @@ type WorkspaceSettings = Pick<
 
 type BooleanWorkspaceSettingKey = Exclude<
   keyof WorkspaceSettings,
-  "settingRequestTimeout" | "settingRequestMessageSize"
+  "settingRequestTimeout" | "settingRequestMessageSize" | "settingHttp3"
 >;
 
 function formatMegabytes(bytes: number) {
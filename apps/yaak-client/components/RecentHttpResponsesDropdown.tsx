@@ -9,6 +9,7 @@ import {
   isYesterday,
 } from "date-fns";
 import { useDeleteHttpResponses } from "../hooks/useDeleteHttpResponses";
+import { useExportHar } from "../hooks/useExportHar";
 import { useKeyValue } from "../hooks/useKeyValue";
 import { DismissibleBanner } from "./core/DismissibleBanner";
 import { Dropdown, type DropdownItem } from "./core/Dropdown";
@@ -30,6 +31,7 @@ export const RecentHttpResponsesDropdown = function ResponsePane({
   onPinnedResponseId,
 }: Props) {
   const deleteAllResponses = useDeleteHttpResponses(activeResponse?.requestId);
+  const exportHar = useExportHar(responses);
   const movedActionsBannerId = "response-actions-moved-to-response-menu-2026-07-02-v2";
   const { value: dismissedMovedActions } = useKeyValue<boolean>({
     namespace: "global",
@@ -119,6 +121,12 @@ export const RecentHttpResponsesDropdown = function ResponsePane({
           onSelect: deleteAllResponses.mutate,
           disabled: responses.length === 0,
         },
+        {
+          label: "Export as HAR",
+          leftSlot: <Icon icon="arrow_down_to_line" />,
+          onSelect: exportHar.mutate,
+          disabled: responses.length === 0,
+        },
         {
           label: "Unpin Response",
           onSelect: () => onPinnedResponseId(activeResponse.id),
@@ -3,7 +3,9 @@ import type { ProxySetting } from "@yaakapp-internal/models";
 import { Heading, InlineCode, VStack } from "@yaakapp-internal/ui";
 import { useAtomValue } from "jotai";
 import { CommercialUseBanner } from "../CommercialUseBanner";
+import { Input } from "../core/Input";
 import {
+  SettingRow,
   SettingRowBoolean,
   SettingRowSelect,
   SettingRowText,
@@ -15,16 +17,6 @@ export function SettingsProxy() {
   const settings = useAtomValue(settingsAtom);
   const proxy = enabledProxyOrDefault(settings.proxy);
 
-  const patchProxy = async (patch: Partial<EnabledProxySetting>) => {
-    await patchModel(settings, {
-      proxy: {
-        ...proxy,
-        ...patch,
-        auth: Object.hasOwn(patch, "auth") ? (patch.auth ?? null) : proxy.auth,
-      },
-    });
-  };
-
   return (
     <VStack space={1.5} className="mb-4">
       <div className="mb-3">
@@ -61,112 +53,167 @@ export function SettingsProxy() {
         </SettingsSection>
 
         {settings.proxy?.type === "enabled" && (
-          <>
-            <SettingsSection title="Custom Proxy">
-              <SettingRowBoolean
-                checked={!settings.proxy.disabled}
-                title="Enable proxy"
-                description="Temporarily disable the proxy without losing the configuration."
-                onChange={(enabled) => patchProxy({ disabled: !enabled })}
-              />
-              <SettingRowText
-                name="proxyHttp"
-                title={
-                  <>
-                    Proxy for <InlineCode>http://</InlineCode> traffic
-                  </>
-                }
-                description="Proxy host used for unencrypted HTTP traffic."
-                value={settings.proxy.http}
-                placeholder="localhost:9090"
-                onChange={(http) => patchProxy({ http })}
-              />
-              <SettingRowText
-                name="proxyHttps"
-                title={
-                  <>
-                    Proxy for <InlineCode>https://</InlineCode> traffic
-                  </>
-                }
-                description="Proxy host used for HTTPS traffic."
-                value={settings.proxy.https}
-                placeholder="localhost:9090"
-                onChange={(https) => patchProxy({ https })}
-              />
-              <SettingRowText
-                name="proxyBypass"
-                title="Proxy Bypass"
-                description="Comma-separated list of hosts that should bypass the proxy."
-                value={settings.proxy.bypass}
-                placeholder="127.0.0.1, *.example.com, localhost:3000"
-                inputWidthClassName="w-96!"
-                onChange={(bypass) => patchProxy({ bypass })}
-              />
-            </SettingsSection>
+          <CustomProxySettings
+            proxy={settings.proxy}
+            onChange={(proxy) => patchModel(settings, { proxy })}
+          />
+        )}
+      </SettingsList>
+    </VStack>
+  );
+}
+
+export type EnabledProxySetting = Extract<ProxySetting, { type: "enabled" }>;
+
+/**
+ * A custom proxy's fields, shared by the global settings and workspace or folder overrides.
+ * Overrides pass a `stateKey`: those models are synced, so their credentials are templates and
+ * the password is kept encrypted.
+ */
+export function CustomProxySettings({
+  proxy,
+  onChange,
+  stateKey,
+}: {
+  proxy: EnabledProxySetting;
+  onChange: (proxy: EnabledProxySetting) => void;
+  stateKey?: string;
+}) {
+  const patchProxy = (patch: Partial<EnabledProxySetting>) =>
+    onChange({
+      ...proxy,
+      ...patch,
+      auth: Object.hasOwn(patch, "auth") ? (patch.auth ?? null) : proxy.auth,
+    });
+
+  return (
+    <>
+      <SettingsSection title="Custom Proxy">
+        <SettingRowBoolean
+          checked={!proxy.disabled}
+          title="Enable proxy"
+          description="Temporarily disable the proxy without losing the configuration."
+          onChange={(enabled) => patchProxy({ disabled: !enabled })}
+        />
+        <SettingRowText
+          name="proxyHttp"
+          title={
+            <>
+              Proxy for <InlineCode>http://</InlineCode> traffic
+            </>
+          }
+          description="Proxy host used for unencrypted HTTP traffic."
+          value={proxy.http}
+          placeholder="localhost:9090"
+          onChange={(http) => patchProxy({ http })}
+        />
+        <SettingRowText
+          name="proxyHttps"
+          title={
+            <>
+              Proxy for <InlineCode>https://</InlineCode> traffic
+            </>
+          }
+          description="Proxy host used for HTTPS traffic."
+          value={proxy.https}
+          placeholder="localhost:9090"
+          onChange={(https) => patchProxy({ https })}
+        />
+        <SettingRowText
+          name="proxySocks"
+          title="SOCKS proxy"
+          description={
+            <>
+              SOCKS5 proxy for all other traffic. Use <InlineCode>socks5h://</InlineCode>, or
+              no scheme, to resolve host names on the proxy.
+            </>
+          }
+          value={proxy.socks}
+          placeholder="socks5h://bastion:1080"
+          onChange={(socks) => patchProxy({ socks })}
+        />
+        <SettingRowText
+          name="proxyBypass"
+          title="Proxy Bypass"
+          description="Comma-separated list of hosts that should bypass the proxy."
+          value={proxy.bypass}
+          placeholder="127.0.0.1, *.example.com, localhost:3000"
+          inputWidthClassName="w-96!"
+          onChange={(bypass) => patchProxy({ bypass })}
+        />
+      </SettingsSection>
 
-            <SettingsSection title="Authentication">
-              <SettingRowBoolean
-                checked={settings.proxy.auth != null}
-                title="Enable authentication"
-                description="Send proxy credentials with proxied requests."
-                onChange={(enabled) =>
-                  patchProxy({ auth: enabled ? { user: "", password: "" } : null })
-                }
-              />
+      <SettingsSection title="Authentication">
+        <SettingRowBoolean
+          checked={proxy.auth != null}
+          title="Enable authentication"
+          description="Send proxy credentials with proxied requests."
+          onChange={(enabled) => patchProxy({ auth: enabled ? { user: "", password: "" } : null })}
+        />
 
-              {settings.proxy.auth != null && (
-                <>
-                  <SettingRowText
-                    required
-                    name="proxyUser"
-                    title="User"
-                    description="Username for proxy authentication."
-                    value={settings.proxy.auth.user}
-                    placeholder="myUser"
-                    onChange={(user) =>
-                      patchProxy({
-                        auth: {
-                          user,
-                          password:
-                            settings.proxy?.type === "enabled"
-                              ? (settings.proxy.auth?.password ?? "")
-                              : "",
-                        },
-                      })
-                    }
-                  />
-                  <SettingRowText
-                    name="proxyPassword"
-                    title="Password"
-                    description="Password for proxy authentication."
-                    value={settings.proxy.auth.password}
-                    placeholder="s3cretPassw0rd"
-                    type="password"
-                    onChange={(password) =>
-                      patchProxy({
-                        auth: {
-                          user:
-                            settings.proxy?.type === "enabled"
-                              ? (settings.proxy.auth?.user ?? "")
-                              : "",
-                          password,
-                        },
-                      })
-                    }
-                  />
-                </>
-              )}
-            </SettingsSection>
+        {proxy.auth != null && (
+          <>
+            <ProxyCredentialRow
+              required
+              name="proxyUser"
+              title="User"
+              description="Username for proxy authentication."
+              value={proxy.auth.user}
+              placeholder="myUser"
+              stateKey={stateKey}
+              onChange={(user) =>
+                patchProxy({ auth: { user, password: proxy.auth?.password ?? "" } })
+              }
+            />
+            <ProxyCredentialRow
+              name="proxyPassword"
+              title="Password"
+              description="Password for proxy authentication."
+              value={proxy.auth.password}
+              placeholder="s3cretPassw0rd"
+              type="password"
+              stateKey={stateKey}
+              onChange={(password) =>
+                patchProxy({ auth: { user: proxy.auth?.user ?? "", password } })
+              }
+            />
           </>
         )}
-      </SettingsList>
-    </VStack>
+      </SettingsSection>
+    </>
   );
 }
 
-type EnabledProxySetting = Extract<ProxySetting, { type: "enabled" }>;
+function ProxyCredentialRow({
+  stateKey,
+  ...props
+}: Parameters<typeof SettingRowText>[0] & { stateKey?: string }) {
+  if (stateKey == null) {
+    return <SettingRowText {...props} />;
+  }
+
+  return (
+    <SettingRow title={props.title} description={props.description}>
+      <Input
+        hideLabel
+        autocompleteFunctions
+        autocompleteVariables
+        size="sm"
+        required={props.required}
+        name={props.name}
+        label={props.title}
+        placeholder={props.placeholder}
+        defaultValue={props.value}
+        type={props.type}
+        stateKey={`${stateKey}.${props.name}`}
+        containerClassName="w-80!"
+        onChange={props.onChange}
+      />
+    </SettingRow>
+  );
+}
 
-function enabledProxyOrDefault(proxy: ProxySetting | null): EnabledProxySetting {
+export function enabledProxyOrDefault(proxy: ProxySetting | null): EnabledProxySetting {
   if (proxy?.type === "enabled") return proxy;
 
   return {
@@ -174,6 +221,7 @@ function enabledProxyOrDefault(proxy: ProxySetting | null): EnabledProxySetting
     type: "enabled",
     http: "",
     https: "",
+    socks: "",
     auth: { user: "", password: "" },
     bypass: "",
   };
@@ -7,7 +7,7 @@ import { applyFormInputDefaults, validateTemplateFunctionArgs } from "@yaakapp-i
 import type { FormInput, JsonPrimitive, TemplateFunction } from "@yaakapp-internal/plugins";
 import { parseTemplate } from "@yaakapp-internal/templates";
 import type { TwigCompletionOption } from "./completion";
-import { collectArgumentValues } from "./util";
+import { collectArgumentValues, tagHead } from "./util";
 
 class TemplateTagWidget extends WidgetType {
   readonly #clickListenerCallback: () => void;
@@ -80,12 +80,19 @@ function templateTags(
 
           // TODO: Search `node.tree` instead of using Regex here
           const inner = rawTag.replace(/^\$\{\[\s*/, "").replace(/\s*]}$/, "");
-          let name = inner.match(/([\w.]+)[(]/)?.[1] ?? inner;
-
           if (inner.includes("\n")) {
             return;
           }
 
+          // Filters and fallbacks wrap the variable or function the tag is named for
+          const tag = parseTemplate(rawTag).tokens[0];
+          const val = tag?.type === "tag" ? tag.val : null;
+          const head = val == null ? null : tagHead(val);
+          let name =
+            head?.type === "fn" || head?.type === "var"
+              ? head.name
+              : (inner.match(/([\w.]+)[(]/)?.[1] ?? inner);
+
           // The beta named the function `Response` but was changed in stable.
           // Keep this here for a while because there's no easy way to migrate
           if (name === "Response") {
@@ -110,7 +117,16 @@ function templateTags(
             };
           }
 
-          if (option.type === "function") {
+          if (val?.type === "filter" || val?.type === "fallback") {
+            // Shown as written, and not opened in the function dialog, which would drop the
+            // filters and fallbacks. A missing variable with a fallback is fine.
+            option = {
+              ...option,
+              label: inner,
+              invalid: option.invalid && val.type !== "fallback",
+              onClick: () => {},
+            };
+          } else if (option.type === "function") {
             const tokens = parseTemplate(rawTag);
             const rawValues = collectArgumentValues(tokens, option);
             const values = applyFormInputDefaults(option.args, rawValues);
@@ -1,5 +1,5 @@
 import type { FormInput, TemplateFunction } from "@yaakapp-internal/plugins";
-import type { Tokens } from "@yaakapp-internal/templates";
+import type { Tokens, Val } from "@yaakapp-internal/templates";
 
 /**
  * Process the initial tokens from the template and merge those with the default values pulled from
@@ -24,7 +24,9 @@ export function collectArgumentValues(initialTokens: Tokens, templateFunction: T
         ? initialArg?.value.text
         : initialArg?.value.type === "bool"
           ? initialArg.value.value
-          : undefined;
+          : initialArg?.value.type === "num"
+            ? String(initialArg.value.value)
+            : undefined;
     const value = initialArgValue ?? arg.defaultValue;
     if (value != null) {
       initial[arg.name] = value;
@@ -35,3 +37,10 @@ export function collectArgumentValues(initialTokens: Tokens, templateFunction: T
 
   return initial;
 }
+
+/**
+ * The variable or function a tag's value is named for, beneath any filters and fallbacks.
+ */
+export function tagHead(val: Val): Val {
+  return val.type === "filter" || val.type === "fallback" ? tagHead(val.value) : val;
+}
@@ -19,8 +19,17 @@ export function HttpResponseDurationTag({ response }: Props) {
     return () => clearInterval(timeout.current);
   }, [response.createdAt, response.state]);
 
-  const dnsValue = response.elapsedDns > 0 ? formatMillis(response.elapsedDns) : "--";
-  const title = `DNS: ${dnsValue}\nHEADER: ${formatMillis(response.elapsedHeaders)}\nTOTAL: ${formatMillis(response.elapsed)}`;
+  const phase = (ms: number) => (ms > 0 ? formatMillis(ms) : "--");
+  const download = response.elapsed > 0 ? response.elapsed - response.elapsedHeaders : 0;
+  const title = [
+    `DNS: ${phase(response.elapsedDns)}`,
+    `CONNECT: ${phase(response.elapsedConnect)}`,
+    `TLS: ${phase(response.elapsedTls)}`,
+    `WAITING: ${phase(response.elapsedTtfb)}`,
+    `DOWNLOAD: ${phase(download)}`,
+    `HEADER: ${formatMillis(response.elapsedHeaders)}`,
+    `TOTAL: ${formatMillis(response.elapsed)}`,
+  ].join("\n");
 
   const elapsed = response.state === "closed" ? response.elapsed : fallbackElapsed;
 
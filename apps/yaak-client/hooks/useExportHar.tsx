@@ -0,0 +1,38 @@
+import type { HttpResponse } from "@yaakapp-internal/models";
+import { getModel } from "@yaakapp-internal/models";
+import { platform } from "@yaakapp-internal/platform";
+import { InlineCode } from "@yaakapp-internal/ui";
+import slugify from "slugify";
+import { rpc } from "../lib/rpc";
+import { showToast } from "../lib/toast";
+import { useFastMutation } from "./useFastMutation";
+
+export function useExportHar(responses: HttpResponse[]) {
+  return useFastMutation({
+    mutationKey: ["export_har", responses[0]?.requestId],
+    mutationFn: async () => {
+      const first = responses[0];
+      if (first == null) return;
+
+      const request = getModel("http_request", first.requestId);
+      const slug = slugify(request?.name || "responses", { lower: true });
+      const exportPath = await platform.dialog.save({
+        defaultPath: `${slug}.har`,
+        title: "Export as HAR",
+      });
+      if (exportPath == null) {
+        return; // Cancelled
+      }
+
+      await rpc("cmd_export_har", { exportPath, responseIds: responses.map((r) => r.id) });
+      showToast({
+        message: (
+          <>
+            Exported {responses.length} {responses.length === 1 ? "response" : "responses"} to{" "}
+            <InlineCode>{exportPath}</InlineCode>
+          </>
+        ),
+      });
+    },
+  });
+}
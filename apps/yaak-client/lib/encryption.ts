@@ -16,8 +16,8 @@ export function analyzeTemplate(template: string): "global_secured" | "local_sec
       secureTags++;
     } else if (t.type === "tag" && t.val.type === "var") {
       // Variables are secure
-    } else if (t.type === "tag" && t.val.type === "bool") {
-      // Booleans are secure
+    } else if (t.type === "tag" && (t.val.type === "bool" || t.val.type === "num")) {
+      // Booleans and numbers are secure
     } else {
       insecureTags++;
     }
@@ -5,6 +5,8 @@ type ModelType = AnyModel["model"];
 type WorkspaceRequestSettings = Pick<
   Workspace,
   | "settingFollowRedirects"
+  | "settingHttp3"
+  | "settingProxy"
   | "settingRequestMessageSize"
   | "settingRequestTimeout"
   | "settingSendCookies"
@@ -38,9 +40,10 @@ function defineRequestSetting<const K extends RequestSettingKey>(
 
 export const SETTING_REQUEST_TIMEOUT = defineRequestSetting({
   defaultValue: 0,
-  description: "Maximum request duration in milliseconds. Set to 0 to disable.",
+  description:
+    "Maximum request duration, or gRPC call deadline, in milliseconds. Set to 0 to disable.",
   modelKey: "settingRequestTimeout",
-  models: ["workspace", "folder", "http_request"],
+  models: ["workspace", "folder", "http_request", "grpc_request"],
   title: "Request Timeout",
 });
 
@@ -75,6 +78,24 @@ export const SETTING_FOLLOW_REDIRECTS = defineRequestSetting({
   title: "Follow redirects",
 });
 
+export const SETTING_HTTP3 = defineRequestSetting({
+  defaultValue: "off",
+  description:
+    "Send requests over QUIC once the server advertises HTTP/3, or always.",
+  modelKey: "settingHttp3",
+  models: ["workspace", "folder", "http_request"],
+  title: "HTTP/3",
+});
+
+export const SETTING_PROXY = defineRequestSetting({
+  defaultValue: { enabled: false, value: null },
+  description:
+    "Route requests through a different proxy than the one in Settings.",
+  modelKey: "settingProxy",
+  models: ["workspace", "folder"],
+  title: "Proxy",
+});
+
 export const SETTING_SEND_COOKIES = defineRequestSetting({
   defaultValue: true,
   description:
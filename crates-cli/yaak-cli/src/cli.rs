@@ -21,6 +21,14 @@ pub struct Cli {
     #[arg(long, short, global = true)]
     pub environment: Option<String>,
 
+    /// Set a variable for this run only, above every environment (repeatable)
+    #[arg(long = "var", global = true, value_name = "NAME=VALUE")]
+    pub vars: Vec<String>,
+
+    /// Read variables for this run only from a file of NAME=VALUE lines; --var wins over it
+    #[arg(long, global = true, value_name = "PATH")]
+    pub env_file: Option<PathBuf>,
+
     /// Cookie jar ID to use when sending requests
     #[arg(long = "cookie-jar", global = true, value_name = "COOKIE_JAR_ID")]
     pub cookie_jar: Option<String>,
@@ -45,7 +53,7 @@ pub enum Commands {
     /// Authentication commands
     Auth(AuthArgs),
 
-    /// Import API data from Yaak, OpenAPI, Postman, Insomnia, Swagger, or cURL
+    /// Import API data from Yaak, OpenAPI, Postman, Insomnia, Swagger, HAR, or cURL
     Import(ImportArgs),
 
     /// Export Yaak workspace data
@@ -71,6 +79,12 @@ pub enum Commands {
     /// Send a request, folder, or workspace by ID
     Send(SendArgs),
 
+    /// Load-test a request, or each request in a folder
+    Bench(BenchArgs),
+
+    /// Collection runner commands
+    Runner(RunnerArgs),
+
     /// Cookie jar commands
     CookieJar(CookieJarArgs),
 
@@ -112,12 +126,32 @@ pub enum ResponseCommands {
         limit: Option<u64>,
     },
 
-    /// Show a response as JSON, including status, timing, and headers
+    /// Show a response as JSON, including status, timing, headers, and TLS details
     Show {
         /// Response ID, or a request ID to use its most recent response
         id: String,
     },
 
+    /// Show a response's assertion results as JSON
+    Assertions {
+        /// Response ID, or a request ID to use its most recent response
+        id: String,
+    },
+
+    /// List the server-sent events of a `text/event-stream` response, in order
+    Events {
+        /// Response ID, or a request ID to use its most recent response
+        id: String,
+
+        /// Only events of this type (`message` for events that set none)
+        #[arg(long = "type", value_name = "TYPE")]
+        event_type: Option<String>,
+
+        /// Print the events as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Write a stored response body to stdout
     Body {
         /// Response ID, or a request ID to use its most recent response
@@ -198,6 +232,122 @@ pub struct SendArgs {
     /// Stop on first request failure when sending folders/workspaces
     #[arg(long, conflicts_with = "parallel")]
     pub fail_fast: bool,
+
+    /// Write a machine-readable summary of the run (for CI and test dashboards)
+    #[arg(long, value_enum, requires = "report_file")]
+    pub reporter: Option<SendReporter>,
+
+    /// File to write the --reporter summary to
+    #[arg(long, value_name = "FILE", requires = "reporter")]
+    pub report_file: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub websocket: WebsocketSessionArgs,
+}
+
+/// How long a WebSocket request stays connected, and what it sends
+#[derive(Args, Clone, Debug)]
+pub struct WebsocketSessionArgs {
+    /// Close a WebSocket connection after this many seconds without a message
+    #[arg(long, value_name = "SECONDS", default_value_t = 5.0)]
+    pub idle_timeout: f64,
+
+    /// Close a WebSocket connection once this many messages have been received
+    #[arg(long, value_name = "COUNT")]
+    pub max_messages: Option<u64>,
+
+    /// Send each line of stdin as a WebSocket message, instead of the request's message
+    #[arg(long)]
+    pub stdin: bool,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SendReporter {
+    Junit,
+    Tap,
+    Json,
+}
+
+#[derive(Args)]
+pub struct BenchArgs {
+    /// Request or folder ID
+    pub id: String,
+
+    /// Requests in flight at once
+    #[arg(long, short = 'c', default_value_t = 10)]
+    pub concurrency: u32,
+
+    /// Requests per second across all connections (default: as fast as possible)
+    #[arg(long)]
+    pub rate: Option<f64>,
+
+    /// Seconds to run for
+    #[arg(long, short = 'd', value_name = "SECONDS", default_value_t = 10.0)]
+    pub duration: f64,
+
+    /// Stop after this many requests, even if time remains
+    #[arg(long, short = 'n')]
+    pub requests: Option<u64>,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct RunnerArgs {
+    #[command(subcommand)]
+    pub command: RunnerCommands,
+}
+
+#[derive(Subcommand)]
+pub enum RunnerCommands {
+    /// Run HTTP requests in order, once per row of an optional data file
+    Run {
+        /// Request or folder IDs, in the order to run them (folders expand to their HTTP requests)
+        #[arg(required = true)]
+        ids: Vec<String>,
+
+        /// CSV (with a header row) or JSON array of objects; each row is one iteration
+        #[arg(long, value_name = "FILE")]
+        data: Option<PathBuf>,
+
+        /// Milliseconds to wait before each request
+        #[arg(long, value_name = "MS", default_value_t = 0)]
+        delay: i32,
+
+        /// Extra attempts for a request that fails
+        #[arg(long, default_value_t = 0)]
+        retries: i32,
+
+        /// Delay for one request instead of --delay (repeatable)
+        #[arg(long = "step-delay", value_name = "REQUEST_ID=MS")]
+        step_delays: Vec<String>,
+
+        /// Retries for one request instead of --retries (repeatable)
+        #[arg(long = "step-retries", value_name = "REQUEST_ID=N")]
+        step_retries: Vec<String>,
+
+        /// Name to store the run under
+        #[arg(long)]
+        name: Option<String>,
+    },
+
+    /// List stored runs for a workspace, newest first
+    List {
+        /// Workspace ID (optional when exactly one workspace exists)
+        workspace_id: Option<String>,
+
+        /// Maximum number of runs to return
+        #[arg(long)]
+        limit: Option<u64>,
+    },
+
+    /// Show a run and its iterations as JSON
+    Show {
+        /// Run ID
+        run_id: String,
+    },
 }
 
 #[derive(Args)]
@@ -331,6 +481,9 @@ pub enum RequestCommands {
     Send {
         /// Request ID
         request_id: String,
+
+        #[command(flatten)]
+        websocket: WebsocketSessionArgs,
     },
 
     /// Output JSON schema for request create/update payloads
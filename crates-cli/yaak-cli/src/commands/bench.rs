@@ -0,0 +1,144 @@
+use crate::cli::BenchArgs;
+use crate::commands::{request, runner};
+use crate::context::CliContext;
+use std::time::Duration;
+use tokio::sync::watch;
+use yaak::bench::{BenchHttpRequestParams, BenchOptions, bench_http_request_by_id};
+use yaak_models::models::HttpBenchReport;
+use yaak_plugins::events::PluginContext;
+
+type CommandResult<T = ()> = std::result::Result<T, String>;
+
+pub async fn run(
+    ctx: &CliContext,
+    args: BenchArgs,
+    environment: Option<&str>,
+    cookie_jar_id: Option<&str>,
+) -> i32 {
+    match bench(ctx, args, environment, cookie_jar_id).await {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            1
+        }
+    }
+}
+
+async fn bench(
+    ctx: &CliContext,
+    args: BenchArgs,
+    environment: Option<&str>,
+    cookie_jar_id: Option<&str>,
+) -> CommandResult {
+    if args.concurrency == 0 {
+        return Err("--concurrency must be at least 1".to_string());
+    }
+    if !args.duration.is_finite() || args.duration <= 0.0 {
+        return Err("--duration must be a positive number of seconds".to_string());
+    }
+    if args.rate.is_some_and(|rate| !rate.is_finite() || rate <= 0.0) {
+        return Err("--rate must be a positive number of requests per second".to_string());
+    }
+
+    let (workspace_id, request_ids) =
+        runner::resolve_request_ids(ctx, std::slice::from_ref(&args.id))?;
+    let cookie_jar_id = request::resolve_cookie_jar_id(ctx, &workspace_id, cookie_jar_id)?;
+    let plugin_context =
+        PluginContext::new(Some("cli".to_string()), Some(workspace_id.to_string()));
+    let options = BenchOptions {
+        concurrency: args.concurrency,
+        rate: args.rate,
+        duration: Duration::from_secs_f64(args.duration),
+        max_requests: args.requests,
+    };
+
+    // Ctrl-C ends the current request's bench early; what was measured so far is still reported.
+    let (cancel_tx, cancelled_rx) = watch::channel(false);
+    let cancel_handle = tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            let _ = cancel_tx.send(true);
+        }
+    });
+
+    let mut reports = Vec::new();
+    for request_id in &request_ids {
+        let result = bench_http_request_by_id(BenchHttpRequestParams {
+            query_manager: ctx.query_manager(),
+            request_id,
+            environment_id: environment,
+            cookie_jar_id: cookie_jar_id.as_deref(),
+            plugin_manager: ctx.plugin_manager(),
+            encryption_manager: ctx.encryption_manager.clone(),
+            plugin_context: &plugin_context,
+            connection_manager: ctx.connection_manager(),
+            cancelled_rx: Some(cancelled_rx.clone()),
+            options: options.clone(),
+            variables: ctx.variables().to_vec(),
+        })
+        .await;
+        let report = match result {
+            Ok(report) => report,
+            Err(error) => {
+                cancel_handle.abort();
+                return Err(format!("Failed to bench {request_id}: {error}"));
+            }
+        };
+
+        if !args.json {
+            print_report(&report);
+        }
+        let cancelled = report.cancelled;
+        reports.push(report);
+        if cancelled {
+            break;
+        }
+    }
+    cancel_handle.abort();
+
+    if args.json {
+        let output = match reports.as_slice() {
+            [report] => serde_json::to_string_pretty(report),
+            _ => serde_json::to_string_pretty(&reports),
+        }
+        .map_err(|e| format!("Failed to serialize report: {e}"))?;
+        println!("{output}");
+    }
+
+    if reports.iter().any(|r| r.cancelled) {
+        return Err("Bench cancelled".to_string());
+    }
+    Ok(())
+}
+
+fn print_report(report: &HttpBenchReport) {
+    println!("{} {} ({})", report.method, report.url, report.request_id);
+    let rate = report.rate.map(|r| format!(" at {r}/s")).unwrap_or_default();
+    println!(
+        "  {} requests in {:.2}s with {} connections{rate}: {} completed, {} failed",
+        report.total,
+        report.elapsed as f64 / 1000.0,
+        report.concurrency,
+        report.completed,
+        report.failed,
+    );
+    println!("  Throughput: {:.2} req/s", report.throughput);
+
+    let latency = &report.latency;
+    println!(
+        "  Latency (ms): min {:.2}, mean {:.2}, p50 {:.2}, p90 {:.2}, p95 {:.2}, p99 {:.2}, max {:.2}",
+        latency.min, latency.mean, latency.p50, latency.p90, latency.p95, latency.p99, latency.max
+    );
+
+    if !report.status_codes.is_empty() {
+        println!("  Status codes:");
+        for status in &report.status_codes {
+            println!("    {}: {}", status.status, status.count);
+        }
+    }
+    if !report.errors.is_empty() {
+        println!("  Errors:");
+        for error in &report.errors {
+            println!("    {}: {}", error.count, error.error);
+        }
+    }
+}
@@ -47,11 +47,9 @@ async fn import(ctx: &CliContext, args: ImportArgs) -> CommandResult<BatchUpsert
     let file_contents = read_import_file(&args.file)?;
     let plugin_context = PluginContext::new(None, args.workspace_id.clone());
     let plugin_manager = ctx.plugin_manager();
-    let import_result = plugin_manager
-        .import_data(&plugin_context, &file_contents)
+    let resources = import::parse_resources(&plugin_manager, &plugin_context, &file_contents)
         .await
         .map_err(|e| format!("Failed to import data: {e}"))?;
-    let resources = import_result.resources;
     let workspace_id = args.workspace_id;
     if workspace_id.is_none() && resources_need_current_workspace(&resources) {
         return Err(
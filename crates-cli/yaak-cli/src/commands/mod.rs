@@ -1,12 +1,16 @@
 pub mod agent;
 pub mod auth;
+pub mod bench;
 pub mod cookie_jar;
 pub mod environment;
 pub mod folder;
 pub mod import_export;
 pub mod plugin;
+pub mod report;
 pub mod request;
 pub mod response;
+pub mod runner;
 pub mod send;
 pub mod template_function;
+pub mod websocket;
 pub mod workspace;
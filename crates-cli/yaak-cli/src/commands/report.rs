@@ -0,0 +1,249 @@
+use crate::cli::SendReporter;
+use crate::context::CliContext;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+use yaak_models::models::{HttpAssertionResult, HttpResponse};
+use yaak_models::queries::any_request::AnyRequest;
+
+type CommandResult<T = ()> = std::result::Result<T, String>;
+
+/// What happened when `yaak send` sent one request.
+pub struct SendOutcome {
+    pub request_id: String,
+    pub result: CommandResult,
+    /// The response this send stored, if it got far enough to store one (HTTP only).
+    pub response: Option<HttpResponse>,
+    pub duration: Duration,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+    name: String,
+    tests: usize,
+    failures: usize,
+    duration_ms: u128,
+    results: Vec<ReportEntry>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportEntry {
+    request_id: String,
+    name: String,
+    /// Folder names from the workspace root down to the request.
+    folder_path: Vec<String>,
+    status: Option<i32>,
+    duration_ms: u128,
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    assertion_results: Vec<HttpAssertionResult>,
+}
+
+impl ReportEntry {
+    fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// `Folder / Sub Folder / Request`, the name CI tools show for a test.
+    fn qualified_name(&self) -> String {
+        let mut parts = self.folder_path.clone();
+        parts.push(self.name.clone());
+        parts.join(" / ")
+    }
+}
+
+pub fn write(
+    ctx: &CliContext,
+    reporter: SendReporter,
+    path: &Path,
+    target_id: &str,
+    outcomes: &[SendOutcome],
+    duration: Duration,
+) -> CommandResult {
+    let results = outcomes.iter().map(|o| entry(ctx, o)).collect::<Vec<_>>();
+    let report = Report {
+        name: target_name(ctx, target_id),
+        tests: results.len(),
+        failures: results.iter().filter(|r| !r.passed()).count(),
+        duration_ms: duration.as_millis(),
+        results,
+    };
+
+    let contents = match reporter {
+        SendReporter::Junit => junit(&report),
+        SendReporter::Tap => tap(&report),
+        SendReporter::Json => serde_json::to_string_pretty(&report)
+            .map_err(|e| format!("Failed to serialize report: {e}"))?,
+    };
+
+    std::fs::write(path, contents)
+        .map_err(|e| format!("Failed to write report to {}: {e}", path.display()))
+}
+
+fn entry(ctx: &CliContext, outcome: &SendOutcome) -> ReportEntry {
+    let (name, folder_id) = match ctx.db().get_any_request(&outcome.request_id) {
+        Ok(AnyRequest::HttpRequest(r)) => {
+            (if r.name.is_empty() { r.url } else { r.name }, r.folder_id)
+        }
+        Ok(AnyRequest::GrpcRequest(r)) => {
+            (if r.name.is_empty() { r.url } else { r.name }, r.folder_id)
+        }
+        Ok(AnyRequest::WebsocketRequest(r)) => {
+            (if r.name.is_empty() { r.url } else { r.name }, r.folder_id)
+        }
+        Err(_) => (outcome.request_id.clone(), None),
+    };
+
+    ReportEntry {
+        request_id: outcome.request_id.clone(),
+        name,
+        folder_path: folder_path(ctx, folder_id),
+        status: outcome.response.as_ref().map(|r| r.status),
+        duration_ms: outcome.duration.as_millis(),
+        error: outcome.result.clone().err(),
+        assertion_results: outcome
+            .response
+            .as_ref()
+            .map(|r| r.assertion_results.clone())
+            .unwrap_or_default(),
+    }
+}
+
+fn folder_path(ctx: &CliContext, mut folder_id: Option<String>) -> Vec<String> {
+    let mut path = Vec::new();
+    while let Some(id) = folder_id {
+        let Ok(folder) = ctx.db().get_folder(&id) else {
+            break;
+        };
+        path.push(folder.name);
+        folder_id = folder.folder_id;
+    }
+    path.reverse();
+    path
+}
+
+fn target_name(ctx: &CliContext, id: &str) -> String {
+    if let Ok(workspace) = ctx.db().get_workspace(id) {
+        return workspace.name;
+    }
+    if let Ok(folder) = ctx.db().get_folder(id) {
+        return folder.name;
+    }
+    match ctx.db().get_any_request(id) {
+        Ok(AnyRequest::HttpRequest(r)) => r.name,
+        Ok(AnyRequest::GrpcRequest(r)) => r.name,
+        Ok(AnyRequest::WebsocketRequest(r)) => r.name,
+        Err(_) => id.to_string(),
+    }
+}
+
+fn failure_details(entry: &ReportEntry) -> String {
+    entry
+        .assertion_results
+        .iter()
+        .filter(|a| !a.passed)
+        .map(|a| {
+            let actual = a.error.clone().or_else(|| a.actual.clone()).unwrap_or_default();
+            format!("{} {} expected {}, got {}", kind_name(a), a.target, a.expected, actual)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn kind_name(result: &HttpAssertionResult) -> String {
+    serde_json::to_value(&result.kind)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn seconds(ms: u128) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+fn junit(report: &Report) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"yaak\" tests=\"{}\" failures=\"{}\" time=\"{}\">\n",
+        report.tests,
+        report.failures,
+        seconds(report.duration_ms),
+    ));
+    out.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{}\">\n",
+        xml_escape(&report.name),
+        report.tests,
+        report.failures,
+        seconds(report.duration_ms),
+    ));
+    for entry in &report.results {
+        let classname = if entry.folder_path.is_empty() {
+            report.name.clone()
+        } else {
+            entry.folder_path.join(" / ")
+        };
+        out.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\">\n",
+            xml_escape(&entry.name),
+            xml_escape(&classname),
+            seconds(entry.duration_ms),
+        ));
+        if let Some(error) = &entry.error {
+            out.push_str(&format!(
+                "      <failure message=\"{}\">{}</failure>\n",
+                xml_escape(error),
+                xml_escape(&failure_details(entry)),
+            ));
+        }
+        if let Some(status) = entry.status {
+            out.push_str(&format!("      <system-out>HTTP {status}</system-out>\n"));
+        }
+        out.push_str("    </testcase>\n");
+    }
+    out.push_str("  </testsuite>\n</testsuites>\n");
+    out
+}
+
+fn tap(report: &Report) -> String {
+    let mut out = format!("TAP version 13\n1..{}\n", report.tests);
+    for (i, entry) in report.results.iter().enumerate() {
+        let result = if entry.passed() { "ok" } else { "not ok" };
+        // `#` starts a TAP directive, so it cannot appear unescaped in a description.
+        let description = entry.qualified_name().replace('#', "\\#");
+        out.push_str(&format!("{result} {} - {description}\n", i + 1));
+        out.push_str("  ---\n");
+        out.push_str(&format!("  request_id: {}\n", entry.request_id));
+        if let Some(status) = entry.status {
+            out.push_str(&format!("  status: {status}\n"));
+        }
+        out.push_str(&format!("  duration_ms: {}\n", entry.duration_ms));
+        if let Some(error) = &entry.error {
+            out.push_str(&format!("  message: {}\n", yaml_string(error)));
+            let details = failure_details(entry);
+            if !details.is_empty() {
+                out.push_str("  assertions:\n");
+                for line in details.lines() {
+                    out.push_str(&format!("    - {}\n", yaml_string(line)));
+                }
+            }
+        }
+        out.push_str("  ...\n");
+    }
+    out
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// A JSON string is also a valid YAML scalar, and escapes everything that needs it.
+fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
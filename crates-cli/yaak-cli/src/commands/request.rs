@@ -1,6 +1,8 @@
-use crate::cli::{RequestArgs, RequestCommands, RequestSchemaType};
+use crate::cli::{RequestArgs, RequestCommands, RequestSchemaType, WebsocketSessionArgs};
+use crate::commands::websocket::send_websocket_request;
 use crate::context::CliContext;
 use crate::utils::confirm::confirm_delete;
+use crate::utils::grpc::{grpc_config, grpc_uri, proto_files_for_request};
 use crate::utils::json::{
     apply_merge_patch, is_json_shorthand, merge_workspace_id_arg, parse_optional_json,
     parse_required_json, require_id, validate_create_id,
@@ -9,15 +11,30 @@ use crate::utils::schema::append_agent_hints;
 use crate::utils::workspace::resolve_workspace_id;
 use schemars::schema_for;
 use serde_json::{Map, Value, json};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::Write;
 use tokio::sync::mpsc;
-use yaak::send::{SendHttpRequestByIdWithPluginsParams, send_http_request_by_id_with_plugins};
+use yaak::send::{
+    SendHttpRequestByIdWithPluginsParams, layer_variables, send_http_request_by_id_with_plugins,
+};
+use yaak_commands::grpc::{
+    build_metadata as build_grpc_metadata, compression_encoding, grpc_protocol, metadata_to_map,
+};
+use yaak_commands::resolve::resolve_grpc_request;
+use yaak_grpc::manager::{DynamicMessage, GrpcConnection, GrpcHandle, GrpcStreamError};
+use yaak_grpc::{Code, MetadataMap, Status};
 use yaak_http::sender::HttpResponseEvent as SenderHttpResponseEvent;
-use yaak_models::models::{GrpcRequest, HttpRequest, WebsocketRequest};
+use yaak_models::models::{
+    GrpcRequest, HttpAssertionResult, HttpRequest, HttpResponse, WebsocketRequest,
+};
 use yaak_models::queries::any_request::AnyRequest;
+use yaak_models::render::{make_vars_hashmap, render_grpc_request};
 use yaak_models::util::UpdateSource;
-use yaak_plugins::events::{FormInput, FormInputBase, JsonPrimitive, PluginContext};
+use yaak_plugins::events::{FormInput, FormInputBase, JsonPrimitive, PluginContext, RenderPurpose};
+use yaak_plugins::template_callback::PluginTemplateCallback;
+use yaak_templates::strip_json_comments::strip_json_comments;
+use yaak_templates::{RenderOptions, parse_and_render};
+use yaak_tls::{ClientCertificateConfig, find_client_certificate};
 
 type CommandResult<T = ()> = std::result::Result<T, String>;
 
@@ -31,9 +48,17 @@ pub async fn run(
     let result = match args.command {
         RequestCommands::List { workspace_id } => list(ctx, workspace_id.as_deref()),
         RequestCommands::Show { request_id } => show(ctx, &request_id),
-        RequestCommands::Send { request_id } => {
-            return match send_request_by_id(ctx, &request_id, environment, cookie_jar_id, verbose)
-                .await
+        RequestCommands::Send { request_id, websocket } => {
+            return match send_request_by_id(
+                ctx,
+                &request_id,
+                environment,
+                cookie_jar_id,
+                &websocket,
+                verbose,
+            )
+            .await
+            .0
             {
                 Ok(()) => 0,
                 Err(error) => {
@@ -494,16 +519,20 @@ fn delete(ctx: &CliContext, request_id: &str, yes: bool) -> CommandResult {
     Ok(())
 }
 
-/// Send a request by ID and print response in the same format as legacy `send`.
+/// Send a request by ID and print response in the same format as legacy `send`. Also returns
+/// the HTTP response the send stored, which failed assertions still leave behind.
 pub async fn send_request_by_id(
     ctx: &CliContext,
     request_id: &str,
     environment: Option<&str>,
     cookie_jar_id: Option<&str>,
+    websocket: &WebsocketSessionArgs,
     verbose: bool,
-) -> Result<(), String> {
-    let request =
-        ctx.db().get_any_request(request_id).map_err(|e| format!("Failed to get request: {e}"))?;
+) -> (CommandResult, Option<HttpResponse>) {
+    let request = match ctx.db().get_any_request(request_id) {
+        Ok(request) => request,
+        Err(e) => return (Err(format!("Failed to get request: {e}")), None),
+    };
     match request {
         AnyRequest::HttpRequest(http_request) => {
             send_http_request_by_id(
@@ -516,11 +545,20 @@ pub async fn send_request_by_id(
             )
             .await
         }
-        AnyRequest::GrpcRequest(_) => {
-            Err("gRPC request send is not implemented yet in yaak-cli".to_string())
+        AnyRequest::GrpcRequest(grpc_request) => {
+            (send_grpc_request(ctx, &grpc_request, environment, verbose).await, None)
         }
-        AnyRequest::WebsocketRequest(_) => {
-            Err("WebSocket request send is not implemented yet in yaak-cli".to_string())
+        AnyRequest::WebsocketRequest(websocket_request) => {
+            let result = send_websocket_request(
+                ctx,
+                &websocket_request,
+                environment,
+                cookie_jar_id,
+                websocket,
+                verbose,
+            )
+            .await;
+            (result, None)
         }
     }
 }
@@ -532,8 +570,11 @@ async fn send_http_request_by_id(
     environment: Option<&str>,
     cookie_jar_id: Option<&str>,
     verbose: bool,
-) -> Result<(), String> {
-    let cookie_jar_id = resolve_cookie_jar_id(ctx, workspace_id, cookie_jar_id)?;
+) -> (CommandResult, Option<HttpResponse>) {
+    let cookie_jar_id = match resolve_cookie_jar_id(ctx, workspace_id, cookie_jar_id) {
+        Ok(cookie_jar_id) => cookie_jar_id,
+        Err(error) => return (Err(error), None),
+    };
 
     let plugin_context =
         PluginContext::new(Some("cli".to_string()), Some(workspace_id.to_string()));
@@ -583,6 +624,7 @@ async fn send_http_request_by_id(
         plugin_context: &plugin_context,
         cancelled_rx: None,
         connection_manager: ctx.connection_manager(),
+        variables: ctx.variables().to_vec(),
     })
     .await;
 
@@ -596,10 +638,242 @@ async fn send_http_request_by_id(
         let _ = stdout.flush();
     }
 
-    result.map_err(|e| e.to_string())?;
+    match result {
+        Ok(result) => {
+            // Work after the response arrived, such as writing extracted values, can still fail
+            if let Some(error) = &result.response.error {
+                eprintln!("Warning: {error}");
+            }
+            (report_assertions(&result.response.assertion_results), Some(result.response))
+        }
+        Err(error) => (Err(error.to_string()), None),
+    }
+}
+
+/// Call a unary or server-streaming gRPC method. Each response message is printed as a JSON
+/// line, followed by a line with the final status and trailers; any status but OK fails.
+async fn send_grpc_request(
+    ctx: &CliContext,
+    unrendered_request: &GrpcRequest,
+    environment: Option<&str>,
+    verbose: bool,
+) -> CommandResult {
+    let (resolved_request, auth_context_id) =
+        resolve_grpc_request(&ctx.db(), unrendered_request)
+            .map_err(|e| format!("Failed to resolve request: {e}"))?;
+    let (Some(service), Some(method)) =
+        (resolved_request.service.clone(), resolved_request.method.clone())
+    else {
+        return Err("gRPC request has no service and method selected".to_string());
+    };
+
+    let mut environment_chain = ctx
+        .db()
+        .resolve_environments(
+            &unrendered_request.workspace_id,
+            unrendered_request.folder_id.as_deref(),
+            environment,
+        )
+        .map_err(|e| format!("Failed to resolve environments: {e}"))?;
+    layer_variables(&mut environment_chain, &unrendered_request.workspace_id, ctx.variables());
+    let settings = ctx
+        .db()
+        .resolve_settings_for_grpc_request(unrendered_request)
+        .map_err(|e| format!("Failed to resolve settings: {e}"))?;
+    let client_certificates = ctx.db().get_settings().client_certificates;
+    let proto_files = proto_files_for_request(&ctx.db(), &unrendered_request.id);
+
+    let plugin_context =
+        PluginContext::new(Some("cli".to_string()), Some(unrendered_request.workspace_id.clone()));
+    let plugin_manager = ctx.plugin_manager();
+    let template_callback = PluginTemplateCallback::new(
+        plugin_manager.clone(),
+        ctx.encryption_manager.clone(),
+        &plugin_context,
+        RenderPurpose::Send,
+    );
+    let render_options = RenderOptions::throw();
+    let message = match resolved_request.message.as_str() {
+        "" => "{}",
+        message => message,
+    };
+    let message = parse_and_render(
+        message,
+        &make_vars_hashmap(environment_chain.clone()),
+        &template_callback,
+        &render_options,
+    )
+    .await
+    .map_err(|e| format!("Failed to render message: {e}"))?;
+    let message = strip_json_comments(&message);
+    let request = render_grpc_request(
+        &resolved_request,
+        environment_chain,
+        &template_callback,
+        &render_options,
+    )
+    .await
+    .map_err(|e| format!("Failed to render request: {e}"))?;
+
+    let metadata =
+        build_grpc_metadata(&request, &auth_context_id, &plugin_manager, &plugin_context)
+            .await
+            .map_err(|e| e.to_string())?;
+    let uri = grpc_uri(&request.url);
+    let client_cert = find_client_certificate(&request.url, &client_certificates);
+    let protocol = grpc_protocol(request.protocol);
+
+    if verbose {
+        eprintln!("* Connecting to {uri} with {protocol}");
+        for (name, value) in &metadata {
+            eprintln!("> {name}: {value}");
+        }
+    }
+
+    let mut handle = GrpcHandle::new(grpc_config(ctx.data_dir()));
+    let connection = handle
+        .connect(
+            &request.id,
+            &uri,
+            &proto_files,
+            &metadata,
+            settings.validate_certificates.value,
+            client_cert.clone(),
+            settings.request_message_size.value,
+            protocol,
+        )
+        .await
+        .map_err(|e| format!("Failed to connect: {e}"))?
+        .with_timeout(settings.request_timeout.value)
+        .with_compression(compression_encoding(request.compression));
+    let method_desc = connection.method(&service, &method).await.map_err(|e| e.to_string())?;
+    if method_desc.is_client_streaming() {
+        return Err(format!(
+            "{service}/{method} is client-streaming; yaak-cli only sends unary and server-streaming methods"
+        ));
+    }
+
+    let (code, error, trailers) = if method_desc.is_server_streaming() {
+        match connection.server_streaming(&service, &method, &message, &metadata).await {
+            Ok(response) => {
+                print_grpc_headers(verbose, response.metadata());
+                let mut stream = response.into_inner();
+                loop {
+                    match stream.message().await {
+                        Ok(Some(message)) => {
+                            print_grpc_message(&connection, &message, &metadata, &client_cert)
+                                .await?
+                        }
+                        Ok(None) => {
+                            let trailers = match stream.trailers().await {
+                                Ok(trailers) => trailers.unwrap_or_default(),
+                                Err(status) => break grpc_status_parts(&status),
+                            };
+                            break (Code::Ok, String::new(), json!(metadata_to_map(&trailers)));
+                        }
+                        Err(status) => break grpc_status_parts(&status),
+                    }
+                }
+            }
+            Err(e) => grpc_error_parts(e)?,
+        }
+    } else {
+        match connection.unary(&service, &method, &message, &metadata, client_cert.clone()).await {
+            Ok(response) => {
+                print_grpc_headers(verbose, &response.metadata);
+                print_grpc_message(&connection, &response.message, &metadata, &client_cert).await?;
+                (Code::Ok, String::new(), json!(metadata_to_map(&response.trailers)))
+            }
+            Err(e) => grpc_error_parts(e)?,
+        }
+    };
+
+    println!(
+        "{}",
+        json!({
+            "type": "status",
+            "code": code as i32,
+            "status": format!("{code:?}"),
+            "error": (!error.is_empty()).then_some(&error),
+            "trailers": trailers,
+        })
+    );
+
+    if code != Code::Ok {
+        return Err(format!("gRPC call failed with status {code:?}: {error}"));
+    }
+    Ok(())
+}
+
+async fn print_grpc_message(
+    connection: &GrpcConnection,
+    message: &DynamicMessage,
+    metadata: &BTreeMap<String, String>,
+    client_cert: &Option<ClientCertificateConfig>,
+) -> CommandResult {
+    let json = connection
+        .serialize_message(message, metadata, client_cert.clone())
+        .await
+        .map_err(|e| format!("Failed to read response: {e}"))?;
+    let json: Value =
+        serde_json::from_str(&json).map_err(|e| format!("Failed to read response: {e}"))?;
+    println!("{}", json!({ "type": "message", "message": json }));
     Ok(())
 }
 
+fn print_grpc_headers(verbose: bool, metadata: &MetadataMap) {
+    if !verbose {
+        return;
+    }
+    for (name, value) in metadata_to_map(metadata) {
+        eprintln!("< {name}: {value}");
+    }
+}
+
+fn grpc_status_parts(status: &Status) -> (Code, String, Value) {
+    (status.code(), status.message().to_string(), json!(metadata_to_map(status.metadata())))
+}
+
+/// The status a failed call ended with, or an error when it failed before getting one.
+fn grpc_error_parts(error: yaak_grpc::error::Error) -> Result<(Code, String, Value), String> {
+    match error {
+        yaak_grpc::error::Error::TonicError(status) => Ok(grpc_status_parts(&status)),
+        yaak_grpc::error::Error::GrpcStreamError(GrpcStreamError {
+            status: Some(status), ..
+        }) => Ok(grpc_status_parts(&status)),
+        e => Err(format!("Failed to send gRPC request: {e}")),
+    }
+}
+
+/// Print assertion results to stderr, keeping stdout for the body, and fail when any did not pass.
+fn report_assertions(results: &[HttpAssertionResult]) -> CommandResult {
+    if results.is_empty() {
+        return Ok(());
+    }
+
+    for result in results {
+        let label = if result.passed { "PASS" } else { "FAIL" };
+        let kind = serde_json::to_value(&result.kind)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let target =
+            if result.target.is_empty() { String::new() } else { format!(" {}", result.target) };
+        let detail = match (&result.error, &result.actual) {
+            (Some(error), _) => format!(" ({error})"),
+            (None, Some(actual)) if !result.passed => format!(" (got {actual})"),
+            _ => String::new(),
+        };
+        eprintln!("{label} {kind}{target} {}{detail}", result.expected);
+    }
+
+    let failed = results.iter().filter(|r| !r.passed).count();
+    if failed == 0 {
+        return Ok(());
+    }
+    Err(format!("{failed} of {} assertions failed", results.len()))
+}
+
 pub(crate) fn resolve_cookie_jar_id(
     ctx: &CliContext,
     workspace_id: &str,
@@ -2,8 +2,9 @@ use crate::cli::{ResponseArgs, ResponseCommands};
 use crate::context::CliContext;
 use crate::utils::confirm::confirm_delete;
 use crate::utils::workspace::resolve_workspace_id;
+use serde_json::{Value, json};
 use std::io::Write;
-use yaak_models::models::HttpResponse;
+use yaak_models::models::{HttpResponse, HttpResponseEventData};
 use yaak_models::util::UpdateSource;
 
 type CommandResult<T = ()> = std::result::Result<T, String>;
@@ -12,6 +13,10 @@ pub fn run(ctx: &CliContext, args: ResponseArgs) -> i32 {
     let result = match args.command {
         ResponseCommands::List { id, limit } => list(ctx, id.as_deref(), limit),
         ResponseCommands::Show { id } => show(ctx, &id),
+        ResponseCommands::Assertions { id } => assertions(ctx, &id),
+        ResponseCommands::Events { id, event_type, json } => {
+            events(ctx, &id, event_type.as_deref(), json)
+        }
         ResponseCommands::Body { id } => body(ctx, &id),
         ResponseCommands::Delete { id, yes } => delete(ctx, &id, yes),
     };
@@ -88,12 +93,99 @@ fn list(ctx: &CliContext, id: Option<&str>, limit: Option<u64>) -> CommandResult
 
 fn show(ctx: &CliContext, id: &str) -> CommandResult {
     let response = resolve_response(ctx, id)?;
-    let output = serde_json::to_string_pretty(&response)
+    let mut value = serde_json::to_value(&response)
+        .map_err(|e| format!("Failed to serialize response: {e}"))?;
+    value["tls"] = tls(ctx, &response.id)?;
+    let output = serde_json::to_string_pretty(&value)
         .map_err(|e| format!("Failed to serialize response: {e}"))?;
     println!("{output}");
     Ok(())
 }
 
+/// What the timeline recorded about the TLS handshake, or null for plain HTTP.
+fn tls(ctx: &CliContext, response_id: &str) -> CommandResult<Value> {
+    let events = ctx
+        .db()
+        .list_http_response_events(response_id)
+        .map_err(|e| format!("Failed to list response events: {e}"))?;
+
+    let mut tls = json!({
+        "protocolVersion": null,
+        "cipherSuite": null,
+        "alpn": null,
+        "resumed": false,
+        "serverCertificates": [],
+        "clientCertificate": null,
+    });
+    let mut seen = false;
+    for event in events {
+        match event.event {
+            HttpResponseEventData::TlsNegotiated {
+                protocol_version,
+                cipher_suite,
+                alpn,
+                resumed,
+            } => {
+                tls["protocolVersion"] = json!(protocol_version);
+                tls["cipherSuite"] = json!(cipher_suite);
+                tls["alpn"] = json!(alpn);
+                tls["resumed"] = json!(resumed);
+            }
+            HttpResponseEventData::TlsServerCertificates { certificates } => {
+                tls["serverCertificates"] = json!(certificates);
+            }
+            HttpResponseEventData::TlsClientCertificate { requested, presented } => {
+                tls["clientCertificate"] =
+                    json!({ "requested": requested, "presented": presented });
+            }
+            _ => continue,
+        }
+        seen = true;
+    }
+
+    Ok(if seen { tls } else { Value::Null })
+}
+
+fn assertions(ctx: &CliContext, id: &str) -> CommandResult {
+    let response = resolve_response(ctx, id)?;
+    let output = serde_json::to_string_pretty(&response.assertion_results)
+        .map_err(|e| format!("Failed to serialize assertion results: {e}"))?;
+    println!("{output}");
+    Ok(())
+}
+
+fn events(ctx: &CliContext, id: &str, event_type: Option<&str>, json: bool) -> CommandResult {
+    let response = resolve_response(ctx, id)?;
+    let events = ctx
+        .db()
+        .list_http_response_sse_events(&response.id)
+        .map_err(|e| format!("Failed to list events: {e}"))?
+        .into_iter()
+        .filter(|e| event_type.is_none_or(|t| e.event_type == t))
+        .collect::<Vec<_>>();
+
+    if json {
+        let output = serde_json::to_string_pretty(&events)
+            .map_err(|e| format!("Failed to serialize events: {e}"))?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    if events.is_empty() {
+        println!("No events found");
+        return Ok(());
+    }
+
+    for event in events {
+        let id = event.event_id.map(|id| format!(" id={id}")).unwrap_or_default();
+        let time = event.created_at.format("%H:%M:%S%.3f");
+        // Multi-line data stays readable and one event never looks like two.
+        let data = event.data.replace('\n', "\n    ");
+        println!("{time} [{}] {}{id}: {data}", event.index, event.event_type);
+    }
+    Ok(())
+}
+
 fn body(ctx: &CliContext, id: &str) -> CommandResult {
     let response = resolve_response(ctx, id)?;
     let Some(body_path) = response.body_path else {
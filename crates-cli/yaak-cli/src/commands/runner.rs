@@ -0,0 +1,271 @@
+use crate::cli::{RunnerArgs, RunnerCommands};
+use crate::commands::request;
+use crate::context::CliContext;
+use crate::utils::workspace::resolve_workspace_id;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::watch;
+use yaak::runner::{RunCollectionParams, run_collection};
+use yaak_models::models::{RunnerRunState, RunnerStep};
+use yaak_models::util::UpdateSource;
+use yaak_plugins::events::PluginContext;
+
+type CommandResult<T = ()> = std::result::Result<T, String>;
+
+pub async fn run(
+    ctx: &CliContext,
+    args: RunnerArgs,
+    environment: Option<&str>,
+    cookie_jar_id: Option<&str>,
+) -> i32 {
+    let result = match args.command {
+        RunnerCommands::Run { ids, data, delay, retries, step_delays, step_retries, name } => {
+            match StepOptions::parse(delay, retries, &step_delays, &step_retries) {
+                Ok(options) => {
+                    run_requests(ctx, &ids, data, options, name, environment, cookie_jar_id).await
+                }
+                Err(error) => Err(error),
+            }
+        }
+        RunnerCommands::List { workspace_id, limit } => list(ctx, workspace_id.as_deref(), limit),
+        RunnerCommands::Show { run_id } => show(ctx, &run_id),
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            1
+        }
+    }
+}
+
+/// Expand the given request and folder IDs into HTTP request IDs, in order, and return them
+/// with the workspace they all belong to.
+pub(crate) fn resolve_request_ids(
+    ctx: &CliContext,
+    ids: &[String],
+) -> CommandResult<(String, Vec<String>)> {
+    let mut workspace_id: Option<String> = None;
+    let mut request_ids = Vec::new();
+
+    for id in ids {
+        let (id_workspace_id, mut id_request_ids) = if let Ok(r) = ctx.db().get_http_request(id) {
+            (r.workspace_id, vec![r.id])
+        } else if let Ok(folder) = ctx.db().get_folder(id) {
+            let requests = ctx
+                .db()
+                .list_http_requests_for_folder_recursive(id)
+                .map_err(|e| format!("Failed to list HTTP requests in folder: {e}"))?;
+            (folder.workspace_id, requests.into_iter().map(|r| r.id).collect())
+        } else {
+            return Err(format!("Could not resolve ID '{id}' as an HTTP request or folder"));
+        };
+
+        match &workspace_id {
+            Some(w) if *w != id_workspace_id => {
+                return Err("All requests in a run must belong to the same workspace".to_string());
+            }
+            Some(_) => {}
+            None => workspace_id = Some(id_workspace_id),
+        }
+        request_ids.append(&mut id_request_ids);
+    }
+
+    let workspace_id = workspace_id.ok_or("No request IDs given")?;
+    if request_ids.is_empty() {
+        return Err("No HTTP requests found to run".to_string());
+    }
+    Ok((workspace_id, request_ids))
+}
+
+/// The delay and retries for each step: the run-wide values, unless one was given for that
+/// request.
+struct StepOptions {
+    delay: i32,
+    retries: i32,
+    delays: HashMap<String, i32>,
+    retries_by_request: HashMap<String, i32>,
+}
+
+impl StepOptions {
+    fn parse(
+        delay: i32,
+        retries: i32,
+        step_delays: &[String],
+        step_retries: &[String],
+    ) -> CommandResult<Self> {
+        Ok(Self {
+            delay,
+            retries,
+            delays: parse_overrides("--step-delay", step_delays)?,
+            retries_by_request: parse_overrides("--step-retries", step_retries)?,
+        })
+    }
+
+    fn step(&self, request_id: String) -> RunnerStep {
+        RunnerStep {
+            delay_ms: self.delays.get(&request_id).copied().unwrap_or(self.delay),
+            retries: self.retries_by_request.get(&request_id).copied().unwrap_or(self.retries),
+            request_id,
+        }
+    }
+}
+
+/// Parse `REQUEST_ID=VALUE` pairs.
+fn parse_overrides(flag: &str, values: &[String]) -> CommandResult<HashMap<String, i32>> {
+    values
+        .iter()
+        .map(|value| {
+            let (request_id, number) = value
+                .split_once('=')
+                .ok_or_else(|| format!("{flag} expects REQUEST_ID=VALUE, got '{value}'"))?;
+            let number = number
+                .trim()
+                .parse::<i32>()
+                .map_err(|_| format!("{flag} value for {request_id} is not a number"))?;
+            Ok((request_id.trim().to_string(), number))
+        })
+        .collect()
+}
+
+async fn run_requests(
+    ctx: &CliContext,
+    ids: &[String],
+    data: Option<PathBuf>,
+    options: StepOptions,
+    name: Option<String>,
+    environment: Option<&str>,
+    cookie_jar_id: Option<&str>,
+) -> CommandResult {
+    let (workspace_id, request_ids) = resolve_request_ids(ctx, ids)?;
+    if let Some(unknown) = options
+        .delays
+        .keys()
+        .chain(options.retries_by_request.keys())
+        .find(|id| !request_ids.contains(id))
+    {
+        return Err(format!("Request {unknown} is not part of this run"));
+    }
+    let cookie_jar_id = request::resolve_cookie_jar_id(ctx, &workspace_id, cookie_jar_id)?;
+    let plugin_context =
+        PluginContext::new(Some("cli".to_string()), Some(workspace_id.to_string()));
+    let response_dir = ctx.data_dir().join("responses");
+
+    // Ctrl-C cancels between steps, so the iterations that did finish are still stored.
+    let (cancel_tx, cancelled_rx) = watch::channel(false);
+    let cancel_handle = tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            let _ = cancel_tx.send(true);
+        }
+    });
+
+    let steps = request_ids.into_iter().map(|request_id| options.step(request_id)).collect();
+    let name = name.unwrap_or_else(|| default_run_name(ctx, ids));
+
+    let result = run_collection(RunCollectionParams {
+        query_manager: ctx.query_manager(),
+        blob_manager: ctx.blob_manager(),
+        workspace_id: &workspace_id,
+        run_id: None,
+        name,
+        steps,
+        data_file: data.as_deref(),
+        environment_id: environment,
+        update_source: UpdateSource::Sync,
+        cookie_jar_id,
+        response_dir: &response_dir,
+        plugin_manager: ctx.plugin_manager(),
+        encryption_manager: ctx.encryption_manager.clone(),
+        plugin_context: &plugin_context,
+        cancelled_rx: Some(cancelled_rx),
+        connection_manager: ctx.connection_manager(),
+        variables: ctx.variables().to_vec(),
+    })
+    .await;
+    cancel_handle.abort();
+    let run = result.map_err(|e| e.to_string())?;
+
+    let iterations = ctx
+        .db()
+        .list_runner_iterations(&run.id)
+        .map_err(|e| format!("Failed to list iterations: {e}"))?;
+    for iteration in &iterations {
+        let label = if iteration.passed { "PASS" } else { "FAIL" };
+        println!(
+            "{label} iteration {}/{} ({}ms)",
+            iteration.index + 1,
+            run.iteration_count,
+            iteration.elapsed
+        );
+        for result in iteration.results.iter().filter(|r| !r.passed) {
+            let error = result.error.clone().unwrap_or_default();
+            eprintln!("  {}: {error}", result.request_id);
+        }
+    }
+
+    println!(
+        "Run {}: {} passed, {} failed ({}ms)",
+        run.id, run.passed_count, run.failed_count, run.elapsed
+    );
+
+    if run.state == RunnerRunState::Cancelled {
+        return Err("Run cancelled".to_string());
+    }
+    if run.failed_count > 0 {
+        return Err("One or more iterations failed".to_string());
+    }
+    Ok(())
+}
+
+fn default_run_name(ctx: &CliContext, ids: &[String]) -> String {
+    match ids {
+        [id] => ctx
+            .db()
+            .get_folder(id)
+            .map(|f| f.name)
+            .or_else(|_| ctx.db().get_http_request(id).map(|r| r.name))
+            .ok()
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| id.clone()),
+        _ => format!("{} requests", ids.len()),
+    }
+}
+
+fn list(ctx: &CliContext, workspace_id: Option<&str>, limit: Option<u64>) -> CommandResult {
+    let workspace_id = resolve_workspace_id(ctx, workspace_id, "runner list")?;
+    let runs = ctx
+        .db()
+        .list_runner_runs(&workspace_id, limit)
+        .map_err(|e| format!("Failed to list runs: {e}"))?;
+
+    if runs.is_empty() {
+        println!("No runs found");
+        return Ok(());
+    }
+
+    for run in runs {
+        let state = serde_json::to_value(&run.state)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        println!(
+            "{} - {} [{state}] {} passed, {} failed ({}ms)",
+            run.id, run.name, run.passed_count, run.failed_count, run.elapsed
+        );
+    }
+    Ok(())
+}
+
+fn show(ctx: &CliContext, run_id: &str) -> CommandResult {
+    let run = ctx.db().get_runner_run(run_id).map_err(|e| format!("Failed to get run: {e}"))?;
+    let iterations = ctx
+        .db()
+        .list_runner_iterations(run_id)
+        .map_err(|e| format!("Failed to list iterations: {e}"))?;
+    let output = serde_json::to_string_pretty(&json!({ "run": run, "iterations": iterations }))
+        .map_err(|e| format!("Failed to serialize run: {e}"))?;
+    println!("{output}");
+    Ok(())
+}
@@ -1,7 +1,9 @@
-use crate::cli::SendArgs;
+use crate::cli::{SendArgs, WebsocketSessionArgs};
+use crate::commands::report::{self, SendOutcome};
 use crate::commands::request;
 use crate::context::CliContext;
 use futures::future::join_all;
+use std::time::Instant;
 use yaak_models::queries::any_request::AnyRequest;
 
 enum ExecutionMode {
@@ -33,6 +35,8 @@ async fn send_target(
     verbose: bool,
 ) -> Result<(), String> {
     let mode = if args.parallel { ExecutionMode::Parallel } else { ExecutionMode::Sequential };
+    // Reports time the whole run, which parallel sends finish sooner than their sum
+    let started = Instant::now();
 
     if let Ok(request) = ctx.db().get_any_request(&args.id) {
         let workspace_id = match &request {
@@ -43,14 +47,17 @@ async fn send_target(
         let resolved_cookie_jar_id =
             request::resolve_cookie_jar_id(ctx, &workspace_id, cookie_jar_id)?;
 
-        return request::send_request_by_id(
+        let outcome = send_one(
             ctx,
             &args.id,
             environment,
             resolved_cookie_jar_id.as_deref(),
+            &args.websocket,
             verbose,
         )
         .await;
+        write_report(ctx, &args, std::slice::from_ref(&outcome), started)?;
+        return outcome.result;
     }
 
     if let Ok(folder) = ctx.db().get_folder(&args.id) {
@@ -62,16 +69,19 @@ async fn send_target(
             println!("No requests found in folder {}", args.id);
             return Ok(());
         }
-        return send_many(
+        let outcomes = send_many(
             ctx,
             request_ids,
             mode,
             args.fail_fast,
             environment,
             resolved_cookie_jar_id.as_deref(),
+            &args.websocket,
             verbose,
         )
         .await;
+        write_report(ctx, &args, &outcomes, started)?;
+        return summarize(outcomes);
     }
 
     if let Ok(workspace) = ctx.db().get_workspace(&args.id) {
@@ -83,16 +93,19 @@ async fn send_target(
             println!("No requests found in workspace {}", args.id);
             return Ok(());
         }
-        return send_many(
+        let outcomes = send_many(
             ctx,
             request_ids,
             mode,
             args.fail_fast,
             environment,
             resolved_cookie_jar_id.as_deref(),
+            &args.websocket,
             verbose,
         )
         .await;
+        write_report(ctx, &args, &outcomes, started)?;
+        return summarize(outcomes);
     }
 
     Err(format!("Could not resolve ID '{}' as request, folder, or workspace", args.id))
@@ -174,61 +187,44 @@ async fn send_many(
     fail_fast: bool,
     environment: Option<&str>,
     cookie_jar_id: Option<&str>,
+    websocket: &WebsocketSessionArgs,
     verbose: bool,
-) -> Result<(), String> {
-    let mut success_count = 0usize;
-    let mut failures: Vec<(String, String)> = Vec::new();
-
+) -> Vec<SendOutcome> {
     match mode {
         ExecutionMode::Sequential => {
+            let mut outcomes = Vec::new();
             for request_id in request_ids {
-                match request::send_request_by_id(
-                    ctx,
-                    &request_id,
-                    environment,
-                    cookie_jar_id,
-                    verbose,
-                )
-                .await
-                {
-                    Ok(()) => success_count += 1,
-                    Err(error) => {
-                        failures.push((request_id, error));
-                        if fail_fast {
-                            break;
-                        }
-                    }
+                let outcome =
+                    send_one(ctx, &request_id, environment, cookie_jar_id, websocket, verbose)
+                        .await;
+                let failed = outcome.result.is_err();
+                outcomes.push(outcome);
+                if failed && fail_fast {
+                    break;
                 }
             }
+            outcomes
         }
         ExecutionMode::Parallel => {
             let tasks = request_ids
                 .iter()
-                .map(|request_id| async move {
-                    (
-                        request_id.clone(),
-                        request::send_request_by_id(
-                            ctx,
-                            request_id,
-                            environment,
-                            cookie_jar_id,
-                            verbose,
-                        )
-                        .await,
-                    )
+                .map(|request_id| {
+                    send_one(ctx, request_id, environment, cookie_jar_id, websocket, verbose)
                 })
                 .collect::<Vec<_>>();
-
-            for (request_id, result) in join_all(tasks).await {
-                match result {
-                    Ok(()) => success_count += 1,
-                    Err(error) => failures.push((request_id, error)),
-                }
-            }
+            join_all(tasks).await
         }
     }
+}
 
+fn summarize(outcomes: Vec<SendOutcome>) -> Result<(), String> {
+    let total = outcomes.len();
+    let failures = outcomes
+        .into_iter()
+        .filter_map(|o| o.result.err().map(|error| (o.request_id, error)))
+        .collect::<Vec<_>>();
     let failure_count = failures.len();
+    let success_count = total - failure_count;
     println!("Send summary: {success_count} succeeded, {failure_count} failed");
 
     if failure_count == 0 {
@@ -240,3 +236,43 @@ async fn send_many(
     }
     Err("One or more requests failed".to_string())
 }
+
+/// Send one request, keeping what a report needs: how long it took and the response it stored.
+async fn send_one(
+    ctx: &CliContext,
+    request_id: &str,
+    environment: Option<&str>,
+    cookie_jar_id: Option<&str>,
+    websocket: &WebsocketSessionArgs,
+    verbose: bool,
+) -> SendOutcome {
+    let started = Instant::now();
+    let (result, response) = request::send_request_by_id(
+        ctx,
+        request_id,
+        environment,
+        cookie_jar_id,
+        websocket,
+        verbose,
+    )
+    .await;
+
+    SendOutcome {
+        request_id: request_id.to_string(),
+        result,
+        response,
+        duration: started.elapsed(),
+    }
+}
+
+fn write_report(
+    ctx: &CliContext,
+    args: &SendArgs,
+    outcomes: &[SendOutcome],
+    started: Instant,
+) -> Result<(), String> {
+    let (Some(reporter), Some(path)) = (args.reporter, &args.report_file) else {
+        return Ok(());
+    };
+    report::write(ctx, reporter, path, &args.id, outcomes, started.elapsed())
+}
@@ -0,0 +1,442 @@
+use crate::cli::WebsocketSessionArgs;
+use crate::context::CliContext;
+use std::io::BufRead;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use url::Url;
+use yaak::send::layer_variables;
+use yaak_commands::resolve::resolve_websocket_request;
+use yaak_http::cookies::CookieStore;
+use yaak_models::models::{
+    HttpResponseHeader, WebsocketConnection, WebsocketConnectionState, WebsocketEvent,
+    WebsocketEventType, WebsocketRequest,
+};
+use yaak_models::path_placeholders::apply_path_placeholders;
+use yaak_models::util::UpdateSource;
+use yaak_plugins::events::{
+    CallHttpAuthenticationRequest, HttpHeader, PluginContext, RenderPurpose,
+};
+use yaak_plugins::template_callback::PluginTemplateCallback;
+use yaak_templates::RenderOptions;
+use yaak_templates::strip_json_comments::maybe_strip_json_comments;
+use yaak_tls::find_client_certificate;
+use yaak_ws::render_websocket_request;
+use yaak_ws::{HeaderMap, HeaderName, HeaderValue, Message, WebsocketManager};
+
+/// Open a WebSocket connection, send the request's message (or each line of stdin), and print
+/// received messages until the session ends. Everything is recorded as the app records it.
+pub async fn send_websocket_request(
+    ctx: &CliContext,
+    unrendered_request: &WebsocketRequest,
+    environment: Option<&str>,
+    cookie_jar_id: Option<&str>,
+    session: &WebsocketSessionArgs,
+    verbose: bool,
+) -> Result<(), String> {
+    let mut environment_chain = ctx
+        .db()
+        .resolve_environments(
+            &unrendered_request.workspace_id,
+            unrendered_request.folder_id.as_deref(),
+            environment,
+        )
+        .map_err(|e| format!("Failed to resolve environments: {e}"))?;
+    layer_variables(&mut environment_chain, &unrendered_request.workspace_id, ctx.variables());
+    let resolved_settings = ctx
+        .db()
+        .resolve_settings_for_websocket_request(unrendered_request)
+        .map_err(|e| format!("Failed to resolve settings: {e}"))?;
+    let (resolved_request, auth_context_id) =
+        resolve_websocket_request(&ctx.db(), unrendered_request)
+            .map_err(|e| format!("Failed to resolve request: {e}"))?;
+
+    let plugin_context =
+        PluginContext::new(Some("cli".to_string()), Some(unrendered_request.workspace_id.clone()));
+    let plugin_manager = ctx.plugin_manager();
+    let request = render_websocket_request(
+        &resolved_request,
+        environment_chain,
+        &PluginTemplateCallback::new(
+            plugin_manager.clone(),
+            ctx.encryption_manager.clone(),
+            &plugin_context,
+            RenderPurpose::Send,
+        ),
+        &RenderOptions::throw(),
+    )
+    .await
+    .map_err(|e| format!("Failed to render request: {e}"))?;
+
+    let connection = ctx
+        .db()
+        .upsert_websocket_connection(
+            &WebsocketConnection {
+                workspace_id: request.workspace_id.clone(),
+                request_id: request.id.clone(),
+                url: request.url.clone(),
+                ..Default::default()
+            },
+            &UpdateSource::Sync,
+        )
+        .map_err(|e| format!("Failed to save connection: {e}"))?;
+    let started = Instant::now();
+    // Anything that fails from here on leaves the connection recorded as closed with the error
+    let fail = |error: String| {
+        close_connection(ctx, &connection, started, Some(error.clone()));
+        error
+    };
+
+    let (mut url, url_parameters) = apply_path_placeholders(&request.url, &request.url_parameters);
+    if !url.starts_with("ws://") && !url.starts_with("wss://") {
+        url.insert_str(0, "ws://");
+    }
+    let mut url = Url::parse(&url).map_err(|e| fail(format!("Failed to parse URL {url}: {e}")))?;
+
+    let mut headers = HeaderMap::new();
+    for h in &request.headers {
+        if !h.enabled || (h.name.is_empty() && h.value.is_empty()) {
+            continue;
+        }
+        match (HeaderName::from_str(&h.name), HeaderValue::from_str(&h.value)) {
+            (Ok(name), Ok(value)) => {
+                headers.insert(name, value);
+            }
+            _ => return Err(fail(format!("Invalid header {}", h.name))),
+        }
+    }
+
+    match &request.authentication_type {
+        None => {}
+        Some(authentication_type) if authentication_type == "none" => {}
+        Some(authentication_type) => {
+            let plugin_req = CallHttpAuthenticationRequest {
+                context_id: format!("{:x}", md5::compute(&auth_context_id)),
+                values: serde_json::from_value(
+                    serde_json::to_value(&request.authentication)
+                        .map_err(|e| fail(format!("Failed to serialize auth values: {e}")))?,
+                )
+                .map_err(|e| fail(format!("Failed to parse auth values: {e}")))?,
+                method: "GET".to_string(),
+                url: request.url.clone(),
+                headers: headers
+                    .iter()
+                    .map(|(name, value)| HttpHeader {
+                        name: name.to_string(),
+                        value: value.to_str().unwrap_or_default().to_string(),
+                    })
+                    .collect(),
+                body: None,
+            };
+            let plugin_result = plugin_manager
+                .call_http_authentication(&plugin_context, authentication_type, plugin_req)
+                .await
+                .map_err(|e| fail(format!("Failed to apply authentication plugin: {e}")))?;
+            for header in plugin_result.set_headers.unwrap_or_default() {
+                if let (Ok(name), Ok(value)) =
+                    (HeaderName::from_str(&header.name), HeaderValue::from_str(&header.value))
+                {
+                    headers.insert(name, value);
+                }
+            }
+            if let Some(params) = plugin_result.set_query_parameters {
+                let mut query_pairs = url.query_pairs_mut();
+                for p in params {
+                    query_pairs.append_pair(&p.name, &p.value);
+                }
+            }
+        }
+    }
+
+    let url_parameters =
+        url_parameters.into_iter().filter(|p| p.enabled && !p.name.is_empty()).collect::<Vec<_>>();
+    // Only touch the query when there are parameters, or the URL gets an empty `?`
+    if !url_parameters.is_empty() {
+        let mut query_pairs = url.query_pairs_mut();
+        for p in url_parameters {
+            query_pairs.append_pair(&p.name, &p.value);
+        }
+    }
+
+    let send_cookies = resolved_settings.send_cookies.value;
+    let store_cookies = resolved_settings.store_cookies.value;
+    let mut cookie_jar = match (send_cookies || store_cookies, cookie_jar_id) {
+        (true, Some(id)) => Some(
+            ctx.db()
+                .get_cookie_jar(id)
+                .map_err(|e| fail(format!("Failed to get cookie jar: {e}")))?,
+        ),
+        _ => None,
+    };
+    let cookie_store =
+        cookie_jar.as_ref().map(|jar| CookieStore::from_cookies(jar.cookies.clone()));
+    if let (true, Some(store)) = (send_cookies, &cookie_store)
+        && let Some(cookie_header) = store.get_cookie_header(&ws_url_to_http(&url))
+        && let Ok(value) = HeaderValue::from_str(&cookie_header)
+    {
+        headers.insert(HeaderName::from_static("cookie"), value);
+    }
+
+    if verbose {
+        eprintln!("* Connecting to {url}");
+        for (name, value) in &headers {
+            eprintln!("> {name}: {}", value.to_str().unwrap_or_default());
+        }
+    }
+
+    let settings = ctx.db().get_settings();
+    let client_cert = find_client_certificate(url.as_str(), &settings.client_certificates);
+    let (receive_tx, mut receive_rx) = mpsc::channel::<Message>(128);
+    let mut ws_manager = WebsocketManager::new();
+    let response = match ws_manager
+        .connect(
+            &connection.id,
+            url.as_str(),
+            headers,
+            receive_tx,
+            resolved_settings.validate_certificates.value,
+            client_cert,
+            resolved_settings.request_message_size.value,
+        )
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => return Err(fail(format!("Failed to connect: {e}"))),
+    };
+
+    if verbose {
+        eprintln!("< {}", response.status());
+        for (name, value) in response.headers() {
+            eprintln!("< {name}: {}", value.to_str().unwrap_or_default());
+        }
+    }
+
+    if let (true, Some(cookie_jar), Some(store)) =
+        (store_cookies, cookie_jar.as_mut(), &cookie_store)
+    {
+        let set_cookie_headers = response
+            .headers()
+            .get_all("set-cookie")
+            .iter()
+            .filter_map(|value| value.to_str().ok().map(ToString::to_string))
+            .collect::<Vec<_>>();
+        if !set_cookie_headers.is_empty() {
+            store.store_cookies_from_response(&ws_url_to_http(&url), &set_cookie_headers);
+            cookie_jar.cookies = store.get_all_cookies();
+            ctx.db()
+                .upsert_cookie_jar(cookie_jar, &UpdateSource::Sync)
+                .map_err(|e| fail(format!("Failed to save cookies: {e}")))?;
+        }
+    }
+
+    let connection = ctx
+        .db()
+        .upsert_websocket_connection(
+            &WebsocketConnection {
+                state: WebsocketConnectionState::Connected,
+                status: response.status().as_u16() as i32,
+                headers: response
+                    .headers()
+                    .iter()
+                    .map(|(name, value)| HttpResponseHeader {
+                        name: name.to_string(),
+                        value: value.to_str().unwrap_or_default().to_string(),
+                    })
+                    .collect(),
+                ..connection
+            },
+            &UpdateSource::Sync,
+        )
+        .map_err(|e| format!("Failed to save connection: {e}"))?;
+    record_event(ctx, &connection, false, WebsocketEventType::Open, Vec::new());
+
+    let result =
+        run_session(ctx, &connection, &request, &mut ws_manager, &mut receive_rx, session, verbose)
+            .await;
+    let mut closed_by_server = matches!(result, Ok(true));
+    let result = result.map(|_| ());
+
+    if let Err(e) = ws_manager.close(&connection.id).await {
+        log::warn!("Failed to close WebSocket connection: {e:?}");
+    }
+    // Whatever arrived while closing, like the server's close frame, still gets recorded
+    while let Ok(message) = receive_rx.try_recv() {
+        closed_by_server |= matches!(message, Message::Close(_));
+        record_event(ctx, &connection, true, event_type(&message), message.into_data().into());
+    }
+    if !closed_by_server {
+        record_event(ctx, &connection, true, WebsocketEventType::Close, Vec::new());
+    }
+    if let Err(e) = &result {
+        record_event(ctx, &connection, false, WebsocketEventType::Error, e.clone().into_bytes());
+    }
+    close_connection(ctx, &connection, started, result.as_ref().err().cloned());
+    if verbose {
+        eprintln!("* Connection closed");
+    }
+    result
+}
+
+/// Send the outgoing messages and print incoming ones until the server closes the connection,
+/// `--max-messages` have arrived, or nothing happens for `--idle-timeout` seconds. Returns
+/// whether the server sent a close frame.
+async fn run_session(
+    ctx: &CliContext,
+    connection: &WebsocketConnection,
+    request: &WebsocketRequest,
+    ws_manager: &mut WebsocketManager,
+    receive_rx: &mut mpsc::Receiver<Message>,
+    session: &WebsocketSessionArgs,
+    verbose: bool,
+) -> Result<bool, String> {
+    let idle_timeout = Duration::try_from_secs_f64(session.idle_timeout)
+        .map_err(|_| format!("Invalid --idle-timeout {}", session.idle_timeout))?;
+
+    let mut stdin = if session.stdin {
+        Some(stdin_lines())
+    } else {
+        let message = maybe_strip_json_comments(&request.message);
+        if !message.is_empty() {
+            send_message(ctx, connection, ws_manager, message, verbose).await?;
+        }
+        None
+    };
+    let mut reading_stdin = stdin.is_some();
+
+    let mut received = 0u64;
+    loop {
+        if session.max_messages.is_some_and(|max| received >= max) {
+            return Ok(false);
+        }
+        tokio::select! {
+            message = receive_rx.recv() => {
+                let Some(message) = message else {
+                    // The read side ended without a close frame, so the connection broke
+                    return Ok(false);
+                };
+                match &message {
+                    Message::Text(text) => println!("{}", text.as_str()),
+                    Message::Binary(bytes) => println!("{}", String::from_utf8_lossy(bytes)),
+                    message if verbose => eprintln!("< {message:?}"),
+                    _ => {}
+                }
+                if matches!(message, Message::Text(_) | Message::Binary(_)) {
+                    received += 1;
+                }
+                let closed = matches!(message, Message::Close(_));
+                let message_type = event_type(&message);
+                record_event(ctx, connection, true, message_type, message.into_data().into());
+                if closed {
+                    return Ok(true);
+                }
+            }
+            line = async { stdin.as_mut()?.recv().await }, if reading_stdin => {
+                match line {
+                    Some(line) => send_message(ctx, connection, ws_manager, line, verbose).await?,
+                    None => reading_stdin = false,
+                }
+            }
+            _ = tokio::time::sleep(idle_timeout) => {
+                if verbose {
+                    eprintln!("* No messages for {}s, closing", session.idle_timeout);
+                }
+                return Ok(false);
+            }
+        }
+    }
+}
+
+async fn send_message(
+    ctx: &CliContext,
+    connection: &WebsocketConnection,
+    ws_manager: &mut WebsocketManager,
+    message: String,
+    verbose: bool,
+) -> Result<(), String> {
+    if verbose {
+        eprintln!("> {message}");
+    }
+    ws_manager
+        .send(&connection.id, Message::Text(message.clone().into()))
+        .await
+        .map_err(|e| format!("Failed to send message: {e}"))?;
+    record_event(ctx, connection, false, WebsocketEventType::Text, message.into_bytes());
+    Ok(())
+}
+
+/// Lines of stdin, read on a thread of their own as they arrive. The thread is detached, since
+/// a read that never returns would otherwise hold up the runtime's shutdown.
+fn stdin_lines() -> mpsc::Receiver<String> {
+    let (tx, rx) = mpsc::channel(16);
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if tx.blocking_send(line).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+fn event_type(message: &Message) -> WebsocketEventType {
+    match message {
+        Message::Text(_) => WebsocketEventType::Text,
+        Message::Binary(_) => WebsocketEventType::Binary,
+        Message::Ping(_) => WebsocketEventType::Ping,
+        Message::Pong(_) => WebsocketEventType::Pong,
+        Message::Close(_) => WebsocketEventType::Close,
+        // Raw frame will never happen during a read
+        Message::Frame(_) => WebsocketEventType::Frame,
+    }
+}
+
+fn record_event(
+    ctx: &CliContext,
+    connection: &WebsocketConnection,
+    is_server: bool,
+    message_type: WebsocketEventType,
+    message: Vec<u8>,
+) {
+    let event = WebsocketEvent {
+        connection_id: connection.id.clone(),
+        request_id: connection.request_id.clone(),
+        workspace_id: connection.workspace_id.clone(),
+        is_server,
+        message_type,
+        message,
+        ..Default::default()
+    };
+    if let Err(e) = ctx.db().upsert_websocket_event(&event, &UpdateSource::Sync) {
+        log::warn!("Failed to save WebSocket event: {e}");
+    }
+}
+
+fn close_connection(
+    ctx: &CliContext,
+    connection: &WebsocketConnection,
+    started: Instant,
+    error: Option<String>,
+) {
+    let closed = WebsocketConnection {
+        state: WebsocketConnectionState::Closed,
+        elapsed: started.elapsed().as_millis() as i32,
+        error,
+        ..connection.clone()
+    };
+    if let Err(e) = ctx.db().upsert_websocket_connection(&closed, &UpdateSource::Sync) {
+        log::warn!("Failed to save WebSocket connection: {e}");
+    }
+}
+
+/// The upgrade is an HTTP request, so cookies are matched against the HTTP form of the URL
+fn ws_url_to_http(ws_url: &Url) -> Url {
+    let mut http_url = ws_url.clone();
+    let scheme = match ws_url.scheme() {
+        "ws" => "http",
+        "wss" => "https",
+        _ => return http_url,
+    };
+    let _ = http_url.set_scheme(scheme);
+    http_url
+}
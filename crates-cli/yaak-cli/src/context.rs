@@ -8,6 +8,7 @@ use yaak_crypto::manager::EncryptionManager;
 use yaak_http::manager::HttpConnectionManager;
 use yaak_models::blob_manager::BlobManager;
 use yaak_models::client_db::ClientDb;
+use yaak_models::models::EnvironmentVariable;
 use yaak_models::query_manager::QueryManager;
 use yaak_plugins::events::PluginContext;
 use yaak_plugins::manager::PluginManager;
@@ -25,6 +26,7 @@ pub struct CliExecutionContext {
     pub workspace_id: Option<String>,
     pub environment_id: Option<String>,
     pub cookie_jar_id: Option<String>,
+    pub variables: Vec<EnvironmentVariable>,
 }
 
 pub struct CliContext {
@@ -35,6 +37,7 @@ pub struct CliContext {
     connection_manager: Arc<HttpConnectionManager>,
     plugin_manager: Option<Arc<PluginManager>>,
     plugin_event_bridge: Mutex<Option<CliPluginEventBridge>>,
+    variables: Vec<EnvironmentVariable>,
 }
 
 impl CliContext {
@@ -67,9 +70,17 @@ impl CliContext {
             connection_manager: Arc::new(HttpConnectionManager::new()),
             plugin_manager: None,
             plugin_event_bridge: Mutex::new(None),
+            variables: Vec::new(),
         }
     }
 
+    /// Variables from `--var` and `--env-file`, layered above every environment for the
+    /// sends of this invocation
+    pub fn with_variables(mut self, variables: Vec<EnvironmentVariable>) -> Self {
+        self.variables = variables;
+        self
+    }
+
     pub async fn init_plugins(&mut self, execution_context: CliExecutionContext) {
         let vendored_plugin_dir = self.data_dir.join("vendored-plugins");
         let installed_plugin_dir = self.data_dir.join("installed-plugins");
@@ -136,6 +147,10 @@ impl CliContext {
         &self.connection_manager
     }
 
+    pub fn variables(&self) -> &[EnvironmentVariable] {
+        &self.variables
+    }
+
     pub fn plugin_manager(&self) -> Arc<PluginManager> {
         self.plugin_manager.clone().expect("Plugin manager was not initialized for this command")
     }
@@ -8,7 +8,7 @@ mod version;
 mod version_check;
 
 use clap::{CommandFactory, FromArgMatches};
-use cli::{AGENT_HINTS, Cli, Commands, PluginCommands, RequestCommands};
+use cli::{AGENT_HINTS, Cli, Commands, PluginCommands, RequestCommands, RunnerCommands};
 use context::{CliContext, CliExecutionContext};
 use std::path::PathBuf;
 use yaak_models::queries::any_request::AnyRequest;
@@ -27,7 +27,7 @@ fn help_footer() -> String {
 #[tokio::main]
 async fn main() {
     let matches = Cli::command().after_help(help_footer()).get_matches();
-    let Cli { data_dir, environment, cookie_jar, verbose, log, command } =
+    let Cli { data_dir, environment, vars, env_file, cookie_jar, verbose, log, command } =
         match Cli::from_arg_matches(&matches) {
             Ok(cli) => cli,
             Err(error) => error.exit(),
@@ -49,6 +49,14 @@ async fn main() {
 
     let data_dir = data_dir.unwrap_or_else(|| resolve_data_dir(app_id));
 
+    let variables = match utils::vars::parse_cli_variables(&vars, env_file.as_deref()) {
+        Ok(variables) => variables,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            std::process::exit(1);
+        }
+    };
+
     version_check::maybe_check_for_updates().await;
 
     let exit_code = match command {
@@ -103,7 +111,8 @@ async fn main() {
         Commands::Generate(args) => commands::plugin::run_generate(args).await,
         Commands::Publish(args) => commands::plugin::run_publish(args).await,
         Commands::Send(args) => {
-            let mut context = CliContext::new(data_dir.clone(), app_id);
+            let mut context =
+                CliContext::new(data_dir.clone(), app_id).with_variables(variables.clone());
             match resolve_send_execution_context(
                 &context,
                 &args.id,
@@ -129,6 +138,70 @@ async fn main() {
                 }
             }
         }
+        Commands::Bench(args) => {
+            let mut context =
+                CliContext::new(data_dir.clone(), app_id).with_variables(variables.clone());
+            match resolve_send_execution_context(
+                &context,
+                &args.id,
+                environment.as_deref(),
+                cookie_jar.as_deref(),
+            ) {
+                Ok(execution_context) => {
+                    context.init_plugins(execution_context).await;
+                    let exit_code = commands::bench::run(
+                        &context,
+                        args,
+                        environment.as_deref(),
+                        cookie_jar.as_deref(),
+                    )
+                    .await;
+                    context.shutdown().await;
+                    exit_code
+                }
+                Err(error) => {
+                    eprintln!("Error: {error}");
+                    1
+                }
+            }
+        }
+        Commands::Runner(args) => {
+            let mut context =
+                CliContext::new(data_dir.clone(), app_id).with_variables(variables.clone());
+            let execution_context_result = match &args.command {
+                RunnerCommands::Run { ids, .. } => match ids.first() {
+                    Some(id) => resolve_send_execution_context(
+                        &context,
+                        id,
+                        environment.as_deref(),
+                        cookie_jar.as_deref(),
+                    )
+                    .map(Some),
+                    None => Ok(None),
+                },
+                _ => Ok(None),
+            };
+            match execution_context_result {
+                Ok(execution_context) => {
+                    if let Some(execution_context) = execution_context {
+                        context.init_plugins(execution_context).await;
+                    }
+                    let exit_code = commands::runner::run(
+                        &context,
+                        args,
+                        environment.as_deref(),
+                        cookie_jar.as_deref(),
+                    )
+                    .await;
+                    context.shutdown().await;
+                    exit_code
+                }
+                Err(error) => {
+                    eprintln!("Error: {error}");
+                    1
+                }
+            }
+        }
         Commands::CookieJar(args) => {
             let context = CliContext::new(data_dir.clone(), app_id);
             let exit_code = commands::cookie_jar::run(&context, args);
@@ -142,9 +215,10 @@ async fn main() {
             exit_code
         }
         Commands::Request(args) => {
-            let mut context = CliContext::new(data_dir.clone(), app_id);
+            let mut context =
+                CliContext::new(data_dir.clone(), app_id).with_variables(variables.clone());
             let execution_context_result = match &args.command {
-                RequestCommands::Send { request_id } => resolve_request_execution_context(
+                RequestCommands::Send { request_id, .. } => resolve_request_execution_context(
                     &context,
                     request_id,
                     environment.as_deref(),
@@ -215,6 +289,7 @@ fn resolve_send_execution_context(
             workspace_id: Some(workspace_id),
             environment_id: environment.map(str::to_string),
             cookie_jar_id,
+            variables: context.variables().to_vec(),
         });
     }
 
@@ -226,6 +301,7 @@ fn resolve_send_execution_context(
             workspace_id: Some(folder.workspace_id),
             environment_id: environment.map(str::to_string),
             cookie_jar_id,
+            variables: context.variables().to_vec(),
         });
     }
 
@@ -236,6 +312,7 @@ fn resolve_send_execution_context(
             workspace_id: Some(workspace.id),
             environment_id: environment.map(str::to_string),
             cookie_jar_id,
+            variables: context.variables().to_vec(),
         });
     }
 
@@ -265,6 +342,7 @@ fn resolve_request_execution_context(
         workspace_id: Some(workspace_id),
         environment_id: environment.map(str::to_string),
         cookie_jar_id,
+        variables: context.variables().to_vec(),
     })
 }
 
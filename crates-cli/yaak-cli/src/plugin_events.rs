@@ -15,7 +15,9 @@ use yaak::plugin_events::{
 };
 use yaak_models::render::{render_grpc_request, render_http_request};
 use yaak::response_body::FileResponseBodyStore;
-use yaak::send::{SendHttpRequestWithPluginsParams, send_http_request_with_plugins};
+use yaak::send::{
+    SendHttpRequestWithPluginsParams, layer_variables, send_http_request_with_plugins,
+};
 use yaak_crypto::manager::EncryptionManager;
 use yaak_http::cookies::get_cookie_value_from_jar;
 use yaak_http::manager::HttpConnectionManager;
@@ -223,6 +225,7 @@ async fn build_plugin_reply(
                     plugin_context: &plugin_context,
                     cancelled_rx: None,
                     connection_manager: &host_context.connection_manager,
+                    variables: execution_context.variables.clone(),
                 })
                 .await
                 {
@@ -270,7 +273,14 @@ async fn build_plugin_reply(
                         grpc_request.folder_id.as_deref(),
                         execution_context.environment_id.as_deref(),
                     ) {
-                        Ok(chain) => chain,
+                        Ok(mut chain) => {
+                            layer_variables(
+                                &mut chain,
+                                &grpc_request.workspace_id,
+                                &execution_context.variables,
+                            );
+                            chain
+                        }
                         Err(err) => {
                             return Some(InternalEventPayload::ErrorResponse(ErrorResponse {
                                 error: format!("Failed to resolve environments in CLI: {err}"),
@@ -330,7 +340,14 @@ async fn build_plugin_reply(
                         http_request.folder_id.as_deref(),
                         execution_context.environment_id.as_deref(),
                     ) {
-                        Ok(chain) => chain,
+                        Ok(mut chain) => {
+                            layer_variables(
+                                &mut chain,
+                                &http_request.workspace_id,
+                                &execution_context.variables,
+                            );
+                            chain
+                        }
                         Err(err) => {
                             return Some(InternalEventPayload::ErrorResponse(ErrorResponse {
                                 error: format!("Failed to resolve environments in CLI: {err}"),
@@ -388,19 +405,24 @@ async fn build_plugin_reply(
                     }
                 });
 
-                let environment_chain =
-                    match host_context.query_manager.connect().resolve_environments(
+                let environment_chain = match host_context
+                    .query_manager
+                    .connect()
+                    .resolve_environments(
                         &workspace_id,
                         folder_id.as_deref(),
                         execution_context.environment_id.as_deref(),
                     ) {
-                        Ok(chain) => chain,
-                        Err(err) => {
-                            return Some(InternalEventPayload::ErrorResponse(ErrorResponse {
-                                error: format!("Failed to resolve environments in CLI: {err}"),
-                            }));
-                        }
-                    };
+                    Ok(mut chain) => {
+                        layer_variables(&mut chain, &workspace_id, &execution_context.variables);
+                        chain
+                    }
+                    Err(err) => {
+                        return Some(InternalEventPayload::ErrorResponse(ErrorResponse {
+                            error: format!("Failed to resolve environments in CLI: {err}"),
+                        }));
+                    }
+                };
 
                 let template_callback = PluginTemplateCallback::new(
                     host_context.plugin_manager.clone(),
@@ -0,0 +1,33 @@
+use std::path::{Path, PathBuf};
+
+use yaak_grpc::manager::GrpcConfig;
+use yaak_models::client_db::ClientDb;
+
+/// The CLI ships without the app's protoc sidecar, so proto files are compiled with the
+/// `protoc` on PATH, which finds the well-known types in its own include directory.
+pub fn grpc_config(data_dir: &Path) -> GrpcConfig {
+    GrpcConfig {
+        protoc_include_dir: data_dir.join("vendored").join("protoc").join("include"),
+        protoc_bin_path: PathBuf::from("protoc"),
+    }
+}
+
+/// The proto files picked for a request in the app, where an empty list means server reflection.
+pub fn proto_files_for_request(db: &ClientDb, request_id: &str) -> Vec<PathBuf> {
+    let key = format!("proto_files::{request_id}");
+    db.get_key_value_raw("global", &key)
+        .and_then(|kv| serde_json::from_str::<Vec<String>>(&kv.value).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// The endpoint as a URI, assuming plaintext when it has no scheme, as the app does.
+pub fn grpc_uri(endpoint: &str) -> String {
+    if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
+        endpoint.to_string()
+    } else {
+        format!("http://{endpoint}")
+    }
+}
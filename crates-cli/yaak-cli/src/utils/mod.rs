@@ -1,5 +1,7 @@
 pub mod confirm;
+pub mod grpc;
 pub mod http;
 pub mod json;
 pub mod schema;
+pub mod vars;
 pub mod workspace;
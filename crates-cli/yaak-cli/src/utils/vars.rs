@@ -0,0 +1,68 @@
+use std::fs;
+use std::path::Path;
+use yaak_models::models::EnvironmentVariable;
+
+/// Variables from `--env-file` and `--var`, in that order. When both set a name, the later
+/// entry wins, so `--var` overrides the file.
+pub fn parse_cli_variables(
+    vars: &[String],
+    env_file: Option<&Path>,
+) -> Result<Vec<EnvironmentVariable>, String> {
+    let mut variables = Vec::new();
+
+    if let Some(path) = env_file {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read env file {}: {e}", path.display()))?;
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (name, value) = split_assignment(line).ok_or_else(|| {
+                format!(
+                    "Invalid line {} in env file {}: expected NAME=VALUE",
+                    index + 1,
+                    path.display()
+                )
+            })?;
+            variables.push(variable(name, unquote(value.trim())));
+        }
+    }
+
+    for raw in vars {
+        let (name, value) = split_assignment(raw)
+            .ok_or_else(|| format!("Invalid --var '{raw}': expected NAME=VALUE"))?;
+        variables.push(variable(name, value));
+    }
+
+    Ok(variables)
+}
+
+fn split_assignment(raw: &str) -> Option<(&str, &str)> {
+    let (name, value) = raw.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some((name, value))
+}
+
+/// Strip one pair of matching quotes, as dotenv files often quote values with spaces
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(quote).and_then(|v| v.strip_suffix(quote)) {
+            return inner;
+        }
+    }
+    value
+}
+
+fn variable(name: &str, value: &str) -> EnvironmentVariable {
+    EnvironmentVariable {
+        enabled: true,
+        name: name.to_string(),
+        value: value.to_string(),
+        id: None,
+    }
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::http_server::TestHttpServer;
+use common::{cli_cmd, query_manager, seed_workspace};
+use predicates::str::contains;
+use tempfile::TempDir;
+use yaak_models::models::HttpRequest;
+use yaak_models::util::UpdateSource;
+
+#[test]
+fn bench_sends_up_to_the_request_limit_and_reports_statuses() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+
+    let server = TestHttpServer::spawn_ok("bench body");
+    let request = HttpRequest {
+        id: "rq_bench".to_string(),
+        workspace_id: "wk_test".to_string(),
+        method: "GET".to_string(),
+        url: server.url.clone(),
+        ..Default::default()
+    };
+    query_manager(data_dir)
+        .connect()
+        .upsert_http_request(&request, &UpdateSource::Sync)
+        .expect("Failed to seed request");
+
+    let assert = cli_cmd(data_dir)
+        .args([
+            "bench",
+            "rq_bench",
+            "--concurrency",
+            "2",
+            "--requests",
+            "5",
+            "--json",
+        ])
+        .assert()
+        .success();
+    let report: serde_json::Value =
+        serde_json::from_slice(&assert.get_output().stdout).expect("Report is JSON");
+    assert_eq!(report["total"], 5);
+    assert_eq!(report["completed"], 5);
+    assert_eq!(report["statusCodes"][0]["status"], 200);
+    assert_eq!(report["statusCodes"][0]["count"], 5);
+
+    // Benching stores nothing.
+    let responses = query_manager(data_dir)
+        .connect()
+        .list_http_responses_for_request("rq_bench", None)
+        .expect("Failed to list responses");
+    assert!(responses.is_empty());
+}
+
+#[test]
+fn bench_rejects_zero_concurrency() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+
+    cli_cmd(data_dir)
+        .args(["bench", "wk_test", "--concurrency", "0"])
+        .assert()
+        .failure()
+        .stderr(contains("--concurrency must be at least 1"));
+}
@@ -0,0 +1,174 @@
+use std::convert::Infallible;
+use std::net::TcpListener;
+use std::thread;
+
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{
+    DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+    MethodDescriptorProto, ServiceDescriptorProto,
+};
+use tokio::sync::oneshot;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::codec::ProstCodec;
+use tonic::codegen::{Body, BoxFuture, Context, Poll, Service, StdError, http};
+use tonic::server::{Grpc, NamedService, ServerStreamingService, UnaryService};
+use tonic::{Request, Response, Status};
+
+/// A gRPC server with server reflection and a `yaak.test.Echo` service:
+///
+/// ```proto
+/// service Echo {
+///   rpc Say(EchoMessage) returns (EchoMessage);            // "hello <text>"
+///   rpc Repeat(EchoMessage) returns (stream EchoMessage);  // "<text> 1" to "<text> 3"
+/// }
+/// ```
+pub struct TestGrpcServer {
+    pub url: String,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl TestGrpcServer {
+    pub fn spawn() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind test gRPC server");
+        let addr = listener.local_addr().expect("Failed to get local addr");
+        listener.set_nonblocking(true).expect("Failed to set test server listener nonblocking");
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+
+        let handle = thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to build test gRPC runtime");
+            runtime.block_on(async move {
+                let listener = tokio::net::TcpListener::from_std(listener)
+                    .expect("Failed to use test gRPC listener");
+                let reflection = tonic_reflection::server::Builder::configure()
+                    .register_file_descriptor_set(descriptor_set())
+                    .build_v1()
+                    .expect("Failed to build reflection service");
+                let _ = tonic::transport::Server::builder()
+                    .add_service(Echo)
+                    .add_service(reflection)
+                    .serve_with_incoming_shutdown(TcpListenerStream::new(listener), async {
+                        let _ = shutdown_rx.await;
+                    })
+                    .await;
+            });
+        });
+
+        Self { url: format!("http://{addr}"), shutdown: Some(shutdown), handle: Some(handle) }
+    }
+}
+
+impl Drop for TestGrpcServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct EchoMessage {
+    #[prost(string, tag = "1")]
+    text: String,
+}
+
+fn descriptor_set() -> FileDescriptorSet {
+    let message_type = ".yaak.test.EchoMessage".to_string();
+    let method = |name: &str, server_streaming: bool| MethodDescriptorProto {
+        name: Some(name.to_string()),
+        input_type: Some(message_type.clone()),
+        output_type: Some(message_type.clone()),
+        server_streaming: Some(server_streaming),
+        ..Default::default()
+    };
+    FileDescriptorSet {
+        file: vec![FileDescriptorProto {
+            name: Some("yaak/test/echo.proto".to_string()),
+            package: Some("yaak.test".to_string()),
+            syntax: Some("proto3".to_string()),
+            message_type: vec![DescriptorProto {
+                name: Some("EchoMessage".to_string()),
+                field: vec![FieldDescriptorProto {
+                    name: Some("text".to_string()),
+                    json_name: Some("text".to_string()),
+                    number: Some(1),
+                    label: Some(Label::Optional as i32),
+                    r#type: Some(Type::String as i32),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            service: vec![ServiceDescriptorProto {
+                name: Some("Echo".to_string()),
+                method: vec![method("Say", false), method("Repeat", true)],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }],
+    }
+}
+
+#[derive(Clone)]
+struct Echo;
+
+impl NamedService for Echo {
+    const NAME: &'static str = "yaak.test.Echo";
+}
+
+impl<B> Service<http::Request<B>> for Echo
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<tonic::body::BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let mut grpc = Grpc::new(ProstCodec::<EchoMessage, EchoMessage>::default());
+        match req.uri().path() {
+            "/yaak.test.Echo/Say" => Box::pin(async move { Ok(grpc.unary(Say, req).await) }),
+            "/yaak.test.Echo/Repeat" => {
+                Box::pin(async move { Ok(grpc.server_streaming(Repeat, req).await) })
+            }
+            _ => Box::pin(async { Ok(Status::unimplemented("No such method").into_http()) }),
+        }
+    }
+}
+
+struct Say;
+
+impl UnaryService<EchoMessage> for Say {
+    type Response = EchoMessage;
+    type Future = BoxFuture<Response<EchoMessage>, Status>;
+
+    fn call(&mut self, request: Request<EchoMessage>) -> Self::Future {
+        let text = format!("hello {}", request.into_inner().text);
+        Box::pin(async move { Ok(Response::new(EchoMessage { text })) })
+    }
+}
+
+struct Repeat;
+
+impl ServerStreamingService<EchoMessage> for Repeat {
+    type Response = EchoMessage;
+    type ResponseStream = tokio_stream::Iter<std::vec::IntoIter<Result<EchoMessage, Status>>>;
+    type Future = BoxFuture<Response<Self::ResponseStream>, Status>;
+
+    fn call(&mut self, request: Request<EchoMessage>) -> Self::Future {
+        let text = request.into_inner().text;
+        let messages =
+            (1..=3).map(|i| Ok(EchoMessage { text: format!("{text} {i}") })).collect::<Vec<_>>();
+        Box::pin(async move { Ok(Response::new(tokio_stream::iter(messages))) })
+    }
+}
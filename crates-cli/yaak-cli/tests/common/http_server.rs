@@ -14,6 +14,10 @@ pub struct TestHttpServer {
 
 impl TestHttpServer {
     pub fn spawn_ok(body: &'static str) -> Self {
+        Self::spawn_ok_with_content_type("text/plain", body)
+    }
+
+    pub fn spawn_ok_with_content_type(content_type: &'static str, body: &'static str) -> Self {
         let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind test HTTP server");
         let addr = listener.local_addr().expect("Failed to get local addr");
         let url = format!("http://{addr}/test");
@@ -54,7 +58,7 @@ impl TestHttpServer {
                         }
 
                         let response = format!(
-                            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            "HTTP/1.1 200 OK\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
                             body_bytes.len()
                         );
                         let _ = stream.write_all(response.as_bytes());
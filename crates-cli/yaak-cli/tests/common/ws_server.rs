@@ -0,0 +1,72 @@
+use std::net::TcpListener;
+use std::thread;
+
+use futures::{SinkExt, StreamExt};
+use tokio::sync::oneshot;
+use tokio_tungstenite::tungstenite::Message;
+
+/// A WebSocket server that answers each text message twice: as sent, then in upper case.
+pub struct TestWebsocketServer {
+    pub url: String,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl TestWebsocketServer {
+    pub fn spawn() -> Self {
+        let listener =
+            TcpListener::bind("127.0.0.1:0").expect("Failed to bind test WebSocket server");
+        let addr = listener.local_addr().expect("Failed to get local addr");
+        listener.set_nonblocking(true).expect("Failed to set test server listener nonblocking");
+        let (shutdown, mut shutdown_rx) = oneshot::channel::<()>();
+
+        let handle = thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to build test WebSocket runtime");
+            runtime.block_on(async move {
+                let listener = tokio::net::TcpListener::from_std(listener)
+                    .expect("Failed to use test WebSocket listener");
+                loop {
+                    let stream = tokio::select! {
+                        accepted = listener.accept() => match accepted {
+                            Ok((stream, _)) => stream,
+                            Err(_) => break,
+                        },
+                        _ = &mut shutdown_rx => break,
+                    };
+                    tokio::spawn(async move {
+                        let Ok(mut socket) = tokio_tungstenite::accept_async(stream).await else {
+                            return;
+                        };
+                        while let Some(Ok(message)) = socket.next().await {
+                            let Message::Text(text) = message else {
+                                continue;
+                            };
+                            let upper = text.to_uppercase();
+                            if socket.send(Message::Text(text)).await.is_err()
+                                || socket.send(Message::Text(upper.into())).await.is_err()
+                            {
+                                return;
+                            }
+                        }
+                    });
+                }
+            });
+        });
+
+        Self { url: format!("ws://{addr}/socket"), shutdown: Some(shutdown), handle: Some(handle) }
+    }
+}
+
+impl Drop for TestWebsocketServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -1,13 +1,19 @@
 mod common;
 
+use common::grpc_server::TestGrpcServer;
 use common::http_server::TestHttpServer;
+use common::ws_server::TestWebsocketServer;
 use common::{
     cli_cmd, parse_created_id, query_manager, seed_grpc_request, seed_request,
     seed_websocket_request, seed_workspace,
 };
 use predicates::str::contains;
+use std::net::TcpListener;
 use tempfile::TempDir;
-use yaak_models::models::HttpResponseState;
+use yaak_models::models::{
+    GrpcRequest, HttpResponseState, WebsocketConnectionState, WebsocketEventType, WebsocketRequest,
+};
+use yaak_models::util::UpdateSource;
 
 #[test]
 fn show_and_delete_yes_round_trip() {
@@ -261,31 +267,182 @@ fn request_schema_http_pretty_prints_with_flag() {
 }
 
 #[test]
-fn request_send_grpc_returns_explicit_nyi_error() {
+fn request_send_grpc_requires_service_and_method() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let data_dir = temp_dir.path();
     seed_workspace(data_dir, "wk_test");
-    seed_grpc_request(data_dir, "wk_test", "gr_seed_nyi");
+    seed_grpc_request(data_dir, "wk_test", "gr_seed_no_method");
 
     cli_cmd(data_dir)
-        .args(["request", "send", "gr_seed_nyi"])
+        .args(["request", "send", "gr_seed_no_method"])
         .assert()
         .failure()
         .code(1)
-        .stderr(contains("gRPC request send is not implemented yet in yaak-cli"));
+        .stderr(contains("gRPC request has no service and method selected"));
+}
+
+/// Point a seeded gRPC request at `method` of the test server's echo service.
+fn seed_echo_request(data_dir: &std::path::Path, request_id: &str, url: &str, method: &str) {
+    seed_grpc_request(data_dir, "wk_test", request_id);
+    let db = query_manager(data_dir);
+    let request = db.connect().get_grpc_request(request_id).expect("seeded request");
+    db.connect()
+        .upsert_grpc_request(
+            &GrpcRequest {
+                url: url.to_string(),
+                service: Some("yaak.test.Echo".to_string()),
+                method: Some(method.to_string()),
+                message: r#"{"text": "yaak"}"#.to_string(),
+                ..request
+            },
+            &UpdateSource::Sync,
+        )
+        .expect("Failed to update gRPC request");
 }
 
 #[test]
-fn request_send_websocket_returns_explicit_nyi_error() {
+fn request_send_grpc_unary_prints_message_and_status() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let data_dir = temp_dir.path();
     seed_workspace(data_dir, "wk_test");
-    seed_websocket_request(data_dir, "wk_test", "wr_seed_nyi");
+    let server = TestGrpcServer::spawn();
+    seed_echo_request(data_dir, "gr_say", &server.url, "Say");
 
     cli_cmd(data_dir)
-        .args(["request", "send", "wr_seed_nyi"])
+        .args(["request", "send", "gr_say"])
+        .assert()
+        .success()
+        .stdout(contains(r#"{"message":{"text":"hello yaak"},"type":"message"}"#))
+        .stdout(contains(r#""code":0"#))
+        .stdout(contains(r#""status":"Ok""#));
+}
+
+#[test]
+fn request_send_grpc_server_streaming_prints_each_message() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+    let server = TestGrpcServer::spawn();
+    seed_echo_request(data_dir, "gr_repeat", &server.url, "Repeat");
+
+    let assert = cli_cmd(data_dir).args(["request", "send", "gr_repeat"]).assert().success();
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines[..3],
+        [
+            r#"{"message":{"text":"yaak 1"},"type":"message"}"#,
+            r#"{"message":{"text":"yaak 2"},"type":"message"}"#,
+            r#"{"message":{"text":"yaak 3"},"type":"message"}"#,
+        ]
+    );
+    assert!(lines[3].contains(r#""status":"Ok""#));
+}
+
+#[test]
+fn request_send_grpc_renders_message_with_cli_variables() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+    let server = TestGrpcServer::spawn();
+    seed_echo_request(data_dir, "gr_var", &server.url, "Say");
+    let db = query_manager(data_dir);
+    let request = db.connect().get_grpc_request("gr_var").expect("seeded request");
+    db.connect()
+        .upsert_grpc_request(
+            &GrpcRequest { message: r#"{"text": "${[ name ]}"}"#.to_string(), ..request },
+            &UpdateSource::Sync,
+        )
+        .expect("Failed to update gRPC request");
+
+    cli_cmd(data_dir)
+        .args(["request", "send", "gr_var", "--var", "name=yaak"])
+        .assert()
+        .success()
+        .stdout(contains(r#"{"message":{"text":"hello yaak"},"type":"message"}"#));
+}
+
+#[test]
+fn request_send_websocket_records_failed_connection() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+    seed_websocket_request(data_dir, "wk_test", "wr_seed_refused");
+
+    // A port that was just free, so nothing is listening on it
+    let port = TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("Failed to find a free port")
+        .port();
+    let db = query_manager(data_dir);
+    let request = db.connect().get_websocket_request("wr_seed_refused").expect("seeded request");
+    db.connect()
+        .upsert_websocket_request(
+            &WebsocketRequest { url: format!("ws://127.0.0.1:{port}/socket"), ..request },
+            &UpdateSource::Sync,
+        )
+        .expect("Failed to update request URL");
+
+    cli_cmd(data_dir)
+        .args(["request", "send", "wr_seed_refused"])
         .assert()
         .failure()
         .code(1)
-        .stderr(contains("WebSocket request send is not implemented yet in yaak-cli"));
+        .stderr(contains("Failed to connect"));
+
+    let connections = db
+        .connect()
+        .list_websocket_connections_for_request("wr_seed_refused")
+        .expect("Failed to list connections");
+    assert_eq!(connections.len(), 1);
+    assert!(matches!(connections[0].state, WebsocketConnectionState::Closed));
+    assert!(connections[0].error.as_deref().is_some_and(|e| e.starts_with("Failed to connect")));
+}
+
+#[test]
+fn request_send_websocket_prints_and_records_messages() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+    seed_websocket_request(data_dir, "wk_test", "wr_echo");
+    let server = TestWebsocketServer::spawn();
+    let db = query_manager(data_dir);
+    let request = db.connect().get_websocket_request("wr_echo").expect("seeded request");
+    db.connect()
+        .upsert_websocket_request(
+            &WebsocketRequest { url: server.url.clone(), message: "hello".to_string(), ..request },
+            &UpdateSource::Sync,
+        )
+        .expect("Failed to update request");
+
+    // The server answers twice, so the session ends at the limit rather than on idling
+    cli_cmd(data_dir)
+        .args(["request", "send", "wr_echo"])
+        .args(["--max-messages", "1", "--idle-timeout", "30"])
+        .timeout(std::time::Duration::from_secs(20))
+        .assert()
+        .success()
+        .stdout("hello\n");
+
+    let connections = db
+        .connect()
+        .list_websocket_connections_for_request("wr_echo")
+        .expect("Failed to list connections");
+    assert_eq!(connections.len(), 1);
+    assert!(matches!(connections[0].state, WebsocketConnectionState::Closed));
+    assert_eq!(connections[0].status, 101);
+    assert_eq!(connections[0].error, None);
+
+    let events =
+        db.connect().list_websocket_events(&connections[0].id).expect("Failed to list events");
+    let texts = |is_server: bool| {
+        events
+            .iter()
+            .filter(|e| e.is_server == is_server && e.message_type == WebsocketEventType::Text)
+            .map(|e| String::from_utf8_lossy(&e.message).to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(texts(false), ["hello"]);
+    assert_eq!(texts(true)[0], "hello");
+    assert!(events.iter().any(|e| !e.is_server && e.message_type == WebsocketEventType::Open));
 }
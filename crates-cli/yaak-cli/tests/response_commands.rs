@@ -0,0 +1,122 @@
+mod common;
+
+use common::http_server::TestHttpServer;
+use common::{cli_cmd, query_manager, seed_workspace};
+use predicates::str::contains;
+use tempfile::TempDir;
+use yaak_models::models::{
+    HttpRequest, HttpResponseEvent, HttpResponseEventData, HttpResponseTlsCertificate,
+};
+use yaak_models::util::UpdateSource;
+
+#[test]
+fn response_events_lists_server_sent_events_from_a_send() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+
+    let server = TestHttpServer::spawn_ok_with_content_type(
+        "text/event-stream",
+        "id: 1\ndata: hello\n\nevent: done\ndata: line one\ndata: line two\n\n",
+    );
+    let request = HttpRequest {
+        id: "rq_stream".to_string(),
+        workspace_id: "wk_test".to_string(),
+        method: "GET".to_string(),
+        url: server.url.clone(),
+        ..Default::default()
+    };
+    query_manager(data_dir)
+        .connect()
+        .upsert_http_request(&request, &UpdateSource::Sync)
+        .expect("Failed to seed request");
+
+    cli_cmd(data_dir).args(["send", "rq_stream"]).assert().success();
+
+    cli_cmd(data_dir)
+        .args(["response", "events", "rq_stream"])
+        .assert()
+        .success()
+        .stdout(contains("[0] message id=1: hello"))
+        .stdout(contains("[1] done id=1: line one\n    line two"));
+
+    let assert = cli_cmd(data_dir)
+        .args([
+            "response",
+            "events",
+            "rq_stream",
+            "--type",
+            "done",
+            "--json",
+        ])
+        .assert()
+        .success();
+    let events: serde_json::Value =
+        serde_json::from_slice(&assert.get_output().stdout).expect("Events are JSON");
+    assert_eq!(events.as_array().map(Vec::len), Some(1));
+    assert_eq!(events[0]["data"], "line one\nline two");
+    assert_eq!(events[0]["eventId"], "1");
+}
+
+#[test]
+fn response_show_includes_tls_details_from_the_timeline() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+
+    let server = TestHttpServer::spawn_ok("plain");
+    let request = HttpRequest {
+        id: "rq_plain".to_string(),
+        workspace_id: "wk_test".to_string(),
+        method: "GET".to_string(),
+        url: server.url.clone(),
+        ..Default::default()
+    };
+    let qm = query_manager(data_dir);
+    qm.connect()
+        .upsert_http_request(&request, &UpdateSource::Sync)
+        .expect("Failed to seed request");
+
+    cli_cmd(data_dir).args(["send", "rq_plain"]).assert().success();
+
+    let show = |data_dir| {
+        let assert = cli_cmd(data_dir).args(["response", "show", "rq_plain"]).assert().success();
+        serde_json::from_slice::<serde_json::Value>(&assert.get_output().stdout)
+            .expect("Response is JSON")
+    };
+    assert_eq!(show(data_dir)["tls"], serde_json::Value::Null);
+
+    let db = qm.connect();
+    let response = db
+        .list_http_responses_for_request("rq_plain", Some(1))
+        .expect("Failed to load responses")
+        .remove(0);
+    for event in [
+        HttpResponseEventData::TlsNegotiated {
+            protocol_version: "TLSv1.3".to_string(),
+            cipher_suite: "TLS13_AES_128_GCM_SHA256".to_string(),
+            alpn: Some("h2".to_string()),
+            resumed: false,
+        },
+        HttpResponseEventData::TlsServerCertificates {
+            certificates: vec![HttpResponseTlsCertificate {
+                subject: "CN=localhost".to_string(),
+                ..Default::default()
+            }],
+        },
+        HttpResponseEventData::TlsClientCertificate { requested: true, presented: false },
+    ] {
+        db.upsert_http_response_event(
+            &HttpResponseEvent::new(&response.id, "wk_test", event),
+            &UpdateSource::Sync,
+        )
+        .expect("Failed to seed event");
+    }
+
+    let tls = show(data_dir)["tls"].clone();
+    assert_eq!(tls["protocolVersion"], "TLSv1.3");
+    assert_eq!(tls["alpn"], "h2");
+    assert_eq!(tls["serverCertificates"][0]["subject"], "CN=localhost");
+    assert_eq!(tls["clientCertificate"]["requested"], true);
+    assert_eq!(tls["clientCertificate"]["presented"], false);
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use common::http_server::TestHttpServer;
+use common::{cli_cmd, query_manager, seed_folder, seed_workspace};
+use predicates::str::contains;
+use tempfile::TempDir;
+use yaak_models::models::{HttpAssertionKind, HttpRequest, HttpRequestAssertion};
+use yaak_models::util::UpdateSource;
+
+#[test]
+fn runner_run_iterates_data_file_rows_and_stores_the_run() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+    seed_folder(data_dir, "wk_test", "fl_test");
+
+    let server = TestHttpServer::spawn_ok("runner body");
+    let request = HttpRequest {
+        id: "rq_runner".to_string(),
+        workspace_id: "wk_test".to_string(),
+        folder_id: Some("fl_test".to_string()),
+        method: "GET".to_string(),
+        url: format!("{}?user=${{[ user ]}}", server.url),
+        assertions: vec![HttpRequestAssertion {
+            kind: HttpAssertionKind::StatusEquals,
+            value: "${[ expected ]}".to_string(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    query_manager(data_dir)
+        .connect()
+        .upsert_http_request(&request, &UpdateSource::Sync)
+        .expect("Failed to seed request");
+
+    let data_file = temp_dir.path().join("users.csv");
+    std::fs::write(&data_file, "user,expected\nalice,200\nbob,404\n")
+        .expect("Failed to write data file");
+
+    cli_cmd(data_dir)
+        .args(["runner", "run", "fl_test", "--data"])
+        .arg(&data_file)
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(contains("PASS iteration 1/2"))
+        .stdout(contains("FAIL iteration 2/2"))
+        .stdout(contains("1 passed, 1 failed"))
+        .stderr(contains("rq_runner: 1 of 1 assertions failed"));
+
+    let db = query_manager(data_dir);
+    let runs = db.connect().list_runner_runs("wk_test", None).expect("Failed to list runs");
+    assert_eq!(runs.len(), 1);
+    let iterations =
+        db.connect().list_runner_iterations(&runs[0].id).expect("Failed to list iterations");
+    assert_eq!(iterations.len(), 2);
+    assert_eq!(iterations[1].variables.get("user").map(String::as_str), Some("bob"));
+    assert!(iterations[0].passed && !iterations[1].passed);
+
+    cli_cmd(data_dir)
+        .args(["runner", "list", "wk_test"])
+        .assert()
+        .success()
+        .stdout(contains(runs[0].id.as_str()))
+        .stdout(contains("[completed]"));
+}
+
+#[test]
+fn runner_run_rejects_unknown_ids() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+
+    cli_cmd(data_dir)
+        .args(["runner", "run", "does_not_exist"])
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(contains("Could not resolve ID 'does_not_exist'"));
+}
+
+#[test]
+fn runner_run_applies_per_request_delay_and_retries() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+
+    let server = TestHttpServer::spawn_ok("runner body");
+    for id in ["rq_first", "rq_second"] {
+        let request = HttpRequest {
+            id: id.to_string(),
+            workspace_id: "wk_test".to_string(),
+            method: "GET".to_string(),
+            url: server.url.clone(),
+            ..Default::default()
+        };
+        query_manager(data_dir)
+            .connect()
+            .upsert_http_request(&request, &UpdateSource::Sync)
+            .expect("Failed to seed request");
+    }
+
+    cli_cmd(data_dir)
+        .args(["runner", "run", "rq_first", "rq_second", "--retries", "1"])
+        .args([
+            "--step-delay",
+            "rq_second=5",
+            "--step-retries",
+            "rq_first=3",
+        ])
+        .assert()
+        .success();
+
+    let runs =
+        query_manager(data_dir).connect().list_runner_runs("wk_test", None).expect("list runs");
+    let steps = runs[0]
+        .steps
+        .iter()
+        .map(|s| (s.request_id.as_str(), s.delay_ms, s.retries))
+        .collect::<Vec<_>>();
+    assert_eq!(steps, vec![("rq_first", 0, 3), ("rq_second", 5, 1)]);
+
+    cli_cmd(data_dir)
+        .args(["runner", "run", "rq_first", "--step-retries", "rq_other=1"])
+        .assert()
+        .failure()
+        .stderr(contains("Request rq_other is not part of this run"));
+}
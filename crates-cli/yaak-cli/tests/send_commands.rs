@@ -4,7 +4,9 @@ use common::http_server::TestHttpServer;
 use common::{cli_cmd, query_manager, seed_folder, seed_workspace};
 use predicates::str::contains;
 use tempfile::TempDir;
-use yaak_models::models::HttpRequest;
+use yaak_models::models::{
+    EnvironmentVariable, HttpAssertionKind, HttpRequest, HttpRequestAssertion,
+};
 use yaak_models::util::UpdateSource;
 
 #[test]
@@ -49,3 +51,180 @@ fn top_level_send_unknown_id_fails_with_clear_error() {
         .code(1)
         .stderr(contains("Could not resolve ID 'does_not_exist' as request, folder, or workspace"));
 }
+
+#[test]
+fn send_fails_when_an_assertion_fails() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+
+    let server = TestHttpServer::spawn_ok("asserted body");
+    let request = HttpRequest {
+        id: "rq_asserted".to_string(),
+        workspace_id: "wk_test".to_string(),
+        method: "GET".to_string(),
+        url: server.url.clone(),
+        assertions: vec![
+            HttpRequestAssertion {
+                kind: HttpAssertionKind::BodyMatches,
+                value: "^asserted".to_string(),
+                ..Default::default()
+            },
+            HttpRequestAssertion {
+                kind: HttpAssertionKind::StatusEquals,
+                value: "404".to_string(),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    query_manager(data_dir)
+        .connect()
+        .upsert_http_request(&request, &UpdateSource::Sync)
+        .expect("Failed to seed request");
+
+    cli_cmd(data_dir)
+        .args(["send", "rq_asserted"])
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(contains("asserted body"))
+        .stderr(contains("PASS body_matches ^asserted"))
+        .stderr(contains("FAIL status_equals 404 (got 200)"))
+        .stderr(contains("1 of 2 assertions failed"));
+
+    let response = query_manager(data_dir)
+        .connect()
+        .list_http_responses_for_request("rq_asserted", Some(1))
+        .expect("Failed to list responses")
+        .remove(0);
+    assert_eq!(response.assertion_results.len(), 2);
+    assert!(response.assertion_results[0].passed);
+    assert!(!response.assertion_results[1].passed);
+}
+
+#[test]
+fn top_level_send_folder_writes_junit_report() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+    seed_folder(data_dir, "wk_test", "fl_test");
+
+    let server = TestHttpServer::spawn_ok("reported");
+    let request = HttpRequest {
+        id: "rq_reported".to_string(),
+        workspace_id: "wk_test".to_string(),
+        folder_id: Some("fl_test".to_string()),
+        name: "Reported".to_string(),
+        method: "GET".to_string(),
+        url: server.url.clone(),
+        ..Default::default()
+    };
+    query_manager(data_dir)
+        .connect()
+        .upsert_http_request(&request, &UpdateSource::Sync)
+        .expect("Failed to seed folder request");
+
+    let report_path = temp_dir.path().join("report.xml");
+    cli_cmd(data_dir)
+        .args(["send", "fl_test", "--reporter", "junit", "--report-file"])
+        .arg(&report_path)
+        .assert()
+        .success();
+
+    let report = std::fs::read_to_string(&report_path).expect("Failed to read report");
+    assert!(report.contains(r#"<testsuite name="Seed Folder" tests="1" failures="0""#), "{report}");
+    assert!(report.contains(r#"<testcase name="Reported" classname="Seed Folder""#), "{report}");
+    assert!(report.contains("<system-out>HTTP 200</system-out>"), "{report}");
+}
+
+#[test]
+fn top_level_send_writes_tap_report_with_failures() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+
+    let request = HttpRequest {
+        id: "rq_unresolved".to_string(),
+        workspace_id: "wk_test".to_string(),
+        name: "Unresolved".to_string(),
+        method: "GET".to_string(),
+        url: "${[ missing_var ]}".to_string(),
+        ..Default::default()
+    };
+    query_manager(data_dir)
+        .connect()
+        .upsert_http_request(&request, &UpdateSource::Sync)
+        .expect("Failed to seed request");
+
+    let report_path = temp_dir.path().join("report.tap");
+    cli_cmd(data_dir)
+        .args(["send", "wk_test", "--reporter", "tap", "--report-file"])
+        .arg(&report_path)
+        .assert()
+        .failure()
+        .code(1);
+
+    let report = std::fs::read_to_string(&report_path).expect("Failed to read report");
+    assert!(report.starts_with("TAP version 13\n1..1\nnot ok 1 - Unresolved\n"), "{report}");
+    assert!(report.contains("  request_id: rq_unresolved\n"), "{report}");
+}
+
+#[test]
+fn send_layers_cli_variables_above_environments() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+
+    let db_query_manager = query_manager(data_dir);
+    let db = db_query_manager.connect();
+    let mut base_environment =
+        db.get_base_environment("wk_test").expect("Failed to get base environment");
+    base_environment.variables = vec![EnvironmentVariable {
+        enabled: true,
+        name: "base_url".to_string(),
+        value: "http://127.0.0.1:9".to_string(),
+        id: None,
+    }];
+    db.upsert_environment(&base_environment, &UpdateSource::Sync)
+        .expect("Failed to seed environment");
+
+    let server = TestHttpServer::spawn_ok("layered");
+    let request = HttpRequest {
+        id: "rq_layered".to_string(),
+        workspace_id: "wk_test".to_string(),
+        method: "GET".to_string(),
+        url: "${[ base_url ]}".to_string(),
+        ..Default::default()
+    };
+    db.upsert_http_request(&request, &UpdateSource::Sync).expect("Failed to seed request");
+
+    let env_file = temp_dir.path().join("ci.env");
+    std::fs::write(&env_file, "# Overridden by --var\nexport base_url=\"http://127.0.0.1:9\"\n")
+        .expect("Failed to write env file");
+
+    cli_cmd(data_dir)
+        .args(["send", "rq_layered", "--env-file"])
+        .arg(&env_file)
+        .arg("--var")
+        .arg(format!("base_url={}", server.url))
+        .assert()
+        .success()
+        .stdout(contains("layered"));
+
+    let stored = db.get_environment(&base_environment.id).expect("Failed to get environment");
+    assert_eq!(stored.variables[0].value, "http://127.0.0.1:9");
+}
+
+#[test]
+fn send_rejects_malformed_var() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+
+    cli_cmd(data_dir)
+        .args(["send", "rq_any", "--var", "no_equals_sign"])
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(contains("Invalid --var 'no_equals_sign': expected NAME=VALUE"));
+}
@@ -1,8 +1,88 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { ModelChangeEvent } from "./ModelChangeEvent";
 
+/**
+ * Pauses matching traffic so it can be inspected and edited before it goes on. Empty
+ * `method`, `host` and `path` match anything; `host` and `path` take `*` wildcards.
+ */
+export type Breakpoint = { id: string, createdAt: string, updatedAt: string, enabled: boolean, method: string, host: string, path: string, onRequest: boolean, onResponse: boolean, };
+
+export type CaptureMode = "include" | "exclude";
+
+/**
+ * Decides which hosts have their TLS intercepted, and which exchanges are recorded. A host
+ * or exchange gets through when no exclude rule matches it and, if there are include rules,
+ * one of them does. Matches like [`Breakpoint`], except that intercept rules only look at
+ * the host.
+ */
+export type CaptureRule = { id: string, createdAt: string, updatedAt: string, enabled: boolean, scope: CaptureScope, mode: CaptureMode, method: string, host: string, path: string, };
+
+export type CaptureScope = "intercept" | "record";
+
 export type HttpExchange = { id: string, createdAt: string, updatedAt: string, url: string, method: string, reqHeaders: Array<ProxyHeader>, reqBody: Array<number> | null, resStatus: number | null, resHeaders: Array<ProxyHeader>, resBody: Array<number> | null, error: string | null, };
 
 export type ModelPayload = { model: HttpExchange, change: ModelChangeEvent, };
 
 export type ProxyHeader = { name: string, value: string, };
+
+export type ProxySetting = { "type": "enabled", http: string, https: string, auth: ProxySettingAuth | null, bypass: string, disabled: boolean, 
+/**
+ * A `socks5://` or `socks5h://` proxy for traffic the other two don't cover. With
+ * `socks5h`, the proxy resolves host names.
+ */
+socks: string, } | { "type": "disabled" };
+
+export type ProxySettingAuth = { user: string, password: string, };
+
+/**
+ * Changes matching traffic in flight, without pausing it. Matches like [`Breakpoint`]; the
+ * rules apply in the order they were created.
+ */
+export type RewriteRule = { id: string, createdAt: string, updatedAt: string, enabled: boolean, name: string, method: string, host: string, path: string, action: RewriteRuleAction, };
+
+export type RewriteRuleAction = { "type": "add_header", target: RewriteTarget, name: string, value: string, } | { "type": "set_header", target: RewriteTarget, name: string, value: string, } | { "type": "remove_header", target: RewriteTarget, name: string, } | { "type": "replace_body", target: RewriteTarget, pattern: string, replacement: string, } | { "type": "map_remote", url: string, } | { "type": "map_local", path: string, content_type: string, };
+
+export type RewriteTarget = "request" | "response";
+
+/**
+ * The proxy's own settings. There is only ever the one row, with the id `default`.
+ */
+export type Settings = { id: string, createdAt: string, updatedAt: string, 
+/**
+ * Another proxy to send traffic on through, as the client app configures its own
+ */
+upstreamProxy: ProxySetting | null, };
+
+/**
+ * An event of a `text/event-stream` response, recorded as it arrived. Belongs to the
+ * exchange whose response it was part of.
+ */
+export type SseEvent = { id: string, createdAt: string, updatedAt: string, exchangeId: string, 
+/**
+ * Zero-based position in the stream
+ */
+index: number, eventType: string, data: string, 
+/**
+ * The last event ID in effect when this event was dispatched
+ */
+eventId: string | null, 
+/**
+ * Reconnection delay in milliseconds, when the event set one
+ */
+retry: number | null, };
+
+/**
+ * A frame of a WebSocket connection, recorded as it passed through. Belongs to the exchange
+ * that upgraded the connection.
+ */
+export type WebsocketFrame = { id: string, createdAt: string, updatedAt: string, exchangeId: string, 
+/**
+ * Zero-based position on the connection, counting both directions
+ */
+index: number, isServer: boolean, opcode: WebsocketOpcode, 
+/**
+ * Unmasked, and never compressed, since the proxy doesn't let connections negotiate it
+ */
+payload: Array<number>, };
+
+export type WebsocketOpcode = "continuation" | "text" | "binary" | "close" | "ping" | "pong" | "other";
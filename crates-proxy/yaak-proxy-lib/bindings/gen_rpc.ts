@@ -1,28 +1,119 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
-import type { HttpExchange, ModelPayload } from "./gen_models";
+import type { Breakpoint, CaptureRule, HttpExchange, ModelPayload, ProxyHeader, RewriteRule, Settings, SseEvent, WebsocketFrame } from "./gen_models";
 
 export type ActionInvocation = { "scope": "global", action: GlobalAction, };
 
 export type ActionMetadata = { label: string, defaultHotkey: string | null, };
 
+/**
+ * What to do with a paused message.
+ */
+export type BreakpointResolution = { "type": "continue_request", request: EditableRequest, } | { "type": "continue_response", response: EditableResponse, } | { "type": "respond", response: EditableResponse, } | { "type": "drop" };
+
+export type BreakpointResumedPayload = { id: number, };
+
+/**
+ * How an exported root certificate is encoded.
+ */
+export type CaCertificateFormat = "pem" | "der";
+
+export type DeleteBreakpointRequest = { id: string, };
+
+export type DeleteCaptureRuleRequest = { id: string, };
+
+export type DeleteRewriteRuleRequest = { id: string, };
+
+export type EditableRequest = { method: string, url: string, headers: Array<ProxyHeader>, body: Array<number>, };
+
+export type EditableResponse = { status: number, headers: Array<ProxyHeader>, body: Array<number>, };
+
+export type ExportCaCertificateRequest = { 
+/**
+ * Where to write the certificate. An existing file is replaced.
+ */
+path: string, format: CaCertificateFormat, };
+
+export type ExportCaCertificateResponse = { path: string, };
+
+export type ExportHarRequest = { exchangeIds: Array<string>, 
+/**
+ * Where to write the HAR file. An existing file is replaced.
+ */
+path: string, };
+
+export type ExportHarResponse = { path: string, count: number, };
+
+export type ExportHttpRequestsRequest = { exchangeIds: Array<string>, 
+/**
+ * Where to write the Yaak export, for the client app to import
+ */
+path: string, 
+/**
+ * Put the origin of hosts with several requests in a folder environment variable
+ */
+parameterizeHosts: boolean, };
+
+export type ExportHttpRequestsResponse = { path: string, count: number, };
+
+export type GetCaCertificateRequest = Record<string, never>;
+
+export type GetCaCertificateResponse = { pem: string, };
+
 export type GetProxyStateRequest = Record<string, never>;
 
 export type GetProxyStateResponse = { state: ProxyState, };
 
-export type GlobalAction = "proxy_start" | "proxy_stop";
+export type GetSettingsRequest = Record<string, never>;
+
+export type GlobalAction = "proxy_start" | "proxy_stop" | "ca_rotate";
+
+export type ImportHarRequest = { path: string, };
+
+export type ImportHarResponse = { count: number, };
 
 export type ListActionsRequest = Record<string, never>;
 
 export type ListActionsResponse = { actions: Array<[ActionInvocation, ActionMetadata]>, };
 
+export type ListBreakpointsRequest = Record<string, never>;
+
+export type ListBreakpointsResponse = { breakpoints: Array<Breakpoint>, };
+
+export type ListCaptureRulesRequest = Record<string, never>;
+
+export type ListCaptureRulesResponse = { captureRules: Array<CaptureRule>, };
+
 export type ListModelsRequest = Record<string, never>;
 
 export type ListModelsResponse = { httpExchanges: Array<HttpExchange>, };
 
+export type ListPausedRequest = Record<string, never>;
+
+export type ListPausedResponse = { paused: Array<PausedMessage>, };
+
+export type ListRewriteRulesRequest = Record<string, never>;
+
+export type ListRewriteRulesResponse = { rewriteRules: Array<RewriteRule>, };
+
+export type ListSseEventsRequest = { exchangeId: string, };
+
+export type ListSseEventsResponse = { sseEvents: Array<SseEvent>, };
+
+export type ListWebsocketFramesRequest = { exchangeId: string, };
+
+export type ListWebsocketFramesResponse = { websocketFrames: Array<WebsocketFrame>, };
+
+/**
+ * A request or response a breakpoint is holding.
+ */
+export type PausedMessage = { "stage": "request", id: number, request: EditableRequest, } | { "stage": "response", id: number, method: string, url: string, response: EditableResponse, };
+
 export type ProxyState = "running" | "stopped";
 
 export type ProxyStatePayload = { state: ProxyState, };
 
-export type RpcEventSchema = { model_write: ModelPayload, proxy_state_changed: ProxyStatePayload, };
+export type ResolveBreakpointRequest = { id: number, resolution: BreakpointResolution, };
+
+export type RpcEventSchema = { breakpoint_paused: PausedMessage, breakpoint_resumed: BreakpointResumedPayload, model_write: ModelPayload, proxy_state_changed: ProxyStatePayload, sse_event: SseEvent, websocket_frame: WebsocketFrame, };
 
-export type RpcSchema = { execute_action: [ActionInvocation, boolean], get_proxy_state: [GetProxyStateRequest, GetProxyStateResponse], list_actions: [ListActionsRequest, ListActionsResponse], list_models: [ListModelsRequest, ListModelsResponse], };
+export type RpcSchema = { delete_breakpoint: [DeleteBreakpointRequest, boolean], delete_capture_rule: [DeleteCaptureRuleRequest, boolean], delete_rewrite_rule: [DeleteRewriteRuleRequest, boolean], execute_action: [ActionInvocation, boolean], export_ca_certificate: [ExportCaCertificateRequest, ExportCaCertificateResponse], export_har: [ExportHarRequest, ExportHarResponse], export_http_requests: [ExportHttpRequestsRequest, ExportHttpRequestsResponse], get_ca_certificate: [GetCaCertificateRequest, GetCaCertificateResponse], get_proxy_state: [GetProxyStateRequest, GetProxyStateResponse], get_settings: [GetSettingsRequest, Settings], import_har: [ImportHarRequest, ImportHarResponse], list_actions: [ListActionsRequest, ListActionsResponse], list_breakpoints: [ListBreakpointsRequest, ListBreakpointsResponse], list_capture_rules: [ListCaptureRulesRequest, ListCaptureRulesResponse], list_models: [ListModelsRequest, ListModelsResponse], list_paused: [ListPausedRequest, ListPausedResponse], list_rewrite_rules: [ListRewriteRulesRequest, ListRewriteRulesResponse], list_sse_events: [ListSseEventsRequest, ListSseEventsResponse], list_websocket_frames: [ListWebsocketFramesRequest, ListWebsocketFramesResponse], resolve_breakpoint: [ResolveBreakpointRequest, boolean], update_settings: [Settings, Settings], upsert_breakpoint: [Breakpoint, Breakpoint], upsert_capture_rule: [CaptureRule, CaptureRule], upsert_rewrite_rule: [RewriteRule, RewriteRule], };
@@ -0,0 +1,8 @@
+CREATE TABLE root_certificates
+(
+    id            TEXT NOT NULL PRIMARY KEY,
+    created_at    DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at    DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    cert_pem      TEXT NOT NULL DEFAULT '',
+    encrypted_key BLOB NOT NULL
+);
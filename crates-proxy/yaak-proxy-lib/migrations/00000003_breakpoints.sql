@@ -0,0 +1,12 @@
+CREATE TABLE breakpoints
+(
+    id          TEXT    NOT NULL PRIMARY KEY,
+    created_at  DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at  DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    enabled     BOOLEAN NOT NULL DEFAULT TRUE,
+    method      TEXT    NOT NULL DEFAULT '',
+    host        TEXT    NOT NULL DEFAULT '',
+    path        TEXT    NOT NULL DEFAULT '',
+    on_request  BOOLEAN NOT NULL DEFAULT TRUE,
+    on_response BOOLEAN NOT NULL DEFAULT FALSE
+);
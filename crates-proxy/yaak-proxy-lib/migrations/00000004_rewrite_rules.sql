@@ -0,0 +1,12 @@
+CREATE TABLE rewrite_rules
+(
+    id         TEXT    NOT NULL PRIMARY KEY,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    enabled    BOOLEAN NOT NULL DEFAULT TRUE,
+    name       TEXT    NOT NULL DEFAULT '',
+    method     TEXT    NOT NULL DEFAULT '',
+    host       TEXT    NOT NULL DEFAULT '',
+    path       TEXT    NOT NULL DEFAULT '',
+    action     TEXT    NOT NULL DEFAULT '{}'
+);
@@ -0,0 +1,12 @@
+CREATE TABLE capture_rules
+(
+    id         TEXT    NOT NULL PRIMARY KEY,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    enabled    BOOLEAN NOT NULL DEFAULT TRUE,
+    scope      TEXT    NOT NULL DEFAULT 'intercept',
+    mode       TEXT    NOT NULL DEFAULT 'include',
+    method     TEXT    NOT NULL DEFAULT '',
+    host       TEXT    NOT NULL DEFAULT '',
+    path       TEXT    NOT NULL DEFAULT ''
+);
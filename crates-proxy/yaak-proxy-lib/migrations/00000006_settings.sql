@@ -0,0 +1,7 @@
+CREATE TABLE settings
+(
+    id             TEXT NOT NULL PRIMARY KEY,
+    created_at     DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at     DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    upstream_proxy TEXT
+);
@@ -0,0 +1,28 @@
+CREATE TABLE websocket_frames
+(
+    id          TEXT    NOT NULL PRIMARY KEY,
+    created_at  DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at  DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    exchange_id TEXT    NOT NULL REFERENCES http_exchanges (id) ON DELETE CASCADE,
+    "index"     INTEGER NOT NULL DEFAULT 0,
+    is_server   BOOLEAN NOT NULL DEFAULT FALSE,
+    opcode      TEXT    NOT NULL DEFAULT 'text',
+    payload     BLOB    NOT NULL
+);
+
+CREATE INDEX idx_websocket_frames_exchange_id ON websocket_frames (exchange_id);
+
+CREATE TABLE sse_events
+(
+    id          TEXT    NOT NULL PRIMARY KEY,
+    created_at  DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at  DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    exchange_id TEXT    NOT NULL REFERENCES http_exchanges (id) ON DELETE CASCADE,
+    "index"     INTEGER NOT NULL DEFAULT 0,
+    event_type  TEXT    NOT NULL DEFAULT '',
+    data        TEXT    NOT NULL DEFAULT '',
+    event_id    TEXT,
+    retry       INTEGER
+);
+
+CREATE INDEX idx_sse_events_exchange_id ON sse_events (exchange_id);
@@ -7,6 +7,7 @@ use ts_rs::TS;
 pub enum GlobalAction {
     ProxyStart,
     ProxyStop,
+    CaRotate,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -45,5 +46,9 @@ pub fn all_global_actions() -> Vec<(ActionInvocation, ActionMetadata)> {
                 default_hotkey: default_hotkey("Meta+Shift+S", "Ctrl+Shift+S"),
             },
         ),
+        (
+            ActionInvocation::Global { action: GlobalAction::CaRotate },
+            ActionMetadata { label: "Rotate CA Certificate".into(), default_hotkey: None },
+        ),
     ]
 }
@@ -0,0 +1,137 @@
+//! Breakpoint rules and paused traffic, as the frontend sees them.
+
+use crate::db::ProxyQueryManager;
+use crate::models::{Breakpoint, ProxyHeader};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use yaak_proxy::breakpoint::{
+    BreakpointRule, Breakpoints, InterceptedRequest, InterceptedResponse, Paused, Resolution,
+};
+use yaak_proxy::pattern::UrlPattern;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct EditableRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<ProxyHeader>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct EditableResponse {
+    pub status: u16,
+    pub headers: Vec<ProxyHeader>,
+    pub body: Vec<u8>,
+}
+
+/// A request or response a breakpoint is holding.
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub enum PausedMessage {
+    Request {
+        #[ts(type = "number")]
+        id: u64,
+        request: EditableRequest,
+    },
+    Response {
+        #[ts(type = "number")]
+        id: u64,
+        method: String,
+        url: String,
+        response: EditableResponse,
+    },
+}
+
+/// What to do with a paused message.
+#[derive(Debug, Clone, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub enum BreakpointResolution {
+    /// Send the paused request upstream, as edited
+    ContinueRequest { request: EditableRequest },
+    /// Send the paused response to the client, as edited
+    ContinueResponse { response: EditableResponse },
+    /// Answer the client with this response instead
+    Respond { response: EditableResponse },
+    /// Close the client's connection without answering
+    Drop,
+}
+
+/// Give the proxy the enabled breakpoints in the database.
+pub fn load_rules(db: &ProxyQueryManager, breakpoints: &Breakpoints) -> Result<(), String> {
+    let saved = db
+        .with_conn(|ctx| ctx.find_all::<Breakpoint>())
+        .map_err(|e| format!("Failed to read breakpoints: {e}"))?;
+    let rules = saved
+        .into_iter()
+        .filter(|b| b.enabled)
+        .map(|b| BreakpointRule {
+            pattern: UrlPattern { method: b.method, host: b.host, path: b.path },
+            on_request: b.on_request,
+            on_response: b.on_response,
+        })
+        .collect();
+    breakpoints.set_rules(rules);
+    Ok(())
+}
+
+pub fn paused_message(id: u64, paused: Paused) -> PausedMessage {
+    match paused {
+        Paused::Request(request) => PausedMessage::Request { id, request: request.into() },
+        Paused::Response { method, url, response } => {
+            PausedMessage::Response { id, method, url, response: response.into() }
+        }
+    }
+}
+
+impl From<BreakpointResolution> for Resolution {
+    fn from(value: BreakpointResolution) -> Self {
+        match value {
+            BreakpointResolution::ContinueRequest { request } => {
+                Resolution::ContinueRequest(request.into())
+            }
+            BreakpointResolution::ContinueResponse { response } => {
+                Resolution::ContinueResponse(response.into())
+            }
+            BreakpointResolution::Respond { response } => Resolution::Respond(response.into()),
+            BreakpointResolution::Drop => Resolution::Drop,
+        }
+    }
+}
+
+impl From<InterceptedRequest> for EditableRequest {
+    fn from(r: InterceptedRequest) -> Self {
+        Self { method: r.method, url: r.url, headers: to_headers(r.headers), body: r.body }
+    }
+}
+
+impl From<EditableRequest> for InterceptedRequest {
+    fn from(r: EditableRequest) -> Self {
+        Self { method: r.method, url: r.url, headers: from_headers(r.headers), body: r.body }
+    }
+}
+
+impl From<InterceptedResponse> for EditableResponse {
+    fn from(r: InterceptedResponse) -> Self {
+        Self { status: r.status, headers: to_headers(r.headers), body: r.body }
+    }
+}
+
+impl From<EditableResponse> for InterceptedResponse {
+    fn from(r: EditableResponse) -> Self {
+        Self { status: r.status, headers: from_headers(r.headers), body: r.body }
+    }
+}
+
+fn to_headers(headers: Vec<(String, String)>) -> Vec<ProxyHeader> {
+    headers.into_iter().map(|(name, value)| ProxyHeader { name, value }).collect()
+}
+
+fn from_headers(headers: Vec<ProxyHeader>) -> Vec<(String, String)> {
+    headers.into_iter().map(|h| (h.name, h.value)).collect()
+}
@@ -0,0 +1,105 @@
+//! The root certificate the proxy issues its TLS certificates from.
+//!
+//! Clients have to be told to trust the root before they accept intercepted TLS, so it is
+//! kept across launches rather than generated on every start. The certificate is stored in
+//! the proxy database as-is; its key is encrypted with the app's master key, which lives in
+//! the OS keychain, so a copy of the database is not enough to impersonate sites.
+
+use crate::db::ProxyQueryManager;
+use crate::models::RootCertificate;
+use log::info;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use yaak_crypto::master_key::MasterKey;
+use yaak_database::UpdateSource;
+use yaak_proxy::cert::CertificateAuthority;
+
+const KEY_USER: &str = "encryption-key";
+
+/// How an exported root certificate is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub enum CaCertificateFormat {
+    Pem,
+    Der,
+}
+
+/// The saved root, or a newly saved one when there is none yet.
+pub fn load_or_create(
+    db: &ProxyQueryManager,
+    app_id: &str,
+) -> Result<CertificateAuthority, String> {
+    let master_key = master_key(app_id)?;
+    let saved = db
+        .with_conn(|ctx| ctx.find_all::<RootCertificate>())
+        .map_err(|e| format!("Failed to read the CA: {e}"))?;
+
+    let Some(saved) = saved.into_iter().next() else {
+        return create(db, &master_key);
+    };
+
+    let key_pem = master_key
+        .decrypt(&saved.encrypted_key)
+        .map_err(|e| format!("Failed to decrypt the CA key: {e}"))?;
+    let key_pem = String::from_utf8(key_pem).map_err(|e| format!("Invalid CA key: {e}"))?;
+    CertificateAuthority::from_pem(&saved.cert_pem, &key_pem)
+        .map_err(|e| format!("Failed to load the CA: {e}"))
+}
+
+/// Replace the saved root with a new one. Clients must trust the new root again.
+pub fn rotate(db: &ProxyQueryManager, app_id: &str) -> Result<CertificateAuthority, String> {
+    let master_key = master_key(app_id)?;
+    let ca = create(db, &master_key)?;
+    let current = ca.ca_pem();
+    db.with_conn(|ctx| -> yaak_database::Result<()> {
+        for old in ctx.find_all::<RootCertificate>()? {
+            if old.cert_pem != current {
+                ctx.delete(&old)?;
+            }
+        }
+        Ok(())
+    })
+    .map_err(|e| format!("Failed to remove the old CA: {e}"))?;
+    Ok(ca)
+}
+
+/// `ca`'s certificate, encoded as `format`.
+pub fn export(ca: &CertificateAuthority, format: CaCertificateFormat) -> Vec<u8> {
+    match format {
+        CaCertificateFormat::Pem => ca.ca_pem().into_bytes(),
+        CaCertificateFormat::Der => ca.ca_der(),
+    }
+}
+
+fn create(db: &ProxyQueryManager, master_key: &MasterKey) -> Result<CertificateAuthority, String> {
+    info!("Creating proxy CA");
+    let ca = CertificateAuthority::new().map_err(|e| format!("Failed to create CA: {e}"))?;
+    let encrypted_key = master_key
+        .encrypt(ca.key_pem().as_bytes())
+        .map_err(|e| format!("Failed to encrypt the CA key: {e}"))?;
+    let record = RootCertificate { cert_pem: ca.ca_pem(), encrypted_key, ..Default::default() };
+    db.with_conn(|ctx| ctx.upsert(&record, &UpdateSource::Background))
+        .map_err(|e| format!("Failed to save the CA: {e}"))?;
+    Ok(ca)
+}
+
+fn master_key(app_id: &str) -> Result<MasterKey, String> {
+    MasterKey::get_or_create(app_id, KEY_USER).map_err(|e| format!("Failed to get master key: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_a_root_that_loads_back() {
+        let ca = CertificateAuthority::new().unwrap();
+        let pem = String::from_utf8(export(&ca, CaCertificateFormat::Pem)).unwrap();
+        let reloaded = CertificateAuthority::from_pem(&pem, &ca.key_pem()).unwrap();
+        assert_eq!(
+            export(&reloaded, CaCertificateFormat::Der),
+            export(&ca, CaCertificateFormat::Der)
+        );
+    }
+}
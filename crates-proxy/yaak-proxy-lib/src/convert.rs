@@ -0,0 +1,76 @@
+//! Turning captured exchanges into requests for the client app.
+//!
+//! The conversion itself lives in [`yaak_models::captured`], shared with HAR imports; this
+//! only wraps the exchanges' requests in a regular Yaak export for the client's importer.
+
+use crate::models::HttpExchange;
+use chrono::Utc;
+use yaak_models::captured::{CapturedHttpRequest, captured_to_resources};
+use yaak_models::util::WorkspaceExport;
+
+impl From<HttpExchange> for CapturedHttpRequest {
+    fn from(exchange: HttpExchange) -> Self {
+        CapturedHttpRequest {
+            method: exchange.method,
+            url: exchange.url,
+            headers: exchange.req_headers.into_iter().map(|h| (h.name, h.value)).collect(),
+            body: exchange.req_body,
+        }
+    }
+}
+
+/// Build an importable export from `exchanges`, oldest first; see [`captured_to_resources`].
+pub fn to_workspace_export(
+    exchanges: &[HttpExchange],
+    parameterize_hosts: bool,
+) -> WorkspaceExport {
+    let mut exchanges = exchanges.to_vec();
+    exchanges.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    let requests = exchanges.into_iter().map(CapturedHttpRequest::from).collect();
+
+    WorkspaceExport {
+        yaak_version: String::new(),
+        yaak_schema: 4,
+        timestamp: Utc::now().naive_utc(),
+        resources: captured_to_resources(requests, parameterize_hosts),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProxyHeader;
+    use chrono::{Duration, NaiveDateTime};
+
+    fn exchange(url: &str, created_at: NaiveDateTime) -> HttpExchange {
+        HttpExchange {
+            created_at,
+            url: url.into(),
+            method: "POST".into(),
+            req_headers: vec![ProxyHeader { name: "X-Trace".into(), value: "1".into() }],
+            req_body: Some(b"hello".to_vec()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn exports_the_requests_oldest_first() {
+        let now = Utc::now().naive_utc();
+        let export = to_workspace_export(
+            &[
+                exchange("https://api.test/second", now),
+                exchange("https://api.test/first", now - Duration::seconds(1)),
+            ],
+            false,
+        );
+
+        let requests = &export.resources.http_requests;
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].url, "https://api.test/first");
+        assert_eq!(requests[1].url, "https://api.test/second");
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].headers[0].name, "X-Trace");
+        assert_eq!(requests[0].body["text"], "hello");
+    }
+}
@@ -0,0 +1,30 @@
+//! Capture rules, as saved, turned into the filter the proxy applies.
+
+use crate::db::ProxyQueryManager;
+use crate::models::{CaptureMode, CaptureRule, CaptureScope};
+use yaak_proxy::filter::{CaptureFilter, FilterMode, FilterRule, FilterScope};
+use yaak_proxy::pattern::UrlPattern;
+
+/// Give the proxy the enabled capture rules in the database.
+pub fn load_rules(db: &ProxyQueryManager, filter: &CaptureFilter) -> Result<(), String> {
+    let saved = db
+        .with_conn(|ctx| ctx.find_all::<CaptureRule>())
+        .map_err(|e| format!("Failed to read capture rules: {e}"))?;
+    let rules = saved
+        .into_iter()
+        .filter(|r| r.enabled)
+        .map(|r| FilterRule {
+            scope: match r.scope {
+                CaptureScope::Intercept => FilterScope::Intercept,
+                CaptureScope::Record => FilterScope::Record,
+            },
+            mode: match r.mode {
+                CaptureMode::Include => FilterMode::Include,
+                CaptureMode::Exclude => FilterMode::Exclude,
+            },
+            pattern: UrlPattern { method: r.method, host: r.host, path: r.path },
+        })
+        .collect();
+    filter.set_rules(rules);
+    Ok(())
+}
@@ -0,0 +1,119 @@
+//! HAR export and import of captured exchanges.
+//!
+//! The proxy doesn't time the phases of an exchange, so exported timings are left unknown.
+
+use crate::models::{HttpExchange, ProxyHeader};
+use yaak_har::{
+    Content, Creator, Entry, Har, Header, PostData, Request, Response, format_timestamp,
+    parse_timestamp, query_string,
+};
+
+/// A HAR document of `exchanges`, oldest first.
+pub fn to_har(exchanges: &[HttpExchange], version: &str) -> Har {
+    let mut exchanges = exchanges.to_vec();
+    exchanges.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    let creator = Creator { name: "Yaak Proxy".to_string(), version: version.to_string() };
+    Har::new(creator, exchanges.iter().map(to_entry).collect())
+}
+
+/// The exchanges in `har`, as new rows that keep the times they were captured at.
+pub fn from_har(har: &Har) -> Vec<HttpExchange> {
+    har.log
+        .entries
+        .iter()
+        .map(|e| HttpExchange {
+            created_at: parse_timestamp(&e.started_date_time).unwrap_or_default(),
+            url: e.request.url.clone(),
+            method: e.request.method.clone(),
+            req_headers: from_headers(&e.request.headers),
+            req_body: e.request.body(),
+            // Browsers record requests that never got an answer with a status of 0
+            res_status: Some(e.response.status as i32).filter(|s| *s > 0),
+            res_headers: from_headers(&e.response.headers),
+            res_body: e.response.content.bytes().filter(|b| !b.is_empty()),
+            error: e.comment.clone(),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn to_entry(exchange: &HttpExchange) -> Entry {
+    let request_content_type = find_header(&exchange.req_headers, "content-type");
+    let response_content_type = find_header(&exchange.res_headers, "content-type");
+    let response_body = exchange.res_body.as_deref().unwrap_or_default();
+
+    Entry {
+        started_date_time: format_timestamp(exchange.created_at),
+        request: Request {
+            method: exchange.method.clone(),
+            url: exchange.url.clone(),
+            headers: to_headers(&exchange.req_headers),
+            query_string: query_string(&exchange.url),
+            body_size: exchange.req_body.as_ref().map(|b| b.len() as i64).unwrap_or(0),
+            post_data: exchange
+                .req_body
+                .as_ref()
+                .map(|b| PostData::from_bytes(request_content_type, b)),
+            ..Default::default()
+        },
+        response: Response {
+            status: exchange.res_status.unwrap_or(0) as i64,
+            headers: to_headers(&exchange.res_headers),
+            redirect_url: find_header(&exchange.res_headers, "location").to_string(),
+            content: Content::from_bytes(response_content_type, response_body),
+            ..Default::default()
+        },
+        comment: exchange.error.clone(),
+        ..Default::default()
+    }
+}
+
+fn to_headers(headers: &[ProxyHeader]) -> Vec<Header> {
+    headers.iter().map(|h| Header { name: h.name.clone(), value: h.value.clone() }).collect()
+}
+
+fn from_headers(headers: &[Header]) -> Vec<ProxyHeader> {
+    headers.iter().map(|h| ProxyHeader { name: h.name.clone(), value: h.value.clone() }).collect()
+}
+
+fn find_header<'a>(headers: &'a [ProxyHeader], name: &str) -> &'a str {
+    headers
+        .iter()
+        .find(|h| h.name.eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str())
+        .unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_exchanges() {
+        let exchange = HttpExchange {
+            created_at: parse_timestamp("2024-05-06T07:08:09.010Z").unwrap(),
+            url: "https://api.test/users?page=2".into(),
+            method: "POST".into(),
+            req_headers: vec![ProxyHeader {
+                name: "Content-Type".into(),
+                value: "text/plain".into(),
+            }],
+            req_body: Some(vec![0xff, 0x00]),
+            res_status: Some(201),
+            res_headers: vec![ProxyHeader { name: "Location".into(), value: "/users/1".into() }],
+            res_body: Some(b"ok".to_vec()),
+            ..Default::default()
+        };
+
+        let har = to_har(&[exchange.clone()], "1.0.0");
+        assert_eq!(har.log.entries[0].response.redirect_url, "/users/1");
+
+        let har = Har::parse(&serde_json::to_string(&har).unwrap()).unwrap();
+        let imported = from_har(&har).remove(0);
+        assert_eq!(imported.created_at, exchange.created_at);
+        assert_eq!(imported.req_body, exchange.req_body);
+        assert_eq!(imported.res_status, Some(201));
+        assert_eq!(imported.res_body, exchange.res_body);
+        assert_eq!(imported.req_headers[0].value, "text/plain");
+    }
+}
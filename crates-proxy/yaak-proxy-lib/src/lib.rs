@@ -1,18 +1,35 @@
 pub mod actions;
+pub mod breakpoints;
+pub mod ca;
+pub mod convert;
 pub mod db;
+pub mod filters;
+pub mod har;
 pub mod models;
+pub mod rewrites;
+pub mod upstream;
 
 use crate::actions::{ActionInvocation, ActionMetadata, GlobalAction};
+use crate::breakpoints::{BreakpointResolution, PausedMessage, paused_message};
+use crate::ca::CaCertificateFormat;
 use crate::db::ProxyQueryManager;
-use crate::models::{HttpExchange, ModelPayload, ProxyHeader};
+use crate::models::{
+    Breakpoint, CaptureRule, HttpExchange, ModelPayload, ProxyHeader, RewriteRule, Settings,
+    SseEvent, SseEventIden, WebsocketFrame, WebsocketFrameIden,
+};
 use log::warn;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 use ts_rs::TS;
-use yaak_database::{ModelChangeEvent, UpdateSource};
-use yaak_proxy::{CapturedRequest, ProxyEvent, ProxyHandle, RequestState};
+use yaak_database::{ModelChangeEvent, UpdateSource, UpsertModelInfo};
+use yaak_proxy::breakpoint::Breakpoints;
+use yaak_proxy::cert::CertificateAuthority;
+use yaak_proxy::filter::CaptureFilter;
+use yaak_proxy::rewrite::Rewrites;
+use yaak_proxy::upstream::UpstreamProxy;
+use yaak_proxy::{CapturedRequest, ProxyEvent, ProxyHandle, ProxyOptions, RequestState};
 use yaak_rpc::{RpcError, RpcEventEmitter, define_rpc};
 
 // -- Context --
@@ -21,13 +38,58 @@ use yaak_rpc::{RpcError, RpcEventEmitter, define_rpc};
 #[derive(Clone)]
 pub struct ProxyCtx {
     handle: Arc<Mutex<Option<ProxyHandle>>>,
+    ca: Arc<Mutex<Option<Arc<CertificateAuthority>>>>,
+    breakpoints: Arc<Breakpoints>,
+    rewrites: Arc<Rewrites>,
+    filter: Arc<CaptureFilter>,
+    upstream: Arc<UpstreamProxy>,
+    app_id: String,
     pub db: ProxyQueryManager,
     pub events: RpcEventEmitter,
 }
 
 impl ProxyCtx {
-    pub fn new(db_path: &Path, events: RpcEventEmitter) -> Self {
-        Self { handle: Arc::new(Mutex::new(None)), db: ProxyQueryManager::new(db_path), events }
+    pub fn new(db_path: &Path, app_id: impl Into<String>, events: RpcEventEmitter) -> Self {
+        let db = ProxyQueryManager::new(db_path);
+        let breakpoints = Arc::new(Breakpoints::default());
+        if let Err(e) = breakpoints::load_rules(&db, &breakpoints) {
+            warn!("{e}");
+        }
+        let rewrites = Arc::new(Rewrites::default());
+        if let Err(e) = rewrites::load_rules(&db, &rewrites) {
+            warn!("{e}");
+        }
+        let filter = Arc::new(CaptureFilter::default());
+        if let Err(e) = filters::load_rules(&db, &filter) {
+            warn!("{e}");
+        }
+        let upstream = Arc::new(UpstreamProxy::default());
+        upstream::load_settings(&db, &upstream);
+        Self {
+            handle: Arc::new(Mutex::new(None)),
+            ca: Arc::new(Mutex::new(None)),
+            breakpoints,
+            rewrites,
+            filter,
+            upstream,
+            app_id: app_id.into(),
+            db,
+            events,
+        }
+    }
+
+    /// The root CA, loaded from the database (or created) the first time it is needed, which
+    /// is also when the keychain is first touched.
+    fn ca(&self) -> Result<Arc<CertificateAuthority>, RpcError> {
+        let mut ca = self.ca.lock().map_err(|_| RpcError { message: "lock poisoned".into() })?;
+        if let Some(ca) = ca.as_ref() {
+            return Ok(ca.clone());
+        }
+        let loaded = Arc::new(
+            ca::load_or_create(&self.db, &self.app_id).map_err(|e| RpcError { message: e })?,
+        );
+        *ca = Some(loaded.clone());
+        Ok(loaded)
     }
 }
 
@@ -70,6 +132,184 @@ pub struct ListModelsResponse {
     pub http_exchanges: Vec<HttpExchange>,
 }
 
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct ListWebsocketFramesRequest {
+    pub exchange_id: String,
+}
+
+#[derive(Serialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct ListWebsocketFramesResponse {
+    pub websocket_frames: Vec<WebsocketFrame>,
+}
+
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct ListSseEventsRequest {
+    pub exchange_id: String,
+}
+
+#[derive(Serialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct ListSseEventsResponse {
+    pub sse_events: Vec<SseEvent>,
+}
+
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct GetCaCertificateRequest {}
+
+#[derive(Serialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct GetCaCertificateResponse {
+    pub pem: String,
+}
+
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct ExportCaCertificateRequest {
+    /// Where to write the certificate. An existing file is replaced.
+    pub path: String,
+    pub format: CaCertificateFormat,
+}
+
+#[derive(Serialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct ExportCaCertificateResponse {
+    pub path: String,
+}
+
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct ExportHttpRequestsRequest {
+    pub exchange_ids: Vec<String>,
+    /// Where to write the Yaak export, for the client app to import
+    pub path: String,
+    /// Put the origin of hosts with several requests in a folder environment variable
+    #[serde(default)]
+    pub parameterize_hosts: bool,
+}
+
+#[derive(Serialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct ExportHttpRequestsResponse {
+    pub path: String,
+    pub count: usize,
+}
+
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct ExportHarRequest {
+    pub exchange_ids: Vec<String>,
+    /// Where to write the HAR file. An existing file is replaced.
+    pub path: String,
+}
+
+#[derive(Serialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct ExportHarResponse {
+    pub path: String,
+    pub count: usize,
+}
+
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct ImportHarRequest {
+    pub path: String,
+}
+
+#[derive(Serialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct ImportHarResponse {
+    pub count: usize,
+}
+
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct ListBreakpointsRequest {}
+
+#[derive(Serialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct ListBreakpointsResponse {
+    pub breakpoints: Vec<Breakpoint>,
+}
+
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct DeleteBreakpointRequest {
+    pub id: String,
+}
+
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct ListRewriteRulesRequest {}
+
+#[derive(Serialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct ListRewriteRulesResponse {
+    pub rewrite_rules: Vec<RewriteRule>,
+}
+
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct DeleteRewriteRuleRequest {
+    pub id: String,
+}
+
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct ListCaptureRulesRequest {}
+
+#[derive(Serialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct ListCaptureRulesResponse {
+    pub capture_rules: Vec<CaptureRule>,
+}
+
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct DeleteCaptureRuleRequest {
+    pub id: String,
+}
+
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct GetSettingsRequest {}
+
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct ListPausedRequest {}
+
+#[derive(Serialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct ListPausedResponse {
+    pub paused: Vec<PausedMessage>,
+}
+
+#[derive(Deserialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct ResolveBreakpointRequest {
+    #[ts(type = "number")]
+    pub id: u64,
+    pub resolution: BreakpointResolution,
+}
+
+#[derive(Serialize, TS)]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct BreakpointResumedPayload {
+    #[ts(type = "number")]
+    pub id: u64,
+}
+
 #[derive(Deserialize, TS)]
 #[ts(export, export_to = "gen_rpc.ts")]
 pub struct GetProxyStateRequest {}
@@ -93,16 +333,7 @@ fn execute_action(ctx: &ProxyCtx, invocation: ActionInvocation) -> Result<bool,
                     return Ok(true); // already running
                 }
 
-                let mut proxy_handle =
-                    yaak_proxy::start_proxy(9090).map_err(|e| RpcError { message: e })?;
-
-                if let Some(event_rx) = proxy_handle.take_event_rx() {
-                    let db = ctx.db.clone();
-                    let events = ctx.events.clone();
-                    std::thread::spawn(move || run_event_loop(event_rx, db, events));
-                }
-
-                *handle = Some(proxy_handle);
+                *handle = Some(start_proxy(ctx, ctx.ca()?)?);
                 ctx.events
                     .emit("proxy_state_changed", &ProxyStatePayload { state: ProxyState::Running });
                 Ok(true)
@@ -111,14 +342,266 @@ fn execute_action(ctx: &ProxyCtx, invocation: ActionInvocation) -> Result<bool,
                 let mut handle =
                     ctx.handle.lock().map_err(|_| RpcError { message: "lock poisoned".into() })?;
                 handle.take();
+                // Nothing is left to hold them for
+                ctx.breakpoints.resume_all();
                 ctx.events
                     .emit("proxy_state_changed", &ProxyStatePayload { state: ProxyState::Stopped });
                 Ok(true)
             }
+            GlobalAction::CaRotate => {
+                let mut handle =
+                    ctx.handle.lock().map_err(|_| RpcError { message: "lock poisoned".into() })?;
+                let mut ca =
+                    ctx.ca.lock().map_err(|_| RpcError { message: "lock poisoned".into() })?;
+                let rotated = Arc::new(
+                    ca::rotate(&ctx.db, &ctx.app_id).map_err(|e| RpcError { message: e })?,
+                );
+                *ca = Some(rotated.clone());
+
+                // A running proxy keeps issuing from the old root until it is restarted
+                if handle.take().is_some() {
+                    match start_proxy(ctx, rotated) {
+                        Ok(h) => *handle = Some(h),
+                        Err(e) => {
+                            ctx.events.emit(
+                                "proxy_state_changed",
+                                &ProxyStatePayload { state: ProxyState::Stopped },
+                            );
+                            return Err(e);
+                        }
+                    }
+                }
+                Ok(true)
+            }
         },
     }
 }
 
+fn start_proxy(ctx: &ProxyCtx, ca: Arc<CertificateAuthority>) -> Result<ProxyHandle, RpcError> {
+    let options = ProxyOptions {
+        ca,
+        breakpoints: ctx.breakpoints.clone(),
+        rewrites: ctx.rewrites.clone(),
+        filter: ctx.filter.clone(),
+        upstream: ctx.upstream.clone(),
+    };
+    let mut proxy_handle =
+        yaak_proxy::start_proxy(9090, options).map_err(|e| RpcError { message: e })?;
+
+    if let Some(event_rx) = proxy_handle.take_event_rx() {
+        let db = ctx.db.clone();
+        let events = ctx.events.clone();
+        let filter = ctx.filter.clone();
+        std::thread::spawn(move || run_event_loop(event_rx, db, events, filter));
+    }
+    Ok(proxy_handle)
+}
+
+fn list_breakpoints(
+    ctx: &ProxyCtx,
+    _req: ListBreakpointsRequest,
+) -> Result<ListBreakpointsResponse, RpcError> {
+    ctx.db.with_conn(|db| {
+        Ok(ListBreakpointsResponse {
+            breakpoints: db
+                .find_all::<Breakpoint>()
+                .map_err(|e| RpcError { message: e.to_string() })?,
+        })
+    })
+}
+
+fn upsert_breakpoint(ctx: &ProxyCtx, breakpoint: Breakpoint) -> Result<Breakpoint, RpcError> {
+    let (saved, _) = ctx
+        .db
+        .with_conn(|db| db.upsert(&breakpoint, &UpdateSource::Background))
+        .map_err(|e| RpcError { message: e.to_string() })?;
+    breakpoints::load_rules(&ctx.db, &ctx.breakpoints).map_err(|e| RpcError { message: e })?;
+    Ok(saved)
+}
+
+fn delete_breakpoint(ctx: &ProxyCtx, req: DeleteBreakpointRequest) -> Result<bool, RpcError> {
+    let breakpoint = Breakpoint { id: req.id, ..Default::default() };
+    ctx.db
+        .with_conn(|db| db.delete(&breakpoint))
+        .map_err(|e| RpcError { message: e.to_string() })?;
+    breakpoints::load_rules(&ctx.db, &ctx.breakpoints).map_err(|e| RpcError { message: e })?;
+    Ok(true)
+}
+
+fn list_rewrite_rules(
+    ctx: &ProxyCtx,
+    _req: ListRewriteRulesRequest,
+) -> Result<ListRewriteRulesResponse, RpcError> {
+    ctx.db.with_conn(|db| {
+        Ok(ListRewriteRulesResponse {
+            rewrite_rules: db
+                .find_all::<RewriteRule>()
+                .map_err(|e| RpcError { message: e.to_string() })?,
+        })
+    })
+}
+
+fn upsert_rewrite_rule(ctx: &ProxyCtx, rule: RewriteRule) -> Result<RewriteRule, RpcError> {
+    rewrites::validate(&rule).map_err(|e| RpcError { message: e })?;
+    let (saved, _) = ctx
+        .db
+        .with_conn(|db| db.upsert(&rule, &UpdateSource::Background))
+        .map_err(|e| RpcError { message: e.to_string() })?;
+    rewrites::load_rules(&ctx.db, &ctx.rewrites).map_err(|e| RpcError { message: e })?;
+    Ok(saved)
+}
+
+fn delete_rewrite_rule(ctx: &ProxyCtx, req: DeleteRewriteRuleRequest) -> Result<bool, RpcError> {
+    let rule = RewriteRule { id: req.id, ..Default::default() };
+    ctx.db.with_conn(|db| db.delete(&rule)).map_err(|e| RpcError { message: e.to_string() })?;
+    rewrites::load_rules(&ctx.db, &ctx.rewrites).map_err(|e| RpcError { message: e })?;
+    Ok(true)
+}
+
+fn list_capture_rules(
+    ctx: &ProxyCtx,
+    _req: ListCaptureRulesRequest,
+) -> Result<ListCaptureRulesResponse, RpcError> {
+    ctx.db.with_conn(|db| {
+        Ok(ListCaptureRulesResponse {
+            capture_rules: db
+                .find_all::<CaptureRule>()
+                .map_err(|e| RpcError { message: e.to_string() })?,
+        })
+    })
+}
+
+fn upsert_capture_rule(ctx: &ProxyCtx, rule: CaptureRule) -> Result<CaptureRule, RpcError> {
+    let (saved, _) = ctx
+        .db
+        .with_conn(|db| db.upsert(&rule, &UpdateSource::Background))
+        .map_err(|e| RpcError { message: e.to_string() })?;
+    filters::load_rules(&ctx.db, &ctx.filter).map_err(|e| RpcError { message: e })?;
+    Ok(saved)
+}
+
+fn delete_capture_rule(ctx: &ProxyCtx, req: DeleteCaptureRuleRequest) -> Result<bool, RpcError> {
+    let rule = CaptureRule { id: req.id, ..Default::default() };
+    ctx.db.with_conn(|db| db.delete(&rule)).map_err(|e| RpcError { message: e.to_string() })?;
+    filters::load_rules(&ctx.db, &ctx.filter).map_err(|e| RpcError { message: e })?;
+    Ok(true)
+}
+
+fn get_settings(ctx: &ProxyCtx, _req: GetSettingsRequest) -> Result<Settings, RpcError> {
+    Ok(upstream::get_settings(&ctx.db))
+}
+
+fn update_settings(ctx: &ProxyCtx, settings: Settings) -> Result<Settings, RpcError> {
+    if let Some(config) = upstream::to_config(settings.upstream_proxy.as_ref()) {
+        config.validate().map_err(|message| RpcError { message })?;
+    }
+    let settings = Settings { id: upstream::SETTINGS_ID.to_string(), ..settings };
+    let (saved, _) = ctx
+        .db
+        .with_conn(|db| db.upsert(&settings, &UpdateSource::Background))
+        .map_err(|e| RpcError { message: e.to_string() })?;
+    // Applies from the next connection, so a running proxy needn't restart
+    upstream::load_settings(&ctx.db, &ctx.upstream);
+    Ok(saved)
+}
+
+fn list_paused(ctx: &ProxyCtx, _req: ListPausedRequest) -> Result<ListPausedResponse, RpcError> {
+    let paused = ctx
+        .breakpoints
+        .paused()
+        .into_iter()
+        .map(|(id, message)| paused_message(id, message))
+        .collect();
+    Ok(ListPausedResponse { paused })
+}
+
+fn resolve_breakpoint(ctx: &ProxyCtx, req: ResolveBreakpointRequest) -> Result<bool, RpcError> {
+    ctx.breakpoints.resolve(req.id, req.resolution.into()).map_err(|e| RpcError { message: e })?;
+    Ok(true)
+}
+
+fn get_ca_certificate(
+    ctx: &ProxyCtx,
+    _req: GetCaCertificateRequest,
+) -> Result<GetCaCertificateResponse, RpcError> {
+    Ok(GetCaCertificateResponse { pem: ctx.ca()?.ca_pem() })
+}
+
+fn export_ca_certificate(
+    ctx: &ProxyCtx,
+    req: ExportCaCertificateRequest,
+) -> Result<ExportCaCertificateResponse, RpcError> {
+    let contents = ca::export(&ctx.ca()?, req.format);
+    std::fs::write(&req.path, contents)
+        .map_err(|e| RpcError { message: format!("Failed to write {}: {e}", req.path) })?;
+    Ok(ExportCaCertificateResponse { path: req.path })
+}
+
+fn export_http_requests(
+    ctx: &ProxyCtx,
+    req: ExportHttpRequestsRequest,
+) -> Result<ExportHttpRequestsResponse, RpcError> {
+    let exchanges: Vec<HttpExchange> = ctx
+        .db
+        .with_conn(|db| db.find_all::<HttpExchange>())
+        .map_err(|e| RpcError { message: e.to_string() })?
+        .into_iter()
+        .filter(|e| req.exchange_ids.contains(&e.id))
+        .collect();
+    if exchanges.is_empty() {
+        return Err(RpcError { message: "No exchanges to export".into() });
+    }
+
+    let export = convert::to_workspace_export(&exchanges, req.parameterize_hosts);
+    let contents = serde_json::to_string_pretty(&export)
+        .map_err(|e| RpcError { message: format!("Failed to serialize export: {e}") })?;
+    std::fs::write(&req.path, contents)
+        .map_err(|e| RpcError { message: format!("Failed to write {}: {e}", req.path) })?;
+    Ok(ExportHttpRequestsResponse { path: req.path, count: exchanges.len() })
+}
+
+fn export_har(ctx: &ProxyCtx, req: ExportHarRequest) -> Result<ExportHarResponse, RpcError> {
+    let exchanges: Vec<HttpExchange> = ctx
+        .db
+        .with_conn(|db| db.find_all::<HttpExchange>())
+        .map_err(|e| RpcError { message: e.to_string() })?
+        .into_iter()
+        .filter(|e| req.exchange_ids.contains(&e.id))
+        .collect();
+    if exchanges.is_empty() {
+        return Err(RpcError { message: "No exchanges to export".into() });
+    }
+
+    let har = har::to_har(&exchanges, env!("CARGO_PKG_VERSION"));
+    let contents = serde_json::to_string_pretty(&har)
+        .map_err(|e| RpcError { message: format!("Failed to serialize HAR: {e}") })?;
+    std::fs::write(&req.path, contents)
+        .map_err(|e| RpcError { message: format!("Failed to write {}: {e}", req.path) })?;
+    Ok(ExportHarResponse { path: req.path, count: exchanges.len() })
+}
+
+fn import_har(ctx: &ProxyCtx, req: ImportHarRequest) -> Result<ImportHarResponse, RpcError> {
+    let contents = std::fs::read_to_string(&req.path)
+        .map_err(|e| RpcError { message: format!("Failed to read {}: {e}", req.path) })?;
+    let har = yaak_har::Har::parse(&contents)
+        .ok_or_else(|| RpcError { message: format!("{} is not a HAR file", req.path) })?;
+
+    let mut count = 0;
+    for exchange in har::from_har(&har) {
+        // Imported, so the capture time in the file is kept
+        let (saved, created) =
+            ctx.db
+                .with_conn(|db| db.upsert(&exchange, &UpdateSource::Import))
+                .map_err(|e| RpcError { message: format!("Failed to save exchange: {e}") })?;
+        ctx.events.emit(
+            "model_write",
+            &ModelPayload { model: saved, change: ModelChangeEvent::Upsert { created } },
+        );
+        count += 1;
+    }
+    Ok(ImportHarResponse { count })
+}
+
 fn get_proxy_state(
     ctx: &ProxyCtx,
     _req: GetProxyStateRequest,
@@ -145,18 +628,50 @@ fn list_models(ctx: &ProxyCtx, _req: ListModelsRequest) -> Result<ListModelsResp
     })
 }
 
+fn list_websocket_frames(
+    ctx: &ProxyCtx,
+    req: ListWebsocketFramesRequest,
+) -> Result<ListWebsocketFramesResponse, RpcError> {
+    ctx.db.with_conn(|db| {
+        Ok(ListWebsocketFramesResponse {
+            websocket_frames: db
+                .find_many::<WebsocketFrame>(WebsocketFrameIden::ExchangeId, req.exchange_id, None)
+                .map_err(|e| RpcError { message: e.to_string() })?,
+        })
+    })
+}
+
+fn list_sse_events(
+    ctx: &ProxyCtx,
+    req: ListSseEventsRequest,
+) -> Result<ListSseEventsResponse, RpcError> {
+    ctx.db.with_conn(|db| {
+        Ok(ListSseEventsResponse {
+            sse_events: db
+                .find_many::<SseEvent>(SseEventIden::ExchangeId, req.exchange_id, None)
+                .map_err(|e| RpcError { message: e.to_string() })?,
+        })
+    })
+}
+
 // -- Event loop --
 
 fn run_event_loop(
     rx: std::sync::mpsc::Receiver<ProxyEvent>,
     db: ProxyQueryManager,
     events: RpcEventEmitter,
+    filter: Arc<CaptureFilter>,
 ) {
     let mut in_flight: HashMap<u64, CapturedRequest> = HashMap::new();
+    let mut streams: HashMap<u64, Stream> = HashMap::new();
 
     while let Ok(event) = rx.recv() {
         match event {
             ProxyEvent::RequestStart { id, method, url, http_version } => {
+                // Exchanges that aren't in flight are never written, so nothing else is needed
+                if !filter.records(&method, &url) {
+                    continue;
+                }
                 in_flight.insert(
                     id,
                     CapturedRequest {
@@ -209,22 +724,117 @@ fn run_event_loop(
                     r.response_body_size = size;
                     r.elapsed_ms = r.elapsed_ms.or(Some(elapsed_ms));
                     r.state = RequestState::Complete;
-                    write_entry(&db, &events, &r);
+                    let stream = streams.remove(&id);
+                    let exchange_id = stream.as_ref().map(|s| s.exchange_id.as_str());
+                    let saved = write_entry(&db, &events, &r, exchange_id);
+                    // The connection was only just handed over, so its frames are yet to come
+                    if let Some(exchange_id) = saved
+                        && r.status == Some(101)
+                    {
+                        let next_index = stream.map(|s| s.next_index).unwrap_or(0);
+                        streams.insert(id, Stream { exchange_id, next_index });
+                    }
                 }
             }
             ProxyEvent::Error { id, error } => {
                 if let Some(mut r) = in_flight.remove(&id) {
                     r.error = Some(error);
                     r.state = RequestState::Error;
-                    write_entry(&db, &events, &r);
+                    let stream = streams.remove(&id);
+                    write_entry(&db, &events, &r, stream.as_ref().map(|s| s.exchange_id.as_str()));
                 }
             }
+            ProxyEvent::Paused { id, message } => {
+                events.emit("breakpoint_paused", &paused_message(id, message));
+            }
+            ProxyEvent::Resumed { id } => {
+                events.emit("breakpoint_resumed", &BreakpointResumedPayload { id });
+            }
+            ProxyEvent::WebSocketFrame { id, is_server, opcode, payload } => {
+                let Some(stream) = open_stream(&mut streams, &in_flight, id, &db, &events) else {
+                    continue;
+                };
+                let frame = WebsocketFrame {
+                    exchange_id: stream.exchange_id.clone(),
+                    index: stream.next(),
+                    is_server,
+                    opcode: opcode.into(),
+                    payload,
+                    ..Default::default()
+                };
+                write_message(&db, &events, "websocket_frame", &frame);
+            }
+            ProxyEvent::WebSocketClosed { id } => {
+                streams.remove(&id);
+            }
+            ProxyEvent::ServerSentEvent { id, event } => {
+                let Some(stream) = open_stream(&mut streams, &in_flight, id, &db, &events) else {
+                    continue;
+                };
+                let event = SseEvent {
+                    exchange_id: stream.exchange_id.clone(),
+                    index: stream.next(),
+                    event_type: event.event_type,
+                    data: event.data,
+                    event_id: event.id,
+                    retry: event.retry.map(|r| i32::try_from(r).unwrap_or(i32::MAX)),
+                    ..Default::default()
+                };
+                write_message(&db, &events, "sse_event", &event);
+            }
         }
     }
 }
 
-fn write_entry(db: &ProxyQueryManager, events: &RpcEventEmitter, r: &CapturedRequest) {
+/// An exchange that keeps recording messages after it was first saved: an event stream, or a
+/// connection upgraded to WebSocket.
+struct Stream {
+    exchange_id: String,
+    next_index: i32,
+}
+
+impl Stream {
+    fn next(&mut self) -> i32 {
+        self.next_index += 1;
+        self.next_index - 1
+    }
+}
+
+/// The stream of exchange `id`, saving the exchange first if it is still in flight, so that
+/// its messages show up as they arrive rather than once it completes.
+fn open_stream<'a>(
+    streams: &'a mut HashMap<u64, Stream>,
+    in_flight: &HashMap<u64, CapturedRequest>,
+    id: u64,
+    db: &ProxyQueryManager,
+    events: &RpcEventEmitter,
+) -> Option<&'a mut Stream> {
+    if !streams.contains_key(&id) {
+        let exchange_id = write_entry(db, events, in_flight.get(&id)?, None)?;
+        streams.insert(id, Stream { exchange_id, next_index: 0 });
+    }
+    streams.get_mut(&id)
+}
+
+fn write_message<M>(db: &ProxyQueryManager, events: &RpcEventEmitter, event: &'static str, m: &M)
+where
+    M: UpsertModelInfo + Clone + Serialize,
+{
+    match db.with_conn(|ctx| ctx.upsert(m, &UpdateSource::Background)) {
+        Ok((saved, _)) => events.emit(event, &saved),
+        Err(e) => warn!("Failed to write proxy message: {e}"),
+    }
+}
+
+/// Save the exchange, as a new row or over `exchange_id`, and return its id.
+fn write_entry(
+    db: &ProxyQueryManager,
+    events: &RpcEventEmitter,
+    r: &CapturedRequest,
+    exchange_id: Option<&str>,
+) -> Option<String> {
     let entry = HttpExchange {
+        id: exchange_id.unwrap_or_default().to_string(),
         url: r.url.clone(),
         method: r.method.clone(),
         req_headers: r
@@ -245,13 +855,18 @@ fn write_entry(db: &ProxyQueryManager, events: &RpcEventEmitter, r: &CapturedReq
     };
     db.with_conn(|ctx| match ctx.upsert(&entry, &UpdateSource::Background) {
         Ok((saved, created)) => {
+            let id = saved.id.clone();
             events.emit(
                 "model_write",
                 &ModelPayload { model: saved, change: ModelChangeEvent::Upsert { created } },
             );
+            Some(id)
+        }
+        Err(e) => {
+            warn!("Failed to write proxy entry: {e}");
+            None
         }
-        Err(e) => warn!("Failed to write proxy entry: {e}"),
-    });
+    })
 }
 
 // -- Router + Schema --
@@ -259,13 +874,37 @@ fn write_entry(db: &ProxyQueryManager, events: &RpcEventEmitter, r: &CapturedReq
 define_rpc! {
     ProxyCtx;
     commands {
+        delete_breakpoint(DeleteBreakpointRequest) -> bool,
+        delete_capture_rule(DeleteCaptureRuleRequest) -> bool,
+        delete_rewrite_rule(DeleteRewriteRuleRequest) -> bool,
         execute_action(ActionInvocation) -> bool,
+        export_ca_certificate(ExportCaCertificateRequest) -> ExportCaCertificateResponse,
+        export_har(ExportHarRequest) -> ExportHarResponse,
+        export_http_requests(ExportHttpRequestsRequest) -> ExportHttpRequestsResponse,
+        get_ca_certificate(GetCaCertificateRequest) -> GetCaCertificateResponse,
         get_proxy_state(GetProxyStateRequest) -> GetProxyStateResponse,
+        get_settings(GetSettingsRequest) -> Settings,
+        import_har(ImportHarRequest) -> ImportHarResponse,
         list_actions(ListActionsRequest) -> ListActionsResponse,
+        list_breakpoints(ListBreakpointsRequest) -> ListBreakpointsResponse,
+        list_capture_rules(ListCaptureRulesRequest) -> ListCaptureRulesResponse,
         list_models(ListModelsRequest) -> ListModelsResponse,
+        list_paused(ListPausedRequest) -> ListPausedResponse,
+        list_rewrite_rules(ListRewriteRulesRequest) -> ListRewriteRulesResponse,
+        list_sse_events(ListSseEventsRequest) -> ListSseEventsResponse,
+        list_websocket_frames(ListWebsocketFramesRequest) -> ListWebsocketFramesResponse,
+        resolve_breakpoint(ResolveBreakpointRequest) -> bool,
+        update_settings(Settings) -> Settings,
+        upsert_breakpoint(Breakpoint) -> Breakpoint,
+        upsert_capture_rule(CaptureRule) -> CaptureRule,
+        upsert_rewrite_rule(RewriteRule) -> RewriteRule,
     }
     events {
+        breakpoint_paused(PausedMessage),
+        breakpoint_resumed(BreakpointResumedPayload),
         model_write(ModelPayload),
         proxy_state_changed(ProxyStatePayload),
+        sse_event(SseEvent),
+        websocket_frame(WebsocketFrame),
     }
 }
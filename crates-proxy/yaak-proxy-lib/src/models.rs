@@ -7,6 +7,7 @@ use yaak_database::{
     ModelChangeEvent, Result as DbResult, UpdateSource, UpsertModelInfo, generate_prefixed_id,
     upsert_date,
 };
+use yaak_models::models::ProxySetting;
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
@@ -117,3 +118,751 @@ impl UpsertModelInfo for HttpExchange {
         })
     }
 }
+
+/// Pauses matching traffic so it can be inspected and edited before it goes on. Empty
+/// `method`, `host` and `path` match anything; `host` and `path` take `*` wildcards.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+#[enum_def(table_name = "breakpoints")]
+pub struct Breakpoint {
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub enabled: bool,
+    pub method: String,
+    pub host: String,
+    pub path: String,
+    pub on_request: bool,
+    pub on_response: bool,
+}
+
+/// Enabled and pausing requests, the same as the table's column defaults.
+impl Default for Breakpoint {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            created_at: NaiveDateTime::default(),
+            updated_at: NaiveDateTime::default(),
+            enabled: true,
+            method: String::new(),
+            host: String::new(),
+            path: String::new(),
+            on_request: true,
+            on_response: false,
+        }
+    }
+}
+
+impl UpsertModelInfo for Breakpoint {
+    fn table_name() -> impl IntoTableRef + IntoIden {
+        BreakpointIden::Table
+    }
+
+    fn id_column() -> impl IntoIden + Eq + Clone {
+        BreakpointIden::Id
+    }
+
+    fn generate_id() -> String {
+        generate_prefixed_id("bp")
+    }
+
+    fn order_by() -> (impl IntoColumnRef, Order) {
+        (BreakpointIden::CreatedAt, Order::Asc)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn insert_values(
+        self,
+        source: &UpdateSource,
+    ) -> DbResult<Vec<(impl IntoIden + Eq, impl Into<SimpleExpr>)>> {
+        use BreakpointIden::*;
+        Ok(vec![
+            (CreatedAt, upsert_date(source, self.created_at)),
+            (UpdatedAt, upsert_date(source, self.updated_at)),
+            (Enabled, self.enabled.into()),
+            (Method, self.method.into()),
+            (Host, self.host.into()),
+            (Path, self.path.into()),
+            (OnRequest, self.on_request.into()),
+            (OnResponse, self.on_response.into()),
+        ])
+    }
+
+    fn update_columns() -> Vec<impl IntoIden> {
+        vec![
+            BreakpointIden::UpdatedAt,
+            BreakpointIden::Enabled,
+            BreakpointIden::Method,
+            BreakpointIden::Host,
+            BreakpointIden::Path,
+            BreakpointIden::OnRequest,
+            BreakpointIden::OnResponse,
+        ]
+    }
+
+    fn from_row(r: &Row) -> rusqlite::Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self {
+            id: r.get("id")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            enabled: r.get("enabled")?,
+            method: r.get("method")?,
+            host: r.get("host")?,
+            path: r.get("path")?,
+            on_request: r.get("on_request")?,
+            on_response: r.get("on_response")?,
+        })
+    }
+}
+
+/// Changes matching traffic in flight, without pausing it. Matches like [`Breakpoint`]; the
+/// rules apply in the order they were created.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+#[enum_def(table_name = "rewrite_rules")]
+pub struct RewriteRule {
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub enabled: bool,
+    pub name: String,
+    pub method: String,
+    pub host: String,
+    pub path: String,
+    pub action: RewriteRuleAction,
+}
+
+impl Default for RewriteRule {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            created_at: NaiveDateTime::default(),
+            updated_at: NaiveDateTime::default(),
+            enabled: true,
+            name: String::new(),
+            method: String::new(),
+            host: String::new(),
+            path: String::new(),
+            action: RewriteRuleAction::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum RewriteTarget {
+    #[default]
+    Request,
+    Response,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum RewriteRuleAction {
+    /// Add a header, keeping any others with the same name
+    AddHeader {
+        target: RewriteTarget,
+        name: String,
+        value: String,
+    },
+    /// Replace all headers with this name by a single one
+    SetHeader {
+        target: RewriteTarget,
+        name: String,
+        value: String,
+    },
+    RemoveHeader {
+        target: RewriteTarget,
+        name: String,
+    },
+    /// Regex-replace the body; `replacement` may refer to groups as `$1`
+    ReplaceBody {
+        target: RewriteTarget,
+        pattern: String,
+        replacement: String,
+    },
+    /// Send the request to another origin, like `http://localhost:3000`
+    MapRemote {
+        url: String,
+    },
+    /// Answer with a local file; the content type is guessed when empty
+    MapLocal {
+        path: String,
+        content_type: String,
+    },
+}
+
+impl Default for RewriteRuleAction {
+    fn default() -> Self {
+        Self::SetHeader {
+            target: RewriteTarget::Request,
+            name: String::new(),
+            value: String::new(),
+        }
+    }
+}
+
+impl UpsertModelInfo for RewriteRule {
+    fn table_name() -> impl IntoTableRef + IntoIden {
+        RewriteRuleIden::Table
+    }
+
+    fn id_column() -> impl IntoIden + Eq + Clone {
+        RewriteRuleIden::Id
+    }
+
+    fn generate_id() -> String {
+        generate_prefixed_id("rw")
+    }
+
+    fn order_by() -> (impl IntoColumnRef, Order) {
+        (RewriteRuleIden::CreatedAt, Order::Asc)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn insert_values(
+        self,
+        source: &UpdateSource,
+    ) -> DbResult<Vec<(impl IntoIden + Eq, impl Into<SimpleExpr>)>> {
+        use RewriteRuleIden::*;
+        Ok(vec![
+            (CreatedAt, upsert_date(source, self.created_at)),
+            (UpdatedAt, upsert_date(source, self.updated_at)),
+            (Enabled, self.enabled.into()),
+            (Name, self.name.into()),
+            (Method, self.method.into()),
+            (Host, self.host.into()),
+            (Path, self.path.into()),
+            (Action, serde_json::to_string(&self.action)?.into()),
+        ])
+    }
+
+    fn update_columns() -> Vec<impl IntoIden> {
+        vec![
+            RewriteRuleIden::UpdatedAt,
+            RewriteRuleIden::Enabled,
+            RewriteRuleIden::Name,
+            RewriteRuleIden::Method,
+            RewriteRuleIden::Host,
+            RewriteRuleIden::Path,
+            RewriteRuleIden::Action,
+        ]
+    }
+
+    fn from_row(r: &Row) -> rusqlite::Result<Self>
+    where
+        Self: Sized,
+    {
+        let action: String = r.get("action")?;
+        Ok(Self {
+            id: r.get("id")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            enabled: r.get("enabled")?,
+            name: r.get("name")?,
+            method: r.get("method")?,
+            host: r.get("host")?,
+            path: r.get("path")?,
+            action: serde_json::from_str(&action).unwrap_or_default(),
+        })
+    }
+}
+
+/// A frame of a WebSocket connection, recorded as it passed through. Belongs to the exchange
+/// that upgraded the connection.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+#[enum_def(table_name = "websocket_frames")]
+pub struct WebsocketFrame {
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub exchange_id: String,
+    /// Zero-based position on the connection, counting both directions
+    pub index: i32,
+    pub is_server: bool,
+    pub opcode: WebsocketOpcode,
+    /// Unmasked, and never compressed, since the proxy doesn't let connections negotiate it
+    pub payload: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum WebsocketOpcode {
+    /// Carries on the message of the frame before it
+    Continuation,
+    #[default]
+    Text,
+    Binary,
+    Close,
+    Ping,
+    Pong,
+    /// Reserved by the protocol
+    Other,
+}
+
+impl From<u8> for WebsocketOpcode {
+    fn from(opcode: u8) -> Self {
+        match opcode {
+            0x0 => Self::Continuation,
+            0x1 => Self::Text,
+            0x2 => Self::Binary,
+            0x8 => Self::Close,
+            0x9 => Self::Ping,
+            0xa => Self::Pong,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl UpsertModelInfo for WebsocketFrame {
+    fn table_name() -> impl IntoTableRef + IntoIden {
+        WebsocketFrameIden::Table
+    }
+
+    fn id_column() -> impl IntoIden + Eq + Clone {
+        WebsocketFrameIden::Id
+    }
+
+    fn generate_id() -> String {
+        generate_prefixed_id("wf")
+    }
+
+    fn order_by() -> (impl IntoColumnRef, Order) {
+        (WebsocketFrameIden::Index, Order::Asc)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn insert_values(
+        self,
+        source: &UpdateSource,
+    ) -> DbResult<Vec<(impl IntoIden + Eq, impl Into<SimpleExpr>)>> {
+        use WebsocketFrameIden::*;
+        Ok(vec![
+            (CreatedAt, upsert_date(source, self.created_at)),
+            (UpdatedAt, upsert_date(source, self.updated_at)),
+            (ExchangeId, self.exchange_id.into()),
+            (Index, self.index.into()),
+            (IsServer, self.is_server.into()),
+            (Opcode, serde_json::to_value(self.opcode)?.as_str().into()),
+            (Payload, self.payload.into()),
+        ])
+    }
+
+    fn update_columns() -> Vec<impl IntoIden> {
+        vec![
+            WebsocketFrameIden::UpdatedAt,
+            WebsocketFrameIden::IsServer,
+            WebsocketFrameIden::Opcode,
+            WebsocketFrameIden::Payload,
+        ]
+    }
+
+    fn from_row(r: &Row) -> rusqlite::Result<Self>
+    where
+        Self: Sized,
+    {
+        let opcode: String = r.get("opcode")?;
+        Ok(Self {
+            id: r.get("id")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            exchange_id: r.get("exchange_id")?,
+            index: r.get("index")?,
+            is_server: r.get("is_server")?,
+            opcode: serde_json::from_str(&format!(r#""{opcode}""#)).unwrap_or_default(),
+            payload: r.get("payload")?,
+        })
+    }
+}
+
+/// An event of a `text/event-stream` response, recorded as it arrived. Belongs to the
+/// exchange whose response it was part of.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+#[enum_def(table_name = "sse_events")]
+pub struct SseEvent {
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub exchange_id: String,
+    /// Zero-based position in the stream
+    pub index: i32,
+    pub event_type: String,
+    pub data: String,
+    /// The last event ID in effect when this event was dispatched
+    pub event_id: Option<String>,
+    /// Reconnection delay in milliseconds, when the event set one
+    pub retry: Option<i32>,
+}
+
+impl UpsertModelInfo for SseEvent {
+    fn table_name() -> impl IntoTableRef + IntoIden {
+        SseEventIden::Table
+    }
+
+    fn id_column() -> impl IntoIden + Eq + Clone {
+        SseEventIden::Id
+    }
+
+    fn generate_id() -> String {
+        generate_prefixed_id("se")
+    }
+
+    fn order_by() -> (impl IntoColumnRef, Order) {
+        (SseEventIden::Index, Order::Asc)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn insert_values(
+        self,
+        source: &UpdateSource,
+    ) -> DbResult<Vec<(impl IntoIden + Eq, impl Into<SimpleExpr>)>> {
+        use SseEventIden::*;
+        Ok(vec![
+            (CreatedAt, upsert_date(source, self.created_at)),
+            (UpdatedAt, upsert_date(source, self.updated_at)),
+            (ExchangeId, self.exchange_id.into()),
+            (Index, self.index.into()),
+            (EventType, self.event_type.into()),
+            (Data, self.data.into()),
+            (EventId, self.event_id.into()),
+            (Retry, self.retry.into()),
+        ])
+    }
+
+    fn update_columns() -> Vec<impl IntoIden> {
+        vec![
+            SseEventIden::UpdatedAt,
+            SseEventIden::EventType,
+            SseEventIden::Data,
+            SseEventIden::EventId,
+            SseEventIden::Retry,
+        ]
+    }
+
+    fn from_row(r: &Row) -> rusqlite::Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self {
+            id: r.get("id")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            exchange_id: r.get("exchange_id")?,
+            index: r.get("index")?,
+            event_type: r.get("event_type")?,
+            data: r.get("data")?,
+            event_id: r.get("event_id")?,
+            retry: r.get("retry")?,
+        })
+    }
+}
+
+/// Decides which hosts have their TLS intercepted, and which exchanges are recorded. A host
+/// or exchange gets through when no exclude rule matches it and, if there are include rules,
+/// one of them does. Matches like [`Breakpoint`], except that intercept rules only look at
+/// the host.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+#[enum_def(table_name = "capture_rules")]
+pub struct CaptureRule {
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub enabled: bool,
+    pub scope: CaptureScope,
+    pub mode: CaptureMode,
+    pub method: String,
+    pub host: String,
+    pub path: String,
+}
+
+impl Default for CaptureRule {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            created_at: NaiveDateTime::default(),
+            updated_at: NaiveDateTime::default(),
+            enabled: true,
+            scope: CaptureScope::default(),
+            mode: CaptureMode::default(),
+            method: String::new(),
+            host: String::new(),
+            path: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum CaptureScope {
+    /// Decrypt TLS to matching hosts; the rest is tunneled untouched
+    #[default]
+    Intercept,
+    /// Save matching exchanges
+    Record,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum CaptureMode {
+    #[default]
+    Include,
+    Exclude,
+}
+
+impl UpsertModelInfo for CaptureRule {
+    fn table_name() -> impl IntoTableRef + IntoIden {
+        CaptureRuleIden::Table
+    }
+
+    fn id_column() -> impl IntoIden + Eq + Clone {
+        CaptureRuleIden::Id
+    }
+
+    fn generate_id() -> String {
+        generate_prefixed_id("cr")
+    }
+
+    fn order_by() -> (impl IntoColumnRef, Order) {
+        (CaptureRuleIden::CreatedAt, Order::Asc)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn insert_values(
+        self,
+        source: &UpdateSource,
+    ) -> DbResult<Vec<(impl IntoIden + Eq, impl Into<SimpleExpr>)>> {
+        use CaptureRuleIden::*;
+        Ok(vec![
+            (CreatedAt, upsert_date(source, self.created_at)),
+            (UpdatedAt, upsert_date(source, self.updated_at)),
+            (Enabled, self.enabled.into()),
+            (Scope, serde_json::to_value(self.scope)?.as_str().into()),
+            (Mode, serde_json::to_value(self.mode)?.as_str().into()),
+            (Method, self.method.into()),
+            (Host, self.host.into()),
+            (Path, self.path.into()),
+        ])
+    }
+
+    fn update_columns() -> Vec<impl IntoIden> {
+        vec![
+            CaptureRuleIden::UpdatedAt,
+            CaptureRuleIden::Enabled,
+            CaptureRuleIden::Scope,
+            CaptureRuleIden::Mode,
+            CaptureRuleIden::Method,
+            CaptureRuleIden::Host,
+            CaptureRuleIden::Path,
+        ]
+    }
+
+    fn from_row(r: &Row) -> rusqlite::Result<Self>
+    where
+        Self: Sized,
+    {
+        let scope: String = r.get("scope")?;
+        let mode: String = r.get("mode")?;
+        Ok(Self {
+            id: r.get("id")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            enabled: r.get("enabled")?,
+            scope: serde_json::from_str(&format!(r#""{scope}""#)).unwrap_or_default(),
+            mode: serde_json::from_str(&format!(r#""{mode}""#)).unwrap_or_default(),
+            method: r.get("method")?,
+            host: r.get("host")?,
+            path: r.get("path")?,
+        })
+    }
+}
+
+/// The proxy's own settings. There is only ever the one row, with the id `default`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+#[enum_def(table_name = "settings")]
+pub struct Settings {
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    /// Another proxy to send traffic on through, as the client app configures its own
+    pub upstream_proxy: Option<ProxySetting>,
+}
+
+impl UpsertModelInfo for Settings {
+    fn table_name() -> impl IntoTableRef + IntoIden {
+        SettingsIden::Table
+    }
+
+    fn id_column() -> impl IntoIden + Eq + Clone {
+        SettingsIden::Id
+    }
+
+    fn generate_id() -> String {
+        "default".to_string()
+    }
+
+    fn order_by() -> (impl IntoColumnRef, Order) {
+        (SettingsIden::CreatedAt, Order::Asc)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn insert_values(
+        self,
+        source: &UpdateSource,
+    ) -> DbResult<Vec<(impl IntoIden + Eq, impl Into<SimpleExpr>)>> {
+        use SettingsIden::*;
+        let upstream_proxy = self.upstream_proxy.map(|p| serde_json::to_string(&p)).transpose()?;
+        Ok(vec![
+            (CreatedAt, upsert_date(source, self.created_at)),
+            (UpdatedAt, upsert_date(source, self.updated_at)),
+            (UpstreamProxy, upstream_proxy.into()),
+        ])
+    }
+
+    fn update_columns() -> Vec<impl IntoIden> {
+        vec![SettingsIden::UpdatedAt, SettingsIden::UpstreamProxy]
+    }
+
+    fn from_row(r: &Row) -> rusqlite::Result<Self>
+    where
+        Self: Sized,
+    {
+        let upstream_proxy: Option<String> = r.get("upstream_proxy")?;
+        Ok(Self {
+            id: r.get("id")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            upstream_proxy: upstream_proxy.and_then(|p| serde_json::from_str(&p).ok()),
+        })
+    }
+}
+
+/// The root the proxy issues its TLS certificates from. Not sent to the frontend; the key
+/// is encrypted with the app's master key.
+#[derive(Debug, Clone, Default)]
+#[enum_def(table_name = "root_certificates")]
+pub struct RootCertificate {
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub cert_pem: String,
+    pub encrypted_key: Vec<u8>,
+}
+
+impl UpsertModelInfo for RootCertificate {
+    fn table_name() -> impl IntoTableRef + IntoIden {
+        RootCertificateIden::Table
+    }
+
+    fn id_column() -> impl IntoIden + Eq + Clone {
+        RootCertificateIden::Id
+    }
+
+    fn generate_id() -> String {
+        generate_prefixed_id("rc")
+    }
+
+    fn order_by() -> (impl IntoColumnRef, Order) {
+        (RootCertificateIden::CreatedAt, Order::Desc)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn insert_values(
+        self,
+        source: &UpdateSource,
+    ) -> DbResult<Vec<(impl IntoIden + Eq, impl Into<SimpleExpr>)>> {
+        use RootCertificateIden::*;
+        Ok(vec![
+            (CreatedAt, upsert_date(source, self.created_at)),
+            (UpdatedAt, upsert_date(source, self.updated_at)),
+            (CertPem, self.cert_pem.into()),
+            (EncryptedKey, self.encrypted_key.into()),
+        ])
+    }
+
+    fn update_columns() -> Vec<impl IntoIden> {
+        vec![
+            RootCertificateIden::UpdatedAt,
+            RootCertificateIden::CertPem,
+            RootCertificateIden::EncryptedKey,
+        ]
+    }
+
+    fn from_row(r: &Row) -> rusqlite::Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self {
+            id: r.get("id")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            cert_pem: r.get("cert_pem")?,
+            encrypted_key: r.get("encrypted_key")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn omitted_breakpoint_fields_take_the_column_defaults() {
+        let breakpoint: Breakpoint = serde_json::from_str(r#"{"host":"api.example.com"}"#).unwrap();
+        assert!(breakpoint.enabled);
+        assert!(breakpoint.on_request);
+        assert!(!breakpoint.on_response);
+    }
+
+    #[test]
+    fn omitted_rewrite_rule_fields_take_the_column_defaults() {
+        let rule: RewriteRule = serde_json::from_str(r#"{"host":"api.example.com"}"#).unwrap();
+        assert!(rule.enabled);
+    }
+
+    #[test]
+    fn omitted_capture_rule_fields_take_the_column_defaults() {
+        let rule: CaptureRule = serde_json::from_str(r#"{"host":"api.example.com"}"#).unwrap();
+        assert!(rule.enabled);
+        assert_eq!(rule.scope, CaptureScope::Intercept);
+        assert_eq!(rule.mode, CaptureMode::Include);
+    }
+}
@@ -0,0 +1,54 @@
+//! Rewrite rules, as saved, turned into what the proxy applies.
+
+use crate::db::ProxyQueryManager;
+use crate::models::{RewriteRule, RewriteRuleAction, RewriteTarget};
+use yaak_proxy::pattern::UrlPattern;
+use yaak_proxy::rewrite::{self, Rewrite, RewriteAction, Rewrites};
+
+/// Give the proxy the enabled rewrite rules in the database.
+pub fn load_rules(db: &ProxyQueryManager, rewrites: &Rewrites) -> Result<(), String> {
+    let saved = db
+        .with_conn(|ctx| ctx.find_all::<RewriteRule>())
+        .map_err(|e| format!("Failed to read rewrite rules: {e}"))?;
+    rewrites.set_rules(saved.into_iter().filter(|r| r.enabled).map(to_rewrite).collect());
+    Ok(())
+}
+
+/// Check that `rule` can be applied, so a broken one is not saved.
+pub fn validate(rule: &RewriteRule) -> Result<(), String> {
+    to_rewrite(rule.clone()).validate()
+}
+
+fn to_rewrite(rule: RewriteRule) -> Rewrite {
+    let action = match rule.action {
+        RewriteRuleAction::AddHeader { target, name, value } => {
+            RewriteAction::AddHeader { target: target.into(), name, value }
+        }
+        RewriteRuleAction::SetHeader { target, name, value } => {
+            RewriteAction::SetHeader { target: target.into(), name, value }
+        }
+        RewriteRuleAction::RemoveHeader { target, name } => {
+            RewriteAction::RemoveHeader { target: target.into(), name }
+        }
+        RewriteRuleAction::ReplaceBody { target, pattern, replacement } => {
+            RewriteAction::ReplaceBody { target: target.into(), pattern, replacement }
+        }
+        RewriteRuleAction::MapRemote { url } => RewriteAction::MapRemote { url },
+        RewriteRuleAction::MapLocal { path, content_type } => {
+            RewriteAction::MapLocal { path: path.into(), content_type }
+        }
+    };
+    Rewrite {
+        pattern: UrlPattern { method: rule.method, host: rule.host, path: rule.path },
+        action,
+    }
+}
+
+impl From<RewriteTarget> for rewrite::RewriteTarget {
+    fn from(value: RewriteTarget) -> Self {
+        match value {
+            RewriteTarget::Request => rewrite::RewriteTarget::Request,
+            RewriteTarget::Response => rewrite::RewriteTarget::Response,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! The saved upstream proxy setting, turned into the config the proxy dials with.
+
+use crate::db::ProxyQueryManager;
+use crate::models::{Settings, SettingsIden};
+use yaak_models::models::ProxySetting;
+use yaak_proxy::upstream::{UpstreamAuth, UpstreamConfig, UpstreamProxy};
+
+pub const SETTINGS_ID: &str = "default";
+
+/// The saved settings, or the defaults when nothing was saved yet.
+pub fn get_settings(db: &ProxyQueryManager) -> Settings {
+    db.with_conn(|ctx| ctx.find_optional::<Settings>(SettingsIden::Id, SETTINGS_ID))
+        .unwrap_or_else(|| Settings { id: SETTINGS_ID.to_string(), ..Default::default() })
+}
+
+/// The config for `setting`, or `None` when traffic goes straight to origins.
+pub fn to_config(setting: Option<&ProxySetting>) -> Option<UpstreamConfig> {
+    match setting? {
+        ProxySetting::Disabled | ProxySetting::Enabled { disabled: true, .. } => None,
+        ProxySetting::Enabled { http, https, auth, bypass, socks, .. } => Some(UpstreamConfig {
+            http: http.clone(),
+            https: https.clone(),
+            socks: socks.clone(),
+            auth: auth
+                .as_ref()
+                .filter(|a| !a.user.is_empty())
+                .map(|a| UpstreamAuth { user: a.user.clone(), password: a.password.clone() }),
+            bypass: bypass.clone(),
+        }),
+    }
+}
+
+/// Give the proxy the upstream proxy in the saved settings.
+pub fn load_settings(db: &ProxyQueryManager, upstream: &UpstreamProxy) {
+    upstream.set_config(to_config(get_settings(db).upstream_proxy.as_ref()));
+}
@@ -8,11 +8,62 @@ export type CookieExpires = { "AtUtc": string } | "SessionEnd";
 
 export type CookieSameSite = "Strict" | "Lax" | "None";
 
+/**
+ * When to send a request over HTTP/3 (QUIC) instead of TCP.
+ */
+export type Http3Mode = "off" | "alt_svc" | "force";
+
+export type HttpAssertionKind = "status_equals" | "header_matches" | "json_path_equals" | "json_path_contains" | "body_matches" | "elapsed_under";
+
+export type HttpExtractionSource = "json_path" | "xpath" | "header" | "body_regex" | "cookie";
+
 export type HttpRequest = { model: "http_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, body: Record<string, any>, bodyType: string | null, description: string, headers: Array<HttpRequestHeader>, method: string, name: string, sortPriority: number, url: string, 
 /**
  * URL parameters used for both path placeholders (`:id`) and query string entries.
  */
-urlParameters: Array<HttpUrlParameter>, settingSendCookies: InheritedBoolSetting, settingStoreCookies: InheritedBoolSetting, settingValidateCertificates: InheritedBoolSetting, settingFollowRedirects: InheritedBoolSetting, settingRequestTimeout: InheritedIntSetting, };
+urlParameters: Array<HttpUrlParameter>, settingSendCookies: InheritedBoolSetting, settingStoreCookies: InheritedBoolSetting, settingValidateCertificates: InheritedBoolSetting, settingFollowRedirects: InheritedBoolSetting, settingRequestTimeout: InheritedIntSetting, settingHttp3: InheritedHttp3Setting, 
+/**
+ * Checks evaluated against every response this request produces.
+ */
+assertions: Array<HttpRequestAssertion>, 
+/**
+ * Values written into environment variables after each successful response.
+ */
+extractions: Array<HttpRequestExtraction>, };
+
+/**
+ * A check run against a response once it has been stored. Both `target` and `value` are
+ * templates, rendered with the rest of the request.
+ */
+export type HttpRequestAssertion = { enabled?: boolean, kind: HttpAssertionKind, 
+/**
+ * What the check looks at, for kinds that need one: a header name or a JSON path
+ */
+target: string, 
+/**
+ * The expected value, pattern, or limit
+ */
+value: string, id?: string, };
+
+/**
+ * A value read out of a successful response and written into an environment variable, so a
+ * later request can use it without re-reading or re-sending this one. `expression` is a
+ * template, rendered with the rest of the request.
+ */
+export type HttpRequestExtraction = { enabled?: boolean, source: HttpExtractionSource, 
+/**
+ * The JSON path, XPath, header name, regex, or cookie name to read
+ */
+expression: string, 
+/**
+ * Name of the environment variable to write
+ */
+variable: string, 
+/**
+ * Environment to write into. Defaults to the environment the request was sent with, or the
+ * base environment when there is none.
+ */
+environmentId?: string, id?: string, };
 
 export type HttpRequestHeader = { enabled?: boolean, name: string, value: string, id?: string, };
 
@@ -21,10 +72,39 @@ export type HttpRequestHeader = { enabled?: boolean, name: string, value: string
  * This mirrors `yaak_http::sender::HttpResponseEvent` but with serde support.
  * The `From` impl is in yaak-http to avoid circular dependencies.
  */
-export type HttpResponseEventData = { "type": "setting", name: string, value: string, source_model?: string, source_id?: string, source_name?: string, } | { "type": "info", message: string, } | { "type": "redirect", url: string, status: number, behavior: string, dropped_body: boolean, dropped_headers: Array<string>, } | { "type": "send_url", method: string, scheme: string, username: string, password: string, host: string, port: number, path: string, query: string, fragment: string, } | { "type": "receive_url", version: string, status: string, } | { "type": "header_up", name: string, value: string, } | { "type": "header_down", name: string, value: string, } | { "type": "chunk_sent", bytes: number, } | { "type": "chunk_received", bytes: number, } | { "type": "dns_resolved", hostname: string, addresses: Array<string>, duration: bigint, overridden: boolean, };
+export type HttpResponseEventData = { "type": "setting", name: string, value: string, source_model?: string, source_id?: string, source_name?: string, } | { "type": "info", message: string, } | { "type": "redirect", url: string, status: number, behavior: string, dropped_body: boolean, dropped_headers: Array<string>, } | { "type": "send_url", method: string, scheme: string, username: string, password: string, host: string, port: number, path: string, query: string, fragment: string, } | { "type": "receive_url", version: string, status: string, } | { "type": "header_up", name: string, value: string, } | { "type": "header_down", name: string, value: string, } | { "type": "chunk_sent", bytes: number, } | { "type": "chunk_received", bytes: number, } | { "type": "dns_resolved", hostname: string, addresses: Array<string>, duration: bigint, overridden: boolean, } | { "type": "tcp_connected", duration: bigint, } | { "type": "tls_handshake_completed", duration: bigint, } | { "type": "first_byte_received", duration: bigint, } | { "type": "download_completed", duration: bigint, } | { "type": "tls_negotiated", protocol_version: string, cipher_suite: string, 
+/**
+ * The ALPN protocol, as implied by the HTTP version the connection spoke.
+ */
+alpn?: string, 
+/**
+ * Whether an earlier session was resumed, in which case no certificates were sent.
+ */
+resumed: boolean, } | { "type": "tls_server_certificates", 
+/**
+ * Leaf first, as the server sent them.
+ */
+certificates: Array<HttpResponseTlsCertificate>, } | { "type": "tls_client_certificate", requested: boolean, presented: boolean, };
 
 export type HttpResponseHeader = { name: string, value: string, };
 
+/**
+ * A certificate the server presented during the TLS handshake.
+ */
+export type HttpResponseTlsCertificate = { subject: string, issuer: string, 
+/**
+ * Subject alternative names, prefixed by kind (`DNS:`, `IP:`, `email:`, `URI:`).
+ */
+subject_alt_names: Array<string>, serial_number: string, 
+/**
+ * RFC 3339, in UTC.
+ */
+not_before: string, 
+/**
+ * RFC 3339, in UTC.
+ */
+not_after: string, sha1_fingerprint: string, sha256_fingerprint: string, };
+
 /**
  * The resolved send settings, values only: what an executor has to obey, with the sources
  * (which model each came from) left behind in [`ResolvedHttpRequestSettings`]. This is what
@@ -34,7 +114,7 @@ export type HttpSendSettings = { validateCertificates: boolean, followRedirects:
 /**
  * Milliseconds. Zero or negative means no timeout.
  */
-timeoutMs: number, sendCookies: boolean, storeCookies: boolean, };
+timeoutMs: number, sendCookies: boolean, storeCookies: boolean, http3: Http3Mode, };
 
 export type HttpUrlParameter = { enabled?: boolean, 
 /**
@@ -45,4 +125,6 @@ name: string, value: string, id?: string, };
 
 export type InheritedBoolSetting = { enabled?: boolean, value: boolean, };
 
+export type InheritedHttp3Setting = { enabled?: boolean, value: Http3Mode, };
+
 export type InheritedIntSetting = { enabled?: boolean, value: number, };
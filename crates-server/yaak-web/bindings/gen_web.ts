@@ -25,7 +25,19 @@ elapsedHeaders: number,
 /**
  * Milliseconds spent in DNS on the last lookup, or zero.
  */
-elapsedDns: number, } | { "type": "body", data: string, } | { "type": "done", 
+elapsedDns: number, 
+/**
+ * Milliseconds spent opening the last connection after DNS, or zero.
+ */
+elapsedConnect: number, 
+/**
+ * Milliseconds spent in the last TLS handshake, or zero.
+ */
+elapsedTls: number, 
+/**
+ * Milliseconds spent waiting on the server for the response head.
+ */
+elapsedTtfb: number, } | { "type": "body", data: string, } | { "type": "done", 
 /**
  * Milliseconds from the start of the send to the end of the body.
  */
@@ -21,7 +21,7 @@ use yaak_http::cookies::CookieStore;
 use yaak_http::sender::{HttpResponseEvent, ReqwestSender};
 use yaak_http::transaction::HttpTransaction;
 use yaak_http::types::{SendableHttpRequest, SendableHttpRequestOptions};
-use yaak_models::models::HttpResponseHeader;
+use yaak_models::models::{Http3Mode, HttpResponseHeader};
 
 /// How many frames may sit unread by the client before body reading pauses. Backpressure, so a
 /// slow tab slows the upstream read rather than filling memory.
@@ -94,6 +94,7 @@ pub async fn prepare(limits: Arc<SendLimits>, send: SendRequest) -> Result<Prepa
         SendableHttpRequestOptions {
             timeout: Some(timeout),
             follow_redirects: send.settings.follow_redirects,
+            http3: send.settings.http3,
         },
     )
     .await
@@ -163,6 +164,7 @@ impl PreparedSend {
             client_certificate: None,
             dns_overrides: Vec::new(),
             address_filter: Some(limits.policy.address_filter()),
+            http3: self.settings.http3 != Http3Mode::Off,
         }
         .build_client()
         .map_err(|e| format!("Failed to build HTTP client: {e}"))?;
@@ -171,14 +173,12 @@ impl PreparedSend {
         // The desktop persists them from a task like this one; here the task serialises them.
         let (event_tx, mut event_rx) = mpsc::channel::<HttpResponseEvent>(EVENT_CHANNEL_CAPACITY);
         resolver.set_event_sender(Some(event_tx.clone())).await;
-        let dns_elapsed = Arc::new(AtomicU64::new(0));
+        let phases = Arc::new(PhaseTimings::default());
         let event_frames = frames.clone();
-        let event_dns = dns_elapsed.clone();
+        let event_phases = phases.clone();
         let event_task = tokio::spawn(async move {
             while let Some(event) = event_rx.recv().await {
-                if let HttpResponseEvent::DnsResolved { duration, .. } = &event {
-                    event_dns.store(*duration, Ordering::Relaxed);
-                }
+                event_phases.observe(&event);
                 let frame = Frame::Event { event: event.into() };
                 if write_frame(&event_frames, &frame).await.is_err() {
                     break;
@@ -248,7 +248,10 @@ impl PreparedSend {
             request_headers: to_wire_headers(&response.request_headers),
             content_length: response.content_length,
             elapsed_headers,
-            elapsed_dns: dns_elapsed.load(Ordering::Relaxed),
+            elapsed_dns: phases.dns.load(Ordering::Relaxed),
+            elapsed_connect: phases.connect.load(Ordering::Relaxed),
+            elapsed_tls: phases.tls.load(Ordering::Relaxed),
+            elapsed_ttfb: phases.ttfb.load(Ordering::Relaxed),
         };
         write_frame(&frames, &head).await.map_err(|_| "Client went away".to_string())?;
 
@@ -332,6 +335,28 @@ struct DoneStats {
     content_length_compressed: u64,
 }
 
+/// Connection phase durations from the timeline events, in milliseconds.
+#[derive(Default)]
+struct PhaseTimings {
+    dns: AtomicU64,
+    connect: AtomicU64,
+    tls: AtomicU64,
+    ttfb: AtomicU64,
+}
+
+impl PhaseTimings {
+    fn observe(&self, event: &HttpResponseEvent) {
+        let (slot, duration) = match event {
+            HttpResponseEvent::DnsResolved { duration, .. } => (&self.dns, duration),
+            HttpResponseEvent::TcpConnected { duration } => (&self.connect, duration),
+            HttpResponseEvent::TlsHandshakeCompleted { duration } => (&self.tls, duration),
+            HttpResponseEvent::FirstByteReceived { duration } => (&self.ttfb, duration),
+            _ => return,
+        };
+        slot.store(*duration, Ordering::Relaxed);
+    }
+}
+
 fn to_wire_headers(headers: &[(String, String)]) -> Vec<HttpResponseHeader> {
     headers
         .iter()
@@ -71,6 +71,15 @@ pub enum Frame {
         /// Milliseconds spent in DNS on the last lookup, or zero.
         #[ts(type = "number")]
         elapsed_dns: u64,
+        /// Milliseconds spent opening the last connection after DNS, or zero.
+        #[ts(type = "number")]
+        elapsed_connect: u64,
+        /// Milliseconds spent in the last TLS handshake, or zero.
+        #[ts(type = "number")]
+        elapsed_tls: u64,
+        /// Milliseconds spent waiting on the server for the response head.
+        #[ts(type = "number")]
+        elapsed_ttfb: u64,
     },
     /// A piece of the response body, decompressed, base64-encoded.
     Body { data: String },
@@ -59,9 +59,6 @@ pub enum Error {
     #[error("Tauri error: {0}")]
     TauriError(#[from] tauri::Error),
 
-    #[error("Event source error: {0}")]
-    EventSourceError(#[from] eventsource_client::Error),
-
     #[error("I/O error: {0}")]
     IOError(#[from] io::Error),
 
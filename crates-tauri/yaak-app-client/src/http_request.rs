@@ -4,14 +4,20 @@ use crate::error::Result;
 use crate::models_ext::BlobManagerExt;
 use crate::models_ext::QueryManagerExt;
 use log::warn;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Instant;
-use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+use tauri::{AppHandle, Listener, Manager, Runtime, WebviewWindow};
 use tokio::sync::watch::Receiver;
+use yaak::bench::{BenchHttpRequestParams, BenchOptions, bench_http_request_by_id};
+use yaak::runner::RunCollectionParams;
 use yaak::send::{ResponseBody, SendHttpRequestWithPluginsParams, send_http_request_with_plugins};
 use yaak_crypto::manager::EncryptionManager;
 use yaak_http::manager::HttpConnectionManager;
-use yaak_models::models::{CookieJar, Environment, HttpRequest, HttpResponse, HttpResponseState};
+use yaak_models::models::{
+    CookieJar, Environment, HttpBenchReport, HttpRequest, HttpResponse, HttpResponseState,
+    RunnerRun, RunnerStep,
+};
 use yaak_models::util::UpdateSource;
 use yaak_plugins::events::PluginContext;
 use yaak_plugins::manager::PluginManager;
@@ -88,6 +94,90 @@ pub async fn send_http_request<R: Runtime>(
     .await
 }
 
+/// Fire a saved request repeatedly as a load check, until the duration runs out or the frontend
+/// emits `cancel_http_bench_{request_id}`.
+pub async fn bench_http_request<R: Runtime>(
+    window: &WebviewWindow<R>,
+    request_id: &str,
+    environment_id: Option<&str>,
+    cookie_jar_id: Option<&str>,
+    options: BenchOptions,
+) -> Result<HttpBenchReport> {
+    let app_handle = window.app_handle().clone();
+    let (cancel_tx, cancelled_rx) = tokio::sync::watch::channel(false);
+    let cancel_listener =
+        app_handle.listen_any(format!("cancel_http_bench_{request_id}"), move |_event| {
+            if let Err(e) = cancel_tx.send(true) {
+                warn!("Failed to send cancel event for bench {e:?}");
+            }
+        });
+
+    let result = bench_http_request_by_id(BenchHttpRequestParams {
+        query_manager: app_handle.db_manager().inner(),
+        request_id,
+        environment_id,
+        cookie_jar_id,
+        plugin_manager: Arc::new((*app_handle.state::<PluginManager>()).clone()),
+        encryption_manager: Arc::new((*app_handle.state::<EncryptionManager>()).clone()),
+        plugin_context: &window.plugin_context(),
+        connection_manager: app_handle.state::<HttpConnectionManager>().inner(),
+        cancelled_rx: Some(cancelled_rx),
+        options,
+        variables: Vec::new(),
+    })
+    .await;
+    app_handle.unlisten(cancel_listener);
+
+    result.map_err(|e| GenericError(e.to_string()))
+}
+
+/// Run requests in order, once per row of an optional data file, storing the run under `run_id`
+/// as it goes, until the frontend emits `cancel_runner_run_{run_id}`.
+pub async fn run_collection<R: Runtime>(
+    window: &WebviewWindow<R>,
+    workspace_id: &str,
+    run_id: &str,
+    name: String,
+    steps: Vec<RunnerStep>,
+    data_file: Option<&Path>,
+    environment_id: Option<&str>,
+    cookie_jar_id: Option<String>,
+) -> Result<RunnerRun> {
+    let app_handle = window.app_handle().clone();
+    let (cancel_tx, cancelled_rx) = tokio::sync::watch::channel(false);
+    let cancel_listener =
+        app_handle.listen_any(format!("cancel_runner_run_{run_id}"), move |_event| {
+            if let Err(e) = cancel_tx.send(true) {
+                warn!("Failed to send cancel event for run {e:?}");
+            }
+        });
+
+    let response_dir = app_handle.path().app_data_dir()?.join("responses");
+    let result = yaak::runner::run_collection(RunCollectionParams {
+        query_manager: app_handle.db_manager().inner(),
+        blob_manager: app_handle.blob_manager().inner(),
+        workspace_id,
+        run_id: Some(run_id),
+        name,
+        steps,
+        data_file,
+        environment_id,
+        update_source: UpdateSource::from_window_label(window.label()),
+        cookie_jar_id,
+        response_dir: &response_dir,
+        plugin_manager: Arc::new((*app_handle.state::<PluginManager>()).clone()),
+        encryption_manager: Arc::new((*app_handle.state::<EncryptionManager>()).clone()),
+        plugin_context: &window.plugin_context(),
+        cancelled_rx: Some(cancelled_rx),
+        connection_manager: app_handle.state::<HttpConnectionManager>().inner(),
+        variables: Vec::new(),
+    })
+    .await;
+    app_handle.unlisten(cancel_listener);
+
+    result.map_err(|e| GenericError(e.to_string()))
+}
+
 pub async fn send_http_request_with_context<R: Runtime>(
     window: &WebviewWindow<R>,
     unrendered_request: &HttpRequest,
@@ -172,10 +262,10 @@ async fn send_http_request_inner<R: Runtime>(
         plugin_context,
         cancelled_rx: Some(cancelled_rx.clone()),
         connection_manager: connection_manager.inner(),
+        variables: Vec::new(),
     })
     .await
     .map_err(|e| GenericError(e.to_string()))?;
 
     Ok(SentHttpRequest { response: result.response, body: result.response_body })
 }
-
@@ -2,7 +2,7 @@ extern crate core;
 use crate::encoding::read_response_body;
 use crate::error::Error::GenericError;
 use crate::error::Result;
-use crate::grpc::{build_metadata, metadata_to_map};
+use crate::grpc::response_encoding;
 use crate::http_request::send_http_request;
 use crate::import::{import_data, import_url};
 use crate::models_ext::{BlobManagerExt, QueryManagerExt};
@@ -11,7 +11,6 @@ use crate::render::{render_grpc_request, render_template};
 use crate::updates::{UpdateMode, UpdateTrigger, YaakUpdater};
 use crate::uri_scheme::handle_deep_link;
 use error::Result as YaakResult;
-use eventsource_client::{EventParser, SSE};
 use log::{debug, error, info, warn};
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -29,15 +28,16 @@ use tokio::sync::Mutex;
 use tokio::task::block_in_place;
 use tokio::time;
 use yaak::send::ResponseBody;
+use yaak_commands::grpc::{build_metadata, compression_encoding, grpc_protocol, metadata_to_map};
 use yaak_commands::responses::locate_response_body;
 use yaak_commands::resolve::resolve_grpc_request;
 use yaak_common::command::new_checked_command;
 use yaak_crypto::manager::EncryptionManager;
 use yaak_grpc::manager::{GrpcConfig, GrpcHandle};
-use yaak_grpc::{Code, ServiceDefinition};
+use yaak_grpc::{Code, Protocol, ServiceDefinition};
 use yaak_mac_window::AppHandleMacWindowExt;
 use yaak_models::models::{
-    CookieJar, Environment, GrpcConnection, GrpcConnectionState, GrpcEvent,
+    CookieJar, Environment, GrpcCompression, GrpcConnection, GrpcConnectionState, GrpcEvent,
     GrpcEventType, HttpRequest, HttpResponse, HttpResponseState, Workspace,
 };
 use yaak_models::util::{BatchUpsertResult, UpdateSource};
@@ -48,6 +48,7 @@ use yaak_plugins::events::{
 use yaak_plugins::manager::PluginManager;
 use yaak_plugins::template_callback::PluginTemplateCallback;
 use yaak_rpc_schema::{AppMetaData, EphemeralHttpResponse};
+use yaak_sse::parser::EventStreamParser;
 use yaak_sse::sse::ServerSentEvent;
 use yaak_tauri_utils::window::WorkspaceWindowTrait;
 use yaak_templates::strip_json_comments::strip_json_comments;
@@ -266,7 +267,13 @@ async fn cmd_grpc_reflect<R: Runtime>(
     .await?;
 
     let uri = safe_uri(&req.url);
-    let metadata = build_metadata(&window, &req, &auth_context_id).await?;
+    let metadata = build_metadata(
+        &req,
+        &auth_context_id,
+        &window.state::<PluginManager>(),
+        &window.plugin_context(),
+    )
+    .await?;
     let settings = window.db().get_settings();
     let client_certificate =
         find_client_certificate(req.url.as_str(), &settings.client_certificates);
@@ -286,6 +293,7 @@ async fn cmd_grpc_reflect<R: Runtime>(
             resolved_settings.validate_certificates.value,
             client_certificate,
             resolved_settings.request_message_size.value,
+            grpc_protocol(req.protocol),
         )
         .await
         .map_err(|e| GenericError(e.to_string()))?)
@@ -325,7 +333,13 @@ async fn cmd_grpc_go<R: Runtime>(
     )
     .await?;
 
-    let metadata = build_metadata(&window, &request, &auth_context_id).await?;
+    let metadata = build_metadata(
+        &request,
+        &auth_context_id,
+        &window.state::<PluginManager>(),
+        &window.plugin_context(),
+    )
+    .await?;
 
     // Find matching client certificate for this URL
     let settings = app_handle.db().get_settings();
@@ -381,8 +395,13 @@ async fn cmd_grpc_go<R: Runtime>(
             resolved_settings.validate_certificates.value,
             client_cert.clone(),
             resolved_settings.request_message_size.value,
+            grpc_protocol(request.protocol),
         )
-        .await;
+        .await
+        .map(|c| {
+            c.with_timeout(resolved_settings.request_timeout.value)
+                .with_compression(compression_encoding(request.compression))
+        });
 
     let connection = match connection {
         Ok(c) => c,
@@ -505,6 +524,28 @@ async fn cmd_grpc_go<R: Runtime>(
             },
             &UpdateSource::from_window_label(window.label()),
         )?;
+        let protocol = grpc_protocol(req.protocol);
+        if protocol != Protocol::Grpc {
+            app_handle.db().upsert_grpc_event(
+                &GrpcEvent {
+                    content: format!("Calling with {protocol}"),
+                    event_type: GrpcEventType::Info,
+                    ..base_event.clone()
+                },
+                &UpdateSource::from_window_label(window.label()),
+            )?;
+        }
+        if req.compression != GrpcCompression::None {
+            let content = if protocol.supports_compression() {
+                format!("Compressing messages with {}", req.compression)
+            } else {
+                format!("Sending messages uncompressed, as {protocol} calls are")
+            };
+            app_handle.db().upsert_grpc_event(
+                &GrpcEvent { content, event_type: GrpcEventType::Info, ..base_event.clone() },
+                &UpdateSource::from_window_label(window.label()),
+            )?;
+        }
 
         async move {
             // Create callback for streaming methods that handles both success and error
@@ -597,13 +638,13 @@ async fn cmd_grpc_go<R: Runtime>(
             }
 
             match maybe_msg {
-                Some(Ok(msg)) => {
+                Some(Ok(response)) => {
                     app_handle
                         .db()
                         .upsert_grpc_event(
                             &GrpcEvent {
-                                metadata: metadata_to_map(msg.metadata().clone()),
-                                content: if msg.metadata().len() == 0 {
+                                metadata: metadata_to_map(&response.metadata),
+                                content: if response.metadata.len() == 0 {
                                     "Received response"
                                 } else {
                                     "Received response with metadata"
@@ -615,9 +656,21 @@ async fn cmd_grpc_go<R: Runtime>(
                             &UpdateSource::from_window_label(window.label()),
                         )
                         .unwrap();
-                    let response_message = msg.into_inner();
+                    if let Some(encoding) = response_encoding(&response.metadata) {
+                        app_handle
+                            .db()
+                            .upsert_grpc_event(
+                                &GrpcEvent {
+                                    content: format!("Response compressed with {encoding}"),
+                                    event_type: GrpcEventType::Info,
+                                    ..base_event.clone()
+                                },
+                                &UpdateSource::from_window_label(window.label()),
+                            )
+                            .unwrap();
+                    }
                     let content = match connection
-                        .serialize_message(&response_message, &metadata, client_cert.clone())
+                        .serialize_message(&response.message, &metadata, client_cert.clone())
                         .await
                     {
                         Ok(content) => content,
@@ -656,6 +709,7 @@ async fn cmd_grpc_go<R: Runtime>(
                                 content: "Connection complete".to_string(),
                                 event_type: GrpcEventType::ConnectionEnd,
                                 status: Some(Code::Ok as i32),
+                                metadata: metadata_to_map(&response.trailers),
                                 ..base_event.clone()
                             },
                             &UpdateSource::from_window_label(window.label()),
@@ -671,7 +725,7 @@ async fn cmd_grpc_go<R: Runtime>(
                                     error: Some(s.message().to_string()),
                                     status: Some(s.code() as i32),
                                     content: "Request failed".to_string(),
-                                    metadata: metadata_to_map(s.metadata().clone()),
+                                    metadata: metadata_to_map(s.metadata()),
                                     event_type: GrpcEventType::ConnectionEnd,
                                     ..base_event.clone()
                                 },
@@ -713,7 +767,7 @@ async fn cmd_grpc_go<R: Runtime>(
                         .db()
                         .upsert_grpc_event(
                             &GrpcEvent {
-                                metadata: metadata_to_map(stream.metadata().clone()),
+                                metadata: metadata_to_map(stream.metadata()),
                                 content: if stream.metadata().len() == 0 {
                                     "Received response"
                                 } else {
@@ -726,6 +780,19 @@ async fn cmd_grpc_go<R: Runtime>(
                             &UpdateSource::from_window_label(window.label()),
                         )
                         .unwrap();
+                    if let Some(encoding) = response_encoding(stream.metadata()) {
+                        app_handle
+                            .db()
+                            .upsert_grpc_event(
+                                &GrpcEvent {
+                                    content: format!("Responses compressed with {encoding}"),
+                                    event_type: GrpcEventType::Info,
+                                    ..base_event.clone()
+                                },
+                                &UpdateSource::from_window_label(window.label()),
+                            )
+                            .unwrap();
+                    }
                     stream.into_inner()
                 }
                 Some(Err(yaak_grpc::error::Error::GrpcStreamError(e))) => {
@@ -738,7 +805,7 @@ async fn cmd_grpc_go<R: Runtime>(
                                     error: Some(s.message().to_string()),
                                     status: Some(s.code() as i32),
                                     content: "Stream failed".to_string(),
-                                    metadata: metadata_to_map(s.metadata().clone()),
+                                    metadata: metadata_to_map(s.metadata()),
                                     event_type: GrpcEventType::ConnectionEnd,
                                     ..base_event.clone()
                                 },
@@ -820,7 +887,7 @@ async fn cmd_grpc_go<R: Runtime>(
                                 &GrpcEvent {
                                     content: "Connection complete".to_string(),
                                     status: Some(Code::Ok as i32),
-                                    metadata: metadata_to_map(trailers),
+                                    metadata: metadata_to_map(&trailers),
                                     event_type: GrpcEventType::ConnectionEnd,
                                     ..base_event.clone()
                                 },
@@ -837,7 +904,7 @@ async fn cmd_grpc_go<R: Runtime>(
                                     content: "Stream failed".to_string(),
                                     error: Some(status.message().to_string()),
                                     status: Some(status.code() as i32),
-                                    metadata: metadata_to_map(status.metadata().clone()),
+                                    metadata: metadata_to_map(status.metadata()),
                                     event_type: GrpcEventType::ConnectionEnd,
                                     ..base_event.clone()
                                 },
@@ -993,22 +1060,7 @@ async fn cmd_get_sse_events<R: Runtime>(
     };
 
     let body = fs::read(body_path)?;
-    let mut event_parser = EventParser::new();
-    event_parser.process_bytes(body.into())?;
-
-    let mut events = Vec::new();
-    while let Some(e) = event_parser.get_event() {
-        if let SSE::Event(e) = e {
-            events.push(ServerSentEvent {
-                event_type: e.event_type,
-                data: e.data,
-                id: e.id,
-                retry: e.retry,
-            });
-        }
-    }
-
-    Ok(events)
+    Ok(EventStreamParser::new().feed(&body))
 }
 
 async fn cmd_import_data<R: Runtime>(
@@ -1202,7 +1254,6 @@ pub fn run() {
             ])
             .level_for("plugin_runtime", log::LevelFilter::Info)
             .level_for("cookie_store", log::LevelFilter::Info)
-            .level_for("eventsource_client::event_parser", log::LevelFilter::Info)
             .level_for("h2", log::LevelFilter::Info)
             .level_for("hyper", log::LevelFilter::Info)
             .level_for("hyper_util", log::LevelFilter::Info)
@@ -23,8 +23,11 @@ use log::warn;
 use serde::Serialize;
 use tauri::{Manager, Runtime, State, WebviewWindow};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
+use yaak::bench::BenchOptions;
 use yaak_commands::{Host, PluginHost};
 use yaak_core::WorkspaceContext;
 use yaak_crypto::manager::EncryptionManager;
@@ -36,8 +39,9 @@ use yaak_grpc::manager::GrpcHandle;
 use yaak_grpc::ServiceDefinition;
 use yaak_models::blob_manager::BlobManager;
 use yaak_models::models::{
-    GraphQlIntrospection, GrpcEvent, HttpRequest, HttpRequestHeader, HttpResponse,
-    HttpResponseEvent, Plugin, Settings, WebsocketConnection, WebsocketEvent, WorkspaceMeta,
+    GraphQlIntrospection, GrpcEvent, HttpBenchReport, HttpRequest, HttpRequestHeader, HttpResponse,
+    HttpResponseEvent, Plugin, RunnerRun, Settings, WebsocketConnection, WebsocketEvent,
+    WorkspaceMeta,
 };
 use yaak_models::query_manager::QueryManager;
 use yaak_models::util::BatchUpsertResult;
@@ -517,6 +521,10 @@ async fn cmd_export_data<R: Runtime>(ctx: ClientCtx<R>, req: CmdExportDataReq) -
     Ok(yaak_commands::data::cmd_export_data(ctx, req).await?)
 }
 
+async fn cmd_export_har<R: Runtime>(ctx: ClientCtx<R>, req: CmdExportHarReq) -> Result<()> {
+    Ok(yaak_commands::data::cmd_export_har(ctx, req).await?)
+}
+
 async fn cmd_save_base64_to_binary<R: Runtime>(ctx: ClientCtx<R>, req: CmdSaveBase64ToBinaryReq) -> Result<()> {
     Ok(crate::cmd_save_base64_to_binary(ctx.window.app_handle().clone(), &req.filepath, &req.data).await?)
 }
@@ -529,6 +537,21 @@ async fn cmd_send_http_request<R: Runtime>(ctx: ClientCtx<R>, req: CmdSendHttpRe
     Ok(crate::cmd_send_http_request(ctx.window.app_handle().clone(), ctx.window.clone(), req.environment_id.as_deref(), req.cookie_jar_id.as_deref(), req.request_id).await?)
 }
 
+async fn cmd_bench_http_request<R: Runtime>(ctx: ClientCtx<R>, req: CmdBenchHttpRequestReq) -> Result<HttpBenchReport> {
+    let options = BenchOptions {
+        concurrency: req.concurrency,
+        rate: req.rate,
+        duration: Duration::from_millis(req.duration_ms as u64),
+        max_requests: req.max_requests.map(u64::from),
+    };
+    Ok(crate::http_request::bench_http_request(&ctx.window, &req.request_id, req.environment_id.as_deref(), req.cookie_jar_id.as_deref(), options).await?)
+}
+
+async fn cmd_run_collection<R: Runtime>(ctx: ClientCtx<R>, req: CmdRunCollectionReq) -> Result<RunnerRun> {
+    let data_file = req.data_file.map(PathBuf::from);
+    Ok(crate::http_request::run_collection(&ctx.window, &req.workspace_id, &req.run_id, req.name, req.steps, data_file.as_deref(), req.environment_id.as_deref(), req.cookie_jar_id).await?)
+}
+
 async fn cmd_reload_plugins<R: Runtime>(ctx: ClientCtx<R>, req: CmdReloadPluginsReq) -> Result<Vec<(String, String)>> {
     Ok(yaak_commands::actions::cmd_reload_plugins(ctx, req).await?)
 }
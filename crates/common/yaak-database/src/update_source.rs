@@ -5,6 +5,7 @@ use ts_rs::TS;
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum UpdateSource {
     Background,
+    Extraction,
     Import,
     Plugin,
     Sync,
@@ -20,8 +20,9 @@ use yaak_git::{
 };
 use yaak_grpc::ServiceDefinition;
 use yaak_models::models::{
-    AnyModel, GraphQlIntrospection, GrpcEvent, HttpRequest, HttpRequestHeader, HttpResponse,
-    HttpResponseEvent, Plugin, Settings, WebsocketConnection, WebsocketEvent, WorkspaceMeta,
+    AnyModel, GraphQlIntrospection, GrpcEvent, HttpBenchReport, HttpRequest, HttpRequestHeader,
+    HttpResponse, HttpResponseEvent, Plugin, RunnerRun, RunnerStep, Settings, WebsocketConnection,
+    WebsocketEvent, WorkspaceMeta,
 };
 use yaak_models::util::BatchUpsertResult;
 use yaak_plugins::api::{PluginNameVersion, PluginSearchResponse, PluginUpdatesResponse};
@@ -349,6 +350,15 @@ pub struct CmdExportDataReq {
     pub include_private_environments: bool,
 }
 
+/// Export responses from the send history as a HAR file.
+#[derive(Debug, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct CmdExportHarReq {
+    pub export_path: String,
+    pub response_ids: Vec<String>,
+}
+
 #[derive(Debug, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "gen_rpc.ts")]
@@ -374,6 +384,40 @@ pub struct CmdSendHttpRequestReq {
     pub request_id: String,
 }
 
+/// Fire a saved request repeatedly as a load check. Nothing is stored; cancel by emitting
+/// `cancel_http_bench_{requestId}`.
+#[derive(Debug, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct CmdBenchHttpRequestReq {
+    pub request_id: String,
+    pub environment_id: Option<String>,
+    pub cookie_jar_id: Option<String>,
+    pub concurrency: u32,
+    /// Requests per second across all workers; unlimited when absent
+    pub rate: Option<f64>,
+    pub duration_ms: u32,
+    pub max_requests: Option<u32>,
+}
+
+/// Run requests in order, once per row of an optional data file, storing the run and its
+/// iterations as they finish. Cancel by emitting `cancel_runner_run_{runId}`.
+#[derive(Debug, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_rpc.ts")]
+pub struct CmdRunCollectionReq {
+    pub workspace_id: String,
+    /// The id the run is stored under, chosen by the caller so it can cancel this run alone
+    pub run_id: String,
+    pub name: String,
+    /// Each step carries its own delay and retries
+    pub steps: Vec<RunnerStep>,
+    /// A CSV file with a header row, or a JSON array of objects
+    pub data_file: Option<String>,
+    pub environment_id: Option<String>,
+    pub cookie_jar_id: Option<String>,
+}
+
 #[derive(Debug, Deserialize, TS)]
 #[ts(export, export_to = "gen_rpc.ts")]
 pub struct CmdReloadPluginsReq {}
@@ -928,9 +972,12 @@ macro_rules! with_commands {
     cmd_call_http_authentication_action(CmdCallHttpAuthenticationActionReq) -> (),
     cmd_curl_to_request(CmdCurlToRequestReq) -> HttpRequest,
     cmd_export_data(CmdExportDataReq) -> (),
+    cmd_export_har(CmdExportHarReq) -> (),
     cmd_save_base64_to_binary(CmdSaveBase64ToBinaryReq) -> (),
     cmd_save_response(CmdSaveResponseReq) -> (),
     cmd_send_http_request(CmdSendHttpRequestReq) -> HttpResponse,
+    cmd_bench_http_request(CmdBenchHttpRequestReq) -> HttpBenchReport,
+    cmd_run_collection(CmdRunCollectionReq) -> RunnerRun,
     cmd_reload_plugins(CmdReloadPluginsReq) -> Vec<(String, String)>,
     cmd_plugin_info(CmdPluginInfoReq) -> PluginMetadata,
     cmd_delete_all_grpc_connections(CmdDeleteAllGrpcConnectionsReq) -> (),
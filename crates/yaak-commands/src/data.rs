@@ -4,6 +4,7 @@ use crate::error::Result;
 use crate::host::Host;
 use std::path::Path;
 use yaak::export::{self, ExportDataParams};
+use yaak::har::{self, ExportHarParams};
 use yaak_rpc_schema::*;
 use yaak_templates::format_json::format_json;
 
@@ -18,6 +19,17 @@ pub async fn cmd_export_data<H: Host>(host: H, req: CmdExportDataReq) -> Result<
     })?)
 }
 
+pub async fn cmd_export_har<H: Host>(host: H, req: CmdExportHarReq) -> Result<()> {
+    let version = host.app_version();
+    Ok(har::export_har(ExportHarParams {
+        query_manager: host.query_manager(),
+        blob_manager: host.blob_manager(),
+        yaak_version: &version,
+        export_path: Path::new(&req.export_path),
+        response_ids: req.response_ids.iter().map(|s| s.as_str()).collect(),
+    })?)
+}
+
 pub async fn cmd_format_json<H: Host>(_host: H, req: CmdFormatJsonReq) -> Result<String> {
     Ok(format_json(&req.text, "  "))
 }
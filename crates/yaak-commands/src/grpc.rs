@@ -0,0 +1,92 @@
+//! gRPC helpers shared by every host that sends gRPC requests.
+
+use crate::error::Result;
+use std::collections::BTreeMap;
+use yaak_grpc::KeyAndValueRef::{Ascii, Binary};
+use yaak_grpc::{CompressionEncoding, MetadataMap, Protocol};
+use yaak_models::models::{GrpcCompression, GrpcProtocol, GrpcRequest};
+use yaak_plugins::events::{CallHttpAuthenticationRequest, HttpHeader, PluginContext};
+use yaak_plugins::manager::PluginManager;
+
+/// Enabled metadata of a rendered request, plus whatever its authentication adds.
+pub async fn build_metadata(
+    request: &GrpcRequest,
+    authentication_context_id: &str,
+    plugin_manager: &PluginManager,
+    plugin_context: &PluginContext,
+) -> Result<BTreeMap<String, String>> {
+    let mut metadata = BTreeMap::new();
+    for h in &request.metadata {
+        if !h.enabled || (h.name.is_empty() && h.value.is_empty()) {
+            continue;
+        }
+        metadata.insert(h.name.clone(), h.value.clone());
+    }
+
+    match &request.authentication_type {
+        // No authentication, not even inherited
+        None => {}
+        // Explicitly no authentication
+        Some(authentication_type) if authentication_type == "none" => {}
+        Some(authentication_type) => {
+            let plugin_req = CallHttpAuthenticationRequest {
+                context_id: format!("{:x}", md5::compute(authentication_context_id)),
+                values: serde_json::from_value(serde_json::to_value(&request.authentication)?)?,
+                method: "POST".to_string(),
+                url: request.url.clone(),
+                headers: metadata
+                    .iter()
+                    .map(|(name, value)| HttpHeader {
+                        name: name.to_string(),
+                        value: value.to_string(),
+                    })
+                    .collect(),
+                body: None,
+            };
+            let plugin_result = plugin_manager
+                .call_http_authentication(plugin_context, authentication_type, plugin_req)
+                .await?;
+            for header in plugin_result.set_headers.unwrap_or_default() {
+                metadata.insert(header.name, header.value);
+            }
+        }
+    }
+
+    Ok(metadata)
+}
+
+/// Metadata as name and value. Binary values (`-bin` keys) are base64, as on the wire and as
+/// binary metadata is entered.
+pub fn metadata_to_map(metadata: &MetadataMap) -> BTreeMap<String, String> {
+    let mut entries = BTreeMap::new();
+    for r in metadata.iter() {
+        match r {
+            Ascii(k, v) => {
+                entries.insert(k.to_string(), v.to_str().map(str::to_string).unwrap_or_default())
+            }
+            Binary(k, v) => entries
+                .insert(k.to_string(), String::from_utf8_lossy(v.as_encoded_bytes()).to_string()),
+        };
+    }
+    entries
+}
+
+/// The encoding sent messages are compressed with, if any
+pub fn compression_encoding(compression: GrpcCompression) -> Option<CompressionEncoding> {
+    match compression {
+        GrpcCompression::None => None,
+        GrpcCompression::Gzip => Some(CompressionEncoding::Gzip),
+        GrpcCompression::Zstd => Some(CompressionEncoding::Zstd),
+    }
+}
+
+/// The wire protocol a request is sent with
+pub fn grpc_protocol(protocol: GrpcProtocol) -> Protocol {
+    match protocol {
+        GrpcProtocol::Grpc => Protocol::Grpc,
+        GrpcProtocol::GrpcWeb => Protocol::GrpcWeb,
+        GrpcProtocol::GrpcWebText => Protocol::GrpcWebText,
+        GrpcProtocol::ConnectJson => Protocol::ConnectJson,
+        GrpcProtocol::ConnectProto => Protocol::ConnectProto,
+    }
+}
@@ -120,6 +120,14 @@ pub async fn models_workspace_models<H: PluginHost>(
         l.append(&mut db.list_grpc_requests(wid)?.into_iter().map(Into::into).collect());
         l.append(&mut db.list_http_requests(wid)?.into_iter().map(Into::into).collect());
         l.append(&mut db.list_http_responses(wid, None)?.into_iter().map(Into::into).collect());
+        l.append(&mut db.list_runner_runs(wid, None)?.into_iter().map(Into::into).collect());
+        l.append(
+            &mut db
+                .list_runner_iterations_for_workspace(wid)?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        );
         l.append(&mut db.list_websocket_connections(wid)?.into_iter().map(Into::into).collect());
         l.append(&mut db.list_websocket_requests(wid)?.into_iter().map(Into::into).collect());
         l.append(&mut db.list_workspace_metas(wid)?.into_iter().map(Into::into).collect());
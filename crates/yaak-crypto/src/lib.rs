@@ -3,5 +3,5 @@ extern crate core;
 pub mod encryption;
 pub mod error;
 pub mod manager;
-mod master_key;
+pub mod master_key;
 mod workspace_key;
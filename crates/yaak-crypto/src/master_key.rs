@@ -9,13 +9,15 @@ use log::info;
 
 const HUMAN_PREFIX: &str = "YKM_";
 
+/// The app's own key, kept in the OS keychain. Workspace keys are stored encrypted with it,
+/// and so can anything else that must not sit in a database in the clear.
 #[derive(Debug, Clone)]
-pub(crate) struct MasterKey {
+pub struct MasterKey {
     key: Key<XChaCha20Poly1305>,
 }
 
 impl MasterKey {
-    pub(crate) fn get_or_create(app_id: &str, user: &str) -> Result<Self> {
+    pub fn get_or_create(app_id: &str, user: &str) -> Result<Self> {
         let id = format!("{app_id}.EncryptionKey");
         let entry = Entry::new(&id, user)?;
 
@@ -40,11 +42,11 @@ impl MasterKey {
         Ok(Self { key })
     }
 
-    pub(crate) fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+    pub fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
         encrypt_data(data, &self.key)
     }
 
-    pub(crate) fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
         decrypt_data(data, &self.key)
     }
 
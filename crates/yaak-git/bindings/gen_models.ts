@@ -47,8 +47,27 @@ export type Folder = {
   settingFollowRedirects: InheritedBoolSetting;
   settingRequestTimeout: InheritedIntSetting;
   settingRequestMessageSize: InheritedIntSetting;
+  settingHttp3: InheritedHttp3Setting;
+  settingProxy: InheritedProxySetting;
 };
 
+/**
+ * How the messages a gRPC request sends are compressed. Compressed responses are accepted
+ * either way.
+ */
+export type GrpcCompression = "none" | "gzip" | "zstd";
+
+/**
+ * The wire protocol a gRPC request is sent with. gRPC-Web and Connect also work over
+ * HTTP/1.1, so they reach services behind browser-facing proxies.
+ */
+export type GrpcProtocol =
+  | "grpc"
+  | "grpc_web"
+  | "grpc_web_text"
+  | "connect_json"
+  | "connect_proto";
+
 export type GrpcRequest = {
   model: "grpc_request";
   id: string;
@@ -69,10 +88,31 @@ export type GrpcRequest = {
    * Server URL (http for plaintext or https for secure)
    */
   url: string;
+  protocol: GrpcProtocol;
+  compression: GrpcCompression;
   settingValidateCertificates: InheritedBoolSetting;
   settingRequestMessageSize: InheritedIntSetting;
+  /**
+   * Deadline for each call in milliseconds, sent as `grpc-timeout`. 0 means none.
+   */
+  settingRequestTimeout: InheritedIntSetting;
 };
 
+/**
+ * When to send a request over HTTP/3 (QUIC) instead of TCP.
+ */
+export type Http3Mode = "off" | "alt_svc" | "force";
+
+export type HttpAssertionKind =
+  | "status_equals"
+  | "header_matches"
+  | "json_path_equals"
+  | "json_path_contains"
+  | "body_matches"
+  | "elapsed_under";
+
+export type HttpExtractionSource = "json_path" | "xpath" | "header" | "body_regex" | "cookie";
+
 export type HttpRequest = {
   model: "http_request";
   id: string;
@@ -99,6 +139,57 @@ export type HttpRequest = {
   settingValidateCertificates: InheritedBoolSetting;
   settingFollowRedirects: InheritedBoolSetting;
   settingRequestTimeout: InheritedIntSetting;
+  settingHttp3: InheritedHttp3Setting;
+  /**
+   * Checks evaluated against every response this request produces.
+   */
+  assertions: Array<HttpRequestAssertion>;
+  /**
+   * Values written into environment variables after each successful response.
+   */
+  extractions: Array<HttpRequestExtraction>;
+};
+
+/**
+ * A check run against a response once it has been stored. Both `target` and `value` are
+ * templates, rendered with the rest of the request.
+ */
+export type HttpRequestAssertion = {
+  enabled?: boolean;
+  kind: HttpAssertionKind;
+  /**
+   * What the check looks at, for kinds that need one: a header name or a JSON path
+   */
+  target: string;
+  /**
+   * The expected value, pattern, or limit
+   */
+  value: string;
+  id?: string;
+};
+
+/**
+ * A value read out of a successful response and written into an environment variable, so a
+ * later request can use it without re-reading or re-sending this one. `expression` is a
+ * template, rendered with the rest of the request.
+ */
+export type HttpRequestExtraction = {
+  enabled?: boolean;
+  source: HttpExtractionSource;
+  /**
+   * The JSON path, XPath, header name, regex, or cookie name to read
+   */
+  expression: string;
+  /**
+   * Name of the environment variable to write
+   */
+  variable: string;
+  /**
+   * Environment to write into. Defaults to the environment the request was sent with, or the
+   * base environment when there is none.
+   */
+  environmentId?: string;
+  id?: string;
 };
 
 export type HttpRequestHeader = { enabled?: boolean; name: string; value: string; id?: string };
@@ -116,8 +207,35 @@ export type HttpUrlParameter = {
 
 export type InheritedBoolSetting = { enabled?: boolean; value: boolean };
 
+export type InheritedHttp3Setting = { enabled?: boolean; value: Http3Mode };
+
 export type InheritedIntSetting = { enabled?: boolean; value: number };
 
+/**
+ * A proxy for everything under a workspace or folder, in place of the one from the global
+ * settings. As there, a `value` of `None` is the system proxy. These models are synced, so the
+ * credentials are rendered as templates when sending, and the password is normally `secure()`.
+ */
+export type InheritedProxySetting = { enabled?: boolean; value: ProxySetting | null };
+
+export type ProxySetting =
+  | {
+      type: "enabled";
+      http: string;
+      https: string;
+      auth: ProxySettingAuth | null;
+      bypass: string;
+      disabled: boolean;
+      /**
+       * A `socks5://` or `socks5h://` proxy for traffic the other two don't cover. With
+       * `socks5h`, the proxy resolves host names.
+       */
+      socks: string;
+    }
+  | { type: "disabled" };
+
+export type ProxySettingAuth = { user: string; password: string };
+
 export type SyncModel =
   | ({ type: "workspace" } & Workspace)
   | ({ type: "environment" } & Environment)
@@ -169,4 +287,6 @@ export type Workspace = {
   settingDnsOverrides: Array<DnsOverride>;
   settingSendCookies: boolean;
   settingStoreCookies: boolean;
+  settingHttp3: Http3Mode;
+  settingProxy: InheritedProxySetting;
 };
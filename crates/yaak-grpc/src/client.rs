@@ -1,16 +1,13 @@
 use crate::error::Error::GenericError;
 use crate::error::Result;
 use crate::manager::decorate_req;
+use crate::protocol::{Protocol, Transport};
 use crate::transport::get_transport;
 use async_recursion::async_recursion;
-use hyper_rustls::HttpsConnector;
-use hyper_util::client::legacy::Client;
-use hyper_util::client::legacy::connect::HttpConnector;
 use log::debug;
 use std::collections::BTreeMap;
 use tokio_stream::StreamExt;
 use tonic::Request;
-use tonic::body::BoxBody;
 use tonic::transport::Uri;
 use tonic_reflection::pb::v1::server_reflection_request::MessageRequest;
 use tonic_reflection::pb::v1::server_reflection_response::MessageResponse;
@@ -22,7 +19,7 @@ use tonic_reflection::pb::v1::{ExtensionRequest, FileDescriptorResponse};
 use tonic_reflection::pb::{v1, v1alpha};
 use yaak_tls::ClientCertificateConfig;
 
-pub struct AutoReflectionClient<T = Client<HttpsConnector<HttpConnector>, BoxBody>> {
+pub struct AutoReflectionClient<T = Transport> {
     use_v1alpha: bool,
     client_v1: v1::server_reflection_client::ServerReflectionClient<T>,
     client_v1alpha: v1alpha::server_reflection_client::ServerReflectionClient<T>,
@@ -34,16 +31,17 @@ impl AutoReflectionClient {
         validate_certificates: bool,
         client_cert: Option<ClientCertificateConfig>,
         max_message_size: usize,
+        protocol: Protocol,
     ) -> Result<Self> {
         let client_v1 = v1::server_reflection_client::ServerReflectionClient::with_origin(
-            get_transport(validate_certificates, client_cert.clone())?,
+            get_transport(validate_certificates, client_cert.clone(), protocol)?,
             uri.clone(),
         )
         .max_decoding_message_size(max_message_size)
         .max_encoding_message_size(max_message_size);
         let client_v1alpha =
             v1alpha::server_reflection_client::ServerReflectionClient::with_origin(
-                get_transport(validate_certificates, client_cert.clone())?,
+                get_transport(validate_certificates, client_cert.clone(), protocol)?,
                 uri.clone(),
             )
             .max_decoding_message_size(max_message_size)
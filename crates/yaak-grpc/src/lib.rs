@@ -8,11 +8,16 @@ mod codec;
 pub mod error;
 mod json_schema;
 pub mod manager;
+mod protocol;
 mod reflection;
+#[cfg(test)]
+mod test_server;
 mod transport;
 
-pub use tonic::Code;
+pub use protocol::Protocol;
+pub use tonic::codec::CompressionEncoding;
 pub use tonic::metadata::*;
+pub use tonic::{Code, Status};
 
 pub fn serialize_options() -> SerializeOptions {
     SerializeOptions::new().skip_default_fields(false)
@@ -1,15 +1,15 @@
 use crate::codec::DynamicCodec;
 use crate::error::Error::GenericError;
 use crate::error::Result;
+use crate::protocol::{Protocol, Transport};
 use crate::reflection::{
     fill_pool_from_files, fill_pool_from_reflection, method_desc_to_path,
     reflect_types_for_dynamic_message, reflect_types_for_message,
 };
 use crate::transport::get_transport;
 use crate::{MethodDefinition, ServiceDefinition, json_schema};
-use hyper_rustls::HttpsConnector;
-use hyper_util::client::legacy::Client;
-use hyper_util::client::legacy::connect::HttpConnector;
+use base64::Engine;
+use base64::prelude::{BASE64_STANDARD_NO_PAD, BASE64_URL_SAFE_NO_PAD};
 use log::{info, warn};
 pub use prost_reflect::DynamicMessage;
 use prost_reflect::ReflectMessage;
@@ -24,11 +24,12 @@ use std::fmt::Display;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio_stream::StreamExt;
 use tokio_stream::wrappers::ReceiverStream;
-use tonic::body::BoxBody;
-use tonic::metadata::{MetadataKey, MetadataValue};
+use tonic::codec::CompressionEncoding;
+use tonic::metadata::{Binary, MetadataKey, MetadataMap, MetadataValue};
 use tonic::transport::Uri;
 use tonic::{IntoRequest, IntoStreamingRequest, Request, Response, Status, Streaming};
 use yaak_tls::ClientCertificateConfig;
@@ -36,10 +37,19 @@ use yaak_tls::ClientCertificateConfig;
 #[derive(Clone)]
 pub struct GrpcConnection {
     pool: Arc<RwLock<DescriptorPool>>,
-    conn: Client<HttpsConnector<HttpConnector>, BoxBody>,
+    transport: Transport,
     pub uri: Uri,
     use_reflection: bool,
     max_message_size: usize,
+    timeout: Option<Duration>,
+    compression: Option<CompressionEncoding>,
+}
+
+/// A unary response, with the trailers kept apart from the headers
+pub struct GrpcUnaryResponse {
+    pub metadata: MetadataMap,
+    pub message: DynamicMessage,
+    pub trailers: MetadataMap,
 }
 
 #[derive(Default, Debug)]
@@ -72,6 +82,24 @@ impl From<Status> for GrpcStreamError {
 }
 
 impl GrpcConnection {
+    /// Give every call a deadline, from a request timeout setting in milliseconds where 0
+    /// means none. The server is told through `grpc-timeout`, and the call fails locally
+    /// with `DEADLINE_EXCEEDED` if the server doesn't answer in time.
+    pub fn with_timeout(mut self, request_timeout: i32) -> Self {
+        self.timeout = match request_timeout.try_into() {
+            Ok(0) | Err(_) => None,
+            Ok(ms) => Some(Duration::from_millis(ms)),
+        };
+        self
+    }
+
+    /// Compress the messages sent on this connection, unless it uses Connect. Compressed
+    /// responses are accepted either way.
+    pub fn with_compression(mut self, compression: Option<CompressionEncoding>) -> Self {
+        self.compression = compression;
+        self
+    }
+
     pub async fn method(&self, service: &str, method: &str) -> Result<MethodDescriptor> {
         let service = self.service(service).await?;
         let method = service
@@ -96,7 +124,7 @@ impl GrpcConnection {
         message: &str,
         metadata: &BTreeMap<String, String>,
         client_cert: Option<ClientCertificateConfig>,
-    ) -> Result<Response<DynamicMessage>> {
+    ) -> Result<GrpcUnaryResponse> {
         if self.use_reflection {
             reflect_types_for_message(
                 self.pool.clone(),
@@ -105,6 +133,7 @@ impl GrpcConnection {
                 metadata,
                 client_cert,
                 self.max_message_size,
+                self.transport.protocol(),
             )
             .await?;
         }
@@ -115,16 +144,28 @@ impl GrpcConnection {
         let req_message = DynamicMessage::deserialize(input_message, &mut deserializer)?;
         deserializer.end()?;
 
-        let mut client = grpc_client(self.conn.clone(), self.uri.clone(), self.max_message_size);
+        let mut client = self.client(method);
 
         let mut req = req_message.into_request();
-        decorate_req(metadata, &mut req)?;
+        self.decorate(metadata, &mut req)?;
 
         let path = method_desc_to_path(method);
         let codec = DynamicCodec::new(method.clone());
         client.ready().await.map_err(|e| GenericError(format!("Failed to connect: {}", e)))?;
 
-        Ok(client.unary(req, path, codec).await?)
+        // Unary and server-streaming calls look the same on the wire. Reading the response as a
+        // stream keeps the trailers, which `Grpc::unary` merges into the headers.
+        let call = async {
+            let (metadata, mut stream, _) =
+                client.server_streaming(req, path, codec).await?.into_parts();
+            let message = stream
+                .message()
+                .await?
+                .ok_or_else(|| Status::internal("Missing response message."))?;
+            let trailers = stream.trailers().await?.unwrap_or_default();
+            Ok::<_, Status>(GrpcUnaryResponse { metadata, message, trailers })
+        };
+        Ok(self.within_deadline(call).await.map_err(GrpcStreamError::from)?)
     }
 
     pub async fn serialize_message(
@@ -141,6 +182,7 @@ impl GrpcConnection {
                 metadata,
                 client_cert,
                 self.max_message_size,
+                self.transport.protocol(),
             )
             .await?;
 
@@ -181,6 +223,7 @@ impl GrpcConnection {
             let use_reflection = self.use_reflection.clone();
             let client_cert = client_cert.clone();
             let max_message_size = self.max_message_size;
+            let protocol = self.transport.protocol();
             stream
                 .then(move |json| {
                     let pool = pool.clone();
@@ -200,6 +243,7 @@ impl GrpcConnection {
                                 &md,
                                 client_cert,
                                 max_message_size,
+                                protocol,
                             )
                             .await
                             {
@@ -223,15 +267,15 @@ impl GrpcConnection {
                 .filter_map(|x| x)
         };
 
-        let mut client = grpc_client(self.conn.clone(), self.uri.clone(), self.max_message_size);
+        let mut client = self.client(method);
         let path = method_desc_to_path(method);
         let codec = DynamicCodec::new(method.clone());
 
         let mut req = mapped_stream.into_streaming_request();
-        decorate_req(metadata, &mut req)?;
+        self.decorate(metadata, &mut req)?;
 
         client.ready().await.map_err(|e| GenericError(format!("Failed to connect: {}", e)))?;
-        Ok(client.streaming(req, path, codec).await?)
+        Ok(self.within_deadline(client.streaming(req, path, codec)).await?)
     }
 
     pub async fn client_streaming<F>(
@@ -255,6 +299,7 @@ impl GrpcConnection {
             let use_reflection = self.use_reflection.clone();
             let client_cert = client_cert.clone();
             let max_message_size = self.max_message_size;
+            let protocol = self.transport.protocol();
             stream
                 .then(move |json| {
                     let pool = pool.clone();
@@ -274,6 +319,7 @@ impl GrpcConnection {
                                 &md,
                                 client_cert,
                                 max_message_size,
+                                protocol,
                             )
                             .await
                             {
@@ -297,16 +343,16 @@ impl GrpcConnection {
                 .filter_map(|x| x)
         };
 
-        let mut client = grpc_client(self.conn.clone(), self.uri.clone(), self.max_message_size);
+        let mut client = self.client(method);
         let path = method_desc_to_path(method);
         let codec = DynamicCodec::new(method.clone());
 
         let mut req = mapped_stream.into_streaming_request();
-        decorate_req(metadata, &mut req)?;
+        self.decorate(metadata, &mut req)?;
 
         client.ready().await.map_err(|e| GenericError(format!("Failed to connect: {}", e)))?;
-        Ok(client
-            .client_streaming(req, path, codec)
+        Ok(self
+            .within_deadline(client.client_streaming(req, path, codec))
             .await
             .map_err(|e| GrpcStreamError { message: e.message().to_string(), status: Some(e) })?)
     }
@@ -325,26 +371,54 @@ impl GrpcConnection {
         let req_message = DynamicMessage::deserialize(input_message, &mut deserializer)?;
         deserializer.end()?;
 
-        let mut client = grpc_client(self.conn.clone(), self.uri.clone(), self.max_message_size);
+        let mut client = self.client(method);
 
         let mut req = req_message.into_request();
-        decorate_req(metadata, &mut req)?;
+        self.decorate(metadata, &mut req)?;
 
         let path = method_desc_to_path(method);
         let codec = DynamicCodec::new(method.clone());
         client.ready().await.map_err(|e| GenericError(format!("Failed to connect: {}", e)))?;
-        Ok(client.server_streaming(req, path, codec).await?)
+        Ok(self.within_deadline(client.server_streaming(req, path, codec)).await?)
     }
-}
 
-fn grpc_client(
-    conn: Client<HttpsConnector<HttpConnector>, BoxBody>,
-    uri: Uri,
-    max_message_size: usize,
-) -> tonic::client::Grpc<Client<HttpsConnector<HttpConnector>, BoxBody>> {
-    tonic::client::Grpc::with_origin(conn, uri)
-        .max_decoding_message_size(max_message_size)
-        .max_encoding_message_size(max_message_size)
+    fn client(&self, method: &MethodDescriptor) -> tonic::client::Grpc<Transport> {
+        let transport = self.transport.for_method(method);
+        let client = tonic::client::Grpc::with_origin(transport, self.uri.clone())
+            .max_decoding_message_size(self.max_message_size)
+            .max_encoding_message_size(self.max_message_size)
+            .accept_compressed(CompressionEncoding::Gzip)
+            .accept_compressed(CompressionEncoding::Zstd);
+        match self.compression.filter(|_| self.transport.protocol().supports_compression()) {
+            Some(encoding) => client.send_compressed(encoding),
+            None => client,
+        }
+    }
+
+    fn decorate<T>(&self, metadata: &BTreeMap<String, String>, req: &mut Request<T>) -> Result<()> {
+        decorate_req(metadata, req)?;
+        if let Some(timeout) = self.timeout {
+            req.set_timeout(timeout);
+        }
+        Ok(())
+    }
+
+    /// Run a call, failing it once the deadline passes. For streams this covers getting the
+    /// response headers, after which the server holds itself to `grpc-timeout`.
+    async fn within_deadline<T>(
+        &self,
+        call: impl Future<Output = std::result::Result<T, Status>>,
+    ) -> std::result::Result<T, Status> {
+        let Some(timeout) = self.timeout else {
+            return call.await;
+        };
+        tokio::time::timeout(timeout, call).await.unwrap_or_else(|_| {
+            Err(Status::deadline_exceeded(format!(
+                "Deadline of {}ms exceeded",
+                timeout.as_millis()
+            )))
+        })
+    }
 }
 
 fn message_size_limit(setting: i32) -> usize {
@@ -391,6 +465,7 @@ impl GrpcHandle {
         validate_certificates: bool,
         client_cert: Option<ClientCertificateConfig>,
         request_message_size: i32,
+        protocol: Protocol,
     ) -> Result<bool> {
         let server_reflection = proto_files.is_empty();
         let key = make_pool_key(id, uri, proto_files);
@@ -408,6 +483,7 @@ impl GrpcHandle {
                 validate_certificates,
                 client_cert,
                 message_size_limit(request_message_size),
+                protocol,
             )
             .await
         } else {
@@ -427,6 +503,7 @@ impl GrpcHandle {
         validate_certificates: bool,
         client_cert: Option<ClientCertificateConfig>,
         request_message_size: i32,
+        protocol: Protocol,
     ) -> Result<Vec<ServiceDefinition>> {
         // Ensure we have a pool; reflect only if missing
         if self.get_pool(id, uri, proto_files).is_none() {
@@ -439,6 +516,7 @@ impl GrpcHandle {
                 validate_certificates,
                 client_cert,
                 request_message_size,
+                protocol,
             )
             .await?;
         }
@@ -481,6 +559,7 @@ impl GrpcHandle {
         validate_certificates: bool,
         client_cert: Option<ClientCertificateConfig>,
         request_message_size: i32,
+        protocol: Protocol,
     ) -> Result<GrpcConnection> {
         let use_reflection = proto_files.is_empty();
         let max_message_size = message_size_limit(request_message_size);
@@ -493,6 +572,7 @@ impl GrpcHandle {
                 validate_certificates,
                 client_cert.clone(),
                 request_message_size,
+                protocol,
             )
             .await?;
         }
@@ -501,13 +581,15 @@ impl GrpcHandle {
             .ok_or(GenericError("Failed to get pool".to_string()))?
             .clone();
         let uri = uri_from_str(uri)?;
-        let conn = get_transport(validate_certificates, client_cert.clone())?;
+        let transport = get_transport(validate_certificates, client_cert.clone(), protocol)?;
         Ok(GrpcConnection {
             pool: Arc::new(RwLock::new(pool)),
             use_reflection,
-            conn,
+            transport,
             uri,
             max_message_size,
+            timeout: None,
+            compression: None,
         })
     }
 
@@ -521,12 +603,28 @@ pub(crate) fn decorate_req<T>(
     req: &mut Request<T>,
 ) -> Result<()> {
     for (k, v) in metadata {
-        req.metadata_mut()
-            .insert(MetadataKey::from_str(k.as_str())?, MetadataValue::from_str(v.as_str())?);
+        if k.to_ascii_lowercase().ends_with("-bin") {
+            // Binary values can't be typed in, so they are entered as base64
+            let bytes = decode_binary_metadata(v)
+                .map_err(|e| GenericError(format!("Binary metadata {k} must be base64: {e}")))?;
+            req.metadata_mut().insert_bin(
+                MetadataKey::<Binary>::from_bytes(k.as_bytes())?,
+                MetadataValue::from_bytes(&bytes),
+            );
+        } else {
+            req.metadata_mut()
+                .insert(MetadataKey::from_str(k.as_str())?, MetadataValue::from_str(v.as_str())?);
+        }
     }
     Ok(())
 }
 
+/// Standard or URL-safe base64, with or without padding
+fn decode_binary_metadata(value: &str) -> std::result::Result<Vec<u8>, base64::DecodeError> {
+    let value = value.trim().trim_end_matches('=');
+    BASE64_STANDARD_NO_PAD.decode(value).or_else(|_| BASE64_URL_SAFE_NO_PAD.decode(value))
+}
+
 fn uri_from_str(uri_str: &str) -> Result<Uri> {
     match Uri::from_str(uri_str) {
         Ok(uri) => Ok(uri),
@@ -551,3 +649,76 @@ fn make_pool_key(id: &str, uri: &str, proto_files: &Vec<PathBuf>) -> String {
 
     format!("{:x}", md5::compute(pool_key))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::test_server::{echo_pool, echo_text, spawn_echo_server};
+    use tonic::Code;
+
+    fn connection(uri: &str) -> GrpcConnection {
+        GrpcConnection {
+            pool: Arc::new(RwLock::new(echo_pool())),
+            transport: get_transport(false, None, Protocol::Grpc).unwrap(),
+            uri: uri_from_str(uri).unwrap(),
+            use_reflection: false,
+            max_message_size: usize::MAX,
+            timeout: None,
+            compression: None,
+        }
+    }
+
+    #[test]
+    fn test_decorate_binary_metadata() {
+        let metadata = BTreeMap::from([
+            ("x-trace-bin".to_string(), "AP8/+g==".to_string()),
+            ("x-url-bin".to_string(), "AP8_-g".to_string()),
+            ("x-plain".to_string(), "value".to_string()),
+        ]);
+        let mut req = Request::new(());
+        decorate_req(&metadata, &mut req).unwrap();
+
+        let expected = [0x00, 0xff, 0x3f, 0xfa];
+        assert_eq!(
+            req.metadata().get_bin("x-trace-bin").unwrap().to_bytes().unwrap(),
+            &expected[..]
+        );
+        assert_eq!(req.metadata().get_bin("x-url-bin").unwrap().to_bytes().unwrap(), &expected[..]);
+        assert_eq!(req.metadata().get("x-plain").unwrap(), "value");
+
+        let invalid = BTreeMap::from([("x-trace-bin".to_string(), "not base64!".to_string())]);
+        assert!(decorate_req(&invalid, &mut Request::new(())).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unary_deadline_exceeded() {
+        // Accepts connections but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let uri = format!("http://{}", listener.local_addr().unwrap());
+
+        let result = connection(&uri)
+            .with_timeout(50)
+            .unary("yaak.test.Echo", "Say", r#"{"text": "yaak"}"#, &BTreeMap::new(), None)
+            .await;
+        let Err(Error::GrpcStreamError(err)) = result else {
+            panic!("Expected the call to miss its deadline");
+        };
+        assert_eq!(err.status.map(|s| s.code()), Some(Code::DeadlineExceeded));
+        assert_eq!(err.message, "Deadline of 50ms exceeded");
+    }
+
+    #[tokio::test]
+    async fn test_unary_compressed_round_trip() {
+        let uri = spawn_echo_server().await;
+
+        let response = connection(&uri)
+            .with_compression(Some(CompressionEncoding::Gzip))
+            .unary("yaak.test.Echo", "Say", r#"{"text": "yaak"}"#, &BTreeMap::new(), None)
+            .await
+            .unwrap();
+        assert_eq!(echo_text(&response.message), "hello yaak");
+        assert_eq!(response.metadata.get("x-request-encoding").unwrap(), "gzip");
+        assert_eq!(response.metadata.get("grpc-encoding").unwrap(), "gzip");
+    }
+}
@@ -0,0 +1,342 @@
+//! Connect sends unary calls as plain HTTP requests with the bare message as the body, and
+//! streams with gRPC-style framing that ends in a JSON end-of-stream message. Errors are JSON
+//! with string codes. See <https://connectrpc.com/docs/protocol>.
+
+use super::frame::{self, Decoder};
+use super::{BodyRewriter, BoxError, HttpClient, frames_body, full_body, rewrite_body};
+use bytes::Bytes;
+use http::header::{CONTENT_TYPE, TE};
+use http::{HeaderMap, HeaderName, HeaderValue, Request, Response, StatusCode, Version};
+use http_body::{Body, Frame};
+use http_body_util::BodyExt;
+use prost_reflect::prost::Message;
+use prost_reflect::{DeserializeOptions, DynamicMessage, MessageDescriptor, MethodDescriptor};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use tonic::body::BoxBody;
+use tonic::{Code, Status};
+
+/// Marks the JSON message that ends a stream
+const FLAG_END_STREAM: u8 = 0x02;
+
+const TRAILER_PREFIX: &str = "trailer-";
+
+pub(super) async fn call(
+    client: HttpClient,
+    req: Request<BoxBody>,
+    method: Option<MethodDescriptor>,
+    json: bool,
+) -> Result<Response<BoxBody>, BoxError> {
+    let unary =
+        method.as_ref().is_some_and(|m| !m.is_client_streaming() && !m.is_server_streaming());
+    // Without a method there are no message types to transcode with
+    let json_method = method.filter(|_| json);
+
+    let (mut parts, body) = req.into_parts();
+    parts.version = Version::HTTP_11;
+    parts.headers.remove(TE);
+    parts.headers.remove("grpc-encoding");
+    parts.headers.remove("grpc-accept-encoding");
+    if let Some(ms) = parts.headers.remove("grpc-timeout").as_ref().and_then(timeout_ms) {
+        parts.headers.insert("connect-timeout-ms", HeaderValue::from(ms));
+    }
+    parts.headers.insert("connect-protocol-version", HeaderValue::from_static("1"));
+    let content_type = match (unary, json_method.is_some()) {
+        (true, true) => "application/json",
+        (true, false) => "application/proto",
+        (false, true) => "application/connect+json",
+        (false, false) => "application/connect+proto",
+    };
+    parts.headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+
+    if unary {
+        let body = body.collect().await?.to_bytes();
+        let mut messages = Decoder::default();
+        messages.push(&body);
+        let message = messages.next_message().map(|m| m.payload).unwrap_or_default();
+        let message = match &json_method {
+            Some(method) => proto_to_json(method.input(), &message)?,
+            None => message,
+        };
+        let response = client.request(Request::from_parts(parts, full_body(message))).await?;
+        return unary_response(response, json_method.map(|m| m.output())).await;
+    }
+
+    let body = match &json_method {
+        Some(method) => {
+            rewrite_body(body, JsonRequest { messages: Decoder::default(), input: method.input() })
+        }
+        None => body,
+    };
+    let response = client.request(Request::from_parts(parts, body)).await?;
+    if response.status() != StatusCode::OK {
+        return unary_response(response, None).await;
+    }
+    let (mut parts, body) = response.into_parts();
+    parts.headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/grpc"));
+    let rewriter = StreamResponse {
+        messages: Decoder::default(),
+        output: json_method.map(|m| m.output()),
+        trailers: None,
+    };
+    Ok(Response::from_parts(parts, rewrite_body(body, rewriter)))
+}
+
+/// A unary response, or a failed stream, as a gRPC response
+async fn unary_response<B>(
+    response: Response<B>,
+    output: Option<MessageDescriptor>,
+) -> Result<Response<BoxBody>, BoxError>
+where
+    B: Body<Data = Bytes>,
+    B::Error: Into<BoxError>,
+{
+    let (mut parts, body) = response.into_parts();
+    let body = body.collect().await.map_err(Into::into)?.to_bytes();
+    let mut trailers = split_trailers(&mut parts.headers);
+    parts.headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/grpc"));
+
+    if parts.status != StatusCode::OK {
+        // A trailers-only response, which tonic turns into an error without reading the body
+        let status = error_status(parts.status, &body);
+        parts.headers.extend(trailers);
+        status.add_header(&mut parts.headers)?;
+        parts.status = StatusCode::OK;
+        return Ok(Response::from_parts(parts, frames_body(Vec::new())));
+    }
+
+    let message = match output {
+        Some(output) => json_to_proto(output, &body)?,
+        None => body,
+    };
+    Status::new(Code::Ok, "").add_header(&mut trailers)?;
+    let frames = vec![
+        Frame::data(frame::encode(0, &message)),
+        Frame::trailers(trailers),
+    ];
+    Ok(Response::from_parts(parts, frames_body(frames)))
+}
+
+/// Transcodes the messages of a streaming request to JSON
+struct JsonRequest {
+    messages: Decoder,
+    input: MessageDescriptor,
+}
+
+impl BodyRewriter for JsonRequest {
+    fn data(&mut self, data: Bytes) -> Result<Vec<Frame<Bytes>>, Status> {
+        self.messages.push(&data);
+        let mut frames = Vec::new();
+        while let Some(message) = self.messages.next_message() {
+            let json = proto_to_json(self.input.clone(), &message.payload)?;
+            frames.push(Frame::data(frame::encode(message.flags, &json)));
+        }
+        Ok(frames)
+    }
+
+    fn end(&mut self, _trailers: Option<HeaderMap>) -> Result<Vec<Frame<Bytes>>, Status> {
+        Ok(Vec::new())
+    }
+}
+
+/// Turns a streaming response into gRPC messages, ending with the end-of-stream message as
+/// trailers
+struct StreamResponse {
+    messages: Decoder,
+    output: Option<MessageDescriptor>,
+    trailers: Option<HeaderMap>,
+}
+
+impl BodyRewriter for StreamResponse {
+    fn data(&mut self, data: Bytes) -> Result<Vec<Frame<Bytes>>, Status> {
+        self.messages.push(&data);
+        let mut frames = Vec::new();
+        while let Some(message) = self.messages.next_message() {
+            if message.flags & FLAG_END_STREAM != 0 {
+                self.trailers = Some(end_stream_trailers(&message.payload)?);
+                continue;
+            }
+            let payload = match &self.output {
+                Some(output) => json_to_proto(output.clone(), &message.payload)?,
+                None => message.payload,
+            };
+            let flags = message.flags & frame::FLAG_COMPRESSED;
+            frames.push(Frame::data(frame::encode(flags, &payload)));
+        }
+        Ok(frames)
+    }
+
+    fn end(&mut self, _trailers: Option<HeaderMap>) -> Result<Vec<Frame<Bytes>>, Status> {
+        match self.trailers.take() {
+            Some(trailers) => Ok(vec![Frame::trailers(trailers)]),
+            None => Err(Status::internal("Connect stream ended without an end-of-stream message")),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ConnectError {
+    code: String,
+    #[serde(default)]
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct EndStream {
+    error: Option<ConnectError>,
+    #[serde(default)]
+    metadata: BTreeMap<String, Vec<String>>,
+}
+
+fn end_stream_trailers(payload: &[u8]) -> Result<HeaderMap, Status> {
+    let end: EndStream = serde_json::from_slice(payload)
+        .map_err(|e| Status::internal(format!("Invalid Connect end-of-stream message: {e}")))?;
+
+    let mut trailers = HeaderMap::new();
+    for (name, values) in end.metadata {
+        let Ok(name) = HeaderName::from_bytes(name.as_bytes()) else {
+            continue;
+        };
+        for value in values {
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                trailers.append(name.clone(), value);
+            }
+        }
+    }
+    let status = match end.error {
+        Some(error) => Status::new(code_from_name(&error.code), error.message),
+        None => Status::new(Code::Ok, ""),
+    };
+    status.add_header(&mut trailers)?;
+    Ok(trailers)
+}
+
+/// Unary trailers are sent as headers with a `trailer-` prefix
+fn split_trailers(headers: &mut HeaderMap) -> HeaderMap {
+    let names: Vec<HeaderName> =
+        headers.keys().filter(|n| n.as_str().starts_with(TRAILER_PREFIX)).cloned().collect();
+    let mut trailers = HeaderMap::new();
+    for name in names {
+        let Ok(trailer) = HeaderName::from_bytes(&name.as_str().as_bytes()[TRAILER_PREFIX.len()..])
+        else {
+            continue;
+        };
+        for value in headers.get_all(&name) {
+            trailers.append(trailer.clone(), value.clone());
+        }
+        headers.remove(&name);
+    }
+    trailers
+}
+
+fn error_status(http_status: StatusCode, body: &[u8]) -> Status {
+    match serde_json::from_slice::<ConnectError>(body) {
+        Ok(error) => Status::new(code_from_name(&error.code), error.message),
+        // Not from a Connect server, so likely a proxy in between
+        Err(_) => Status::new(code_from_http_status(http_status), format!("HTTP {http_status}")),
+    }
+}
+
+fn code_from_name(name: &str) -> Code {
+    match name {
+        "canceled" => Code::Cancelled,
+        "invalid_argument" => Code::InvalidArgument,
+        "deadline_exceeded" => Code::DeadlineExceeded,
+        "not_found" => Code::NotFound,
+        "already_exists" => Code::AlreadyExists,
+        "permission_denied" => Code::PermissionDenied,
+        "resource_exhausted" => Code::ResourceExhausted,
+        "failed_precondition" => Code::FailedPrecondition,
+        "aborted" => Code::Aborted,
+        "out_of_range" => Code::OutOfRange,
+        "unimplemented" => Code::Unimplemented,
+        "internal" => Code::Internal,
+        "unavailable" => Code::Unavailable,
+        "data_loss" => Code::DataLoss,
+        "unauthenticated" => Code::Unauthenticated,
+        _ => Code::Unknown,
+    }
+}
+
+/// The code for an error response without a Connect error body
+fn code_from_http_status(status: StatusCode) -> Code {
+    match status.as_u16() {
+        400 => Code::Internal,
+        401 => Code::Unauthenticated,
+        403 => Code::PermissionDenied,
+        404 => Code::Unimplemented,
+        429 | 502 | 503 | 504 => Code::Unavailable,
+        _ => Code::Unknown,
+    }
+}
+
+/// Milliseconds for a `grpc-timeout` value, such as `1500m` or `30S`
+fn timeout_ms(value: &HeaderValue) -> Option<u64> {
+    let value = value.to_str().ok()?;
+    let (amount, unit) = value.split_at(value.len().checked_sub(1)?);
+    let nanos_per_unit: u64 = match unit {
+        "H" => 3_600_000_000_000,
+        "M" => 60_000_000_000,
+        "S" => 1_000_000_000,
+        "m" => 1_000_000,
+        "u" => 1_000,
+        "n" => 1,
+        _ => return None,
+    };
+    Some(amount.parse::<u64>().ok()?.saturating_mul(nanos_per_unit).div_ceil(1_000_000))
+}
+
+fn proto_to_json(descriptor: MessageDescriptor, payload: &[u8]) -> Result<Bytes, Status> {
+    let message = DynamicMessage::decode(descriptor, payload)
+        .map_err(|e| Status::internal(format!("Failed to decode message: {e}")))?;
+    let json = serde_json::to_vec(&message)
+        .map_err(|e| Status::internal(format!("Failed to encode message as JSON: {e}")))?;
+    Ok(json.into())
+}
+
+fn json_to_proto(descriptor: MessageDescriptor, payload: &[u8]) -> Result<Bytes, Status> {
+    let mut deserializer = serde_json::Deserializer::from_slice(payload);
+    let options = DeserializeOptions::new().deny_unknown_fields(false);
+    let message = DynamicMessage::deserialize_with_options(descriptor, &mut deserializer, &options)
+        .and_then(|m| deserializer.end().map(|_| m))
+        .map_err(|e| Status::internal(format!("Failed to decode JSON message: {e}")))?;
+    Ok(message.encode_to_vec().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeout_ms() {
+        let ms = |v: &'static str| timeout_ms(&HeaderValue::from_static(v));
+        assert_eq!(ms("1500m"), Some(1500));
+        assert_eq!(ms("30S"), Some(30_000));
+        assert_eq!(ms("2500u"), Some(3));
+        assert_eq!(ms("10"), None);
+    }
+
+    #[test]
+    fn test_end_stream_trailers() {
+        let trailers = end_stream_trailers(
+            br#"{"error":{"code":"not_found","message":"no such user"},"metadata":{"x-id":["a","b"]}}"#,
+        )
+        .unwrap();
+        assert_eq!(trailers.get("grpc-status").unwrap(), "5");
+        assert_eq!(trailers.get_all("x-id").iter().count(), 2);
+
+        let trailers = end_stream_trailers(b"{}").unwrap();
+        assert_eq!(trailers.get("grpc-status").unwrap(), "0");
+    }
+
+    #[test]
+    fn test_unary_trailers_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/proto"));
+        headers.insert("trailer-x-cost", HeaderValue::from_static("3"));
+        let trailers = split_trailers(&mut headers);
+        assert_eq!(trailers.get("x-cost").unwrap(), "3");
+        assert!(headers.get("trailer-x-cost").is_none());
+        assert!(headers.get("content-type").is_some());
+    }
+}
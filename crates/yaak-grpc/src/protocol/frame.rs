@@ -0,0 +1,70 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+/// Length of the flags byte and big-endian length that prefix every message
+const PREFIX_LEN: usize = 5;
+
+/// The message is compressed with the call's encoding
+pub(super) const FLAG_COMPRESSED: u8 = 0x01;
+
+/// A length-prefixed message. gRPC, gRPC-Web and Connect streams all frame messages like
+/// this, and only differ in the flags they use.
+pub(super) struct LengthPrefixed {
+    pub flags: u8,
+    pub payload: Bytes,
+}
+
+pub(super) fn encode(flags: u8, payload: &[u8]) -> Bytes {
+    let mut buf = BytesMut::with_capacity(PREFIX_LEN + payload.len());
+    buf.put_u8(flags);
+    buf.put_u32(payload.len() as u32);
+    buf.put_slice(payload);
+    buf.freeze()
+}
+
+/// Splits a body into messages as its bytes arrive
+#[derive(Default)]
+pub(super) struct Decoder {
+    buf: BytesMut,
+}
+
+impl Decoder {
+    pub fn push(&mut self, data: &[u8]) {
+        self.buf.extend_from_slice(data);
+    }
+
+    /// The next complete message, if all of it has arrived
+    pub fn next_message(&mut self) -> Option<LengthPrefixed> {
+        if self.buf.len() < PREFIX_LEN {
+            return None;
+        }
+        let len = u32::from_be_bytes([self.buf[1], self.buf[2], self.buf[3], self.buf[4]]);
+        if self.buf.len() < PREFIX_LEN + len as usize {
+            return None;
+        }
+        let flags = self.buf.get_u8();
+        self.buf.advance(4);
+        Some(LengthPrefixed { flags, payload: self.buf.split_to(len as usize).freeze() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_split_messages() {
+        let mut body = encode(0, b"first").to_vec();
+        body.extend_from_slice(&encode(0x80, b"grpc-status: 0\r\n"));
+
+        let mut decoder = Decoder::default();
+        decoder.push(&body[..3]);
+        assert!(decoder.next_message().is_none());
+        decoder.push(&body[3..12]);
+        let first = decoder.next_message().unwrap();
+        assert_eq!((first.flags, first.payload.as_ref()), (0, &b"first"[..]));
+        assert!(decoder.next_message().is_none());
+        decoder.push(&body[12..]);
+        let trailers = decoder.next_message().unwrap();
+        assert_eq!((trailers.flags, trailers.payload.as_ref()), (0x80, &b"grpc-status: 0\r\n"[..]));
+    }
+}
@@ -0,0 +1,148 @@
+//! gRPC-Web frames messages exactly like gRPC, but sends trailers as a last message flagged
+//! `0x80`, since HTTP/1.1 and browsers have no usable trailers. The text variant also base64
+//! encodes both bodies.
+
+use super::frame::{self, Decoder};
+use super::{BodyRewriter, BoxError, HttpClient, rewrite_body};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use bytes::Bytes;
+use http::header::{ACCEPT, CONTENT_TYPE, TE};
+use http::{HeaderMap, HeaderName, HeaderValue, Request, Response, Version};
+use http_body::Frame;
+use http_body_util::BodyExt;
+use tonic::Status;
+use tonic::body::BoxBody;
+
+const FLAG_TRAILERS: u8 = 0x80;
+
+pub(super) async fn call(
+    client: HttpClient,
+    req: Request<BoxBody>,
+    text: bool,
+) -> Result<Response<BoxBody>, BoxError> {
+    let (mut parts, body) = req.into_parts();
+    let content_type = HeaderValue::from_static(if text {
+        "application/grpc-web-text+proto"
+    } else {
+        "application/grpc-web+proto"
+    });
+    parts.version = Version::HTTP_11;
+    parts.headers.remove(TE);
+    parts.headers.insert(CONTENT_TYPE, content_type.clone());
+    parts.headers.insert(ACCEPT, content_type);
+    parts.headers.insert("x-grpc-web", HeaderValue::from_static("1"));
+
+    let body = if text {
+        // Each chunk is padded on its own, which servers decode as they would one string
+        body.map_frame(|f| f.map_data(|d| Bytes::from(BASE64_STANDARD.encode(d)))).boxed_unsync()
+    } else {
+        body
+    };
+
+    let response = client.request(Request::from_parts(parts, body)).await?;
+    let (mut parts, body) = response.into_parts();
+    parts.headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/grpc"));
+    let rewriter = WebResponse {
+        base64: text.then(Base64Decoder::default),
+        messages: Decoder::default(),
+        trailers: None,
+    };
+    Ok(Response::from_parts(parts, rewrite_body(body, rewriter)))
+}
+
+struct WebResponse {
+    base64: Option<Base64Decoder>,
+    messages: Decoder,
+    trailers: Option<HeaderMap>,
+}
+
+impl BodyRewriter for WebResponse {
+    fn data(&mut self, data: Bytes) -> Result<Vec<Frame<Bytes>>, Status> {
+        match &mut self.base64 {
+            Some(base64) => self.messages.push(&base64.push(&data)?),
+            None => self.messages.push(&data),
+        }
+
+        let mut frames = Vec::new();
+        while let Some(message) = self.messages.next_message() {
+            if message.flags & FLAG_TRAILERS != 0 {
+                self.trailers = Some(parse_trailers(&message.payload));
+            } else {
+                frames.push(Frame::data(frame::encode(message.flags, &message.payload)));
+            }
+        }
+        Ok(frames)
+    }
+
+    fn end(&mut self, trailers: Option<HeaderMap>) -> Result<Vec<Frame<Bytes>>, Status> {
+        // Over HTTP/2 a proxy may still send real trailers instead
+        Ok(self.trailers.take().or(trailers).map(Frame::trailers).into_iter().collect())
+    }
+}
+
+/// Trailers sent as a message, written like HTTP/1.1 headers
+fn parse_trailers(payload: &[u8]) -> HeaderMap {
+    let mut trailers = HeaderMap::new();
+    for line in payload.split(|b| *b == b'\n') {
+        let Some(colon) = line.iter().position(|b| *b == b':') else {
+            continue;
+        };
+        let name = HeaderName::from_bytes(line[..colon].trim_ascii());
+        let value = HeaderValue::from_bytes(line[colon + 1..].trim_ascii());
+        if let (Ok(name), Ok(value)) = (name, value) {
+            trailers.append(name, value);
+        }
+    }
+    trailers
+}
+
+/// Decodes base64 as it arrives. Servers pad each chunk they write, so padding can show up
+/// in the middle of the body and not only at its end.
+#[derive(Default)]
+struct Base64Decoder {
+    pending: Vec<u8>,
+}
+
+impl Base64Decoder {
+    fn push(&mut self, data: &[u8]) -> Result<Vec<u8>, Status> {
+        self.pending.extend(data.iter().filter(|b| !b.is_ascii_whitespace()));
+        let complete = self.pending.len() - self.pending.len() % 4;
+
+        let mut decoded = Vec::new();
+        let mut start = 0;
+        for end in (4..=complete).step_by(4) {
+            if end == complete || self.pending[end - 1] == b'=' {
+                let chunk = BASE64_STANDARD
+                    .decode(&self.pending[start..end])
+                    .map_err(|e| Status::internal(format!("Invalid gRPC-Web text body: {e}")))?;
+                decoded.extend(chunk);
+                start = end;
+            }
+        }
+        self.pending.drain(..complete);
+        Ok(decoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_trailers() {
+        let trailers = parse_trailers(b"grpc-status: 5\r\nGrpc-Message: not found\r\nx-id:a\r\n");
+        assert_eq!(trailers.get("grpc-status").unwrap(), "5");
+        assert_eq!(trailers.get("grpc-message").unwrap(), "not found");
+        assert_eq!(trailers.get("x-id").unwrap(), "a");
+    }
+
+    #[test]
+    fn test_decode_padded_chunks() {
+        let mut decoder = Base64Decoder::default();
+        // "ab" and "cde" encoded separately, then split mid-group
+        assert_eq!(decoder.push(b"YWI=Y2").unwrap(), b"ab");
+        assert_eq!(decoder.push(b"Rl").unwrap(), b"cde");
+        assert!(decoder.pending.is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+//! gRPC-Web and Connect, spoken by rewriting the HTTP/2 gRPC calls tonic makes.
+//!
+//! Every call is still built by tonic with [`DynamicCodec`](crate::codec::DynamicCodec), so
+//! descriptor pools, reflection, deadlines and streaming work the same for each protocol.
+//! [`Transport`] sits between tonic and the HTTP client. It rewrites each request into the
+//! chosen protocol, then rewrites the response back into gRPC framing and trailers, which is
+//! all tonic has to understand.
+
+mod connect;
+mod frame;
+mod grpc_web;
+
+use bytes::Bytes;
+use http::HeaderMap;
+use http_body::{Body, Frame};
+use http_body_util::{BodyExt, Full, StreamBody};
+use hyper_rustls::HttpsConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::client::legacy::connect::HttpConnector;
+use prost_reflect::MethodDescriptor;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::Status;
+use tonic::body::BoxBody;
+use tower_service::Service;
+
+pub(crate) type HttpClient = Client<HttpsConnector<HttpConnector>, BoxBody>;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The wire protocol a call is made with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Protocol {
+    /// gRPC over HTTP/2
+    #[default]
+    Grpc,
+    /// gRPC-Web with binary bodies, as spoken by Envoy's `grpc_web` filter
+    GrpcWeb,
+    /// gRPC-Web with base64 bodies, for proxies that only pass text through
+    GrpcWebText,
+    /// Connect with JSON messages
+    ConnectJson,
+    /// Connect with binary protobuf messages
+    ConnectProto,
+}
+
+impl Protocol {
+    /// Whether messages can be compressed per message. Connect calls are sent uncompressed.
+    pub fn supports_compression(self) -> bool {
+        matches!(self, Protocol::Grpc | Protocol::GrpcWeb | Protocol::GrpcWebText)
+    }
+
+    /// Whether calls need HTTP/2. The others also work over HTTP/1.1, which is all many
+    /// browser-facing proxies speak.
+    pub(crate) fn requires_http2(self) -> bool {
+        self == Protocol::Grpc
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Protocol::Grpc => "gRPC",
+            Protocol::GrpcWeb => "gRPC-Web",
+            Protocol::GrpcWebText => "gRPC-Web (text)",
+            Protocol::ConnectJson => "Connect (JSON)",
+            Protocol::ConnectProto => "Connect (protobuf)",
+        })
+    }
+}
+
+/// The HTTP client tonic calls through, translating each call into the chosen protocol
+#[derive(Clone)]
+pub(crate) struct Transport {
+    client: HttpClient,
+    protocol: Protocol,
+    /// The method being called, whose message types Connect JSON transcodes with. Without
+    /// one, as for reflection, Connect calls are made with protobuf instead.
+    method: Option<MethodDescriptor>,
+}
+
+impl Transport {
+    pub(crate) fn new(client: HttpClient, protocol: Protocol) -> Self {
+        Self { client, protocol, method: None }
+    }
+
+    pub(crate) fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    pub(crate) fn for_method(&self, method: &MethodDescriptor) -> Self {
+        Self { method: Some(method.clone()), ..self.clone() }
+    }
+}
+
+impl Service<http::Request<BoxBody>> for Transport {
+    type Response = http::Response<BoxBody>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // The legacy client is always ready, and checks out a connection per request
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<BoxBody>) -> Self::Future {
+        let client = self.client.clone();
+        let method = self.method.clone();
+        match self.protocol {
+            Protocol::Grpc => {
+                Box::pin(async move { Ok(client.request(req).await?.map(tonic::body::boxed)) })
+            }
+            Protocol::GrpcWeb => Box::pin(grpc_web::call(client, req, false)),
+            Protocol::GrpcWebText => Box::pin(grpc_web::call(client, req, true)),
+            Protocol::ConnectJson => Box::pin(connect::call(client, req, method, true)),
+            Protocol::ConnectProto => Box::pin(connect::call(client, req, method, false)),
+        }
+    }
+}
+
+/// Rewrites a body as it streams. Bodies are rewritten on a task, so the new body yields
+/// each message as soon as the original has all of it.
+trait BodyRewriter: Send + 'static {
+    /// Frames for the next chunk of the original body
+    fn data(&mut self, data: Bytes) -> Result<Vec<Frame<Bytes>>, Status>;
+
+    /// Frames to end with, once the original body ends with `trailers`
+    fn end(&mut self, trailers: Option<HeaderMap>) -> Result<Vec<Frame<Bytes>>, Status>;
+}
+
+fn rewrite_body<B, R>(mut body: B, mut rewriter: R) -> BoxBody
+where
+    B: Body<Data = Bytes> + Send + Unpin + 'static,
+    B::Error: Into<BoxError>,
+    R: BodyRewriter,
+{
+    let (tx, rx) = mpsc::channel(16);
+    tokio::spawn(async move {
+        let result: Result<(), Status> = async {
+            let mut trailers = None;
+            while let Some(frame) = body.frame().await {
+                let frame = frame.map_err(|e| Status::from_error(e.into()))?;
+                let frames = match frame.into_data() {
+                    Ok(data) => rewriter.data(data)?,
+                    Err(frame) => {
+                        trailers = frame.into_trailers().ok();
+                        continue;
+                    }
+                };
+                for frame in frames {
+                    if tx.send(Ok(frame)).await.is_err() {
+                        // Nobody is reading the body anymore
+                        return Ok(());
+                    }
+                }
+            }
+            for frame in rewriter.end(trailers)? {
+                if tx.send(Ok(frame)).await.is_err() {
+                    return Ok(());
+                }
+            }
+            Ok(())
+        }
+        .await;
+        if let Err(status) = result {
+            let _ = tx.send(Err(status)).await;
+        }
+    });
+    StreamBody::new(ReceiverStream::new(rx)).boxed_unsync()
+}
+
+fn full_body(data: Bytes) -> BoxBody {
+    Full::new(data).map_err(|never| match never {}).boxed_unsync()
+}
+
+fn frames_body(frames: Vec<Frame<Bytes>>) -> BoxBody {
+    StreamBody::new(tokio_stream::iter(frames.into_iter().map(Ok::<_, Status>))).boxed_unsync()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::DynamicCodec;
+    use crate::reflection::method_desc_to_path;
+    use crate::test_server::{
+        echo_message, echo_method, echo_text, spawn_connect_server, spawn_echo_server,
+    };
+    use crate::transport::get_transport;
+    use tonic::{Code, IntoRequest};
+
+    /// Call an echo method with "yaak", returning the messages received and how the call ended
+    async fn call(uri: &str, protocol: Protocol, method: &str) -> (Vec<String>, Status) {
+        let method = echo_method(method);
+        let transport = get_transport(false, None, protocol).unwrap().for_method(&method);
+        let mut client = tonic::client::Grpc::with_origin(transport, uri.parse().unwrap());
+        client.ready().await.unwrap();
+
+        // Unary calls look the same as server-streaming ones on the wire
+        let request = echo_message(&method, "yaak").into_request();
+        let codec = DynamicCodec::new(method.clone());
+        let mut stream =
+            match client.server_streaming(request, method_desc_to_path(&method), codec).await {
+                Ok(response) => response.into_inner(),
+                Err(status) => return (Vec::new(), status),
+            };
+        let mut messages = Vec::new();
+        loop {
+            match stream.message().await {
+                Ok(Some(message)) => messages.push(echo_text(&message)),
+                Ok(None) => return (messages, Status::new(Code::Ok, "")),
+                Err(status) => return (messages, status),
+            }
+        }
+    }
+
+    async fn assert_round_trips(uri: &str, protocol: Protocol) {
+        let (messages, status) = call(uri, protocol, "Say").await;
+        assert_eq!(messages, ["hello yaak"], "{protocol}");
+        assert_eq!(status.code(), Code::Ok, "{protocol}: {status}");
+
+        let (messages, status) = call(uri, protocol, "Repeat").await;
+        assert_eq!(messages, ["yaak 1", "yaak 2", "yaak 3"], "{protocol}");
+        assert_eq!(status.code(), Code::Ok, "{protocol}: {status}");
+
+        let (messages, status) = call(uri, protocol, "Fail").await;
+        assert!(messages.is_empty(), "{protocol}");
+        assert_eq!(status.code(), Code::NotFound, "{protocol}: {status}");
+        assert_eq!(status.message(), "no such echo", "{protocol}");
+    }
+
+    #[tokio::test]
+    async fn test_grpc_web_round_trip() {
+        let uri = spawn_echo_server().await;
+        assert_round_trips(&uri, Protocol::GrpcWeb).await;
+        assert_round_trips(&uri, Protocol::GrpcWebText).await;
+    }
+
+    #[tokio::test]
+    async fn test_connect_round_trip() {
+        let uri = spawn_connect_server().await;
+        assert_round_trips(&uri, Protocol::ConnectJson).await;
+        assert_round_trips(&uri, Protocol::ConnectProto).await;
+    }
+}
@@ -3,6 +3,7 @@ use crate::client::AutoReflectionClient;
 use crate::error::Error::GenericError;
 use crate::error::Result;
 use crate::manager::GrpcConfig;
+use crate::protocol::Protocol;
 use anyhow::anyhow;
 use async_recursion::async_recursion;
 use log::{debug, info, warn};
@@ -120,10 +121,16 @@ pub async fn fill_pool_from_reflection(
     validate_certificates: bool,
     client_cert: Option<ClientCertificateConfig>,
     max_message_size: usize,
+    protocol: Protocol,
 ) -> Result<DescriptorPool> {
     let mut pool = DescriptorPool::new();
-    let mut client =
-        AutoReflectionClient::new(uri, validate_certificates, client_cert, max_message_size)?;
+    let mut client = AutoReflectionClient::new(
+        uri,
+        validate_certificates,
+        client_cert,
+        max_message_size,
+        protocol,
+    )?;
 
     for service in list_services(&mut client, metadata).await? {
         if service == "grpc.reflection.v1alpha.ServerReflection" {
@@ -195,6 +202,7 @@ pub(crate) async fn reflect_types_for_message(
     metadata: &BTreeMap<String, String>,
     client_cert: Option<ClientCertificateConfig>,
     max_message_size: usize,
+    protocol: Protocol,
 ) -> Result<()> {
     // 1. Collect all Any types in the JSON
     let mut extra_types = Vec::new();
@@ -204,7 +212,8 @@ pub(crate) async fn reflect_types_for_message(
         return Ok(()); // nothing to do
     }
 
-    let mut client = AutoReflectionClient::new(uri, false, client_cert, max_message_size)?;
+    let mut client =
+        AutoReflectionClient::new(uri, false, client_cert, max_message_size, protocol)?;
     for extra_type in extra_types {
         {
             let guard = pool.read().await;
@@ -243,6 +252,7 @@ pub(crate) async fn reflect_types_for_dynamic_message(
     metadata: &BTreeMap<String, String>,
     client_cert: Option<ClientCertificateConfig>,
     max_message_size: usize,
+    protocol: Protocol,
 ) -> Result<()> {
     let mut extra_types = HashSet::new();
     collect_any_types_from_dynamic_message(message, &mut extra_types);
@@ -251,7 +261,8 @@ pub(crate) async fn reflect_types_for_dynamic_message(
         return Ok(());
     }
 
-    let mut client = AutoReflectionClient::new(uri, false, client_cert, max_message_size)?;
+    let mut client =
+        AutoReflectionClient::new(uri, false, client_cert, max_message_size, protocol)?;
     for extra_type in extra_types {
         {
             let guard = pool.read().await;
@@ -0,0 +1,289 @@
+//! Local servers for tests, with a `yaak.test.Echo` service:
+//!
+//! ```proto
+//! service Echo {
+//!   rpc Say(EchoMessage) returns (EchoMessage);            // "hello <text>"
+//!   rpc Repeat(EchoMessage) returns (stream EchoMessage);  // "<text> 1" to "<text> 3"
+//!   rpc Fail(EchoMessage) returns (EchoMessage);           // NOT_FOUND "no such echo"
+//! }
+//! ```
+//!
+//! The gRPC server also speaks gRPC-Web, and Say answers with the request's `grpc-encoding` in
+//! `x-request-encoding`, so tests can tell the request arrived compressed.
+
+use crate::codec::DynamicCodec;
+use bytes::{BufMut, Bytes, BytesMut};
+use http::header::CONTENT_TYPE;
+use http::{HeaderValue, StatusCode};
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use prost_reflect::prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, MethodDescriptor, Value};
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{
+    DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+    MethodDescriptorProto, ServiceDescriptorProto,
+};
+use serde_json::json;
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::body::BoxBody;
+use tonic::codec::CompressionEncoding;
+use tonic::server::{Grpc, NamedService, ServerStreamingService, UnaryService};
+use tonic::{Request, Response, Status};
+use tonic_web::GrpcWebLayer;
+use tower_service::Service;
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// Serve the echo service over gRPC and gRPC-Web on a free local port, until the test's
+/// runtime shuts down
+pub(crate) async fn spawn_echo_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("Failed to bind test server");
+    let addr = listener.local_addr().expect("Failed to get local addr");
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .accept_http1(true)
+            .layer(GrpcWebLayer::new())
+            .add_service(Echo)
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    format!("http://{addr}")
+}
+
+/// Serve the echo service over Connect, with JSON or protobuf messages, on a free local port
+/// until the test's runtime shuts down
+pub(crate) async fn spawn_connect_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("Failed to bind test server");
+    let addr = listener.local_addr().expect("Failed to get local addr");
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(
+                hyper::server::conn::http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service_fn(connect_echo)),
+            );
+        }
+    });
+    format!("http://{addr}")
+}
+
+pub(crate) fn echo_pool() -> DescriptorPool {
+    DescriptorPool::from_file_descriptor_set(descriptor_set()).expect("Invalid test descriptors")
+}
+
+pub(crate) fn echo_method(name: &str) -> MethodDescriptor {
+    find_method(name).expect("Missing test method")
+}
+
+fn find_method(name: &str) -> Option<MethodDescriptor> {
+    echo_pool().get_service_by_name("yaak.test.Echo")?.methods().find(|m| m.name() == name)
+}
+
+/// An `EchoMessage` with the given `text`
+pub(crate) fn echo_message(method: &MethodDescriptor, text: &str) -> DynamicMessage {
+    let mut message = DynamicMessage::new(method.input());
+    message.set_field_by_name("text", Value::String(text.to_string()));
+    message
+}
+
+/// The `text` of an `EchoMessage`
+pub(crate) fn echo_text(message: &DynamicMessage) -> String {
+    message
+        .get_field_by_name("text")
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn descriptor_set() -> FileDescriptorSet {
+    let message_type = ".yaak.test.EchoMessage".to_string();
+    let method = |name: &str, server_streaming: bool| MethodDescriptorProto {
+        name: Some(name.to_string()),
+        input_type: Some(message_type.clone()),
+        output_type: Some(message_type.clone()),
+        server_streaming: Some(server_streaming),
+        ..Default::default()
+    };
+    FileDescriptorSet {
+        file: vec![FileDescriptorProto {
+            name: Some("yaak/test/echo.proto".to_string()),
+            package: Some("yaak.test".to_string()),
+            syntax: Some("proto3".to_string()),
+            message_type: vec![DescriptorProto {
+                name: Some("EchoMessage".to_string()),
+                field: vec![FieldDescriptorProto {
+                    name: Some("text".to_string()),
+                    json_name: Some("text".to_string()),
+                    number: Some(1),
+                    label: Some(Label::Optional as i32),
+                    r#type: Some(Type::String as i32),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            service: vec![ServiceDescriptorProto {
+                name: Some("Echo".to_string()),
+                method: vec![
+                    method("Say", false),
+                    method("Repeat", true),
+                    method("Fail", false),
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }],
+    }
+}
+
+#[derive(Clone)]
+struct Echo;
+
+impl NamedService for Echo {
+    const NAME: &'static str = "yaak.test.Echo";
+}
+
+impl<B> Service<http::Request<B>> for Echo
+where
+    B: http_body::Body + Send + 'static,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let Some(method) = req.uri().path().strip_prefix("/yaak.test.Echo/").and_then(find_method)
+        else {
+            return Box::pin(async { Ok(Status::unimplemented("No such method").into_http()) });
+        };
+        // The request and response are both an EchoMessage, so one codec decodes both
+        let mut grpc = Grpc::new(DynamicCodec::new(method.clone()))
+            .accept_compressed(CompressionEncoding::Gzip)
+            .send_compressed(CompressionEncoding::Gzip);
+        let name = method.name().to_string();
+        match name.as_str() {
+            "Say" => Box::pin(async move { Ok(grpc.unary(Say(method), req).await) }),
+            "Repeat" => {
+                Box::pin(async move { Ok(grpc.server_streaming(Repeat(method), req).await) })
+            }
+            _ => Box::pin(async move { Ok(grpc.unary(Fail, req).await) }),
+        }
+    }
+}
+
+struct Say(MethodDescriptor);
+
+impl UnaryService<DynamicMessage> for Say {
+    type Response = DynamicMessage;
+    type Future = BoxFuture<Result<Response<DynamicMessage>, Status>>;
+
+    fn call(&mut self, request: Request<DynamicMessage>) -> Self::Future {
+        let encoding = request.metadata().get("grpc-encoding").cloned();
+        let text = format!("hello {}", echo_text(request.get_ref()));
+        let mut response = Response::new(echo_message(&self.0, &text));
+        if let Some(encoding) = encoding {
+            response.metadata_mut().insert("x-request-encoding", encoding);
+        }
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+struct Repeat(MethodDescriptor);
+
+impl ServerStreamingService<DynamicMessage> for Repeat {
+    type Response = DynamicMessage;
+    type ResponseStream = tokio_stream::Iter<std::vec::IntoIter<Result<DynamicMessage, Status>>>;
+    type Future = BoxFuture<Result<Response<Self::ResponseStream>, Status>>;
+
+    fn call(&mut self, request: Request<DynamicMessage>) -> Self::Future {
+        let text = echo_text(request.get_ref());
+        let messages =
+            (1..=3).map(|i| Ok(echo_message(&self.0, &format!("{text} {i}")))).collect::<Vec<_>>();
+        Box::pin(async move { Ok(Response::new(tokio_stream::iter(messages))) })
+    }
+}
+
+struct Fail;
+
+impl UnaryService<DynamicMessage> for Fail {
+    type Response = DynamicMessage;
+    type Future = BoxFuture<Result<Response<DynamicMessage>, Status>>;
+
+    fn call(&mut self, _request: Request<DynamicMessage>) -> Self::Future {
+        Box::pin(async { Err(Status::not_found("no such echo")) })
+    }
+}
+
+/// The echo service as a Connect handler. Requests hold a single message, enveloped when
+/// the call streams.
+async fn connect_echo(
+    req: http::Request<Incoming>,
+) -> Result<http::Response<Full<Bytes>>, Infallible> {
+    let name = req.uri().path().strip_prefix("/yaak.test.Echo/").unwrap_or_default().to_string();
+    let content_type = req.headers().get(CONTENT_TYPE).cloned();
+    let content_type = content_type.as_ref().and_then(|v| v.to_str().ok()).unwrap_or_default();
+    let json = content_type.ends_with("json");
+    let body = req.into_body().collect().await.map(|b| b.to_bytes()).unwrap_or_default();
+    let text = if content_type.starts_with("application/connect+") {
+        connect_text(body.get(5..).unwrap_or_default(), json)
+    } else {
+        connect_text(&body, json)
+    };
+
+    let (status, content_type, body) = match name.as_str() {
+        "Say" => (StatusCode::OK, content_type, connect_message(&format!("hello {text}"), json)),
+        "Repeat" => {
+            let mut body = BytesMut::new();
+            for i in 1..=3 {
+                envelope(&mut body, 0, &connect_message(&format!("{text} {i}"), json));
+            }
+            // The end-of-stream message, which is always JSON
+            envelope(&mut body, 0x02, b"{}");
+            (StatusCode::OK, content_type, body.freeze())
+        }
+        _ => {
+            let error = json!({"code": "not_found", "message": "no such echo"});
+            (StatusCode::NOT_FOUND, "application/json", error.to_string().into())
+        }
+    };
+    let mut response = http::Response::new(Full::new(body));
+    *response.status_mut() = status;
+    if let Ok(content_type) = HeaderValue::from_str(content_type) {
+        response.headers_mut().insert(CONTENT_TYPE, content_type);
+    }
+    Ok(response)
+}
+
+fn connect_text(payload: &[u8], json: bool) -> String {
+    if json {
+        let message: serde_json::Value = serde_json::from_slice(payload).unwrap_or_default();
+        message["text"].as_str().unwrap_or_default().to_string()
+    } else {
+        DynamicMessage::decode(echo_method("Say").input(), payload)
+            .map(|m| echo_text(&m))
+            .unwrap_or_default()
+    }
+}
+
+fn connect_message(text: &str, json: bool) -> Bytes {
+    if json {
+        json!({ "text": text }).to_string().into()
+    } else {
+        echo_message(&echo_method("Say"), text).encode_to_vec().into()
+    }
+}
+
+fn envelope(buf: &mut BytesMut, flags: u8, payload: &[u8]) {
+    buf.put_u8(flags);
+    buf.put_u32(payload.len() as u32);
+    buf.put_slice(payload);
+}
@@ -1,10 +1,10 @@
 use crate::error::Result;
+use crate::protocol::{Protocol, Transport};
 use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
 use hyper_util::client::legacy::Client;
 use hyper_util::client::legacy::connect::HttpConnector;
 use hyper_util::rt::TokioExecutor;
 use log::info;
-use tonic::body::BoxBody;
 use yaak_tls::{ClientCertificateConfig, get_tls_config};
 
 // I think ALPN breaks this because we're specifying http2_only
@@ -13,28 +13,33 @@ const WITH_ALPN: bool = false;
 pub(crate) fn get_transport(
     validate_certificates: bool,
     client_cert: Option<ClientCertificateConfig>,
-) -> Result<Client<HttpsConnector<HttpConnector>, BoxBody>> {
+    protocol: Protocol,
+) -> Result<Transport> {
     let tls_config = get_tls_config(validate_certificates, WITH_ALPN, client_cert.clone())?;
 
     let mut http = HttpConnector::new();
     http.enforce_http(false);
 
-    let connector = HttpsConnectorBuilder::new()
-        .with_tls_config(tls_config)
-        .https_or_http()
-        .enable_http2()
-        .build();
+    let http2_only = protocol.requires_http2();
+    let builder = HttpsConnectorBuilder::new().with_tls_config(tls_config).https_or_http();
+    let connector: HttpsConnector<HttpConnector> = if http2_only {
+        builder.enable_http2().build()
+    } else {
+        // Negotiate HTTP/2 where the server offers it, since Connect's bidi streams need it
+        builder.enable_all_versions().build()
+    };
 
     let client = Client::builder(TokioExecutor::new())
         .pool_max_idle_per_host(0)
-        .http2_only(true)
+        .http2_only(http2_only)
         .build(connector);
 
     info!(
-        "Created gRPC client validate_certs={} client_cert={}",
+        "Created gRPC client protocol={} validate_certs={} client_cert={}",
+        protocol,
         validate_certificates,
         client_cert.is_some()
     );
 
-    Ok(client)
+    Ok(Transport::new(client, protocol))
 }
@@ -0,0 +1,380 @@
+//! HTTP Archive (HAR) 1.2, as browser devtools and most HTTP tooling read and write it.
+//!
+//! Only the parts of the format Yaak has data for are modeled. Parsing is lenient, since
+//! tools disagree on which fields are optional, and unknown fields are ignored.
+//!
+//! See <http://www.softwareishard.com/blog/har-12-spec/>.
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use chrono::{DateTime, NaiveDateTime, SecondsFormat};
+use serde::{Deserialize, Serialize};
+
+pub const HAR_VERSION: &str = "1.2";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Har {
+    pub log: Log,
+}
+
+impl Har {
+    pub fn new(creator: Creator, entries: Vec<Entry>) -> Self {
+        Self { log: Log { version: HAR_VERSION.to_string(), creator, entries, comment: None } }
+    }
+
+    /// Parse a HAR document, or `None` when `contents` is not one.
+    pub fn parse(contents: &str) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_str(contents).ok()?;
+        // Anything JSON would parse into a default HAR, so require the shape first
+        value.get("log")?.get("entries")?.as_array()?;
+        serde_json::from_value(value).ok()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Log {
+    pub version: String,
+    pub creator: Creator,
+    pub entries: Vec<Entry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Creator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Entry {
+    /// When the request started, as RFC 3339; see [`format_timestamp`].
+    pub started_date_time: String,
+    /// Total milliseconds, the sum of the non-negative [`Timings`].
+    pub time: f64,
+    pub request: Request,
+    pub response: Response,
+    pub cache: Cache,
+    pub timings: Timings,
+    #[serde(rename = "serverIPAddress", skip_serializing_if = "Option::is_none")]
+    pub server_ip_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Request {
+    pub method: String,
+    pub url: String,
+    pub http_version: String,
+    pub cookies: Vec<Cookie>,
+    pub headers: Vec<Header>,
+    pub query_string: Vec<QueryParam>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<PostData>,
+    /// -1 when unknown, as it is for everything but HTTP/1 captures
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+impl Default for Request {
+    fn default() -> Self {
+        Self {
+            method: "GET".to_string(),
+            url: String::new(),
+            http_version: "HTTP/1.1".to_string(),
+            cookies: Vec::new(),
+            headers: Vec::new(),
+            query_string: Vec::new(),
+            post_data: None,
+            headers_size: -1,
+            body_size: -1,
+        }
+    }
+}
+
+impl Request {
+    /// The request body, if it had one.
+    pub fn body(&self) -> Option<Vec<u8>> {
+        self.post_data.as_ref().map(|p| p.bytes())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Response {
+    pub status: i64,
+    pub status_text: String,
+    pub http_version: String,
+    pub cookies: Vec<Cookie>,
+    pub headers: Vec<Header>,
+    pub content: Content,
+    #[serde(rename = "redirectURL")]
+    pub redirect_url: String,
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+impl Default for Response {
+    fn default() -> Self {
+        Self {
+            status: 0,
+            status_text: String::new(),
+            http_version: "HTTP/1.1".to_string(),
+            cookies: Vec::new(),
+            headers: Vec::new(),
+            content: Content::default(),
+            redirect_url: String::new(),
+            headers_size: -1,
+            body_size: -1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Header {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueryParam {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PostData {
+    pub mime_type: String,
+    /// Form fields, which some tools give instead of `text` for form bodies
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<PostParam>,
+    pub text: String,
+    /// `base64` when `text` is not the body itself. Not in the spec, but what tools that
+    /// capture binary request bodies use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+impl PostData {
+    pub fn from_bytes(mime_type: &str, body: &[u8]) -> Self {
+        let (text, encoding) = encode_text(body);
+        Self { mime_type: mime_type.to_string(), params: Vec::new(), text, encoding }
+    }
+
+    pub fn bytes(&self) -> Vec<u8> {
+        if self.text.is_empty() && !self.params.is_empty() {
+            let pairs: Vec<String> = self
+                .params
+                .iter()
+                .map(|p| {
+                    let value = p.value.as_deref().unwrap_or_default();
+                    format!("{}={}", urlencoding::encode(&p.name), urlencoding::encode(value))
+                })
+                .collect();
+            return pairs.join("&").into_bytes();
+        }
+        decode_text(&self.text, self.encoding.as_deref())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PostParam {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Content {
+    /// Length of the decoded body
+    pub size: i64,
+    /// Bytes saved by compression, when the body was compressed on the wire
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression: Option<i64>,
+    pub mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// `base64` when `text` is not the body itself
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+impl Content {
+    pub fn from_bytes(mime_type: &str, body: &[u8]) -> Self {
+        let (text, encoding) = encode_text(body);
+        Self {
+            size: body.len() as i64,
+            compression: None,
+            mime_type: mime_type.to_string(),
+            text: Some(text),
+            encoding,
+        }
+    }
+
+    /// The decoded body, or `None` when the HAR left it out.
+    pub fn bytes(&self) -> Option<Vec<u8>> {
+        self.text.as_deref().map(|t| decode_text(t, self.encoding.as_deref()))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cache {}
+
+/// Milliseconds spent in each phase; -1 for phases that did not happen or were not timed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Timings {
+    pub blocked: f64,
+    pub dns: f64,
+    /// Includes `ssl`
+    pub connect: f64,
+    pub send: f64,
+    pub wait: f64,
+    pub receive: f64,
+    pub ssl: f64,
+}
+
+impl Default for Timings {
+    fn default() -> Self {
+        Self {
+            blocked: -1.0,
+            dns: -1.0,
+            connect: -1.0,
+            send: 0.0,
+            wait: 0.0,
+            receive: 0.0,
+            ssl: -1.0,
+        }
+    }
+}
+
+impl Timings {
+    /// The entry's total time. `ssl` is part of `connect`, so it is not counted again.
+    pub fn total(&self) -> f64 {
+        [
+            self.blocked,
+            self.dns,
+            self.connect,
+            self.send,
+            self.wait,
+            self.receive,
+        ]
+        .into_iter()
+        .filter(|t| *t > 0.0)
+        .sum()
+    }
+}
+
+/// A UTC timestamp as HAR wants it, like `2024-01-02T03:04:05.678Z`.
+pub fn format_timestamp(timestamp: NaiveDateTime) -> String {
+    timestamp.and_utc().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// The UTC time of a HAR timestamp, in whatever offset it was written.
+pub fn parse_timestamp(timestamp: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(timestamp).ok().map(|t| t.naive_utc())
+}
+
+/// The query string of `url` as name/value pairs, decoded.
+pub fn query_string(url: &str) -> Vec<QueryParam> {
+    let Some((_, query)) = url.split_once('?') else {
+        return Vec::new();
+    };
+    let query = query.split('#').next().unwrap_or_default();
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            QueryParam { name: decode_component(name), value: decode_component(value) }
+        })
+        .collect()
+}
+
+fn decode_component(value: &str) -> String {
+    let value = value.replace('+', " ");
+    urlencoding::decode(&value).map(|v| v.into_owned()).unwrap_or(value)
+}
+
+fn encode_text(body: &[u8]) -> (String, Option<String>) {
+    match std::str::from_utf8(body) {
+        Ok(text) => (text.to_string(), None),
+        Err(_) => (BASE64_STANDARD.encode(body), Some("base64".to_string())),
+    }
+}
+
+fn decode_text(text: &str, encoding: Option<&str>) -> Vec<u8> {
+    match encoding {
+        Some(e) if e.eq_ignore_ascii_case("base64") => {
+            BASE64_STANDARD.decode(text.trim()).unwrap_or_else(|_| text.as_bytes().to_vec())
+        }
+        _ => text.as_bytes().to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_text_and_binary_bodies() {
+        let text = Content::from_bytes("application/json", b"{\"a\":1}");
+        assert_eq!(text.encoding, None);
+        assert_eq!(text.bytes().unwrap(), b"{\"a\":1}");
+
+        let binary = PostData::from_bytes("image/png", &[0x89, 0x50, 0xff]);
+        assert_eq!(binary.encoding.as_deref(), Some("base64"));
+        assert_eq!(binary.bytes(), vec![0x89, 0x50, 0xff]);
+    }
+
+    #[test]
+    fn parses_only_har_documents() {
+        assert!(Har::parse(r#"{"yaakSchema": 4, "resources": {}}"#).is_none());
+        assert!(Har::parse("not json").is_none());
+
+        let har = Har::parse(
+            r#"{"log": {"version": "1.2", "entries": [{
+                "startedDateTime": "2024-01-02T03:04:05.678+01:00",
+                "request": {"method": "POST", "url": "https://a.test/?q=a+b",
+                    "postData": {"mimeType": "application/x-www-form-urlencoded",
+                        "params": [{"name": "x", "value": "1 2"}]}},
+                "response": {"status": 200, "content": {"size": 0, "mimeType": ""}},
+                "extra": true
+            }]}}"#,
+        )
+        .unwrap();
+        let entry = &har.log.entries[0];
+        assert_eq!(entry.request.body().unwrap(), b"x=1%202");
+        assert_eq!(
+            format_timestamp(parse_timestamp(&entry.started_date_time).unwrap()),
+            "2024-01-02T02:04:05.678Z"
+        );
+        assert_eq!(query_string(&entry.request.url)[0].value, "a b");
+    }
+}
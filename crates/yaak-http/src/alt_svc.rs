@@ -0,0 +1,179 @@
+//! HTTP/3 discovery through `Alt-Svc` (RFC 7838).
+//!
+//! An origin announces HTTP/3 by answering over TCP with something like
+//! `Alt-Svc: h3=":443"; ma=86400`. The cache remembers which origins did, for as long as
+//! they said to, so that later requests to them can go over QUIC.
+//!
+//! reqwest connects to the host and port of the request URL, so only alternatives on the
+//! same host and port can be used. Those are what servers advertise in practice.
+//!
+//! Clients are built for each send, so what origins advertised is kept for the whole process,
+//! the way a browser keeps it for a profile.
+
+use reqwest::Url;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// How long an alternative stays fresh when the header gives no `ma`.
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Default)]
+pub struct AltSvcCache {
+    origins: Mutex<HashMap<String, Instant>>,
+}
+
+impl AltSvcCache {
+    /// The cache every client shares.
+    pub fn shared() -> Arc<AltSvcCache> {
+        static SHARED: OnceLock<Arc<AltSvcCache>> = OnceLock::new();
+        SHARED.get_or_init(Default::default).clone()
+    }
+
+    /// Whether `url`'s origin has advertised HTTP/3, and the advertisement is still fresh.
+    pub fn advertises_h3(&self, url: &Url) -> bool {
+        let Some(origin) = origin(url) else {
+            return false;
+        };
+        let mut origins = self.origins();
+        match origins.get(&origin) {
+            Some(expires) if *expires > Instant::now() => true,
+            Some(_) => {
+                origins.remove(&origin);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Record the `Alt-Svc` header of a response from `url`. A response without one leaves
+    /// what is known alone; any other header replaces it, as the RFC asks.
+    pub fn update(&self, url: &Url, alt_svc: Option<&str>) {
+        let (Some(origin), Some(alt_svc)) = (origin(url), alt_svc) else {
+            return;
+        };
+        let port = url.port_or_known_default().unwrap_or(443);
+        let mut origins = self.origins();
+        match h3_max_age(alt_svc, url.host_str().unwrap_or_default(), port) {
+            Some(max_age) => {
+                origins.insert(origin, Instant::now() + max_age);
+            }
+            None => {
+                origins.remove(&origin);
+            }
+        }
+    }
+
+    /// Stop using HTTP/3 for `url`'s origin, after a QUIC connection to it failed.
+    pub fn forget(&self, url: &Url) {
+        if let Some(origin) = origin(url) {
+            self.origins().remove(&origin);
+        }
+    }
+
+    fn origins(&self) -> std::sync::MutexGuard<'_, HashMap<String, Instant>> {
+        self.origins.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// HTTP/3 needs TLS, so only `https` origins count.
+fn origin(url: &Url) -> Option<String> {
+    if url.scheme() != "https" {
+        return None;
+    }
+    let host = url.host_str()?.to_ascii_lowercase();
+    Some(format!("{}:{}", host, url.port_or_known_default()?))
+}
+
+/// The freshness of an `h3` alternative on `host:port` in an `Alt-Svc` value, if it has one.
+fn h3_max_age(alt_svc: &str, host: &str, port: u16) -> Option<Duration> {
+    for alternative in alt_svc.split(',') {
+        let mut parts = alternative.split(';').map(str::trim);
+        let Some((protocol, authority)) = parts.next().and_then(|p| p.split_once('=')) else {
+            continue;
+        };
+        if protocol.trim() != "h3" {
+            continue;
+        }
+
+        let authority = authority.trim().trim_matches('"');
+        let Some((alt_host, alt_port)) = authority.rsplit_once(':') else {
+            continue;
+        };
+        let same_host = alt_host.is_empty() || alt_host.eq_ignore_ascii_case(host);
+        if !same_host || alt_port.parse::<u16>().ok() != Some(port) {
+            continue;
+        }
+
+        let max_age = parts
+            .filter_map(|p| p.split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("ma"))
+            .and_then(|(_, value)| value.trim().trim_matches('"').parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_MAX_AGE);
+        return Some(max_age);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn reads_an_h3_alternative_on_the_same_port() {
+        assert_eq!(
+            h3_max_age(r#"h3=":443"; ma=3600, h2=":443""#, "example.com", 443),
+            Some(Duration::from_secs(3600))
+        );
+        assert_eq!(
+            h3_max_age(r#"h3="example.com:443""#, "example.com", 443),
+            Some(DEFAULT_MAX_AGE)
+        );
+    }
+
+    #[test]
+    fn ignores_alternatives_it_cannot_use() {
+        // Another port, another host, or a draft version
+        assert_eq!(h3_max_age(r#"h3=":8443""#, "example.com", 443), None);
+        assert_eq!(h3_max_age(r#"h3="cdn.example.net:443""#, "example.com", 443), None);
+        assert_eq!(h3_max_age(r#"h3-29=":443""#, "example.com", 443), None);
+        assert_eq!(h3_max_age("clear", "example.com", 443), None);
+    }
+
+    #[test]
+    fn remembers_and_forgets_origins() {
+        let cache = AltSvcCache::default();
+        let page = url("https://Example.com/a");
+        let other_page = url("https://example.com/b?q=1");
+
+        cache.update(&page, None);
+        assert!(!cache.advertises_h3(&page));
+
+        cache.update(&page, Some(r#"h3=":443"; ma=60"#));
+        assert!(cache.advertises_h3(&other_page));
+        assert!(!cache.advertises_h3(&url("http://example.com/")));
+
+        // No header keeps it, a header without h3 replaces it
+        cache.update(&page, None);
+        assert!(cache.advertises_h3(&page));
+        cache.update(&page, Some("clear"));
+        assert!(!cache.advertises_h3(&page));
+
+        cache.update(&page, Some(r#"h3=":443""#));
+        cache.forget(&other_page);
+        assert!(!cache.advertises_h3(&page));
+    }
+
+    #[test]
+    fn expires_stale_advertisements() {
+        let cache = AltSvcCache::default();
+        let page = url("https://example.com/");
+        cache.update(&page, Some(r#"h3=":443"; ma=0"#));
+        assert!(!cache.advertises_h3(&page));
+    }
+}
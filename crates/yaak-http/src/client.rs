@@ -1,11 +1,18 @@
+use crate::alt_svc::AltSvcCache;
 use crate::dns::{AddressFilter, LocalhostResolver};
 use crate::error::Result;
+use crate::sender::HttpResponseEvent;
+use crate::timing::{ConnectTimingLayer, HandshakeTimingStore};
+use crate::tls::{ObserveHandshakeLayer, handshake_events};
 use log::{debug, info, warn};
 use reqwest::{Client, ClientBuilder, Proxy, redirect};
+use rustls::client::Resumption;
 use std::sync::{Arc, Mutex};
 use yaak_models::models::DnsOverride;
+use yaak_tls::handshake::HandshakeObserver;
 use yaak_tls::{
-    ClientCertificateConfig, NativeClientIdentity, get_tls_config, load_native_client_identity,
+    ClientCertificateConfig, NativeClientIdentity, get_observed_tls_config,
+    load_native_client_identity,
 };
 
 pub const HTTP2_MAX_RESPONSE_HEADER_LIST_SIZE: u32 = 1024 * 1024;
@@ -17,20 +24,54 @@ fn client_builder() -> ClientBuilder {
 #[derive(Clone)]
 pub struct ConfiguredClient {
     inner: Client,
+    handshake_timing: bool,
+    client_certificate: bool,
+    http3: bool,
+    alt_svc: Arc<AltSvcCache>,
 }
 
 impl ConfiguredClient {
     pub(crate) fn build_default() -> Result<Self> {
-        Ok(Self { inner: client_builder().build()? })
-    }
-
-    pub(crate) fn from_inner(inner: Client) -> Self {
-        Self { inner }
+        Ok(Self {
+            inner: client_builder().build()?,
+            handshake_timing: false,
+            client_certificate: false,
+            http3: false,
+            alt_svc: Default::default(),
+        })
     }
 
     pub(crate) fn inner(&self) -> &Client {
         &self.inner
     }
+
+    /// Whether TLS handshakes are timed apart from the TCP connect. Only rustls has a hook
+    /// for it.
+    pub(crate) fn handshake_timing(&self) -> bool {
+        self.handshake_timing
+    }
+
+    /// Whether requests can be sent over HTTP/3.
+    pub(crate) fn http3(&self) -> bool {
+        self.http3
+    }
+
+    /// Origins that advertised HTTP/3 to this client.
+    pub(crate) fn alt_svc(&self) -> &AltSvcCache {
+        &self.alt_svc
+    }
+
+    /// Timeline events for the TLS handshake `observer` recorded for the connection `response`
+    /// came over, or for the failed attempt when there is no response. `over_quic` says whether
+    /// that attempt was made over HTTP/3.
+    pub(crate) fn handshake_events(
+        &self,
+        observer: &HandshakeObserver,
+        response: Option<&reqwest::Response>,
+        over_quic: bool,
+    ) -> Vec<HttpResponseEvent> {
+        handshake_events(observer.last(), self.client_certificate, response, over_quic)
+    }
 }
 
 /// Build a native-tls connector for maximum compatibility when certificate
@@ -93,6 +134,7 @@ pub enum HttpConnectionProxySetting {
         https: String,
         auth: Option<HttpConnectionProxySettingAuth>,
         bypass: String,
+        socks: String,
     },
 }
 
@@ -108,6 +150,10 @@ pub struct HttpConnectionOptions {
     /// a user sending to their own machine or their own network is the point.
     /// A hosted sender is the caller that supplies one.
     pub address_filter: Option<AddressFilter>,
+    /// Build the client with a QUIC connector, so requests can use HTTP/3. QUIC only runs
+    /// over rustls, so this also moves unvalidated connections off native TLS, and with
+    /// it TLS 1.0 and 1.1.
+    pub http3: bool,
 }
 
 impl HttpConnectionOptions {
@@ -128,10 +174,40 @@ impl HttpConnectionOptions {
             // This is needed so we can emit DNS timing events for each request
             .pool_max_idle_per_host(0);
 
+        // Configure DNS resolver - keep a reference to configure per-request
+        let resolver = LocalhostResolver::with_address_filter(
+            self.dns_overrides.clone(),
+            self.address_filter.clone(),
+        );
+        client = client.dns_resolver(resolver.clone());
+
+        // Time each connection for the send that opens it
+        client = client.connector_layer(ConnectTimingLayer);
+
+        // Record what each TLS handshake settles on
+        client = client.connector_layer(ObserveHandshakeLayer);
+        let mut client_certificate = false;
+        let mut handshake_timing = false;
+
+        // QUIC connects straight to the server, so a configured proxy rules it out
+        let http3 = self.http3 && !matches!(self.proxy, HttpConnectionProxySetting::Enabled { .. });
+
         // Configure TLS
-        if self.validate_certificates {
-            // Use rustls with platform certificate verification (TLS 1.2+ only)
-            let config = get_tls_config(true, true, self.client_certificate.clone())?;
+        if self.validate_certificates || http3 {
+            // Use rustls (TLS 1.2+ only), with platform certificate verification when enabled
+            let mut config = get_observed_tls_config(
+                self.validate_certificates,
+                true,
+                self.client_certificate.clone(),
+            )?;
+            config.resumption = Resumption::store(Arc::new(HandshakeTimingStore::new()));
+            handshake_timing = true;
+            if http3 {
+                // reqwest hands the QUIC connector this same config, and QUIC servers only
+                // accept h3. Servers reached over TCP never pick it.
+                config.alpn_protocols.insert(0, b"h3".to_vec());
+            }
+            client_certificate = config.client_auth_cert_resolver.has_certs();
             client = client.use_preconfigured_tls(config);
         } else {
             // Use native TLS for maximum compatibility (supports TLS 1.0+)
@@ -139,43 +215,45 @@ impl HttpConnectionOptions {
             client = client.use_preconfigured_tls(connector);
         }
 
-        // Configure DNS resolver - keep a reference to configure per-request
-        let resolver = LocalhostResolver::with_address_filter(
-            self.dns_overrides.clone(),
-            self.address_filter.clone(),
-        );
-        client = client.dns_resolver(resolver.clone());
-
         // Configure proxy
         match self.proxy.clone() {
             HttpConnectionProxySetting::System => { /* Default */ }
             HttpConnectionProxySetting::Disabled => {
                 client = client.no_proxy();
             }
-            HttpConnectionProxySetting::Enabled { http, https, auth, bypass } => {
-                for p in build_enabled_proxy(http, https, auth, bypass) {
+            HttpConnectionProxySetting::Enabled { http, https, auth, bypass, socks } => {
+                for p in build_enabled_proxy(http, https, socks, auth, bypass) {
                     client = client.proxy(p)
                 }
             }
         }
 
         info!(
-            "Building new HTTP client validate_certificates={} client_cert={}",
+            "Building new HTTP client validate_certificates={} client_cert={} http3={}",
             self.validate_certificates,
-            self.client_certificate.is_some()
+            self.client_certificate.is_some(),
+            http3
         );
 
-        Ok((ConfiguredClient::from_inner(client.build()?), resolver))
+        let configured = ConfiguredClient {
+            inner: client.build()?,
+            handshake_timing,
+            client_certificate,
+            http3,
+            alt_svc: AltSvcCache::shared(),
+        };
+        Ok((configured, resolver))
     }
 }
 
 fn build_enabled_proxy(
     http: String,
     https: String,
+    socks: String,
     auth: Option<HttpConnectionProxySettingAuth>,
     bypass: String,
 ) -> Vec<Proxy> {
-    debug!("Using proxy http={http} https={https} bypass={bypass}");
+    debug!("Using proxy http={http} https={https} socks={socks} bypass={bypass}");
 
     let mut proxies = Vec::new();
 
@@ -197,7 +275,7 @@ fn build_enabled_proxy(
     if !https.is_empty() {
         match Proxy::https(https) {
             Ok(mut proxy) => {
-                if let Some(HttpConnectionProxySettingAuth { user, password }) = auth {
+                if let Some(HttpConnectionProxySettingAuth { user, password }) = auth.clone() {
                     debug!("Using https proxy auth");
                     proxy = proxy.basic_auth(user.as_str(), password.as_str());
                 }
@@ -209,9 +287,31 @@ fn build_enabled_proxy(
         };
     }
 
+    // Added last, so it only carries what the http and https proxies don't
+    if !socks.is_empty() {
+        match Proxy::all(socks_proxy_url(&socks)) {
+            Ok(mut proxy) => {
+                if let Some(HttpConnectionProxySettingAuth { user, password }) = auth {
+                    debug!("Using socks proxy auth");
+                    proxy = proxy.basic_auth(user.as_str(), password.as_str());
+                }
+                proxies.push(proxy.no_proxy(reqwest::NoProxy::from_string(&bypass)));
+            }
+            Err(e) => {
+                warn!("Failed to apply socks proxy {e:?}");
+            }
+        };
+    }
+
     proxies
 }
 
+/// A bare `host:port` means SOCKS5 with names resolved by the proxy, since the hosts behind
+/// a bastion are usually only known to it.
+fn socks_proxy_url(socks: &str) -> String {
+    if socks.contains("://") { socks.to_string() } else { format!("socks5h://{socks}") }
+}
+
 #[cfg(test)]
 mod client_certificate_tests {
     use super::*;
@@ -379,3 +479,63 @@ UFNAXPsoutUompC1Z57bKA6OKM2hRANCAATCYYKhzgHEaRaGsYVjJSoXvoroL8qe
         assert!(build_native_tls_identity(Some(empty)).unwrap().is_none());
     }
 }
+
+#[cfg(test)]
+mod proxy_tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn bare_socks_address_resolves_names_on_the_proxy() {
+        assert_eq!(socks_proxy_url("bastion:1080"), "socks5h://bastion:1080");
+        assert_eq!(socks_proxy_url("socks5://bastion:1080"), "socks5://bastion:1080");
+    }
+
+    /// What a client first writes to the proxy on `listener`.
+    async fn first_write(listener: std::net::TcpListener) -> Vec<u8> {
+        tokio::task::spawn_blocking(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = vec![0; 1024];
+            let n = stream.read(&mut buf).unwrap();
+            buf.truncate(n);
+            buf
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn builds_a_socks_proxy_next_to_the_others() {
+        let https_proxy = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let socks_proxy = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let auth = HttpConnectionProxySettingAuth {
+            user: "user".to_string(),
+            password: "secret".to_string(),
+        };
+        let proxies = build_enabled_proxy(
+            String::new(),
+            https_proxy.local_addr().unwrap().to_string(),
+            socks_proxy.local_addr().unwrap().to_string(),
+            Some(auth),
+            String::new(),
+        );
+        assert_eq!(proxies.len(), 2);
+        let client =
+            proxies.into_iter().fold(Client::builder(), |b, p| b.proxy(p)).build().unwrap();
+
+        // https:// traffic tunnels through the https proxy, with the credentials
+        let send = tokio::spawn(client.get("https://api.example.test/").send());
+        let connect = String::from_utf8(first_write(https_proxy).await).unwrap();
+        send.abort();
+        assert!(connect.starts_with("CONNECT api.example.test:443 "), "{connect}");
+        assert!(connect.to_ascii_lowercase().contains("proxy-authorization: basic "), "{connect}");
+        assert!(connect.contains("dXNlcjpzZWNyZXQ="), "{connect}");
+
+        // Everything else goes to the SOCKS proxy, which is offered username/password auth
+        let send = tokio::spawn(client.get("http://api.example.test/").send());
+        let greeting = first_write(socks_proxy).await;
+        send.abort();
+        assert_eq!(greeting.first(), Some(&0x05), "{greeting:?}");
+        assert!(greeting.iter().skip(2).any(|method| *method == 0x02), "{greeting:?}");
+    }
+}
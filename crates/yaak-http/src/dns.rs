@@ -1,4 +1,5 @@
 use crate::sender::HttpResponseEvent;
+use crate::timing;
 use hyper_util::client::legacy::connect::dns::{
     GaiResolver as HyperGaiResolver, Name as HyperName,
 };
@@ -129,6 +130,7 @@ impl Resolve for LocalhostResolver {
 
             return Box::pin(async move {
                 Self::filter_addrs(&address_filter, &addrs)?;
+                timing::dns_resolved();
 
                 // Emit DNS event for override
                 let guard = event_tx.read().await;
@@ -162,6 +164,7 @@ impl Resolve for LocalhostResolver {
 
             return Box::pin(async move {
                 Self::filter_addrs(&address_filter, &addrs)?;
+                timing::dns_resolved();
 
                 // Emit DNS event for localhost resolution
                 let guard = event_tx.read().await;
@@ -200,6 +203,7 @@ impl Resolve for LocalhostResolver {
                     // Collect addresses for event emission
                     let addr_vec: Vec<SocketAddr> = addrs.collect();
                     Self::filter_addrs(&address_filter, &addr_vec)?;
+                    timing::dns_resolved();
                     let addresses: Vec<String> =
                         addr_vec.iter().map(|a| a.ip().to_string()).collect();
 
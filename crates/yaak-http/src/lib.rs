@@ -1,3 +1,4 @@
+pub mod alt_svc;
 mod chained_reader;
 pub mod client;
 pub mod cookies;
@@ -8,6 +9,8 @@ pub mod manager;
 mod proto;
 pub mod sender;
 pub mod tee_reader;
+pub mod timing;
+mod tls;
 pub mod transaction;
 pub mod types;
 
@@ -1,5 +1,7 @@
 use crate::decompress::{ContentEncoding, streaming_decoder};
 use crate::error::{Error, Result};
+use crate::timing::{ConnectionTimer, millis};
+use crate::tls::observe_handshakes;
 use crate::types::{SendableBody, SendableHttpRequest};
 use async_trait::async_trait;
 use bytes::Bytes;
@@ -8,11 +10,14 @@ use http_body::{Body as HttpBody, Frame, SizeHint};
 use reqwest::{Method, Version};
 use std::fmt::Display;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncRead, AsyncReadExt, BufReader, ReadBuf};
 use tokio::sync::mpsc;
 use tokio_util::io::StreamReader;
+use yaak_models::models::{Http3Mode, HttpResponseTlsCertificate};
+use yaak_tls::handshake::HandshakeObserver;
 
 #[derive(Debug, Clone)]
 pub enum RedirectBehavior {
@@ -68,6 +73,37 @@ pub enum HttpResponseEvent {
         duration: u64,
         overridden: bool,
     },
+    /// TCP connect time, from the end of DNS resolution. Includes the TLS handshake when
+    /// that could not be timed on its own.
+    TcpConnected {
+        duration: u64,
+    },
+    TlsHandshakeCompleted {
+        duration: u64,
+    },
+    /// Time from sending the request, or from connecting if that came later, until the
+    /// response headers arrived.
+    FirstByteReceived {
+        duration: u64,
+    },
+    /// Time from the response headers until the end of the body.
+    DownloadCompleted {
+        duration: u64,
+    },
+    TlsNegotiated {
+        protocol_version: String,
+        cipher_suite: String,
+        alpn: Option<String>,
+        resumed: bool,
+    },
+    /// The chain the server sent, leaf first.
+    TlsServerCertificates {
+        certificates: Vec<HttpResponseTlsCertificate>,
+    },
+    TlsClientCertificate {
+        requested: bool,
+        presented: bool,
+    },
 }
 
 impl Display for HttpResponseEvent {
@@ -146,6 +182,47 @@ impl Display for HttpResponseEvent {
                     )
                 }
             }
+            HttpResponseEvent::TcpConnected { duration } => {
+                write!(f, "* Connected in {}ms", duration)
+            }
+            HttpResponseEvent::TlsHandshakeCompleted { duration } => {
+                write!(f, "* TLS handshake completed in {}ms", duration)
+            }
+            HttpResponseEvent::FirstByteReceived { duration } => {
+                write!(f, "* First byte received after {}ms", duration)
+            }
+            HttpResponseEvent::DownloadCompleted { duration } => {
+                write!(f, "* Body downloaded in {}ms", duration)
+            }
+            HttpResponseEvent::TlsNegotiated { protocol_version, cipher_suite, alpn, resumed } => {
+                let alpn_str = alpn.as_ref().map(|a| format!(", ALPN {}", a)).unwrap_or_default();
+                let resumed_str = if *resumed { " (resumed)" } else { "" };
+                write!(f, "* {} with {}{}{}", protocol_version, cipher_suite, alpn_str, resumed_str)
+            }
+            HttpResponseEvent::TlsServerCertificates { certificates } => {
+                for (i, certificate) in certificates.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(
+                        f,
+                        "* Server certificate {}, issued by {}, valid until {}",
+                        certificate.subject, certificate.issuer, certificate.not_after
+                    )?;
+                }
+                Ok(())
+            }
+            HttpResponseEvent::TlsClientCertificate { requested, presented } => {
+                match (requested, presented) {
+                    (_, true) => write!(f, "* Client certificate presented"),
+                    (true, false) => {
+                        write!(f, "* Server requested a client certificate, none was presented")
+                    }
+                    (false, false) => {
+                        write!(f, "* Client certificate configured, server did not request it")
+                    }
+                }
+            }
         }
     }
 }
@@ -197,6 +274,21 @@ impl From<HttpResponseEvent> for yaak_models::models::HttpResponseEventData {
             HttpResponseEvent::DnsResolved { hostname, addresses, duration, overridden } => {
                 D::DnsResolved { hostname, addresses, duration, overridden }
             }
+            HttpResponseEvent::TcpConnected { duration } => D::TcpConnected { duration },
+            HttpResponseEvent::TlsHandshakeCompleted { duration } => {
+                D::TlsHandshakeCompleted { duration }
+            }
+            HttpResponseEvent::FirstByteReceived { duration } => D::FirstByteReceived { duration },
+            HttpResponseEvent::DownloadCompleted { duration } => D::DownloadCompleted { duration },
+            HttpResponseEvent::TlsNegotiated { protocol_version, cipher_suite, alpn, resumed } => {
+                D::TlsNegotiated { protocol_version, cipher_suite, alpn, resumed }
+            }
+            HttpResponseEvent::TlsServerCertificates { certificates } => {
+                D::TlsServerCertificates { certificates }
+            }
+            HttpResponseEvent::TlsClientCertificate { requested, presented } => {
+                D::TlsClientCertificate { requested, presented }
+            }
         }
     }
 }
@@ -210,16 +302,18 @@ pub struct BodyStats {
     pub size_decompressed: u64,
 }
 
-/// An AsyncRead wrapper that sends chunk events as data is read
+/// An AsyncRead wrapper that sends chunk events as data is read, and a download event once
+/// the body ends
 pub struct TrackingRead<R> {
     inner: R,
     event_tx: mpsc::Sender<HttpResponseEvent>,
+    started_at: Instant,
     ended: bool,
 }
 
 impl<R> TrackingRead<R> {
     pub fn new(inner: R, event_tx: mpsc::Sender<HttpResponseEvent>) -> Self {
-        Self { inner, event_tx, ended: false }
+        Self { inner, event_tx, started_at: Instant::now(), ended: false }
     }
 }
 
@@ -239,6 +333,9 @@ impl<R: AsyncRead + Unpin> AsyncRead for TrackingRead<R> {
                     self.event_tx.try_send(HttpResponseEvent::ChunkReceived { bytes: bytes_read });
             } else if !self.ended {
                 self.ended = true;
+                let _ = self.event_tx.try_send(HttpResponseEvent::DownloadCompleted {
+                    duration: millis(self.started_at.elapsed()),
+                });
             }
         }
         result
@@ -492,7 +589,27 @@ impl HttpSender for ReqwestSender {
         }
 
         // Send the request
-        let sendable_req = req_builder.build()?;
+        let mut sendable_req = req_builder.build()?;
+
+        // reqwest only uses QUIC for requests that ask for HTTP/3
+        let mode = request.options.http3;
+        let http3 = self.client.http3()
+            && sendable_req.url().scheme() == "https"
+            && match mode {
+                Http3Mode::Off => false,
+                Http3Mode::AltSvc => self.client.alt_svc().advertises_h3(sendable_req.url()),
+                Http3Mode::Force => true,
+            };
+        let mut fallback = None;
+        if http3 {
+            // Advertised HTTP/3 is only ever a shortcut, so keep a copy to send over TCP if
+            // QUIC fails. A streamed body can't be copied, and then there's no second try.
+            if mode == Http3Mode::AltSvc {
+                fallback = sendable_req.try_clone();
+            }
+            *sendable_req.version_mut() = Version::HTTP_3;
+        }
+
         send_event(HttpResponseEvent::SendUrl {
             method: sendable_req.method().to_string(),
             scheme: sendable_req.url().scheme().to_string(),
@@ -511,10 +628,49 @@ impl HttpSender for ReqwestSender {
             request_headers.push((name.to_string(), v.clone()));
             send_event(HttpResponseEvent::HeaderUp(name.to_string(), v));
         }
+        if http3 {
+            send_event(HttpResponseEvent::Info(match mode {
+                Http3Mode::AltSvc => "Using HTTP/3, as advertised by Alt-Svc".to_string(),
+                _ => "Using HTTP/3".to_string(),
+            }));
+        }
         send_event(HttpResponseEvent::Info("Sending request to server".to_string()));
 
+        // A plain connection has no handshake to miss
+        let hidden_handshake =
+            !self.client.handshake_timing() && sendable_req.url().scheme() == "https";
+        let timer = Arc::new(ConnectionTimer::new(event_tx.clone(), hidden_handshake));
+        let handshake = Arc::new(HandshakeObserver::default());
+
         // Map some errors to our own, so they look nicer
-        let response = self.client.inner().execute(sendable_req).await.map_err(|e| {
+        let sent_at = Instant::now();
+        let mut result = timer
+            .scope(observe_handshakes(&handshake, self.client.inner().execute(sendable_req)))
+            .await;
+        let mut over_quic = http3;
+        // A QUIC timeout usually means UDP is blocked, so it gets the TCP retry like any
+        // other failure, and the origin is forgotten so later requests don't stall on it too
+        if let Err(e) = &result
+            && http3
+            && mode == Http3Mode::AltSvc
+        {
+            if let Ok(url) = reqwest::Url::parse(&request.url) {
+                self.client.alt_svc().forget(&url);
+            }
+            if let Some(fallback) = fallback {
+                send_event(HttpResponseEvent::Info(format!(
+                    "HTTP/3 failed, retrying over TCP: {e}"
+                )));
+                result = timer
+                    .scope(observe_handshakes(&handshake, self.client.inner().execute(fallback)))
+                    .await;
+                over_quic = false;
+            }
+        }
+        for event in self.client.handshake_events(&handshake, result.as_ref().ok(), over_quic) {
+            send_event(event);
+        }
+        let response = result.map_err(|e| {
             if reqwest::Error::is_timeout(&e) {
                 Error::RequestTimeout(
                     request.options.timeout.unwrap_or(Duration::from_secs(0)).clone(),
@@ -531,6 +687,20 @@ impl HttpSender for ReqwestSender {
         let version = Some(version_to_str(&response.version()));
         let content_length = response.content_length();
 
+        if mode != Http3Mode::Off {
+            let alt_svc = response.headers().get(reqwest::header::ALT_SVC);
+            self.client.alt_svc().update(response.url(), alt_svc.and_then(|v| v.to_str().ok()));
+        }
+
+        // A connection opened for this request counts as setup, not as waiting on the server
+        let waiting_since = match timer.connected_at() {
+            Some(connected_at) if connected_at > sent_at => connected_at,
+            _ => sent_at,
+        };
+        send_event(HttpResponseEvent::FirstByteReceived {
+            duration: millis(waiting_since.elapsed()),
+        });
+
         send_event(HttpResponseEvent::ReceiveUrl {
             version: response.version(),
             status: response.status().to_string(),
@@ -0,0 +1,369 @@
+//! Connection phase timing for the response timeline.
+//!
+//! reqwest opens a connection (DNS, TCP, any proxy tunnel, TLS) in one step that can only be
+//! watched from the outside, so the phases are pieced together from three places:
+//!
+//! - the DNS resolver marks when resolution finished,
+//! - the rustls session store marks when the TLS handshake started, since rustls asks it for a
+//!   session to resume right before writing the ClientHello,
+//! - the connector layer wraps the whole step and reports the phases once it completes.
+//!
+//! The resolver, session store and connector are shared by every send on a client, so the marks
+//! go to the [`ConnectionTimer`] of the send being polled, which the sender sets with
+//! [`ConnectionTimer::scope`]. The connection is opened inside that send's future, because
+//! pooling is disabled on clients built for sending.
+//!
+//! With certificate validation off, the native TLS stack is used instead of rustls. It has no
+//! such hook, and TCP time that silently included the handshake would be wrong, so no phases
+//! are reported for those connections.
+
+use crate::sender::HttpResponseEvent;
+use rustls::NamedGroup;
+use rustls::client::{
+    ClientSessionMemoryCache, ClientSessionStore, Tls12ClientSessionValue, Tls13ClientSessionValue,
+};
+use rustls::pki_types::ServerName;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Sessions kept for resumption, the same as rustls' default.
+const SESSION_CACHE_SIZE: usize = 256;
+
+tokio::task_local! {
+    static CURRENT: Arc<ConnectionTimer>;
+}
+
+#[derive(Debug, Default)]
+struct Marks {
+    started: Option<Instant>,
+    dns_resolved: Option<Instant>,
+    tls_started: Option<Instant>,
+    connected: Option<Instant>,
+}
+
+/// Connection timing for one send.
+#[derive(Debug)]
+pub(crate) struct ConnectionTimer {
+    marks: Mutex<Marks>,
+    event_tx: mpsc::Sender<HttpResponseEvent>,
+    /// The handshake can't be seen, so there is no TCP time to report without it.
+    hidden_handshake: bool,
+}
+
+impl ConnectionTimer {
+    pub(crate) fn new(event_tx: mpsc::Sender<HttpResponseEvent>, hidden_handshake: bool) -> Self {
+        Self { marks: Mutex::new(Marks::default()), event_tx, hidden_handshake }
+    }
+
+    /// Run `future` with this timer receiving the marks of any connection it opens.
+    pub(crate) async fn scope<F: Future>(self: &Arc<Self>, future: F) -> F::Output {
+        CURRENT.scope(self.clone(), future).await
+    }
+
+    /// When the most recent connection for this send finished opening, if one has.
+    pub(crate) fn connected_at(&self) -> Option<Instant> {
+        self.marks().connected
+    }
+
+    fn connect_started(&self) {
+        *self.marks() = Marks { started: Some(Instant::now()), ..Marks::default() };
+    }
+
+    fn connected(&self) {
+        let now = Instant::now();
+        let (tcp, tls) = {
+            let mut marks = self.marks();
+            marks.connected = Some(now);
+            split_phases(&marks, now)
+        };
+        if tls.is_none() && self.hidden_handshake {
+            return;
+        }
+
+        let _ = self.event_tx.try_send(HttpResponseEvent::TcpConnected { duration: millis(tcp) });
+        if let Some(tls) = tls {
+            let _ = self
+                .event_tx
+                .try_send(HttpResponseEvent::TlsHandshakeCompleted { duration: millis(tls) });
+        }
+    }
+
+    fn marks(&self) -> std::sync::MutexGuard<'_, Marks> {
+        self.marks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The timer of the send being polled, if it set one.
+fn current() -> Option<Arc<ConnectionTimer>> {
+    CURRENT.try_with(Arc::clone).ok()
+}
+
+/// Mark the end of DNS resolution for the connection being opened.
+pub(crate) fn dns_resolved() {
+    if let Some(timer) = current() {
+        timer.marks().dns_resolved = Some(Instant::now());
+    }
+}
+
+fn tls_started() {
+    if let Some(timer) = current() {
+        timer.marks().tls_started.get_or_insert_with(Instant::now);
+    }
+}
+
+/// TCP connect and TLS handshake durations for a connection that finished at `now`. TCP is
+/// counted from the end of DNS resolution, and runs until the handshake started when there
+/// was one to see.
+fn split_phases(marks: &Marks, now: Instant) -> (Duration, Option<Duration>) {
+    let started = marks.started.unwrap_or(now);
+    let tcp_from = marks.dns_resolved.filter(|at| *at >= started).unwrap_or(started);
+    match marks.tls_started.filter(|at| *at >= tcp_from) {
+        Some(tls_from) => (tls_from.duration_since(tcp_from), Some(now.duration_since(tls_from))),
+        None => (now.duration_since(tcp_from), None),
+    }
+}
+
+pub(crate) fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Wraps reqwest's connector to time each connection it opens.
+#[derive(Clone)]
+pub(crate) struct ConnectTimingLayer;
+
+impl<S> Layer<S> for ConnectTimingLayer {
+    type Service = ConnectTiming<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConnectTiming { inner }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct ConnectTiming<S> {
+    inner: S,
+}
+
+impl<S, Req> Service<Req> for ConnectTiming<S>
+where
+    S: Service<Req>,
+    S::Response: Send,
+    S::Error: Send,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Req) -> Self::Future {
+        let timer = current();
+        if let Some(timer) = &timer {
+            timer.connect_started();
+        }
+        let connecting = self.inner.call(req);
+        Box::pin(async move {
+            let result = connecting.await;
+            if let Some(timer) = timer
+                && result.is_ok()
+            {
+                timer.connected();
+            }
+            result
+        })
+    }
+}
+
+/// rustls' in-memory session cache, noting when each handshake starts on the way through.
+#[derive(Debug)]
+pub(crate) struct HandshakeTimingStore {
+    inner: ClientSessionMemoryCache,
+}
+
+impl HandshakeTimingStore {
+    pub(crate) fn new() -> Self {
+        Self { inner: ClientSessionMemoryCache::new(SESSION_CACHE_SIZE) }
+    }
+}
+
+impl ClientSessionStore for HandshakeTimingStore {
+    fn set_kx_hint(&self, server_name: ServerName<'static>, group: NamedGroup) {
+        self.inner.set_kx_hint(server_name, group)
+    }
+
+    fn kx_hint(&self, server_name: &ServerName<'_>) -> Option<NamedGroup> {
+        tls_started();
+        self.inner.kx_hint(server_name)
+    }
+
+    fn set_tls12_session(&self, server_name: ServerName<'static>, value: Tls12ClientSessionValue) {
+        self.inner.set_tls12_session(server_name, value)
+    }
+
+    fn tls12_session(&self, server_name: &ServerName<'_>) -> Option<Tls12ClientSessionValue> {
+        tls_started();
+        self.inner.tls12_session(server_name)
+    }
+
+    fn remove_tls12_session(&self, server_name: &ServerName<'static>) {
+        self.inner.remove_tls12_session(server_name)
+    }
+
+    fn insert_tls13_ticket(
+        &self,
+        server_name: ServerName<'static>,
+        value: Tls13ClientSessionValue,
+    ) {
+        self.inner.insert_tls13_ticket(server_name, value)
+    }
+
+    fn take_tls13_ticket(
+        &self,
+        server_name: &ServerName<'static>,
+    ) -> Option<Tls13ClientSessionValue> {
+        tls_started();
+        self.inner.take_tls13_ticket(server_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_tcp_from_tls_after_dns() {
+        let started = Instant::now();
+        let marks = Marks {
+            started: Some(started),
+            dns_resolved: Some(started + Duration::from_millis(5)),
+            tls_started: Some(started + Duration::from_millis(25)),
+            connected: None,
+        };
+        let (tcp, tls) = split_phases(&marks, started + Duration::from_millis(70));
+        assert_eq!(tcp, Duration::from_millis(20));
+        assert_eq!(tls, Some(Duration::from_millis(45)));
+    }
+
+    #[test]
+    fn counts_everything_after_dns_as_tcp_without_a_handshake() {
+        let started = Instant::now();
+        let marks = Marks {
+            started: Some(started),
+            dns_resolved: Some(started + Duration::from_millis(5)),
+            ..Marks::default()
+        };
+        let (tcp, tls) = split_phases(&marks, started + Duration::from_millis(15));
+        assert_eq!(tcp, Duration::from_millis(10));
+        assert_eq!(tls, None);
+    }
+
+    #[tokio::test]
+    async fn reports_phases_for_a_connection() {
+        let (tx, mut rx) = mpsc::channel(8);
+        let timer = Arc::new(ConnectionTimer::new(tx, false));
+
+        let mut service = ConnectTimingLayer.layer(ServiceFn(|| async {
+            dns_resolved();
+            tls_started();
+            Ok::<_, ()>(())
+        }));
+        timer.scope(async { service.call(()).await }).await.unwrap();
+
+        assert!(matches!(rx.recv().await, Some(HttpResponseEvent::TcpConnected { .. })));
+        assert!(matches!(rx.recv().await, Some(HttpResponseEvent::TlsHandshakeCompleted { .. })));
+        assert!(timer.connected_at().is_some());
+    }
+
+    #[tokio::test]
+    async fn concurrent_sends_keep_their_own_marks() {
+        let (plain_tx, mut plain_rx) = mpsc::channel(8);
+        let (tls_tx, mut tls_rx) = mpsc::channel(8);
+        let plain = Arc::new(ConnectionTimer::new(plain_tx, false));
+        let tls = Arc::new(ConnectionTimer::new(tls_tx, false));
+
+        // One connector shared by both sends, as it is on a cached client
+        let (plain_dns, release_plain) = tokio::sync::oneshot::channel::<()>();
+        let plain_dns = Arc::new(Mutex::new(Some(plain_dns)));
+        let mut service = ConnectTimingLayer.layer(ServiceFn(move || {
+            let plain_dns = plain_dns.lock().unwrap().take();
+            async move {
+                match plain_dns {
+                    Some(rx) => {
+                        let _ = rx.await;
+                        dns_resolved();
+                    }
+                    None => {
+                        dns_resolved();
+                        tls_started();
+                    }
+                }
+                Ok::<_, ()>(())
+            }
+        }));
+
+        let mut plain_service = service.clone();
+        let plain_connect = plain.scope(async move { plain_service.call(()).await });
+        let tls_connect = tls.scope(async move { service.call(()).await });
+        let (plain_result, tls_result) = tokio::join!(plain_connect, async {
+            let result = tls_connect.await;
+            let _ = release_plain.send(());
+            result
+        });
+        plain_result.unwrap();
+        tls_result.unwrap();
+
+        assert!(matches!(plain_rx.recv().await, Some(HttpResponseEvent::TcpConnected { .. })));
+        assert!(plain_rx.try_recv().is_err());
+        assert!(matches!(tls_rx.recv().await, Some(HttpResponseEvent::TcpConnected { .. })));
+        assert!(matches!(
+            tls_rx.recv().await,
+            Some(HttpResponseEvent::TlsHandshakeCompleted { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn reports_nothing_when_the_handshake_is_hidden() {
+        let (tx, mut rx) = mpsc::channel(8);
+        let timer = Arc::new(ConnectionTimer::new(tx, true));
+
+        let mut service = ConnectTimingLayer.layer(ServiceFn(|| async {
+            dns_resolved();
+            Ok::<_, ()>(())
+        }));
+        timer.scope(async { service.call(()).await }).await.unwrap();
+
+        assert!(rx.try_recv().is_err());
+        assert!(timer.connected_at().is_some());
+    }
+
+    /// A connector stand-in that runs its closure for every connection.
+    #[derive(Clone)]
+    struct ServiceFn<F>(F);
+
+    impl<F, Fut> Service<()> for ServiceFn<F>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        type Response = ();
+        type Error = ();
+        type Future = Fut;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _req: ()) -> Fut {
+            (self.0)()
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! TLS handshake details for the response timeline.
+//!
+//! On the rustls path, [`yaak_tls::handshake`] records what each handshake settled on while
+//! [`ObserveHandshakeLayer`] is polling the connection. The native TLS stack, used when
+//! certificate validation is off, has no such hooks, so there only the server's leaf
+//! certificate is known, from what reqwest attaches to the response.
+
+use crate::sender::HttpResponseEvent;
+use log::warn;
+use reqwest::tls::TlsInfo;
+use reqwest::{Response, Version};
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+use yaak_models::models::HttpResponseTlsCertificate;
+use yaak_tls::certificate::describe_certificate;
+use yaak_tls::handshake::{Handshake, HandshakeObserver, Observed};
+
+tokio::task_local! {
+    static CURRENT: Arc<HandshakeObserver>;
+}
+
+/// Run `future` with `observer` recording the handshake of any connection it opens.
+///
+/// The connector is shared by every send on a client, so each send brings its own observer,
+/// the same way it brings its own [`ConnectionTimer`](crate::timing::ConnectionTimer).
+pub(crate) async fn observe_handshakes<F: Future>(
+    observer: &Arc<HandshakeObserver>,
+    future: F,
+) -> F::Output {
+    CURRENT.scope(observer.clone(), future).await
+}
+
+/// Wraps reqwest's connector so each handshake it performs is recorded.
+#[derive(Clone)]
+pub(crate) struct ObserveHandshakeLayer;
+
+impl<S> Layer<S> for ObserveHandshakeLayer {
+    type Service = ObserveHandshake<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ObserveHandshake { inner }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct ObserveHandshake<S> {
+    inner: S,
+}
+
+impl<S, Req> Service<Req> for ObserveHandshake<S>
+where
+    S: Service<Req>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Observed<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Req) -> Self::Future {
+        // A connection opened outside a send is recorded where nobody will look
+        let observer = CURRENT.try_with(Arc::clone).unwrap_or_default();
+        observer.reset();
+        observer.observe(self.inner.call(req))
+    }
+}
+
+/// Timeline events describing the handshake behind `response`, or behind the failed
+/// attempt when there is none. Empty when no TLS was involved. `over_quic` says whether that
+/// failed attempt was made over HTTP/3.
+pub(crate) fn handshake_events(
+    handshake: Option<Handshake>,
+    client_certificate_configured: bool,
+    response: Option<&Response>,
+    over_quic: bool,
+) -> Vec<HttpResponseEvent> {
+    // QUIC connections don't go through the connector layers, so anything observed came from
+    // an earlier connection over TCP
+    let over_quic = response.map_or(over_quic, |r| r.version() == Version::HTTP_3);
+    let handshake = handshake.filter(|_| !over_quic);
+
+    let peer_certificate = response
+        .and_then(|r| r.extensions().get::<TlsInfo>())
+        .and_then(|info| info.peer_certificate());
+
+    let Some(handshake) = handshake else {
+        // The native TLS path, or a plain HTTP connection
+        return peer_certificate
+            .and_then(|der| server_certificates_event(&[der]))
+            .into_iter()
+            .collect();
+    };
+
+    let mut events = Vec::new();
+    if let (Some(protocol_version), Some(cipher_suite)) =
+        (handshake.protocol_version.clone(), handshake.cipher_suite.clone())
+    {
+        events.push(HttpResponseEvent::TlsNegotiated {
+            protocol_version,
+            cipher_suite,
+            alpn: response.and_then(|r| alpn_for(r.version())),
+            resumed: handshake.resumed(),
+        });
+    }
+
+    // A resumed session skips verification, but reqwest still has the leaf rustls kept
+    let chain: Vec<&[u8]> = if handshake.server_certificates.is_empty() {
+        peer_certificate.into_iter().collect()
+    } else {
+        handshake.server_certificates.iter().map(|c| c.as_ref()).collect()
+    };
+    events.extend(server_certificates_event(&chain));
+
+    if handshake.client_certificate_requested || client_certificate_configured {
+        events.push(HttpResponseEvent::TlsClientCertificate {
+            requested: handshake.client_certificate_requested,
+            presented: handshake.client_certificate_presented,
+        });
+    }
+
+    events
+}
+
+fn server_certificates_event(chain: &[&[u8]]) -> Option<HttpResponseEvent> {
+    let certificates: Vec<HttpResponseTlsCertificate> = chain
+        .iter()
+        .filter_map(|der| {
+            describe_certificate(der)
+                .inspect_err(|e| warn!("Failed to describe server certificate: {e}"))
+                .ok()
+        })
+        .collect();
+    if certificates.is_empty() {
+        return None;
+    }
+    Some(HttpResponseEvent::TlsServerCertificates { certificates })
+}
+
+/// The ALPN protocol a connection must have agreed on to speak `version`. reqwest keeps
+/// what was actually negotiated to itself.
+fn alpn_for(version: Version) -> Option<String> {
+    match version {
+        Version::HTTP_3 => Some("h3".to_string()),
+        Version::HTTP_2 => Some("h2".to_string()),
+        Version::HTTP_11 => Some("http/1.1".to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn negotiated() -> Handshake {
+        Handshake {
+            protocol_version: Some("TLSv1.3".to_string()),
+            cipher_suite: Some("TLS13_AES_128_GCM_SHA256".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plain_http_has_no_events() {
+        assert!(handshake_events(None, false, None, false).is_empty());
+    }
+
+    #[test]
+    fn reports_the_negotiated_parameters() {
+        let events = handshake_events(Some(negotiated()), false, None, false);
+        assert!(matches!(
+            events.as_slice(),
+            [HttpResponseEvent::TlsNegotiated { protocol_version, cipher_suite, alpn: None, resumed: true }]
+                if protocol_version == "TLSv1.3" && cipher_suite == "TLS13_AES_128_GCM_SHA256"
+        ));
+    }
+
+    #[test]
+    fn reports_a_configured_certificate_the_server_never_asked_for() {
+        let events = handshake_events(Some(negotiated()), true, None, false);
+        assert!(matches!(
+            events.last(),
+            Some(HttpResponseEvent::TlsClientCertificate { requested: false, presented: false })
+        ));
+    }
+
+    #[test]
+    fn reports_an_unanswered_certificate_request() {
+        let handshake = Handshake { client_certificate_requested: true, ..negotiated() };
+        let events = handshake_events(Some(handshake), false, None, false);
+        assert!(matches!(
+            events.last(),
+            Some(HttpResponseEvent::TlsClientCertificate { requested: true, presented: false })
+        ));
+    }
+
+    fn response(version: Version) -> Response {
+        Response::from(http::Response::builder().version(version).body(Vec::new()).unwrap())
+    }
+
+    #[test]
+    fn ignores_a_tcp_handshake_for_an_http3_response() {
+        let response = response(Version::HTTP_3);
+        let events = handshake_events(Some(negotiated()), false, Some(&response), true);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn ignores_a_tcp_handshake_when_forced_http3_fails() {
+        assert!(handshake_events(Some(negotiated()), false, None, true).is_empty());
+    }
+
+    #[test]
+    fn reports_the_handshake_of_a_fallback_over_tcp() {
+        let response = response(Version::HTTP_2);
+        let events = handshake_events(Some(negotiated()), false, Some(&response), true);
+        assert!(matches!(
+            events.as_slice(),
+            [HttpResponseEvent::TlsNegotiated { alpn: Some(alpn), .. }] if alpn == "h2"
+        ));
+    }
+}
@@ -10,7 +10,7 @@ use std::pin::Pin;
 use std::time::Duration;
 use tokio::io::AsyncRead;
 use yaak_common::serde::{get_bool, get_bool_map, get_str, get_str_map};
-use yaak_models::models::HttpRequest;
+use yaak_models::models::{Http3Mode, HttpRequest};
 use yaak_templates::strip_json_comments::{maybe_strip_json_comments, strip_json_comments};
 
 pub(crate) const MULTIPART_BOUNDARY: &str = "------YaakFormBoundary";
@@ -58,6 +58,10 @@ pub struct SendableHttpRequest {
 pub struct SendableHttpRequestOptions {
     pub timeout: Option<Duration>,
     pub follow_redirects: bool,
+    /// Only takes effect on a client built with [`HttpConnectionOptions::http3`].
+    ///
+    /// [`HttpConnectionOptions::http3`]: crate::client::HttpConnectionOptions::http3
+    pub http3: Http3Mode,
 }
 
 impl SendableHttpRequest {
@@ -97,6 +101,22 @@ impl SendableHttpRequest {
             self.headers.push(header);
         }
     }
+
+    /// A copy to send again, or `None` when the body is a stream that can only be read once.
+    pub fn try_clone(&self) -> Option<Self> {
+        let body = match &self.body {
+            Some(SendableBody::Bytes(bytes)) => Some(SendableBody::Bytes(bytes.clone())),
+            Some(SendableBody::Stream { .. }) => return None,
+            None => None,
+        };
+        Some(Self {
+            url: self.url.clone(),
+            method: self.method.clone(),
+            headers: self.headers.clone(),
+            body,
+            options: self.options.clone(),
+        })
+    }
 }
 
 pub fn append_query_params(url: &str, params: Vec<(String, String)>) -> String {
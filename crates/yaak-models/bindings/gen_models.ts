@@ -12,8 +12,11 @@ export type AnyModel =
   | HttpRequest
   | HttpResponse
   | HttpResponseEvent
+  | HttpResponseSseEvent
   | KeyValue
   | Plugin
+  | RunnerIteration
+  | RunnerRun
   | Settings
   | SyncState
   | WebsocketConnection
@@ -110,6 +113,8 @@ export type Folder = {
   settingFollowRedirects: InheritedBoolSetting;
   settingRequestTimeout: InheritedIntSetting;
   settingRequestMessageSize: InheritedIntSetting;
+  settingHttp3: InheritedHttp3Setting;
+  settingProxy: InheritedProxySetting;
 };
 
 export type GraphQlIntrospection = {
@@ -122,6 +127,12 @@ export type GraphQlIntrospection = {
   content: string | null;
 };
 
+/**
+ * How the messages a gRPC request sends are compressed. Compressed responses are accepted
+ * either way.
+ */
+export type GrpcCompression = "none" | "gzip" | "zstd";
+
 export type GrpcConnection = {
   model: "grpc_connection";
   id: string;
@@ -164,6 +175,17 @@ export type GrpcEventType =
   | "connection_start"
   | "connection_end";
 
+/**
+ * The wire protocol a gRPC request is sent with. gRPC-Web and Connect also work over
+ * HTTP/1.1, so they reach services behind browser-facing proxies.
+ */
+export type GrpcProtocol =
+  | "grpc"
+  | "grpc_web"
+  | "grpc_web_text"
+  | "connect_json"
+  | "connect_proto";
+
 export type GrpcRequest = {
   model: "grpc_request";
   id: string;
@@ -184,10 +206,112 @@ export type GrpcRequest = {
    * Server URL (http for plaintext or https for secure)
    */
   url: string;
+  protocol: GrpcProtocol;
+  compression: GrpcCompression;
   settingValidateCertificates: InheritedBoolSetting;
   settingRequestMessageSize: InheritedIntSetting;
+  /**
+   * Deadline for each call in milliseconds, sent as `grpc-timeout`. 0 means none.
+   */
+  settingRequestTimeout: InheritedIntSetting;
+};
+
+/**
+ * When to send a request over HTTP/3 (QUIC) instead of TCP.
+ */
+export type Http3Mode = "off" | "alt_svc" | "force";
+
+export type HttpAssertionKind =
+  | "status_equals"
+  | "header_matches"
+  | "json_path_equals"
+  | "json_path_contains"
+  | "body_matches"
+  | "elapsed_under";
+
+/**
+ * The outcome of one [`HttpRequestAssertion`] against a response, with the rendered target and
+ * expectation it ran with so the result still reads correctly after the request changes.
+ */
+export type HttpAssertionResult = {
+  assertionId: string | null;
+  kind: HttpAssertionKind;
+  target: string;
+  expected: string;
+  /**
+   * What the response actually had, when there was something to compare
+   */
+  actual: string | null;
+  passed: boolean;
+  /**
+   * Why the check could not run, such as an invalid regex or a body that is not JSON
+   */
+  error: string | null;
+};
+
+export type HttpBenchErrorCount = {
+  error: string;
+  count: number;
+};
+
+/**
+ * Latency of completed requests in milliseconds, from send until the body was read.
+ */
+export type HttpBenchLatency = {
+  min: number;
+  mean: number;
+  p50: number;
+  p90: number;
+  p95: number;
+  p99: number;
+  max: number;
+};
+
+/**
+ * The result of firing one rendered request repeatedly, as a quick load check.
+ */
+export type HttpBenchReport = {
+  requestId: string;
+  method: string;
+  url: string;
+  concurrency: number;
+  /**
+   * Requests per second the run was limited to, if any
+   */
+  rate: number | null;
+  /**
+   * Milliseconds the run actually took
+   */
+  elapsed: number;
+  /**
+   * Requests sent, whether or not a response came back
+   */
+  total: number;
+  /**
+   * Requests that got a response, of any status
+   */
+  completed: number;
+  /**
+   * Requests that failed before a response arrived
+   */
+  failed: number;
+  /**
+   * Completed requests per second
+   */
+  throughput: number;
+  latency: HttpBenchLatency;
+  statusCodes: Array<HttpBenchStatusCount>;
+  errors: Array<HttpBenchErrorCount>;
+  cancelled: boolean;
 };
 
+export type HttpBenchStatusCount = {
+  status: number;
+  count: number;
+};
+
+export type HttpExtractionSource = "json_path" | "xpath" | "header" | "body_regex" | "cookie";
+
 export type HttpRequest = {
   model: "http_request";
   id: string;
@@ -214,6 +338,57 @@ export type HttpRequest = {
   settingValidateCertificates: InheritedBoolSetting;
   settingFollowRedirects: InheritedBoolSetting;
   settingRequestTimeout: InheritedIntSetting;
+  settingHttp3: InheritedHttp3Setting;
+  /**
+   * Checks evaluated against every response this request produces.
+   */
+  assertions: Array<HttpRequestAssertion>;
+  /**
+   * Values written into environment variables after each successful response.
+   */
+  extractions: Array<HttpRequestExtraction>;
+};
+
+/**
+ * A check run against a response once it has been stored. Both `target` and `value` are
+ * templates, rendered with the rest of the request.
+ */
+export type HttpRequestAssertion = {
+  enabled?: boolean;
+  kind: HttpAssertionKind;
+  /**
+   * What the check looks at, for kinds that need one: a header name or a JSON path
+   */
+  target: string;
+  /**
+   * The expected value, pattern, or limit
+   */
+  value: string;
+  id?: string;
+};
+
+/**
+ * A value read out of a successful response and written into an environment variable, so a
+ * later request can use it without re-reading or re-sending this one. `expression` is a
+ * template, rendered with the rest of the request.
+ */
+export type HttpRequestExtraction = {
+  enabled?: boolean;
+  source: HttpExtractionSource;
+  /**
+   * The JSON path, XPath, header name, regex, or cookie name to read
+   */
+  expression: string;
+  /**
+   * Name of the environment variable to write
+   */
+  variable: string;
+  /**
+   * Environment to write into. Defaults to the environment the request was sent with, or the
+   * base environment when there is none.
+   */
+  environmentId?: string;
+  id?: string;
 };
 
 export type HttpRequestHeader = { enabled?: boolean; name: string; value: string; id?: string };
@@ -230,6 +405,15 @@ export type HttpResponse = {
   elapsed: number;
   elapsedHeaders: number;
   elapsedDns: number;
+  /**
+   * TCP connect time, plus the TLS handshake when that was not timed separately
+   */
+  elapsedConnect: number;
+  elapsedTls: number;
+  /**
+   * Time spent waiting on the server once connected, until the headers arrived
+   */
+  elapsedTtfb: number;
   error: string | null;
   headers: Array<HttpResponseHeader>;
   remoteAddr: string | null;
@@ -240,6 +424,10 @@ export type HttpResponse = {
   state: HttpResponseState;
   url: string;
   version: string | null;
+  /**
+   * One result per enabled assertion on the request, in the request's order
+   */
+  assertionResults: Array<HttpAssertionResult>;
 };
 
 export type HttpResponseEvent = {
@@ -298,12 +486,86 @@ export type HttpResponseEventData =
       addresses: Array<string>;
       duration: bigint;
       overridden: boolean;
-    };
+    }
+  | { type: "tcp_connected"; duration: bigint }
+  | { type: "tls_handshake_completed"; duration: bigint }
+  | { type: "first_byte_received"; duration: bigint }
+  | { type: "download_completed"; duration: bigint }
+  | {
+      type: "tls_negotiated";
+      protocol_version: string;
+      cipher_suite: string;
+      /**
+       * The ALPN protocol, as implied by the HTTP version the connection spoke.
+       */
+      alpn?: string;
+      /**
+       * Whether an earlier session was resumed, in which case no certificates were sent.
+       */
+      resumed: boolean;
+    }
+  | {
+      type: "tls_server_certificates";
+      /**
+       * Leaf first, as the server sent them.
+       */
+      certificates: Array<HttpResponseTlsCertificate>;
+    }
+  | { type: "tls_client_certificate"; requested: boolean; presented: boolean };
 
 export type HttpResponseHeader = { name: string; value: string };
 
+/**
+ * One event parsed from a `text/event-stream` response body, stored as it arrives.
+ */
+export type HttpResponseSseEvent = {
+  model: "http_response_sse_event";
+  id: string;
+  createdAt: string;
+  updatedAt: string;
+  workspaceId: string;
+  responseId: string;
+  /**
+   * Zero-based position in the stream, counting across reconnects
+   */
+  index: number;
+  eventType: string;
+  data: string;
+  /**
+   * The last event ID in effect when this event was dispatched
+   */
+  eventId: string | null;
+  /**
+   * Reconnection delay in milliseconds, when the event set one
+   */
+  retry: number | null;
+};
+
 export type HttpResponseState = "initialized" | "connected" | "closed";
 
+/**
+ * A certificate the server presented during the TLS handshake.
+ */
+export type HttpResponseTlsCertificate = {
+  subject: string;
+  issuer: string;
+  /**
+   * Subject alternative names, prefixed by kind (`DNS:`, `IP:`, `email:`, `URI:`).
+   */
+  subject_alt_names: Array<string>;
+  serial_number: string;
+  /**
+   * RFC 3339, in UTC.
+   */
+  not_before: string;
+  /**
+   * RFC 3339, in UTC.
+   */
+  not_after: string;
+  sha1_fingerprint: string;
+  sha256_fingerprint: string;
+};
+
 /**
  * The resolved send settings, values only: what an executor has to obey, with the sources
  * (which model each came from) left behind in [`ResolvedHttpRequestSettings`]. This is what
@@ -318,6 +580,7 @@ export type HttpSendSettings = {
   timeoutMs: number;
   sendCookies: boolean;
   storeCookies: boolean;
+  http3: Http3Mode;
 };
 
 export type HttpUrlParameter = {
@@ -333,8 +596,17 @@ export type HttpUrlParameter = {
 
 export type InheritedBoolSetting = { enabled?: boolean; value: boolean };
 
+export type InheritedHttp3Setting = { enabled?: boolean; value: Http3Mode };
+
 export type InheritedIntSetting = { enabled?: boolean; value: number };
 
+/**
+ * A proxy for everything under a workspace or folder, in place of the one from the global
+ * settings. As there, a `value` of `None` is the system proxy. These models are synced, so the
+ * credentials are rendered as templates when sending, and the password is normally `secure()`.
+ */
+export type InheritedProxySetting = { enabled?: boolean; value: ProxySetting | null };
+
 export type KeyValue = {
   model: "key_value";
   id: string;
@@ -389,11 +661,99 @@ export type ProxySetting =
       auth: ProxySettingAuth | null;
       bypass: string;
       disabled: boolean;
+      /**
+       * A `socks5://` or `socks5h://` proxy for traffic the other two don't cover. With
+       * `socks5h`, the proxy resolves host names.
+       */
+      socks: string;
     }
   | { type: "disabled" };
 
 export type ProxySettingAuth = { user: string; password: string };
 
+/**
+ * One pass through a run's steps, with the data row it rendered against.
+ */
+export type RunnerIteration = {
+  model: "runner_iteration";
+  id: string;
+  createdAt: string;
+  updatedAt: string;
+  workspaceId: string;
+  runId: string;
+  /**
+   * Zero-based position in the run
+   */
+  index: number;
+  /**
+   * The data row injected as template variables, empty without a data file
+   */
+  variables: { [key in string]?: string };
+  results: Array<RunnerStepResult>;
+  passed: boolean;
+  elapsed: number;
+};
+
+/**
+ * A collection run: an ordered list of steps, repeated once per row of an optional data file.
+ * The results of each repetition are stored as [`RunnerIteration`]s.
+ */
+export type RunnerRun = {
+  model: "runner_run";
+  id: string;
+  createdAt: string;
+  updatedAt: string;
+  workspaceId: string;
+  name: string;
+  steps: Array<RunnerStep>;
+  /**
+   * Path of the CSV or JSON file whose rows drove the iterations
+   */
+  dataFile: string | null;
+  iterationCount: number;
+  passedCount: number;
+  failedCount: number;
+  elapsed: number;
+  state: RunnerRunState;
+  error: string | null;
+};
+
+export type RunnerRunState = "running" | "completed" | "cancelled";
+
+/**
+ * One request in a collection run, in the order it runs.
+ */
+export type RunnerStep = {
+  requestId: string;
+  /**
+   * Milliseconds to wait before sending
+   */
+  delayMs: number;
+  /**
+   * Extra attempts after a failed send before the step counts as failed
+   */
+  retries: number;
+};
+
+/**
+ * What one step did in one iteration of a run.
+ */
+export type RunnerStepResult = {
+  requestId: string;
+  /**
+   * The response from the last attempt, when it got far enough to store one
+   */
+  responseId: string | null;
+  status: number;
+  elapsed: number;
+  attempts: number;
+  /**
+   * The send completed and every assertion on the response passed
+   */
+  passed: boolean;
+  error: string | null;
+};
+
 export type Settings = {
   model: "settings";
   id: string;
@@ -439,6 +799,7 @@ export type SyncState = {
 
 export type UpdateSource =
   | { type: "background" }
+  | { type: "extraction" }
   | { type: "import" }
   | { type: "plugin" }
   | { type: "sync" }
@@ -522,6 +883,8 @@ export type Workspace = {
   settingDnsOverrides: Array<DnsOverride>;
   settingSendCookies: boolean;
   settingStoreCookies: boolean;
+  settingHttp3: Http3Mode;
+  settingProxy: InheritedProxySetting;
 };
 
 export type WorkspaceMeta = {
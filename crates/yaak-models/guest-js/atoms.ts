@@ -20,8 +20,15 @@ export const httpResponseEventsAtom = createOrderedModelAtom(
   "createdAt",
   "asc",
 );
+export const httpResponseSseEventsAtom = createOrderedModelAtom(
+  "http_response_sse_event",
+  "index",
+  "asc",
+);
 export const keyValuesAtom = createModelAtom("key_value");
 export const pluginsAtom = createModelAtom("plugin");
+export const runnerIterationsAtom = createOrderedModelAtom("runner_iteration", "index", "asc");
+export const runnerRunsAtom = createOrderedModelAtom("runner_run", "createdAt", "desc");
 export const settingsAtom = createSingularModelAtom("settings");
 export const websocketRequestsAtom = createModelAtom("websocket_request");
 export const websocketEventsAtom = createOrderedModelAtom("websocket_event", "createdAt", "asc");
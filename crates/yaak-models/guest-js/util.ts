@@ -12,8 +12,11 @@ export function newStoreData(): ModelStoreData {
     http_request: {},
     http_response: {},
     http_response_event: {},
+    http_response_sse_event: {},
     key_value: {},
     plugin: {},
+    runner_iteration: {},
+    runner_run: {},
     settings: {},
     sync_state: {},
     websocket_connection: {},
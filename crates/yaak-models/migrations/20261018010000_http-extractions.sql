@@ -0,0 +1 @@
+ALTER TABLE http_requests ADD COLUMN extractions TEXT DEFAULT '[]' NOT NULL;
@@ -0,0 +1,40 @@
+CREATE TABLE runner_runs
+(
+    id              TEXT                                                    NOT NULL
+        PRIMARY KEY,
+    model           TEXT     DEFAULT 'runner_run'                           NOT NULL,
+    workspace_id    TEXT                                                    NOT NULL
+        REFERENCES workspaces
+            ON DELETE CASCADE,
+    created_at      DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    updated_at      DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    name            TEXT     DEFAULT ''                                     NOT NULL,
+    steps           TEXT     DEFAULT '[]'                                   NOT NULL,
+    data_file       TEXT,
+    iteration_count INTEGER  DEFAULT 0                                      NOT NULL,
+    passed_count    INTEGER  DEFAULT 0                                      NOT NULL,
+    failed_count    INTEGER  DEFAULT 0                                      NOT NULL,
+    elapsed         INTEGER  DEFAULT 0                                      NOT NULL,
+    state           TEXT     DEFAULT 'running'                              NOT NULL,
+    error           TEXT
+);
+
+CREATE TABLE runner_iterations
+(
+    id           TEXT                                                    NOT NULL
+        PRIMARY KEY,
+    model        TEXT     DEFAULT 'runner_iteration'                     NOT NULL,
+    workspace_id TEXT                                                    NOT NULL
+        REFERENCES workspaces
+            ON DELETE CASCADE,
+    run_id       TEXT                                                    NOT NULL
+        REFERENCES runner_runs
+            ON DELETE CASCADE,
+    created_at   DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    updated_at   DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    "index"      INTEGER  DEFAULT 0                                      NOT NULL,
+    variables    TEXT     DEFAULT '{}'                                   NOT NULL,
+    results      TEXT     DEFAULT '[]'                                   NOT NULL,
+    passed       BOOLEAN  DEFAULT FALSE                                  NOT NULL,
+    elapsed      INTEGER  DEFAULT 0                                      NOT NULL
+);
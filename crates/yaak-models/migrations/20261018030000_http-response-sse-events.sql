@@ -0,0 +1,21 @@
+CREATE TABLE http_response_sse_events
+(
+    id           TEXT                                                    NOT NULL
+        PRIMARY KEY,
+    model        TEXT     DEFAULT 'http_response_sse_event'              NOT NULL,
+    workspace_id TEXT                                                    NOT NULL
+        REFERENCES workspaces
+            ON DELETE CASCADE,
+    response_id  TEXT                                                    NOT NULL
+        REFERENCES http_responses
+            ON DELETE CASCADE,
+    created_at   DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    updated_at   DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    "index"      INTEGER  DEFAULT 0                                      NOT NULL,
+    event_type   TEXT     DEFAULT ''                                     NOT NULL,
+    data         TEXT     DEFAULT ''                                     NOT NULL,
+    event_id     TEXT,
+    retry        INTEGER
+);
+
+CREATE INDEX idx_http_response_sse_events_response_id ON http_response_sse_events (response_id);
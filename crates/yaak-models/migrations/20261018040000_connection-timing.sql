@@ -0,0 +1,4 @@
+-- Add TCP connect, TLS handshake and time-to-first-byte timing to http_responses
+ALTER TABLE http_responses ADD COLUMN elapsed_connect INTEGER DEFAULT 0 NOT NULL;
+ALTER TABLE http_responses ADD COLUMN elapsed_tls INTEGER DEFAULT 0 NOT NULL;
+ALTER TABLE http_responses ADD COLUMN elapsed_ttfb INTEGER DEFAULT 0 NOT NULL;
@@ -0,0 +1,4 @@
+-- When to send requests over HTTP/3: off, alt_svc or force
+ALTER TABLE workspaces ADD COLUMN setting_http3 TEXT DEFAULT 'off' NOT NULL;
+ALTER TABLE folders ADD COLUMN setting_http3 TEXT DEFAULT '{"enabled":false,"value":"off"}' NOT NULL;
+ALTER TABLE http_requests ADD COLUMN setting_http3 TEXT DEFAULT '{"enabled":false,"value":"off"}' NOT NULL;
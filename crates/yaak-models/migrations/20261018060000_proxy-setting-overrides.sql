@@ -0,0 +1,3 @@
+-- A proxy that replaces the global one for everything in a workspace or folder
+ALTER TABLE workspaces ADD COLUMN setting_proxy TEXT DEFAULT '{"enabled":false,"value":null}' NOT NULL;
+ALTER TABLE folders ADD COLUMN setting_proxy TEXT DEFAULT '{"enabled":false,"value":null}' NOT NULL;
@@ -0,0 +1,3 @@
+-- Per-call deadline, inherited like the HTTP request timeout, and request compression
+ALTER TABLE grpc_requests ADD COLUMN setting_request_timeout TEXT DEFAULT '{"enabled":false,"value":0}' NOT NULL;
+ALTER TABLE grpc_requests ADD COLUMN compression TEXT DEFAULT 'none' NOT NULL;
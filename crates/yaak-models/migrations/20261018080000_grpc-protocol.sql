@@ -0,0 +1,2 @@
+-- Wire protocol for gRPC requests: gRPC, gRPC-Web or Connect
+ALTER TABLE grpc_requests ADD COLUMN protocol TEXT DEFAULT 'grpc' NOT NULL;
@@ -0,0 +1,244 @@
+//! Turning requests seen on the wire, by a proxy or in a HAR file, into workspace requests.
+//!
+//! The resources reference `CURRENT_WORKSPACE` and `GENERATE_ID::` ids, so they are imported
+//! like any other file: requests land in the current workspace, in a folder per host.
+
+use crate::models::{Environment, EnvironmentVariable, Folder, HttpRequest, HttpRequestHeader};
+use crate::util::BatchUpsertResult;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Headers that describe the captured connection rather than the request, which the client
+/// sets for itself when sending.
+const SKIPPED_HEADERS: &[&str] = &[
+    "host",
+    "content-length",
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "transfer-encoding",
+    "upgrade",
+    "te",
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct CapturedHttpRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+}
+
+/// Build importable resources from `requests`, keeping their order within each host. When
+/// `parameterize_hosts` is set, a host with more than one request gets a folder environment
+/// with its origin in `base_url`, which its requests' URLs use.
+pub fn captured_to_resources(
+    requests: Vec<CapturedHttpRequest>,
+    parameterize_hosts: bool,
+) -> BatchUpsertResult {
+    // Hosts in the order they were first seen, each with its requests
+    let mut hosts: Vec<(String, Vec<CapturedHttpRequest>)> = Vec::new();
+    for request in requests {
+        let (origin, _) = split_origin(&request.url);
+        match hosts.iter_mut().find(|(o, _)| *o == origin) {
+            Some((_, group)) => group.push(request),
+            None => hosts.push((origin, vec![request])),
+        }
+    }
+
+    let mut resources = BatchUpsertResult::default();
+    for (i, (origin, group)) in hosts.into_iter().enumerate() {
+        let folder_id = format!("GENERATE_ID::FOLDER_{i}");
+        let host = origin.split_once("://").map(|(_, h)| h).unwrap_or(&origin).to_string();
+        let parameterize = parameterize_hosts && group.len() > 1 && !origin.is_empty();
+
+        if parameterize {
+            resources.environments.push(Environment {
+                model: "environment".to_string(),
+                id: format!("GENERATE_ID::ENVIRONMENT_{i}"),
+                workspace_id: "CURRENT_WORKSPACE".to_string(),
+                name: format!("{host} Environment"),
+                parent_model: "folder".to_string(),
+                parent_id: Some(folder_id.clone()),
+                variables: vec![EnvironmentVariable {
+                    name: "base_url".to_string(),
+                    value: origin.clone(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            });
+        }
+
+        for (j, captured) in group.iter().enumerate() {
+            let mut request = to_http_request(captured);
+            request.id = format!("GENERATE_ID::HTTP_REQUEST_{i}_{j}");
+            request.folder_id = Some(folder_id.clone());
+            request.sort_priority = j as f64;
+            if parameterize {
+                let (_, rest) = split_origin(&captured.url);
+                request.url = format!("${{[ base_url ]}}{rest}");
+            }
+            resources.http_requests.push(request);
+        }
+
+        resources.folders.push(Folder {
+            id: folder_id,
+            workspace_id: "CURRENT_WORKSPACE".to_string(),
+            name: if host.is_empty() { "Captured".to_string() } else { host },
+            sort_priority: i as f64,
+            ..Default::default()
+        });
+    }
+    resources
+}
+
+fn to_http_request(captured: &CapturedHttpRequest) -> HttpRequest {
+    let (_, rest) = split_origin(&captured.url);
+    let path = rest.split(['?', '#']).next().unwrap_or_default();
+    let content_type = captured
+        .headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        .map(|(_, value)| value.as_str())
+        .unwrap_or_default();
+    let (body_type, body) = match &captured.body {
+        Some(body) => infer_body(content_type, body),
+        None => (None, BTreeMap::new()),
+    };
+
+    HttpRequest {
+        workspace_id: "CURRENT_WORKSPACE".to_string(),
+        name: if path.is_empty() { "/".to_string() } else { path.to_string() },
+        method: captured.method.clone(),
+        url: captured.url.clone(),
+        headers: captured
+            .headers
+            .iter()
+            // HTTP/2 pseudo-headers, as browsers put them in HAR files
+            .filter(|(name, _)| !name.starts_with(':'))
+            .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.to_ascii_lowercase().as_str()))
+            .map(|(name, value)| HttpRequestHeader {
+                enabled: true,
+                name: name.clone(),
+                value: value.clone(),
+                id: None,
+            })
+            .collect(),
+        body_type,
+        body,
+        ..Default::default()
+    }
+}
+
+/// The body type the client would use for `content_type`, and the body in that shape.
+/// Bodies that are not text can't be carried over, since binary bodies are sent from a file.
+fn infer_body(content_type: &str, body: &[u8]) -> (Option<String>, BTreeMap<String, Value>) {
+    let Ok(text) = std::str::from_utf8(body) else {
+        return (None, BTreeMap::new());
+    };
+    if text.is_empty() {
+        return (None, BTreeMap::new());
+    }
+    let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+
+    if mime == "application/x-www-form-urlencoded" {
+        let form = text
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                serde_json::json!({ "enabled": true, "name": decode(name), "value": decode(value) })
+            })
+            .collect();
+        return (Some(mime), BTreeMap::from([("form".to_string(), Value::Array(form))]));
+    }
+
+    let body_type = if mime == "application/json" || mime.ends_with("+json") {
+        "application/json"
+    } else if mime == "text/xml" || mime == "application/xml" || mime.ends_with("+xml") {
+        "text/xml"
+    } else {
+        "other"
+    };
+    let body = BTreeMap::from([("text".to_string(), Value::String(text.to_string()))]);
+    (Some(body_type.to_string()), body)
+}
+
+/// Split a URL into its origin, like `https://example.com`, and the rest.
+fn split_origin(url: &str) -> (String, &str) {
+    let Some((scheme, after)) = url.split_once("://") else {
+        return (String::new(), url);
+    };
+    let end = after.find(['/', '?', '#']).unwrap_or(after.len());
+    (format!("{scheme}://{}", &after[..end]), &after[end..])
+}
+
+fn decode(value: &str) -> String {
+    let value = value.replace('+', " ");
+    urlencoding::decode(&value).map(|v| v.into_owned()).unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn captured(method: &str, url: &str, content_type: &str, body: &str) -> CapturedHttpRequest {
+        CapturedHttpRequest {
+            method: method.into(),
+            url: url.into(),
+            headers: vec![
+                ("Host".into(), "ignored".into()),
+                (":authority".into(), "ignored".into()),
+                ("Content-Type".into(), content_type.into()),
+            ],
+            body: Some(body.as_bytes().to_vec()),
+        }
+    }
+
+    #[test]
+    fn groups_by_host_and_infers_body_types() {
+        let resources = captured_to_resources(
+            vec![
+                captured("POST", "https://api.test/users?x=1", "application/json", "{}"),
+                captured(
+                    "POST",
+                    "http://other.test/login",
+                    "application/x-www-form-urlencoded",
+                    "a=b+c&d=%21",
+                ),
+            ],
+            false,
+        );
+        assert_eq!(resources.folders.len(), 2);
+        assert_eq!(resources.folders[0].name, "api.test");
+        assert!(resources.environments.is_empty());
+
+        let json = &resources.http_requests[0];
+        assert_eq!(json.name, "/users");
+        assert_eq!(json.url, "https://api.test/users?x=1");
+        assert_eq!(json.body_type.as_deref(), Some("application/json"));
+        assert_eq!(json.headers.len(), 1);
+
+        let form = &resources.http_requests[1];
+        assert_eq!(form.body_type.as_deref(), Some("application/x-www-form-urlencoded"));
+        assert_eq!(form.body["form"][0]["value"], "b c");
+        assert_eq!(form.body["form"][1]["value"], "!");
+    }
+
+    #[test]
+    fn parameterizes_repeated_hosts() {
+        let resources = captured_to_resources(
+            vec![
+                captured("GET", "https://api.test/a", "", ""),
+                captured("GET", "https://api.test/b?c=d", "", ""),
+                captured("GET", "https://once.test/", "", ""),
+            ],
+            true,
+        );
+        assert_eq!(resources.environments.len(), 1);
+        assert_eq!(resources.environments[0].variables[0].value, "https://api.test");
+        assert_eq!(resources.environments[0].parent_id, Some(resources.folders[0].id.clone()));
+        assert_eq!(resources.http_requests[1].url, "${[ base_url ]}/b?c=d");
+        assert_eq!(resources.http_requests[2].url, "https://once.test/");
+    }
+}
@@ -9,6 +9,7 @@ use std::sync::mpsc;
 use yaak_database::SqlitePool;
 
 pub mod blob_manager;
+pub mod captured;
 pub mod client_db;
 pub mod cookies;
 mod connection_or_tx;
@@ -1,9 +1,9 @@
 use crate::error::Result;
 use crate::models::HttpRequestIden::{
-    Authentication, AuthenticationType, Body, BodyType, CreatedAt, Description, FolderId, Headers,
-    Method, Name, SettingFollowRedirects, SettingRequestTimeout, SettingSendCookies,
-    SettingStoreCookies, SettingValidateCertificates, SortPriority, UpdatedAt, Url, UrlParameters,
-    WorkspaceId,
+    Assertions, Authentication, AuthenticationType, Body, BodyType, CreatedAt, Description,
+    Extractions, FolderId, Headers, Method, Name, SettingFollowRedirects, SettingRequestTimeout,
+    SettingSendCookies, SettingStoreCookies, SettingValidateCertificates, SortPriority, UpdatedAt,
+    Url, UrlParameters, WorkspaceId,
 };
 use crate::util::generate_prefixed_id;
 use chrono::{NaiveDateTime, Utc};
@@ -34,7 +34,7 @@ macro_rules! impl_model {
     };
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "camelCase", tag = "type")]
 #[ts(export, export_to = "gen_models.ts")]
 pub enum ProxySetting {
@@ -48,11 +48,15 @@ pub enum ProxySetting {
         bypass: String,
         #[serde(default)]
         disabled: bool,
+        /// A `socks5://` or `socks5h://` proxy for traffic the other two don't cover. With
+        /// `socks5h`, the proxy resolves host names.
+        #[serde(default)]
+        socks: String,
     },
     Disabled,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "gen_models.ts")]
 pub struct ProxySettingAuth {
@@ -143,6 +147,9 @@ pub struct ResolvedHttpRequestSettings {
     pub request_message_size: ResolvedSetting<i32>,
     pub send_cookies: ResolvedSetting<bool>,
     pub store_cookies: ResolvedSetting<bool>,
+    pub http3: ResolvedSetting<Http3Mode>,
+    /// `None` is the system proxy.
+    pub proxy: ResolvedSetting<Option<ProxySetting>>,
 }
 
 impl Default for ResolvedHttpRequestSettings {
@@ -154,6 +161,8 @@ impl Default for ResolvedHttpRequestSettings {
             request_message_size: ResolvedSetting::default_source(DEFAULT_REQUEST_MESSAGE_SIZE),
             send_cookies: ResolvedSetting::default_source(true),
             store_cookies: ResolvedSetting::default_source(true),
+            http3: ResolvedSetting::default_source(Http3Mode::Off),
+            proxy: ResolvedSetting::default_source(None),
         }
     }
 }
@@ -191,10 +200,28 @@ impl ResolvedHttpRequestSettings {
             event("timeout", timeout, &self.request_timeout),
             event("send_cookies", self.send_cookies.value.to_string(), &self.send_cookies),
             event("store_cookies", self.store_cookies.value.to_string(), &self.store_cookies),
+            event("http3", self.http3.value.to_string(), &self.http3),
+            event("proxy", describe_proxy(self.proxy.value.as_ref()), &self.proxy),
         ]
     }
 }
 
+/// Which proxies a setting routes through, without addresses or credentials, for the timeline.
+fn describe_proxy(proxy: Option<&ProxySetting>) -> String {
+    match proxy {
+        None | Some(ProxySetting::Enabled { disabled: true, .. }) => "system".to_string(),
+        Some(ProxySetting::Disabled) => "disabled".to_string(),
+        Some(ProxySetting::Enabled { http, https, socks, .. }) => {
+            let kinds: Vec<&str> = [("http", http), ("https", https), ("socks", socks)]
+                .into_iter()
+                .filter(|(_, url)| !url.is_empty())
+                .map(|(kind, _)| kind)
+                .collect();
+            if kinds.is_empty() { "none".to_string() } else { kinds.join(", ") }
+        }
+    }
+}
+
 /// The resolved send settings, values only: what an executor has to obey, with the sources
 /// (which model each came from) left behind in [`ResolvedHttpRequestSettings`]. This is what
 /// crosses from a tab to the Yaak server, and what the server reads.
@@ -208,6 +235,8 @@ pub struct HttpSendSettings {
     pub timeout_ms: i32,
     pub send_cookies: bool,
     pub store_cookies: bool,
+    #[serde(default)]
+    pub http3: Http3Mode,
 }
 
 impl From<&ResolvedHttpRequestSettings> for HttpSendSettings {
@@ -218,6 +247,7 @@ impl From<&ResolvedHttpRequestSettings> for HttpSendSettings {
             timeout_ms: s.request_timeout.value,
             send_cookies: s.send_cookies.value,
             store_cookies: s.store_cookies.value,
+            http3: s.http3.value,
         }
     }
 }
@@ -255,6 +285,55 @@ impl Default for InheritedIntSetting {
     }
 }
 
+/// When to send a request over HTTP/3 (QUIC) instead of TCP.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum Http3Mode {
+    #[default]
+    Off,
+    /// Use HTTP/3 once the origin has advertised it in an `Alt-Svc` header, and fall back to
+    /// TCP if the QUIC connection fails.
+    AltSvc,
+    /// Use HTTP/3 from the first request, without falling back.
+    Force,
+}
+
+impl Display for Http3Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Http3Mode::Off => "off",
+            Http3Mode::AltSvc => "alt_svc",
+            Http3Mode::Force => "force",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct InheritedHttp3Setting {
+    #[serde(default)]
+    #[ts(optional, as = "Option<bool>")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub value: Http3Mode,
+}
+
+/// A proxy for everything under a workspace or folder, in place of the one from the global
+/// settings. As there, a `value` of `None` is the system proxy. These models are synced, so the
+/// credentials are rendered as templates when sending, and the password is normally `secure()`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct InheritedProxySetting {
+    #[serde(default)]
+    #[ts(optional, as = "Option<bool>")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub value: Option<ProxySetting>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "snake_case")]
 #[ts(export, export_to = "gen_models.ts")]
@@ -484,6 +563,8 @@ impl Default for Workspace {
             setting_dns_overrides: Vec::new(),
             setting_send_cookies: true,
             setting_store_cookies: true,
+            setting_http3: Http3Mode::Off,
+            setting_proxy: InheritedProxySetting::default(),
         }
     }
 }
@@ -516,6 +597,8 @@ pub struct Workspace {
     pub setting_dns_overrides: Vec<DnsOverride>,
     pub setting_send_cookies: bool,
     pub setting_store_cookies: bool,
+    pub setting_http3: Http3Mode,
+    pub setting_proxy: InheritedProxySetting,
 }
 
 impl UpsertModelInfo for Workspace {
@@ -560,6 +643,8 @@ impl UpsertModelInfo for Workspace {
             (SettingDnsOverrides, serde_json::to_string(&self.setting_dns_overrides)?.into()),
             (SettingSendCookies, self.setting_send_cookies.into()),
             (SettingStoreCookies, self.setting_store_cookies.into()),
+            (SettingHttp3, serde_json::to_value(self.setting_http3)?.as_str().into()),
+            (SettingProxy, serde_json::to_string(&self.setting_proxy)?.into()),
         ])
     }
 
@@ -579,6 +664,8 @@ impl UpsertModelInfo for Workspace {
             WorkspaceIden::SettingDnsOverrides,
             WorkspaceIden::SettingSendCookies,
             WorkspaceIden::SettingStoreCookies,
+            WorkspaceIden::SettingHttp3,
+            WorkspaceIden::SettingProxy,
         ]
     }
 
@@ -589,6 +676,8 @@ impl UpsertModelInfo for Workspace {
         let headers: String = row.get("headers")?;
         let authentication: String = row.get("authentication")?;
         let setting_dns_overrides: String = row.get("setting_dns_overrides")?;
+        let setting_http3: String = row.get("setting_http3")?;
+        let setting_proxy: String = row.get("setting_proxy")?;
         Ok(Self {
             id: row.get("id")?,
             model: row.get("model")?,
@@ -607,6 +696,9 @@ impl UpsertModelInfo for Workspace {
             setting_dns_overrides: serde_json::from_str(&setting_dns_overrides).unwrap_or_default(),
             setting_send_cookies: row.get("setting_send_cookies")?,
             setting_store_cookies: row.get("setting_store_cookies")?,
+            setting_http3: serde_json::from_str(&format!(r#""{setting_http3}""#))
+                .unwrap_or_default(),
+            setting_proxy: serde_json::from_str(&setting_proxy).unwrap_or_default(),
         })
     }
 }
@@ -1078,6 +1170,8 @@ impl Default for Folder {
                 enabled: false,
                 value: DEFAULT_REQUEST_MESSAGE_SIZE,
             },
+            setting_http3: InheritedHttp3Setting::default(),
+            setting_proxy: InheritedProxySetting::default(),
         }
     }
 }
@@ -1108,6 +1202,8 @@ pub struct Folder {
     pub setting_follow_redirects: InheritedBoolSetting,
     pub setting_request_timeout: InheritedIntSetting,
     pub setting_request_message_size: InheritedIntSetting,
+    pub setting_http3: InheritedHttp3Setting,
+    pub setting_proxy: InheritedProxySetting,
 }
 
 impl UpsertModelInfo for Folder {
@@ -1159,6 +1255,8 @@ impl UpsertModelInfo for Folder {
                 SettingRequestMessageSize,
                 serde_json::to_string(&self.setting_request_message_size)?.into(),
             ),
+            (SettingHttp3, serde_json::to_string(&self.setting_http3)?.into()),
+            (SettingProxy, serde_json::to_string(&self.setting_proxy)?.into()),
         ])
     }
 
@@ -1178,6 +1276,8 @@ impl UpsertModelInfo for Folder {
             FolderIden::SettingFollowRedirects,
             FolderIden::SettingRequestTimeout,
             FolderIden::SettingRequestMessageSize,
+            FolderIden::SettingHttp3,
+            FolderIden::SettingProxy,
         ]
     }
 
@@ -1193,6 +1293,8 @@ impl UpsertModelInfo for Folder {
         let setting_follow_redirects: String = row.get("setting_follow_redirects")?;
         let setting_request_timeout: String = row.get("setting_request_timeout")?;
         let setting_request_message_size: String = row.get("setting_request_message_size")?;
+        let setting_http3: String = row.get("setting_http3")?;
+        let setting_proxy: String = row.get("setting_proxy")?;
         Ok(Self {
             id: row.get("id")?,
             model: row.get("model")?,
@@ -1216,6 +1318,8 @@ impl UpsertModelInfo for Folder {
                 .unwrap_or_default(),
             setting_request_message_size: serde_json::from_str(&setting_request_message_size)
                 .unwrap_or_else(|_| default_request_message_size_setting()),
+            setting_http3: serde_json::from_str(&setting_http3).unwrap_or_default(),
+            setting_proxy: serde_json::from_str(&setting_proxy).unwrap_or_default(),
         })
     }
 }
@@ -1258,6 +1362,119 @@ pub struct HttpUrlParameter {
     pub id: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum HttpAssertionKind {
+    /// The response status equals `value`.
+    StatusEquals,
+    /// The header named by `target` exists and its value matches the regex in `value`.
+    HeaderMatches,
+    /// The JSON body value at the path in `target` equals `value`. `value` is compared as JSON
+    /// when it parses as JSON, and as a plain string otherwise.
+    JsonPathEquals,
+    /// The JSON body value at the path in `target` contains `value`: a substring of a string, an
+    /// element of an array, or a key of an object.
+    JsonPathContains,
+    /// The response body matches the regex in `value`.
+    BodyMatches,
+    /// The whole response, body included, arrived in fewer than `value` milliseconds.
+    ElapsedUnder,
+}
+
+impl Default for HttpAssertionKind {
+    fn default() -> Self {
+        Self::StatusEquals
+    }
+}
+
+impl Default for HttpRequestAssertion {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            kind: HttpAssertionKind::default(),
+            target: String::new(),
+            value: String::new(),
+            id: None,
+        }
+    }
+}
+
+/// A check run against a response once it has been stored. Both `target` and `value` are
+/// templates, rendered with the rest of the request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct HttpRequestAssertion {
+    #[ts(optional, as = "Option<bool>")]
+    pub enabled: bool,
+    pub kind: HttpAssertionKind,
+    /// What the check looks at, for kinds that need one: a header name or a JSON path
+    pub target: String,
+    /// The expected value, pattern, or limit
+    pub value: String,
+    #[ts(optional, as = "Option<String>")]
+    pub id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum HttpExtractionSource {
+    /// The JSON body value at the path in `expression`. Strings are written without quotes.
+    JsonPath,
+    /// The string value of the XPath in `expression`, evaluated against an XML body.
+    Xpath,
+    /// The first response header named by `expression`.
+    Header,
+    /// The first match of the regex in `expression` against the body, or its first capture
+    /// group when it has one.
+    BodyRegex,
+    /// The value of the cookie named by `expression`, as set by the response.
+    Cookie,
+}
+
+impl Default for HttpExtractionSource {
+    fn default() -> Self {
+        Self::JsonPath
+    }
+}
+
+impl Default for HttpRequestExtraction {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            source: HttpExtractionSource::default(),
+            expression: String::new(),
+            variable: String::new(),
+            environment_id: None,
+            id: None,
+        }
+    }
+}
+
+/// A value read out of a successful response and written into an environment variable, so a
+/// later request can use it without re-reading or re-sending this one. `expression` is a
+/// template, rendered with the rest of the request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct HttpRequestExtraction {
+    #[ts(optional, as = "Option<bool>")]
+    pub enabled: bool,
+    pub source: HttpExtractionSource,
+    /// The JSON path, XPath, header name, regex, or cookie name to read
+    pub expression: String,
+    /// Name of the environment variable to write
+    pub variable: String,
+    /// Environment to write into. Defaults to the environment the request was sent with, or the
+    /// base environment when there is none.
+    #[ts(optional, as = "Option<String>")]
+    pub environment_id: Option<String>,
+    #[ts(optional, as = "Option<String>")]
+    pub id: Option<String>,
+}
+
 impl Default for HttpRequest {
     fn default() -> Self {
         Self {
@@ -1283,6 +1500,9 @@ impl Default for HttpRequest {
             setting_validate_certificates: InheritedBoolSetting::default(),
             setting_follow_redirects: InheritedBoolSetting::default(),
             setting_request_timeout: InheritedIntSetting::default(),
+            setting_http3: InheritedHttp3Setting::default(),
+            assertions: Vec::new(),
+            extractions: Vec::new(),
         }
     }
 }
@@ -1319,6 +1539,11 @@ pub struct HttpRequest {
     pub setting_validate_certificates: InheritedBoolSetting,
     pub setting_follow_redirects: InheritedBoolSetting,
     pub setting_request_timeout: InheritedIntSetting,
+    pub setting_http3: InheritedHttp3Setting,
+    /// Checks evaluated against every response this request produces.
+    pub assertions: Vec<HttpRequestAssertion>,
+    /// Values written into environment variables after each successful response.
+    pub extractions: Vec<HttpRequestExtraction>,
 }
 
 impl UpsertModelInfo for HttpRequest {
@@ -1370,6 +1595,9 @@ impl UpsertModelInfo for HttpRequest {
             ),
             (SettingFollowRedirects, serde_json::to_string(&self.setting_follow_redirects)?.into()),
             (SettingRequestTimeout, serde_json::to_string(&self.setting_request_timeout)?.into()),
+            (SettingHttp3, serde_json::to_string(&self.setting_http3)?.into()),
+            (Assertions, serde_json::to_string(&self.assertions)?.into()),
+            (Extractions, serde_json::to_string(&self.extractions)?.into()),
         ])
     }
 
@@ -1394,6 +1622,9 @@ impl UpsertModelInfo for HttpRequest {
             SettingValidateCertificates,
             SettingFollowRedirects,
             SettingRequestTimeout,
+            SettingHttp3,
+            Assertions,
+            Extractions,
         ]
     }
 
@@ -1407,6 +1638,9 @@ impl UpsertModelInfo for HttpRequest {
         let setting_validate_certificates: String = row.get("setting_validate_certificates")?;
         let setting_follow_redirects: String = row.get("setting_follow_redirects")?;
         let setting_request_timeout: String = row.get("setting_request_timeout")?;
+        let setting_http3: String = row.get("setting_http3")?;
+        let assertions: String = row.get("assertions")?;
+        let extractions: String = row.get("extractions")?;
         Ok(Self {
             id: row.get("id")?,
             model: row.get("model")?,
@@ -1433,6 +1667,9 @@ impl UpsertModelInfo for HttpRequest {
                 .unwrap_or_default(),
             setting_request_timeout: serde_json::from_str(&setting_request_timeout)
                 .unwrap_or_default(),
+            setting_http3: serde_json::from_str(&setting_http3).unwrap_or_default(),
+            assertions: serde_json::from_str(&assertions).unwrap_or_default(),
+            extractions: serde_json::from_str(&extractions).unwrap_or_default(),
         })
     }
 }
@@ -1847,6 +2084,23 @@ pub struct HttpResponseHeader {
     pub value: String,
 }
 
+/// The outcome of one [`HttpRequestAssertion`] against a response, with the rendered target and
+/// expectation it ran with so the result still reads correctly after the request changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct HttpAssertionResult {
+    pub assertion_id: Option<String>,
+    pub kind: HttpAssertionKind,
+    pub target: String,
+    pub expected: String,
+    /// What the response actually had, when there was something to compare
+    pub actual: Option<String>,
+    pub passed: bool,
+    /// Why the check could not run, such as an invalid regex or a body that is not JSON
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "snake_case")]
 #[ts(export, export_to = "gen_models.ts")]
@@ -1888,6 +2142,11 @@ pub struct HttpResponse {
     pub elapsed: i32,
     pub elapsed_headers: i32,
     pub elapsed_dns: i32,
+    /// TCP connect time, plus the TLS handshake when that was not timed separately
+    pub elapsed_connect: i32,
+    pub elapsed_tls: i32,
+    /// Time spent waiting on the server once connected, until the headers arrived
+    pub elapsed_ttfb: i32,
     pub error: Option<String>,
     pub headers: Vec<HttpResponseHeader>,
     pub remote_addr: Option<String>,
@@ -1898,6 +2157,8 @@ pub struct HttpResponse {
     pub state: HttpResponseState,
     pub url: String,
     pub version: Option<String>,
+    /// One result per enabled assertion on the request, in the request's order
+    pub assertion_results: Vec<HttpAssertionResult>,
 }
 
 impl UpsertModelInfo for HttpResponse {
@@ -1937,6 +2198,9 @@ impl UpsertModelInfo for HttpResponse {
             (Elapsed, self.elapsed.into()),
             (ElapsedHeaders, self.elapsed_headers.into()),
             (ElapsedDns, self.elapsed_dns.into()),
+            (ElapsedConnect, self.elapsed_connect.into()),
+            (ElapsedTls, self.elapsed_tls.into()),
+            (ElapsedTtfb, self.elapsed_ttfb.into()),
             (Error, self.error.into()),
             (Headers, serde_json::to_string(&self.headers)?.into()),
             (RemoteAddr, self.remote_addr.into()),
@@ -1947,6 +2211,7 @@ impl UpsertModelInfo for HttpResponse {
             (Url, self.url.into()),
             (Version, self.version.into()),
             (RequestContentLength, self.request_content_length.into()),
+            (AssertionResults, serde_json::to_string(&self.assertion_results)?.into()),
         ])
     }
 
@@ -1969,6 +2234,7 @@ impl UpsertModelInfo for HttpResponse {
             HttpResponseIden::StatusReason,
             HttpResponseIden::Url,
             HttpResponseIden::Version,
+            HttpResponseIden::AssertionResults,
         ]
     }
 
@@ -1993,6 +2259,9 @@ impl UpsertModelInfo for HttpResponse {
             elapsed: r.get("elapsed")?,
             elapsed_headers: r.get("elapsed_headers")?,
             elapsed_dns: r.get("elapsed_dns").unwrap_or_default(),
+            elapsed_connect: r.get("elapsed_connect").unwrap_or_default(),
+            elapsed_tls: r.get("elapsed_tls").unwrap_or_default(),
+            elapsed_ttfb: r.get("elapsed_ttfb").unwrap_or_default(),
             remote_addr: r.get("remote_addr")?,
             status: r.get("status")?,
             status_reason: r.get("status_reason")?,
@@ -2004,6 +2273,10 @@ impl UpsertModelInfo for HttpResponse {
                 r.get::<_, String>("request_headers").unwrap_or_default().as_str(),
             )
             .unwrap_or_default(),
+            assertion_results: serde_json::from_str(
+                r.get::<_, String>("assertion_results").unwrap_or_default().as_str(),
+            )
+            .unwrap_or_default(),
         })
     }
 }
@@ -2082,6 +2355,55 @@ pub enum HttpResponseEventData {
         duration: u64,
         overridden: bool,
     },
+    TcpConnected {
+        duration: u64,
+    },
+    TlsHandshakeCompleted {
+        duration: u64,
+    },
+    FirstByteReceived {
+        duration: u64,
+    },
+    DownloadCompleted {
+        duration: u64,
+    },
+    TlsNegotiated {
+        protocol_version: String,
+        cipher_suite: String,
+        /// The ALPN protocol, as implied by the HTTP version the connection spoke.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional, as = "Option<String>")]
+        alpn: Option<String>,
+        /// Whether an earlier session was resumed, in which case no certificates were sent.
+        #[serde(default)]
+        resumed: bool,
+    },
+    TlsServerCertificates {
+        /// Leaf first, as the server sent them.
+        certificates: Vec<HttpResponseTlsCertificate>,
+    },
+    TlsClientCertificate {
+        requested: bool,
+        presented: bool,
+    },
+}
+
+/// A certificate the server presented during the TLS handshake.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct HttpResponseTlsCertificate {
+    pub subject: String,
+    pub issuer: String,
+    /// Subject alternative names, prefixed by kind (`DNS:`, `IP:`, `email:`, `URI:`).
+    #[serde(default)]
+    pub subject_alt_names: Vec<String>,
+    pub serial_number: String,
+    /// RFC 3339, in UTC.
+    pub not_before: String,
+    /// RFC 3339, in UTC.
+    pub not_after: String,
+    pub sha1_fingerprint: String,
+    pub sha256_fingerprint: String,
 }
 
 impl Default for HttpResponseEventData {
@@ -2178,6 +2500,99 @@ impl HttpResponseEvent {
     }
 }
 
+/// One event parsed from a `text/event-stream` response body, stored as it arrives.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+#[enum_def(table_name = "http_response_sse_events")]
+pub struct HttpResponseSseEvent {
+    #[ts(type = "\"http_response_sse_event\"")]
+    pub model: String,
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub workspace_id: String,
+    pub response_id: String,
+
+    /// Zero-based position in the stream, counting across reconnects
+    pub index: i32,
+    pub event_type: String,
+    pub data: String,
+    /// The last event ID in effect when this event was dispatched
+    pub event_id: Option<String>,
+    /// Reconnection delay in milliseconds, when the event set one
+    pub retry: Option<i32>,
+}
+
+impl UpsertModelInfo for HttpResponseSseEvent {
+    fn table_name() -> impl IntoTableRef + IntoIden {
+        HttpResponseSseEventIden::Table
+    }
+
+    fn id_column() -> impl IntoIden + Eq + Clone {
+        HttpResponseSseEventIden::Id
+    }
+
+    fn generate_id() -> String {
+        generate_prefixed_id("se")
+    }
+
+    fn order_by() -> (impl IntoColumnRef, Order) {
+        (HttpResponseSseEventIden::Index, Order::Asc)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn insert_values(
+        self,
+        source: &UpdateSource,
+    ) -> DbResult<Vec<(impl IntoIden + Eq, impl Into<SimpleExpr>)>> {
+        use HttpResponseSseEventIden::*;
+        Ok(vec![
+            (CreatedAt, upsert_date(source, self.created_at)),
+            (UpdatedAt, upsert_date(source, self.updated_at)),
+            (WorkspaceId, self.workspace_id.into()),
+            (ResponseId, self.response_id.into()),
+            (Index, self.index.into()),
+            (EventType, self.event_type.into()),
+            (Data, self.data.into()),
+            (EventId, self.event_id.into()),
+            (Retry, self.retry.into()),
+        ])
+    }
+
+    fn update_columns() -> Vec<impl IntoIden> {
+        vec![
+            HttpResponseSseEventIden::UpdatedAt,
+            HttpResponseSseEventIden::EventType,
+            HttpResponseSseEventIden::Data,
+            HttpResponseSseEventIden::EventId,
+            HttpResponseSseEventIden::Retry,
+        ]
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self {
+            id: row.get("id")?,
+            model: row.get("model")?,
+            workspace_id: row.get("workspace_id")?,
+            response_id: row.get("response_id")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+            index: row.get("index")?,
+            event_type: row.get("event_type")?,
+            data: row.get("data")?,
+            event_id: row.get("event_id")?,
+            retry: row.get("retry")?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
 #[serde(default, rename_all = "camelCase")]
 #[ts(export, export_to = "gen_models.ts")]
@@ -2270,15 +2685,54 @@ impl Default for GrpcRequest {
             service: None,
             sort_priority: 0.0,
             url: String::new(),
+            protocol: GrpcProtocol::Grpc,
+            compression: GrpcCompression::None,
             setting_validate_certificates: InheritedBoolSetting::default(),
             setting_request_message_size: InheritedIntSetting {
                 enabled: false,
                 value: DEFAULT_REQUEST_MESSAGE_SIZE,
             },
+            setting_request_timeout: InheritedIntSetting::default(),
         }
     }
 }
 
+/// How the messages a gRPC request sends are compressed. Compressed responses are accepted
+/// either way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum GrpcCompression {
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Display for GrpcCompression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            GrpcCompression::None => "none",
+            GrpcCompression::Gzip => "gzip",
+            GrpcCompression::Zstd => "zstd",
+        })
+    }
+}
+
+/// The wire protocol a gRPC request is sent with. gRPC-Web and Connect also work over
+/// HTTP/1.1, so they reach services behind browser-facing proxies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum GrpcProtocol {
+    #[default]
+    Grpc,
+    GrpcWeb,
+    GrpcWebText,
+    ConnectJson,
+    ConnectProto,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(default, rename_all = "camelCase")]
 #[ts(export, export_to = "gen_models.ts")]
@@ -2304,8 +2758,12 @@ pub struct GrpcRequest {
     pub sort_priority: f64,
     /// Server URL (http for plaintext or https for secure)
     pub url: String,
+    pub protocol: GrpcProtocol,
+    pub compression: GrpcCompression,
     pub setting_validate_certificates: InheritedBoolSetting,
     pub setting_request_message_size: InheritedIntSetting,
+    /// Deadline for each call in milliseconds, sent as `grpc-timeout`. 0 means none.
+    pub setting_request_timeout: InheritedIntSetting,
 }
 
 impl UpsertModelInfo for GrpcRequest {
@@ -2349,6 +2807,8 @@ impl UpsertModelInfo for GrpcRequest {
             (AuthenticationType, self.authentication_type.into()),
             (Authentication, serde_json::to_string(&self.authentication)?.into()),
             (Metadata, serde_json::to_string(&self.metadata)?.into()),
+            (Protocol, serde_json::to_value(self.protocol)?.as_str().into()),
+            (Compression, serde_json::to_value(self.compression)?.as_str().into()),
             (
                 SettingValidateCertificates,
                 serde_json::to_string(&self.setting_validate_certificates)?.into(),
@@ -2357,6 +2817,7 @@ impl UpsertModelInfo for GrpcRequest {
                 SettingRequestMessageSize,
                 serde_json::to_string(&self.setting_request_message_size)?.into(),
             ),
+            (SettingRequestTimeout, serde_json::to_string(&self.setting_request_timeout)?.into()),
         ])
     }
 
@@ -2375,8 +2836,11 @@ impl UpsertModelInfo for GrpcRequest {
             GrpcRequestIden::AuthenticationType,
             GrpcRequestIden::Authentication,
             GrpcRequestIden::Metadata,
+            GrpcRequestIden::Protocol,
+            GrpcRequestIden::Compression,
             GrpcRequestIden::SettingValidateCertificates,
             GrpcRequestIden::SettingRequestMessageSize,
+            GrpcRequestIden::SettingRequestTimeout,
         ]
     }
 
@@ -2388,6 +2852,9 @@ impl UpsertModelInfo for GrpcRequest {
         let metadata: String = row.get("metadata")?;
         let setting_validate_certificates: String = row.get("setting_validate_certificates")?;
         let setting_request_message_size: String = row.get("setting_request_message_size")?;
+        let setting_request_timeout: String = row.get("setting_request_timeout")?;
+        let protocol: String = row.get("protocol")?;
+        let compression: String = row.get("compression")?;
         Ok(Self {
             id: row.get("id")?,
             model: row.get("model")?,
@@ -2407,8 +2874,12 @@ impl UpsertModelInfo for GrpcRequest {
             metadata: serde_json::from_str(metadata.as_str()).unwrap_or_default(),
             setting_validate_certificates: serde_json::from_str(&setting_validate_certificates)
                 .unwrap_or_default(),
+            protocol: serde_json::from_str(&format!(r#""{protocol}""#)).unwrap_or_default(),
+            compression: serde_json::from_str(&format!(r#""{compression}""#)).unwrap_or_default(),
             setting_request_message_size: serde_json::from_str(&setting_request_message_size)
                 .unwrap_or_else(|_| default_request_message_size_setting()),
+            setting_request_timeout: serde_json::from_str(&setting_request_timeout)
+                .unwrap_or_default(),
         })
     }
 }
@@ -2955,6 +3426,308 @@ impl<'s> TryFrom<&Row<'s>> for PluginKeyValue {
     }
 }
 
+/// One request in a collection run, in the order it runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, JsonSchema, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct RunnerStep {
+    pub request_id: String,
+    /// Milliseconds to wait before sending
+    pub delay_ms: i32,
+    /// Extra attempts after a failed send before the step counts as failed
+    pub retries: i32,
+}
+
+/// What one step did in one iteration of a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct RunnerStepResult {
+    pub request_id: String,
+    /// The response from the last attempt, when it got far enough to store one
+    pub response_id: Option<String>,
+    pub status: i32,
+    pub elapsed: i32,
+    pub attempts: i32,
+    /// The send completed and every assertion on the response passed
+    pub passed: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum RunnerRunState {
+    Running,
+    Completed,
+    Cancelled,
+}
+
+impl Default for RunnerRunState {
+    fn default() -> Self {
+        Self::Running
+    }
+}
+
+/// A collection run: an ordered list of steps, repeated once per row of an optional data file.
+/// The results of each repetition are stored as [`RunnerIteration`]s.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+#[enum_def(table_name = "runner_runs")]
+pub struct RunnerRun {
+    #[ts(type = "\"runner_run\"")]
+    pub model: String,
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub workspace_id: String,
+
+    pub name: String,
+    pub steps: Vec<RunnerStep>,
+    /// Path of the CSV or JSON file whose rows drove the iterations
+    pub data_file: Option<String>,
+    pub iteration_count: i32,
+    pub passed_count: i32,
+    pub failed_count: i32,
+    pub elapsed: i32,
+    pub state: RunnerRunState,
+    pub error: Option<String>,
+}
+
+impl UpsertModelInfo for RunnerRun {
+    fn table_name() -> impl IntoTableRef + IntoIden {
+        RunnerRunIden::Table
+    }
+
+    fn id_column() -> impl IntoIden + Eq + Clone {
+        RunnerRunIden::Id
+    }
+
+    fn generate_id() -> String {
+        generate_prefixed_id("rr")
+    }
+
+    fn order_by() -> (impl IntoColumnRef, Order) {
+        (RunnerRunIden::CreatedAt, Desc)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn insert_values(
+        self,
+        source: &UpdateSource,
+    ) -> DbResult<Vec<(impl IntoIden + Eq, impl Into<SimpleExpr>)>> {
+        use RunnerRunIden::*;
+        Ok(vec![
+            (CreatedAt, upsert_date(source, self.created_at)),
+            (UpdatedAt, upsert_date(source, self.updated_at)),
+            (WorkspaceId, self.workspace_id.into()),
+            (Name, self.name.into()),
+            (Steps, serde_json::to_string(&self.steps)?.into()),
+            (DataFile, self.data_file.into()),
+            (IterationCount, self.iteration_count.into()),
+            (PassedCount, self.passed_count.into()),
+            (FailedCount, self.failed_count.into()),
+            (Elapsed, self.elapsed.into()),
+            (State, serde_json::to_value(&self.state)?.as_str().into()),
+            (Error, self.error.into()),
+        ])
+    }
+
+    fn update_columns() -> Vec<impl IntoIden> {
+        vec![
+            RunnerRunIden::UpdatedAt,
+            RunnerRunIden::Name,
+            RunnerRunIden::Steps,
+            RunnerRunIden::DataFile,
+            RunnerRunIden::IterationCount,
+            RunnerRunIden::PassedCount,
+            RunnerRunIden::FailedCount,
+            RunnerRunIden::Elapsed,
+            RunnerRunIden::State,
+            RunnerRunIden::Error,
+        ]
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self>
+    where
+        Self: Sized,
+    {
+        let steps: String = row.get("steps")?;
+        let state: String = row.get("state")?;
+        Ok(Self {
+            id: row.get("id")?,
+            model: row.get("model")?,
+            workspace_id: row.get("workspace_id")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+            name: row.get("name")?,
+            steps: serde_json::from_str(&steps).unwrap_or_default(),
+            data_file: row.get("data_file")?,
+            iteration_count: row.get("iteration_count")?,
+            passed_count: row.get("passed_count")?,
+            failed_count: row.get("failed_count")?,
+            elapsed: row.get("elapsed")?,
+            state: serde_json::from_str(format!(r#""{state}""#).as_str()).unwrap_or_default(),
+            error: row.get("error")?,
+        })
+    }
+}
+
+/// One pass through a run's steps, with the data row it rendered against.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+#[enum_def(table_name = "runner_iterations")]
+pub struct RunnerIteration {
+    #[ts(type = "\"runner_iteration\"")]
+    pub model: String,
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub workspace_id: String,
+    pub run_id: String,
+
+    /// Zero-based position in the run
+    pub index: i32,
+    /// The data row injected as template variables, empty without a data file
+    pub variables: BTreeMap<String, String>,
+    pub results: Vec<RunnerStepResult>,
+    pub passed: bool,
+    pub elapsed: i32,
+}
+
+impl UpsertModelInfo for RunnerIteration {
+    fn table_name() -> impl IntoTableRef + IntoIden {
+        RunnerIterationIden::Table
+    }
+
+    fn id_column() -> impl IntoIden + Eq + Clone {
+        RunnerIterationIden::Id
+    }
+
+    fn generate_id() -> String {
+        generate_prefixed_id("ri")
+    }
+
+    fn order_by() -> (impl IntoColumnRef, Order) {
+        (RunnerIterationIden::Index, Order::Asc)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn insert_values(
+        self,
+        source: &UpdateSource,
+    ) -> DbResult<Vec<(impl IntoIden + Eq, impl Into<SimpleExpr>)>> {
+        use RunnerIterationIden::*;
+        Ok(vec![
+            (CreatedAt, upsert_date(source, self.created_at)),
+            (UpdatedAt, upsert_date(source, self.updated_at)),
+            (WorkspaceId, self.workspace_id.into()),
+            (RunId, self.run_id.into()),
+            (Index, self.index.into()),
+            (Variables, serde_json::to_string(&self.variables)?.into()),
+            (Results, serde_json::to_string(&self.results)?.into()),
+            (Passed, self.passed.into()),
+            (Elapsed, self.elapsed.into()),
+        ])
+    }
+
+    fn update_columns() -> Vec<impl IntoIden> {
+        vec![
+            RunnerIterationIden::UpdatedAt,
+            RunnerIterationIden::Variables,
+            RunnerIterationIden::Results,
+            RunnerIterationIden::Passed,
+            RunnerIterationIden::Elapsed,
+        ]
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self>
+    where
+        Self: Sized,
+    {
+        let variables: String = row.get("variables")?;
+        let results: String = row.get("results")?;
+        Ok(Self {
+            id: row.get("id")?,
+            model: row.get("model")?,
+            workspace_id: row.get("workspace_id")?,
+            run_id: row.get("run_id")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+            index: row.get("index")?,
+            variables: serde_json::from_str(&variables).unwrap_or_default(),
+            results: serde_json::from_str(&results).unwrap_or_default(),
+            passed: row.get("passed")?,
+            elapsed: row.get("elapsed")?,
+        })
+    }
+}
+
+/// The result of firing one rendered request repeatedly, as a quick load check.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct HttpBenchReport {
+    pub request_id: String,
+    pub method: String,
+    pub url: String,
+    pub concurrency: i32,
+    /// Requests per second the run was limited to, if any
+    pub rate: Option<f64>,
+    /// Milliseconds the run actually took
+    pub elapsed: i32,
+    /// Requests sent, whether or not a response came back
+    pub total: i32,
+    /// Requests that got a response, of any status
+    pub completed: i32,
+    /// Requests that failed before a response arrived
+    pub failed: i32,
+    /// Completed requests per second
+    pub throughput: f64,
+    pub latency: HttpBenchLatency,
+    pub status_codes: Vec<HttpBenchStatusCount>,
+    pub errors: Vec<HttpBenchErrorCount>,
+    pub cancelled: bool,
+}
+
+/// Latency of completed requests in milliseconds, from send until the body was read.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct HttpBenchLatency {
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct HttpBenchStatusCount {
+    pub status: i32,
+    pub count: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct HttpBenchErrorCount {
+    pub error: String,
+    pub count: i32,
+}
+
 /// Only used as a `from_row` fallback for an unparseable settings column. The
 /// value a *new* model gets comes from that model's `Default` impl.
 fn default_request_message_size_setting() -> InheritedIntSetting {
@@ -3026,8 +3799,11 @@ define_any_model! {
     HttpRequest,
     HttpResponse,
     HttpResponseEvent,
+    HttpResponseSseEvent,
     KeyValue,
     Plugin,
+    RunnerIteration,
+    RunnerRun,
     Settings,
     SyncState,
     WebsocketConnection,
@@ -3058,8 +3834,11 @@ impl<'de> Deserialize<'de> for AnyModel {
             Some(m) if m == "http_request" => HttpRequest(fv(value).unwrap()),
             Some(m) if m == "http_response" => HttpResponse(fv(value).unwrap()),
             Some(m) if m == "http_response_event" => HttpResponseEvent(fv(value).unwrap()),
+            Some(m) if m == "http_response_sse_event" => HttpResponseSseEvent(fv(value).unwrap()),
             Some(m) if m == "key_value" => KeyValue(fv(value).unwrap()),
             Some(m) if m == "plugin" => Plugin(fv(value).unwrap()),
+            Some(m) if m == "runner_iteration" => RunnerIteration(fv(value).unwrap()),
+            Some(m) if m == "runner_run" => RunnerRun(fv(value).unwrap()),
             Some(m) if m == "settings" => Settings(fv(value).unwrap()),
             Some(m) if m == "sync_state" => SyncState(fv(value).unwrap()),
             Some(m) if m == "websocket_connection" => WebsocketConnection(fv(value).unwrap()),
@@ -3100,6 +3879,7 @@ impl AnyModel {
             AnyModel::HttpRequest(v) => compute_name(&v.name, &v.url, "HTTP Request"),
             AnyModel::WebsocketRequest(v) => compute_name(&v.name, &v.url, "WebSocket Request"),
             AnyModel::Workspace(v) => v.name,
+            AnyModel::Settings(_) => "Settings".to_string(),
             _ => "No Name".to_string(),
         }
     }
@@ -3148,7 +3928,10 @@ mod tests {
             GrpcRequest,
             WebsocketRequest,
             HttpRequestHeader,
+            HttpRequestAssertion,
+            HttpRequestExtraction,
             HttpUrlParameter,
+            RunnerStep,
             EnvironmentVariable,
             DnsOverride,
             ClientCertificate,
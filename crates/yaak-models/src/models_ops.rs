@@ -55,6 +55,7 @@ pub fn delete_model(
         AnyModel::HttpRequest(m) => tx.delete_http_request(&m, source)?.id,
         AnyModel::HttpResponse(m) => tx.delete_http_response(&m, source, blobs)?.id,
         AnyModel::Plugin(m) => tx.delete_plugin(&m, source)?.id,
+        AnyModel::RunnerRun(m) => tx.delete_runner_run(&m, source)?.id,
         AnyModel::WebsocketConnection(m) => tx.delete_websocket_connection(&m, source)?.id,
         AnyModel::WebsocketRequest(m) => tx.delete_websocket_request(&m, source)?.id,
         AnyModel::Workspace(m) => tx.delete_workspace(&m, source, blobs)?.id,
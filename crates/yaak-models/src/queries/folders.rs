@@ -208,6 +208,22 @@ impl<'a> ClientDb<'a> {
             } else {
                 parent.store_cookies
             },
+            http3: if folder.setting_http3.enabled {
+                ResolvedSetting::from_model(
+                    folder.setting_http3.value,
+                    AnyModel::Folder(folder.clone()),
+                )
+            } else {
+                parent.http3
+            },
+            proxy: if folder.setting_proxy.enabled {
+                ResolvedSetting::from_model(
+                    folder.setting_proxy.value.clone(),
+                    AnyModel::Folder(folder.clone()),
+                )
+            } else {
+                parent.proxy
+            },
         })
     }
 }
@@ -142,6 +142,14 @@ impl<'a> ClientDb<'a> {
             } else {
                 parent.request_message_size
             },
+            request_timeout: if grpc_request.setting_request_timeout.enabled {
+                ResolvedSetting::from_model(
+                    grpc_request.setting_request_timeout.value,
+                    AnyModel::GrpcRequest(grpc_request.clone()),
+                )
+            } else {
+                parent.request_timeout
+            },
             ..parent
         })
     }
@@ -153,6 +153,15 @@ impl<'a> ClientDb<'a> {
             } else {
                 parent.store_cookies
             },
+            http3: if http_request.setting_http3.enabled {
+                ResolvedSetting::from_model(
+                    http_request.setting_http3.value,
+                    AnyModel::HttpRequest(http_request.clone()),
+                )
+            } else {
+                parent.http3
+            },
+            proxy: parent.proxy,
         })
     }
 
@@ -174,7 +183,11 @@ impl<'a> ClientDb<'a> {
 #[cfg(test)]
 mod tests {
     use crate::init_in_memory;
-    use crate::models::{HttpRequest, HttpRequestHeader};
+    use crate::models::{
+        Folder, Http3Mode, HttpRequest, HttpRequestHeader, InheritedHttp3Setting,
+        InheritedProxySetting, ProxySetting,
+    };
+    use crate::util::UpdateSource;
 
     #[test]
     fn request_resolution_preserves_duplicate_request_headers() {
@@ -210,4 +223,76 @@ mod tests {
         assert_eq!(cookies[1].value, "optional=1");
         assert!(!cookies[1].enabled);
     }
+
+    #[test]
+    fn http3_mode_is_inherited_unless_overridden() {
+        let (query_manager, _blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let db = query_manager.connect();
+        let mut workspace = db.list_workspaces().expect("Failed to list workspaces").remove(0);
+        workspace.setting_http3 = Http3Mode::AltSvc;
+        let workspace =
+            db.upsert_workspace(&workspace, &UpdateSource::Background).expect("Failed to save");
+
+        let request = HttpRequest { workspace_id: workspace.id, ..Default::default() };
+        let resolved = db.resolve_settings_for_http_request(&request).expect("Failed to resolve");
+        assert_eq!(resolved.http3.value, Http3Mode::AltSvc);
+
+        let request = HttpRequest {
+            setting_http3: InheritedHttp3Setting { enabled: true, value: Http3Mode::Force },
+            ..request
+        };
+        let resolved = db.resolve_settings_for_http_request(&request).expect("Failed to resolve");
+        assert_eq!(resolved.http3.value, Http3Mode::Force);
+    }
+
+    #[test]
+    fn proxy_is_inherited_from_the_workspace_unless_a_folder_overrides_it() {
+        let (query_manager, _blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let db = query_manager.connect();
+        let mut workspace = db.list_workspaces().expect("Failed to list workspaces").remove(0);
+        workspace.setting_proxy = InheritedProxySetting {
+            enabled: true,
+            value: Some(ProxySetting::Enabled {
+                http: "workspace-proxy:9090".to_string(),
+                https: String::new(),
+                auth: None,
+                bypass: String::new(),
+                disabled: false,
+                socks: String::new(),
+            }),
+        };
+        let workspace =
+            db.upsert_workspace(&workspace, &UpdateSource::Background).expect("Failed to save");
+        let folder = db
+            .upsert_folder(
+                &Folder { workspace_id: workspace.id.clone(), ..Default::default() },
+                &UpdateSource::Background,
+            )
+            .expect("Failed to save folder");
+
+        let request = HttpRequest {
+            workspace_id: workspace.id.clone(),
+            folder_id: Some(folder.id.clone()),
+            ..Default::default()
+        };
+        let resolved = db.resolve_settings_for_http_request(&request).expect("Failed to resolve");
+        assert_eq!(resolved.proxy.value, workspace.setting_proxy.value);
+        assert_eq!(resolved.proxy.source_model, "workspace");
+
+        let folder = db
+            .upsert_folder(
+                &Folder {
+                    setting_proxy: InheritedProxySetting {
+                        enabled: true,
+                        value: Some(ProxySetting::Disabled),
+                    },
+                    ..folder
+                },
+                &UpdateSource::Background,
+            )
+            .expect("Failed to save folder");
+        let resolved = db.resolve_settings_for_http_request(&request).expect("Failed to resolve");
+        assert_eq!(resolved.proxy.value, Some(ProxySetting::Disabled));
+        assert_eq!(resolved.proxy.source_id, Some(folder.id));
+    }
 }
@@ -0,0 +1,21 @@
+use crate::client_db::ClientDb;
+use crate::error::Result;
+use crate::models::{HttpResponseSseEvent, HttpResponseSseEventIden};
+use crate::util::UpdateSource;
+
+impl<'a> ClientDb<'a> {
+    pub fn list_http_response_sse_events(
+        &self,
+        response_id: &str,
+    ) -> Result<Vec<HttpResponseSseEvent>> {
+        self.find_many(HttpResponseSseEventIden::ResponseId, response_id, None)
+    }
+
+    pub fn upsert_http_response_sse_event(
+        &self,
+        event: &HttpResponseSseEvent,
+        source: &UpdateSource,
+    ) -> Result<HttpResponseSseEvent> {
+        self.upsert(event, source)
+    }
+}
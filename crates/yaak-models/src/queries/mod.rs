@@ -10,11 +10,13 @@ mod grpc_events;
 mod grpc_requests;
 mod http_requests;
 mod http_response_events;
+mod http_response_sse_events;
 mod http_responses;
 mod key_values;
 mod model_changes;
 mod plugin_key_values;
 mod plugins;
+mod runner_runs;
 mod settings;
 mod sync_states;
 mod websocket_connections;
@@ -0,0 +1,51 @@
+use crate::client_db::ClientDb;
+use crate::error::Result;
+use crate::models::{RunnerIteration, RunnerIterationIden, RunnerRun, RunnerRunIden};
+use crate::util::UpdateSource;
+
+impl<'a> ClientDb<'a> {
+    pub fn get_runner_run(&self, id: &str) -> Result<RunnerRun> {
+        self.find_one(RunnerRunIden::Id, id)
+    }
+
+    pub fn list_runner_runs(
+        &self,
+        workspace_id: &str,
+        limit: Option<u64>,
+    ) -> Result<Vec<RunnerRun>> {
+        self.find_many(RunnerRunIden::WorkspaceId, workspace_id, limit)
+    }
+
+    pub fn upsert_runner_run(&self, run: &RunnerRun, source: &UpdateSource) -> Result<RunnerRun> {
+        self.upsert(run, source)
+    }
+
+    /// Iterations go with it, by cascade.
+    pub fn delete_runner_run(&self, run: &RunnerRun, source: &UpdateSource) -> Result<RunnerRun> {
+        self.delete(run, source)
+    }
+
+    pub fn delete_runner_run_by_id(&self, id: &str, source: &UpdateSource) -> Result<RunnerRun> {
+        let run = self.get_runner_run(id)?;
+        self.delete_runner_run(&run, source)
+    }
+
+    pub fn list_runner_iterations(&self, run_id: &str) -> Result<Vec<RunnerIteration>> {
+        self.find_many(RunnerIterationIden::RunId, run_id, None)
+    }
+
+    pub fn list_runner_iterations_for_workspace(
+        &self,
+        workspace_id: &str,
+    ) -> Result<Vec<RunnerIteration>> {
+        self.find_many(RunnerIterationIden::WorkspaceId, workspace_id, None)
+    }
+
+    pub fn upsert_runner_iteration(
+        &self,
+        iteration: &RunnerIteration,
+        source: &UpdateSource,
+    ) -> Result<RunnerIteration> {
+        self.upsert(iteration, source)
+    }
+}
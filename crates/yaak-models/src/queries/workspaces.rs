@@ -6,10 +6,11 @@ use crate::models::{
     AnyModel, CookieJar, CookieJarIden, Environment, EnvironmentIden, Folder, FolderIden,
     GraphQlIntrospection, GraphQlIntrospectionIden, GrpcConnection, GrpcConnectionIden, GrpcEvent,
     GrpcEventIden, GrpcRequest, GrpcRequestIden, HttpRequest, HttpRequestHeader, HttpRequestIden,
-    HttpResponse, HttpResponseEvent, HttpResponseEventIden, HttpResponseIden,
-    ResolvedHttpRequestSettings, ResolvedSetting, SyncState, SyncStateIden, WebsocketConnection,
-    WebsocketConnectionIden, WebsocketEvent, WebsocketEventIden, WebsocketRequest,
-    WebsocketRequestIden, Workspace, WorkspaceIden, WorkspaceMeta, WorkspaceMetaIden,
+    HttpResponse, HttpResponseEvent, HttpResponseEventIden, HttpResponseIden, HttpResponseSseEvent,
+    HttpResponseSseEventIden, ResolvedHttpRequestSettings, ResolvedSetting, SyncState,
+    SyncStateIden, WebsocketConnection, WebsocketConnectionIden, WebsocketEvent,
+    WebsocketEventIden, WebsocketRequest, WebsocketRequestIden, Workspace, WorkspaceIden,
+    WorkspaceMeta, WorkspaceMetaIden,
 };
 use crate::util::UpdateSource;
 use log::warn;
@@ -67,6 +68,10 @@ impl<'a> ClientDb<'a> {
                 HttpResponseEventIden::WorkspaceId,
                 wid,
             )?;
+            self.delete_many_untracked::<HttpResponseSseEvent>(
+                HttpResponseSseEventIden::WorkspaceId,
+                wid,
+            )?;
             self.delete_many_untracked::<HttpResponse>(HttpResponseIden::WorkspaceId, wid)?;
             self.delete_many_untracked::<HttpRequest>(HttpRequestIden::WorkspaceId, wid)?;
             self.delete_many_untracked::<GrpcEvent>(GrpcEventIden::WorkspaceId, wid)?;
@@ -177,6 +182,19 @@ impl<'a> ClientDb<'a> {
                 workspace.setting_store_cookies,
                 AnyModel::Workspace(workspace.clone()),
             ),
+            http3: ResolvedSetting::from_model(
+                workspace.setting_http3,
+                AnyModel::Workspace(workspace.clone()),
+            ),
+            proxy: if workspace.setting_proxy.enabled {
+                ResolvedSetting::from_model(
+                    workspace.setting_proxy.value.clone(),
+                    AnyModel::Workspace(workspace.clone()),
+                )
+            } else {
+                let settings = self.get_settings();
+                ResolvedSetting::from_model(settings.proxy.clone(), AnyModel::Settings(settings))
+            },
         }
     }
 }
@@ -5,7 +5,8 @@
 //! desktop renders.
 
 use crate::models::{
-    Environment, EnvironmentVariable, GrpcRequest, HttpRequest, HttpRequestHeader, HttpUrlParameter,
+    Environment, EnvironmentVariable, GrpcRequest, HttpRequest, HttpRequestAssertion,
+    HttpRequestExtraction, HttpRequestHeader, HttpUrlParameter,
 };
 use crate::path_placeholders::apply_path_placeholders;
 use log::info;
@@ -50,6 +51,32 @@ pub async fn render_http_request<T: TemplateCallback>(
         })
     }
 
+    let mut assertions = Vec::new();
+    for assertion in request.assertions.clone() {
+        if !assertion.enabled {
+            continue;
+        }
+
+        assertions.push(HttpRequestAssertion {
+            target: parse_and_render(assertion.target.as_str(), vars, callback, options).await?,
+            value: parse_and_render(assertion.value.as_str(), vars, callback, options).await?,
+            ..assertion
+        })
+    }
+
+    let mut extractions = Vec::new();
+    for extraction in request.extractions.clone() {
+        if !extraction.enabled {
+            continue;
+        }
+
+        extractions.push(HttpRequestExtraction {
+            expression: parse_and_render(extraction.expression.as_str(), vars, callback, options)
+                .await?,
+            ..extraction
+        })
+    }
+
     let mut body = BTreeMap::new();
     for (key, value) in request.body.clone() {
         let value = if key == "form" { strip_disabled_form_entries(value) } else { value };
@@ -94,7 +121,16 @@ pub async fn render_http_request<T: TemplateCallback>(
     let url = parse_and_render(request.url.clone().as_str(), vars, callback, options).await?;
     let (url, url_parameters) = apply_path_placeholders(&url, &url_parameters);
 
-    Ok(HttpRequest { url, url_parameters, headers, body, authentication, ..request.to_owned() })
+    Ok(HttpRequest {
+        url,
+        url_parameters,
+        headers,
+        body,
+        authentication,
+        assertions,
+        extractions,
+        ..request.to_owned()
+    })
 }
 
 pub async fn render_grpc_request<T: TemplateCallback>(
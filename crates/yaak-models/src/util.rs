@@ -28,6 +28,7 @@ pub struct ModelPayload {
 #[ts(export, export_to = "gen_models.ts")]
 pub enum UpdateSource {
     Background,
+    Extraction,
     Import,
     Plugin,
     Sync,
@@ -42,6 +43,7 @@ impl UpdateSource {
     pub fn to_db(&self) -> yaak_database::UpdateSource {
         match self {
             UpdateSource::Background => yaak_database::UpdateSource::Background,
+            UpdateSource::Extraction => yaak_database::UpdateSource::Extraction,
             UpdateSource::Import => yaak_database::UpdateSource::Import,
             UpdateSource::Plugin => yaak_database::UpdateSource::Plugin,
             UpdateSource::Sync => yaak_database::UpdateSource::Sync,
@@ -56,6 +58,7 @@ impl From<yaak_database::UpdateSource> for UpdateSource {
     fn from(source: yaak_database::UpdateSource) -> Self {
         match source {
             yaak_database::UpdateSource::Background => UpdateSource::Background,
+            yaak_database::UpdateSource::Extraction => UpdateSource::Extraction,
             yaak_database::UpdateSource::Import => UpdateSource::Import,
             yaak_database::UpdateSource::Plugin => UpdateSource::Plugin,
             yaak_database::UpdateSource::Sync => UpdateSource::Sync,
@@ -2,8 +2,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use ts_rs::TS;
 use yaak_models::models::{
-    AnyModel, Environment, Folder, GrpcRequest, HttpRequest, HttpResponse, WebsocketRequest,
-    Workspace,
+    AnyModel, Environment, Folder, GrpcRequest, HttpRequest, HttpResponse, HttpResponseSseEvent,
+    WebsocketRequest, Workspace,
 };
 use yaak_models::util::generate_prefixed_id;
 
@@ -171,6 +171,8 @@ pub enum InternalEventPayload {
 
     FindHttpResponsesRequest(FindHttpResponsesRequest),
     FindHttpResponsesResponse(FindHttpResponsesResponse),
+    ListHttpResponseSseEventsRequest(ListHttpResponseSseEventsRequest),
+    ListHttpResponseSseEventsResponse(ListHttpResponseSseEventsResponse),
 
     GetHttpResponseBodyInfoRequest(GetHttpResponseBodyInfoRequest),
     GetHttpResponseBodyInfoResponse(GetHttpResponseBodyInfoResponse),
@@ -1428,6 +1430,22 @@ pub struct FindHttpResponsesResponse {
     pub http_responses: Vec<HttpResponse>,
 }
 
+/// The server-sent events parsed out of a `text/event-stream` response, in the order they
+/// arrived. Empty for any other kind of response.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_events.ts")]
+pub struct ListHttpResponseSseEventsRequest {
+    pub response_id: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_events.ts")]
+pub struct ListHttpResponseSseEventsResponse {
+    pub events: Vec<HttpResponseSseEvent>,
+}
+
 /// Ask what a response's body is, before deciding whether to pull it.
 ///
 /// Bodies are addressed by response id and never by path, so where the host
@@ -1052,6 +1052,19 @@ impl PluginManager {
         values: HashMap<String, JsonPrimitive>,
         purpose: RenderPurpose,
     ) -> TemplateResult<String> {
+        self.try_call_template_function(plugin_context, fn_name, values, purpose)
+            .await?
+            .ok_or_else(|| RenderError(format!("Template function {fn_name}(…) not found ")))
+    }
+
+    /// Call a template function, or `None` when no enabled plugin provides it.
+    pub async fn try_call_template_function(
+        &self,
+        plugin_context: &PluginContext,
+        fn_name: &str,
+        values: HashMap<String, JsonPrimitive>,
+        purpose: RenderPurpose,
+    ) -> TemplateResult<Option<String>> {
         let req = CallTemplateFunctionRequest {
             name: fn_name.to_string(),
             args: CallTemplateFunctionArgs { purpose, values },
@@ -1082,8 +1095,8 @@ impl PluginManager {
             });
 
         match value {
-            None => Err(RenderError(format!("Template function {fn_name}(…) not found "))),
-            Some(Ok(v)) => Ok(v),
+            None => Ok(None),
+            Some(Ok(v)) => Ok(Some(v)),
             Some(Err(e)) => Err(RenderError(e)),
         }
     }
@@ -13,6 +13,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use yaak_crypto::manager::EncryptionManager;
 use yaak_templates::TemplateCallback;
+use yaak_templates::error::Error::RenderError;
 use yaak_templates::error::Result;
 
 #[derive(Clone)]
@@ -55,21 +56,15 @@ impl TemplateCallback for PluginTemplateCallback {
             return template_function_keychain_run(args);
         }
 
-        let mut primitive_args = HashMap::new();
-        for (key, value) in args {
-            primitive_args.insert(key, JsonPrimitive::from(value));
-        }
-
-        let resp = self
-            .plugin_manager
-            .call_template_function(
+        plugins_first(fn_name, args, |primitive_args| {
+            self.plugin_manager.try_call_template_function(
                 &self.plugin_context,
                 fn_name,
                 primitive_args,
                 self.render_purpose.to_owned(),
             )
-            .await?;
-        Ok(resp)
+        })
+        .await
     }
 
     fn transform_arg(&self, fn_name: &str, arg_name: &str, arg_value: &str) -> Result<String> {
@@ -85,3 +80,54 @@ impl TemplateCallback for PluginTemplateCallback {
         Ok(arg_value.to_string())
     }
 }
+
+/// Run `fn_name` with the plugins. The native function of the same name only stands in when
+/// no enabled plugin provides it, since the bundled plugins do more than their native mirrors.
+async fn plugins_first<F, Fut>(
+    fn_name: &str,
+    args: HashMap<String, serde_json::Value>,
+    call_plugins: F,
+) -> Result<String>
+where
+    F: FnOnce(HashMap<String, JsonPrimitive>) -> Fut,
+    Fut: Future<Output = Result<Option<String>>>,
+{
+    let primitive_args =
+        args.iter().map(|(key, value)| (key.clone(), JsonPrimitive::from(value.clone()))).collect();
+    if let Some(value) = call_plugins(primitive_args).await? {
+        return Ok(value);
+    }
+
+    match yaak_template_functions::call(fn_name, &args) {
+        Some(result) => result,
+        None => Err(RenderError(format!("Template function {fn_name}(…) not found "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn args() -> HashMap<String, serde_json::Value> {
+        HashMap::from([("value".to_string(), json!("hello"))])
+    }
+
+    #[tokio::test]
+    async fn a_plugin_function_wins_over_the_native_one() {
+        let result = plugins_first("base64.encode", args(), |_| async {
+            Ok(Some("from plugin".to_string()))
+        })
+        .await;
+        assert_eq!(result.unwrap(), "from plugin");
+    }
+
+    #[tokio::test]
+    async fn the_native_function_stands_in_without_a_plugin() {
+        let result = plugins_first("base64.encode", args(), |_| async { Ok(None) }).await;
+        assert_eq!(result.unwrap(), "aGVsbG8=");
+
+        let missing = plugins_first("nope", args(), |_| async { Ok(None) }).await;
+        assert!(missing.is_err());
+    }
+}
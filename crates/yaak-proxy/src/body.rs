@@ -5,11 +5,13 @@ use std::time::Instant;
 
 use bytes::Bytes;
 use hyper::body::{Body, Frame};
+use yaak_sse::parser::EventStreamParser;
 
 use crate::ProxyEvent;
 
 /// A body wrapper that emits `ResponseBodyChunk` per frame and
-/// `ResponseBodyComplete` when the stream finishes.
+/// `ResponseBodyComplete` when the stream finishes. Event streams also emit
+/// `ServerSentEvent` for each event, as it arrives.
 pub struct MeasuredBody<B> {
     inner: B,
     request_id: u64,
@@ -18,6 +20,7 @@ pub struct MeasuredBody<B> {
     event_tx: std_mpsc::Sender<ProxyEvent>,
     start: Instant,
     finished: bool,
+    event_stream: Option<EventStreamParser>,
 }
 
 impl<B> MeasuredBody<B> {
@@ -35,9 +38,16 @@ impl<B> MeasuredBody<B> {
             event_tx,
             start,
             finished: false,
+            event_stream: None,
         }
     }
 
+    /// Parse the body as a `text/event-stream`.
+    pub fn with_event_stream(mut self) -> Self {
+        self.event_stream = Some(EventStreamParser::new());
+        self
+    }
+
     fn send_complete(&mut self) {
         if !self.finished {
             self.finished = true;
@@ -82,6 +92,12 @@ where
                     let _ = self
                         .event_tx
                         .send(ProxyEvent::ResponseBodyChunk { id: self.request_id, bytes: len });
+                    let events = self.event_stream.as_mut().map(|p| p.feed(data));
+                    for event in events.unwrap_or_default() {
+                        let _ = self
+                            .event_tx
+                            .send(ProxyEvent::ServerSentEvent { id: self.request_id, event });
+                    }
                 }
                 Poll::Ready(Some(Ok(frame)))
             }
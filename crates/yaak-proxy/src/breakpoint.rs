@@ -0,0 +1,201 @@
+//! Pausing matching requests and responses until someone decides what to do with them.
+//!
+//! A paused message is announced with [`ProxyEvent::Paused`] and waits for
+//! [`Breakpoints::resolve`]. It can go on as it was or edited, be answered with a canned
+//! response, or be dropped, which closes the client's connection.
+
+use std::collections::BTreeMap;
+use std::sync::mpsc as std_mpsc;
+use std::sync::{Mutex, MutexGuard, RwLock};
+
+use hyper::Uri;
+use tokio::sync::oneshot;
+
+use crate::ProxyEvent;
+use crate::pattern::UrlPattern;
+
+#[derive(Debug, Clone, Default)]
+pub struct BreakpointRule {
+    pub pattern: UrlPattern,
+    /// Pause before the request goes upstream
+    pub on_request: bool,
+    /// Pause before the response goes back to the client
+    pub on_response: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InterceptedRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InterceptedResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Paused {
+    Request(InterceptedRequest),
+    /// A response, with the request it answers for context.
+    Response {
+        method: String,
+        url: String,
+        response: InterceptedResponse,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum Resolution {
+    /// Send the paused request upstream, with any edits.
+    ContinueRequest(InterceptedRequest),
+    /// Send the paused response to the client, with any edits.
+    ContinueResponse(InterceptedResponse),
+    /// Answer the client with this instead.
+    Respond(InterceptedResponse),
+    /// Close the client's connection without answering.
+    Drop,
+}
+
+/// The breakpoint rules, and the messages they are holding. Shared between the proxy and
+/// whoever resolves the pauses, and kept across proxy restarts.
+#[derive(Default)]
+pub struct Breakpoints {
+    rules: RwLock<Vec<BreakpointRule>>,
+    paused: Mutex<BTreeMap<u64, (Paused, oneshot::Sender<Resolution>)>>,
+}
+
+impl Breakpoints {
+    pub fn set_rules(&self, rules: Vec<BreakpointRule>) {
+        *self.rules.write().unwrap_or_else(|e| e.into_inner()) = rules;
+    }
+
+    /// Everything currently paused, oldest first.
+    pub fn paused(&self) -> Vec<(u64, Paused)> {
+        self.lock_paused().iter().map(|(id, (message, _))| (*id, message.clone())).collect()
+    }
+
+    /// Let the message paused for request `id` go on as `resolution` says.
+    pub fn resolve(&self, id: u64, resolution: Resolution) -> Result<(), String> {
+        let mut paused = self.lock_paused();
+        let Some((message, _)) = paused.get(&id) else {
+            return Err(format!("Request {id} is not paused"));
+        };
+        let fits = match (message, &resolution) {
+            (_, Resolution::Respond(_) | Resolution::Drop) => true,
+            (Paused::Request(_), Resolution::ContinueRequest(_)) => true,
+            (Paused::Response { .. }, Resolution::ContinueResponse(_)) => true,
+            _ => false,
+        };
+        if !fits {
+            return Err(format!("Request {id} is paused at the other stage"));
+        }
+        if let Some((_, tx)) = paused.remove(&id) {
+            // The client may have gone away in the meantime
+            let _ = tx.send(resolution);
+        }
+        Ok(())
+    }
+
+    /// Let everything paused go on unchanged.
+    pub fn resume_all(&self) {
+        self.lock_paused().clear();
+    }
+
+    pub(crate) fn pauses_request(&self, method: &str, uri: &Uri) -> bool {
+        self.rules().iter().any(|r| r.on_request && r.pattern.matches(method, uri))
+    }
+
+    pub(crate) fn pauses_response(&self, method: &str, uri: &Uri) -> bool {
+        self.rules().iter().any(|r| r.on_response && r.pattern.matches(method, uri))
+    }
+
+    /// Hold `message` until it is resolved. `None` means carry on unchanged, which is what
+    /// happens to everything paused when [`Self::resume_all`] is called.
+    pub(crate) async fn pause(
+        &self,
+        id: u64,
+        message: Paused,
+        event_tx: &std_mpsc::Sender<ProxyEvent>,
+    ) -> Option<Resolution> {
+        let (tx, rx) = oneshot::channel();
+        self.lock_paused().insert(id, (message.clone(), tx));
+        let _ = event_tx.send(ProxyEvent::Paused { id, message });
+
+        // Also cleans up when the client disconnects and this future is dropped
+        let _resumed = Resumed { breakpoints: self, id, event_tx: event_tx.clone() };
+        rx.await.ok()
+    }
+
+    fn rules(&self) -> std::sync::RwLockReadGuard<'_, Vec<BreakpointRule>> {
+        self.rules.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_paused(&self) -> MutexGuard<'_, BTreeMap<u64, (Paused, oneshot::Sender<Resolution>)>> {
+        self.paused.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+struct Resumed<'a> {
+    breakpoints: &'a Breakpoints,
+    id: u64,
+    event_tx: std_mpsc::Sender<ProxyEvent>,
+}
+
+impl Drop for Resumed<'_> {
+    fn drop(&mut self) {
+        self.breakpoints.lock_paused().remove(&self.id);
+        let _ = self.event_tx.send(ProxyEvent::Resumed { id: self.id });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> Paused {
+        Paused::Request(InterceptedRequest { method: "GET".into(), ..Default::default() })
+    }
+
+    #[tokio::test]
+    async fn resolves_a_paused_request() {
+        let breakpoints = Breakpoints::default();
+        let (tx, rx) = std_mpsc::channel();
+
+        let pause = breakpoints.pause(7, request(), &tx);
+        let resolve = async {
+            tokio::task::yield_now().await;
+            assert_eq!(breakpoints.paused().len(), 1);
+            assert!(
+                breakpoints.resolve(7, Resolution::ContinueResponse(Default::default())).is_err()
+            );
+            breakpoints.resolve(7, Resolution::Drop).unwrap();
+        };
+        let (resolution, ()) = tokio::join!(pause, resolve);
+
+        assert!(matches!(resolution, Some(Resolution::Drop)));
+        assert!(breakpoints.paused().is_empty());
+        assert!(matches!(rx.try_recv(), Ok(ProxyEvent::Paused { id: 7, .. })));
+        assert!(matches!(rx.try_recv(), Ok(ProxyEvent::Resumed { id: 7 })));
+    }
+
+    #[tokio::test]
+    async fn resume_all_lets_requests_through_unchanged() {
+        let breakpoints = Breakpoints::default();
+        let (tx, _rx) = std_mpsc::channel();
+
+        let pause = breakpoints.pause(1, request(), &tx);
+        let resume = async {
+            tokio::task::yield_now().await;
+            breakpoints.resume_all();
+        };
+        let (resolution, ()) = tokio::join!(pause, resume);
+
+        assert!(resolution.is_none());
+        assert!(breakpoints.resolve(1, Resolution::Drop).is_err());
+    }
+}
@@ -12,30 +12,56 @@ pub struct CertificateAuthority {
     cache: Mutex<HashMap<String, Arc<ServerConfig>>>,
 }
 
-impl CertificateAuthority {
-    pub fn new() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        let mut params = CertificateParams::default();
-        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
-        params.key_usages.push(KeyUsagePurpose::KeyCertSign);
-        params.key_usages.push(KeyUsagePurpose::CrlSign);
-        params.distinguished_name.push(rcgen::DnType::CommonName, "Debug Proxy CA");
-        params.distinguished_name.push(rcgen::DnType::OrganizationName, "Debug Proxy");
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
+impl CertificateAuthority {
+    /// Generate a new root with a fresh key.
+    pub fn new() -> Result<Self> {
         let key = KeyPair::generate()?;
-        let ca_cert = params.self_signed(&key)?;
+        let ca_cert = ca_params().self_signed(&key)?;
         let ca_cert_der = ca_cert.der().clone();
 
         Ok(Self { ca_cert, ca_cert_der, ca_key: key, cache: Mutex::new(HashMap::new()) })
     }
 
+    /// Load a root saved with [`Self::ca_pem`] and [`Self::key_pem`].
+    ///
+    /// rcgen can only sign with a certificate it built, so the root is rebuilt from the same
+    /// parameters and key. Leaves only carry the issuer's name and key identifier, which come
+    /// out the same, so they chain to the saved certificate, and that is the one served.
+    pub fn from_pem(cert_pem: &str, key_pem: &str) -> Result<Self> {
+        let saved = pem::parse(cert_pem)?;
+        if saved.tag() != "CERTIFICATE" {
+            return Err(format!("Expected a CERTIFICATE, got {}", saved.tag()).into());
+        }
+
+        let key = KeyPair::from_pem(key_pem)?;
+        let public_key = key.public_key_der();
+        if !saved.contents().windows(public_key.len()).any(|w| w == public_key.as_slice()) {
+            return Err("The CA key does not belong to the CA certificate".into());
+        }
+
+        let ca_cert = ca_params().self_signed(&key)?;
+        let ca_cert_der = CertificateDer::from(saved.into_contents());
+
+        Ok(Self { ca_cert, ca_cert_der, ca_key: key, cache: Mutex::new(HashMap::new()) })
+    }
+
     pub fn ca_pem(&self) -> String {
         pem::encode(&pem::Pem::new("CERTIFICATE", self.ca_cert_der.to_vec()))
     }
 
-    pub fn server_config(
-        &self,
-        domain: &str,
-    ) -> Result<Arc<ServerConfig>, Box<dyn std::error::Error + Send + Sync>> {
+    /// The certificate as DER, the form Windows and Android prefer to install.
+    pub fn ca_der(&self) -> Vec<u8> {
+        self.ca_cert_der.to_vec()
+    }
+
+    /// The private key as PKCS#8 PEM, for saving. Keep it secret.
+    pub fn key_pem(&self) -> String {
+        self.ca_key.serialize_pem()
+    }
+
+    pub fn server_config(&self, domain: &str) -> Result<Arc<ServerConfig>> {
         {
             let cache = self.cache.lock().unwrap();
             if let Some(config) = cache.get(domain) {
@@ -43,22 +69,14 @@ impl CertificateAuthority {
             }
         }
 
-        let mut params = CertificateParams::new(vec![domain.to_string()])?;
-        params.distinguished_name.push(rcgen::DnType::CommonName, domain);
-
-        let leaf_key = KeyPair::generate()?;
-        let leaf_cert = params.signed_by(&leaf_key, &self.ca_cert, &self.ca_key)?;
-
-        let cert_der = leaf_cert.der().clone();
-        let key_der = leaf_key.serialize_der();
-
+        let (cert_der, key_der) = self.issue(domain)?;
         let mut config =
             ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
                 .with_safe_default_protocol_versions()?
                 .with_no_client_auth()
                 .with_single_cert(
                     vec![cert_der, self.ca_cert_der.clone()],
-                    PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key_der)),
+                    PrivateKeyDer::Pkcs8(key_der),
                 )?;
         config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
 
@@ -66,4 +84,73 @@ impl CertificateAuthority {
         self.cache.lock().unwrap().insert(domain.to_string(), config.clone());
         Ok(config)
     }
+
+    /// A leaf certificate for `domain`, signed by this root, and its key.
+    fn issue(
+        &self,
+        domain: &str,
+    ) -> Result<(CertificateDer<'static>, PrivatePkcs8KeyDer<'static>)> {
+        let mut params = CertificateParams::new(vec![domain.to_string()])?;
+        params.distinguished_name.push(rcgen::DnType::CommonName, domain);
+
+        let leaf_key = KeyPair::generate()?;
+        let leaf_cert = params.signed_by(&leaf_key, &self.ca_cert, &self.ca_key)?;
+        Ok((leaf_cert.der().clone(), PrivatePkcs8KeyDer::from(leaf_key.serialize_der())))
+    }
+}
+
+/// The root's parameters. They must not depend on anything but the key, or roots loaded with
+/// [`CertificateAuthority::from_pem`] would stop matching the ones that were saved.
+fn ca_params() -> CertificateParams {
+    let mut params = CertificateParams::default();
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params.key_usages.push(KeyUsagePurpose::KeyCertSign);
+    params.key_usages.push(KeyUsagePurpose::CrlSign);
+    params.distinguished_name.push(rcgen::DnType::CommonName, "Debug Proxy CA");
+    params.distinguished_name.push(rcgen::DnType::OrganizationName, "Debug Proxy");
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustls::client::WebPkiServerVerifier;
+    use rustls::client::danger::ServerCertVerifier;
+    use rustls::pki_types::{ServerName, UnixTime};
+
+    #[test]
+    fn leaves_from_a_reloaded_root_chain_to_the_saved_certificate() {
+        let ca = CertificateAuthority::new().unwrap();
+        let (cert_pem, key_pem) = (ca.ca_pem(), ca.key_pem());
+
+        let reloaded = CertificateAuthority::from_pem(&cert_pem, &key_pem).unwrap();
+        assert_eq!(reloaded.ca_der(), ca.ca_der());
+        let (leaf, _) = reloaded.issue("api.example.test").unwrap();
+
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(CertificateDer::from(pem::parse(&cert_pem).unwrap().into_contents())).unwrap();
+        let verifier = WebPkiServerVerifier::builder_with_provider(
+            Arc::new(roots),
+            Arc::new(rustls::crypto::ring::default_provider()),
+        )
+        .build()
+        .unwrap();
+        verifier
+            .verify_server_cert(
+                &leaf,
+                &[],
+                &ServerName::try_from("api.example.test").unwrap(),
+                &[],
+                UnixTime::now(),
+            )
+            .expect("the leaf should chain to the saved root");
+    }
+
+    #[test]
+    fn refuses_a_key_from_another_root() {
+        let ca = CertificateAuthority::new().unwrap();
+        let other = CertificateAuthority::new().unwrap();
+        assert!(CertificateAuthority::from_pem(&ca.ca_pem(), &other.key_pem()).is_err());
+        assert!(CertificateAuthority::from_pem(&ca.key_pem(), &ca.key_pem()).is_err());
+    }
 }
@@ -1,18 +1,16 @@
-use std::sync::Arc;
 use std::sync::mpsc as std_mpsc;
 
 use hyper::server::conn::http1;
 use hyper::service::service_fn;
 use tokio::net::TcpStream;
 
-use crate::ProxyEvent;
-use crate::cert::CertificateAuthority;
 use crate::request::handle_request;
+use crate::{ProxyEvent, ProxyOptions};
 
 pub(crate) async fn handle_connection(
     stream: TcpStream,
     event_tx: std_mpsc::Sender<ProxyEvent>,
-    ca: Arc<CertificateAuthority>,
+    options: ProxyOptions,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let tx = event_tx.clone();
     http1::Builder::new()
@@ -22,8 +20,8 @@ pub(crate) async fn handle_connection(
             hyper_util::rt::TokioIo::new(stream),
             service_fn(move |req| {
                 let tx = tx.clone();
-                let ca = ca.clone();
-                async move { handle_request(req, tx, ca).await }
+                let options = options.clone();
+                async move { handle_request(req, tx, options).await }
             }),
         )
         .with_upgrades()
@@ -0,0 +1,123 @@
+//! Which traffic the proxy decrypts, and which of what it sees is recorded.
+//!
+//! TLS to a host that is not intercepted is tunneled as raw TCP, so it stays end to end:
+//! certificate-pinned apps keep working, and none of it is seen, rewritten or recorded.
+//!
+//! For each scope, a host or request is let through when no exclude rule matches it and
+//! either there are no include rules or one of them matches.
+
+use std::sync::RwLock;
+
+use hyper::Uri;
+
+use crate::pattern::UrlPattern;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterScope {
+    /// Whether TLS to a host is decrypted. Only the rule's host is matched, since that is all
+    /// a tunnel tells us before it is opened.
+    Intercept,
+    /// Whether an exchange is recorded.
+    Record,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterMode {
+    Include,
+    Exclude,
+}
+
+#[derive(Debug, Clone)]
+pub struct FilterRule {
+    pub scope: FilterScope,
+    pub mode: FilterMode,
+    pub pattern: UrlPattern,
+}
+
+/// The filter rules the proxy applies. Shared with whoever edits them, so changes apply to a
+/// running proxy, from its next tunnel or exchange on.
+#[derive(Default)]
+pub struct CaptureFilter {
+    rules: RwLock<Vec<FilterRule>>,
+}
+
+impl CaptureFilter {
+    pub fn set_rules(&self, rules: Vec<FilterRule>) {
+        *self.rules.write().unwrap_or_else(|e| e.into_inner()) = rules;
+    }
+
+    /// Whether TLS tunneled to `host` is decrypted, rather than passed through.
+    pub fn intercepts(&self, host: &str) -> bool {
+        self.allows(FilterScope::Intercept, |p| p.matches_host(host))
+    }
+
+    /// Whether the exchange for a request to `url` is recorded.
+    pub fn records(&self, method: &str, url: &str) -> bool {
+        let Ok(uri) = url.parse::<Uri>() else {
+            return true;
+        };
+        self.allows(FilterScope::Record, |p| p.matches(method, &uri))
+    }
+
+    fn allows(&self, scope: FilterScope, matches: impl Fn(&UrlPattern) -> bool) -> bool {
+        let rules = self.rules.read().unwrap_or_else(|e| e.into_inner());
+        let mut rules = rules.iter().filter(|r| r.scope == scope).peekable();
+        if rules.peek().is_none() {
+            return true;
+        }
+
+        let (mut included, mut has_includes) = (false, false);
+        for rule in rules {
+            match rule.mode {
+                FilterMode::Exclude if matches(&rule.pattern) => return false,
+                FilterMode::Exclude => {}
+                FilterMode::Include => {
+                    has_includes = true;
+                    included = included || matches(&rule.pattern);
+                }
+            }
+        }
+        included || !has_includes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(scope: FilterScope, mode: FilterMode, host: &str, path: &str) -> FilterRule {
+        FilterRule {
+            scope,
+            mode,
+            pattern: UrlPattern { host: host.into(), path: path.into(), ..Default::default() },
+        }
+    }
+
+    #[test]
+    fn intercepts_included_hosts_unless_excluded() {
+        let filter = CaptureFilter::default();
+        assert!(filter.intercepts("bank.example.com"));
+
+        filter.set_rules(vec![
+            rule(FilterScope::Intercept, FilterMode::Include, "*.example.com", ""),
+            rule(FilterScope::Intercept, FilterMode::Exclude, "bank.example.com", ""),
+            // Other scopes don't count
+            rule(FilterScope::Record, FilterMode::Include, "other.test", ""),
+        ]);
+        assert!(filter.intercepts("API.example.com"));
+        assert!(!filter.intercepts("bank.example.com"));
+        assert!(!filter.intercepts("other.test"));
+    }
+
+    #[test]
+    fn records_matching_exchanges() {
+        let filter = CaptureFilter::default();
+        filter.set_rules(vec![
+            rule(FilterScope::Record, FilterMode::Exclude, "", "*.png"),
+            rule(FilterScope::Intercept, FilterMode::Include, "api.test", ""),
+        ]);
+        assert!(filter.records("GET", "https://api.test/users"));
+        assert!(filter.records("GET", "https://elsewhere.test/"));
+        assert!(!filter.records("GET", "https://api.test/logo.png"));
+    }
+}
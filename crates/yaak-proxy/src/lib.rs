@@ -1,15 +1,26 @@
 pub mod body;
+pub mod breakpoint;
 pub mod cert;
 mod connection;
+pub mod filter;
+pub mod pattern;
 mod request;
+pub mod rewrite;
+pub mod upstream;
+mod websocket;
 
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
 use std::sync::mpsc as std_mpsc;
 
+use breakpoint::{Breakpoints, Paused};
 use cert::CertificateAuthority;
+use filter::CaptureFilter;
+use rewrite::Rewrites;
 use tokio::net::TcpListener;
+use upstream::UpstreamProxy;
+use yaak_sse::sse::ServerSentEvent;
 
 use connection::handle_connection;
 
@@ -58,6 +69,21 @@ pub enum ProxyEvent {
     },
     /// The upstream request failed.
     Error { id: u64, error: String },
+    /// A breakpoint is holding the request or its response; see [`Breakpoints::resolve`].
+    Paused { id: u64, message: Paused },
+    /// A paused message went on, however it was resolved.
+    Resumed { id: u64 },
+    /// A frame of an upgraded WebSocket connection, in either direction.
+    WebSocketFrame {
+        id: u64,
+        is_server: bool,
+        opcode: u8,
+        payload: Vec<u8>,
+    },
+    /// The WebSocket connection closed; no more frames follow.
+    WebSocketClosed { id: u64 },
+    /// An event of a `text/event-stream` response, as it was received.
+    ServerSentEvent { id: u64, event: ServerSentEvent },
 }
 
 /// Accumulated view of a proxied request, built from `ProxyEvent`s.
@@ -113,10 +139,22 @@ impl Drop for ProxyHandle {
     }
 }
 
-pub fn start_proxy(port: u16) -> Result<ProxyHandle, String> {
-    let ca = CertificateAuthority::new().map_err(|e| format!("Failed to create CA: {e}"))?;
-    let ca_pem = ca.ca_pem();
-    let ca = Arc::new(ca);
+/// What the proxy does with the traffic going through it.
+#[derive(Clone)]
+pub struct ProxyOptions {
+    /// Issues the certificates TLS is intercepted with
+    pub ca: Arc<CertificateAuthority>,
+    pub breakpoints: Arc<Breakpoints>,
+    pub rewrites: Arc<Rewrites>,
+    /// Which hosts are intercepted rather than tunneled
+    pub filter: Arc<CaptureFilter>,
+    /// Where traffic goes on to, when not straight to the origin
+    pub upstream: Arc<UpstreamProxy>,
+}
+
+/// Start the proxy on `port`.
+pub fn start_proxy(port: u16, options: ProxyOptions) -> Result<ProxyHandle, String> {
+    let ca_pem = options.ca.ca_pem();
 
     let (event_tx, event_rx) = std_mpsc::channel();
     let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
@@ -151,9 +189,10 @@ pub fn start_proxy(port: u16) -> Result<ProxyHandle, String> {
                         match result {
                             Ok((stream, _addr)) => {
                                 let tx = event_tx.clone();
-                                let ca = ca.clone();
+                                let options = options.clone();
                                 tokio::spawn(async move {
-                                    if let Err(e) = handle_connection(stream, tx, ca).await {
+                                    let result = handle_connection(stream, tx, options).await;
+                                    if let Err(e) = result {
                                         eprintln!("Connection error: {e}");
                                     }
                                 });
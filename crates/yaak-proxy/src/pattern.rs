@@ -0,0 +1,83 @@
+//! Matching requests by method, host and path, for rules that only apply to some traffic.
+
+use hyper::Uri;
+
+/// Which requests a rule applies to. An empty field matches anything. `host` and `path` may
+/// use `*` as a wildcard, so `/api/*` matches everything under `/api/`; `host` ignores case.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UrlPattern {
+    pub method: String,
+    pub host: String,
+    pub path: String,
+}
+
+impl UrlPattern {
+    pub fn matches(&self, method: &str, uri: &Uri) -> bool {
+        let host = uri.host().unwrap_or_default().to_ascii_lowercase();
+        (self.method.is_empty() || self.method.eq_ignore_ascii_case(method))
+            && (self.host.is_empty() || glob_match(&self.host.to_ascii_lowercase(), &host))
+            && (self.path.is_empty() || glob_match(&self.path, uri.path()))
+    }
+
+    /// Whether `host` matches, whatever the method and path.
+    pub fn matches_host(&self, host: &str) -> bool {
+        self.host.is_empty()
+            || glob_match(&self.host.to_ascii_lowercase(), &host.to_ascii_lowercase())
+    }
+}
+
+/// Whether `text` matches `pattern`, where `*` stands for any run of characters.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let (pattern, text) = (pattern.as_bytes(), text.as_bytes());
+    let (mut p, mut t) = (0, 0);
+    // Where the last `*` was, and how much text it has swallowed so far
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(method: &str, host: &str, path: &str) -> UrlPattern {
+        UrlPattern { method: method.into(), host: host.into(), path: path.into() }
+    }
+
+    #[test]
+    fn empty_pattern_matches_everything() {
+        let uri: Uri = "https://example.com/a?b=c".parse().unwrap();
+        assert!(UrlPattern::default().matches("DELETE", &uri));
+    }
+
+    #[test]
+    fn matches_method_host_and_path() {
+        let uri: Uri = "https://API.example.com/v1/users/42?x=1".parse().unwrap();
+        assert!(pattern("get", "*.example.com", "/v1/users/*").matches("GET", &uri));
+        assert!(pattern("", "api.example.com", "").matches("POST", &uri));
+        assert!(!pattern("POST", "", "").matches("GET", &uri));
+        assert!(!pattern("", "example.com", "").matches("GET", &uri));
+        assert!(!pattern("", "", "/v1").matches("GET", &uri));
+    }
+
+    #[test]
+    fn wildcards_backtrack() {
+        assert!(glob_match("*a*b", "xaxxab"));
+        assert!(glob_match("a**", "a"));
+        assert!(!glob_match("*a*b", "xaxxa"));
+    }
+}
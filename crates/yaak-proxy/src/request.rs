@@ -1,28 +1,35 @@
-use std::convert::Infallible;
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use std::sync::mpsc as std_mpsc;
 use std::time::Instant;
 
 use bytes::Bytes;
+use http::request::Parts;
+use http::uri::Scheme;
 use http_body_util::{BodyExt, Full};
 use hyper::body::Incoming;
-use hyper::header::HeaderMap;
+use hyper::header::{
+    CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, HOST, HeaderMap, HeaderName, HeaderValue,
+    PROXY_AUTHORIZATION, TRANSFER_ENCODING,
+};
 use hyper::service::service_fn;
 use hyper::{Method, Request, Response, StatusCode, Uri};
-use hyper_util::client::legacy::Client;
 use hyper_util::rt::TokioExecutor;
 use hyper_util::server::conn::auto;
 use rustls::ClientConfig;
 use rustls::pki_types::ServerName;
-use tokio::net::TcpStream;
 use tokio_rustls::TlsAcceptor;
+use yaak_sse::parser::EventStreamParser;
 
 use crate::body::MeasuredBody;
-use crate::cert::CertificateAuthority;
-use crate::{ProxyEvent, REQUEST_ID};
+use crate::breakpoint::{Breakpoints, InterceptedRequest, InterceptedResponse, Paused, Resolution};
+use crate::rewrite::LocalFile;
+use crate::upstream::{Connected, UpstreamProxy};
+use crate::websocket;
+use crate::{ProxyEvent, ProxyOptions, REQUEST_ID};
 
 type BoxBody = http_body_util::combinators::BoxBody<Bytes, hyper::Error>;
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
 
 fn full_body(bytes: Bytes) -> BoxBody {
     Full::new(bytes).map_err(|never| match never {}).boxed()
@@ -30,11 +37,21 @@ fn full_body(bytes: Bytes) -> BoxBody {
 
 fn measured_incoming(
     incoming: Incoming,
+    headers: &HeaderMap,
     id: u64,
     start: Instant,
     tx: std_mpsc::Sender<ProxyEvent>,
 ) -> BoxBody {
-    MeasuredBody::new(incoming, id, start, tx).boxed()
+    let body = MeasuredBody::new(incoming, id, start, tx);
+    if is_event_stream(headers) { body.with_event_stream().boxed() } else { body.boxed() }
+}
+
+/// Whether a response is an event stream whose events can be read as they pass. Compressed
+/// streams are left alone.
+fn is_event_stream(headers: &HeaderMap) -> bool {
+    let header = |name| headers.get(name).and_then(|v| v.to_str().ok()).unwrap_or_default();
+    header(CONTENT_TYPE).trim().to_ascii_lowercase().starts_with("text/event-stream")
+        && matches!(header(CONTENT_ENCODING).trim(), "" | "identity")
 }
 
 fn version_str(v: hyper::Version) -> String {
@@ -67,19 +84,31 @@ fn emit_request_events(
     }
 }
 
+/// Record the request as it goes upstream, after any breakpoint edits.
+fn emit_request(tx: &std_mpsc::Sender<ProxyEvent>, id: u64, parts: &Parts, body: &Bytes) {
+    let _ = tx.send(ProxyEvent::RequestStart {
+        id,
+        method: parts.method.to_string(),
+        url: parts.uri.to_string(),
+        http_version: version_str(parts.version),
+    });
+    let body = if body.is_empty() { None } else { Some(body.to_vec()) };
+    emit_request_events(tx, id, &parts.headers, &body);
+}
+
 fn emit_response_events(
     tx: &std_mpsc::Sender<ProxyEvent>,
     id: u64,
-    resp: &Response<Incoming>,
+    resp: &http::response::Parts,
     start: &Instant,
 ) {
     let _ = tx.send(ProxyEvent::ResponseStart {
         id,
-        status: resp.status().as_u16(),
-        http_version: version_str(resp.version()),
+        status: resp.status.as_u16(),
+        http_version: version_str(resp.version),
         elapsed_ms: start.elapsed().as_millis() as u64,
     });
-    for (name, value) in resp.headers().iter() {
+    for (name, value) in resp.headers.iter() {
         let _ = tx.send(ProxyEvent::ResponseHeader {
             id,
             name: name.to_string(),
@@ -88,18 +117,219 @@ fn emit_response_events(
     }
 }
 
+/// Returned when a breakpoint drops a request, so that hyper closes the client's connection
+/// instead of answering.
+#[derive(Debug)]
+struct Dropped;
+
+impl std::fmt::Display for Dropped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Dropped at a breakpoint")
+    }
+}
+
+impl std::error::Error for Dropped {}
+
+fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(n, v)| (n.to_string(), String::from_utf8_lossy(v.as_bytes()).into_owned()))
+        .collect()
+}
+
+/// Replace `headers` with edited ones. The framing headers are left out, since the body may
+/// have changed; hyper sets them from the body it is given.
+fn set_headers(headers: &mut HeaderMap, pairs: &[(String, String)]) -> Result<(), BoxError> {
+    headers.clear();
+    for (name, value) in pairs {
+        let name = HeaderName::from_bytes(name.trim().as_bytes())?;
+        if name == CONTENT_LENGTH || name == TRANSFER_ENCODING {
+            continue;
+        }
+        headers.append(name, HeaderValue::from_str(value)?);
+    }
+    Ok(())
+}
+
+enum Intercepted {
+    Forward(Parts, Bytes),
+    Respond(Response<BoxBody>),
+}
+
+/// Pause the request if a breakpoint asks for it, and apply whatever was decided.
+async fn intercept_request(
+    id: u64,
+    mut parts: Parts,
+    body: Bytes,
+    breakpoints: &Breakpoints,
+    event_tx: &std_mpsc::Sender<ProxyEvent>,
+) -> Result<Intercepted, BoxError> {
+    if !breakpoints.pauses_request(parts.method.as_str(), &parts.uri) {
+        return Ok(Intercepted::Forward(parts, body));
+    }
+
+    let paused = Paused::Request(InterceptedRequest {
+        method: parts.method.to_string(),
+        url: parts.uri.to_string(),
+        headers: header_pairs(&parts.headers),
+        body: body.to_vec(),
+    });
+    match breakpoints.pause(id, paused, event_tx).await {
+        Some(Resolution::ContinueRequest(edited)) => {
+            let uri: Uri = edited.url.parse()?;
+            parts.method = Method::from_bytes(edited.method.trim().as_bytes())?;
+            set_headers(&mut parts.headers, &edited.headers)?;
+            // Sending it somewhere else should not keep telling the server it was for the
+            // original host
+            if uri.authority() != parts.uri.authority() {
+                if let Some(authority) = uri.authority() {
+                    parts.headers.insert(HOST, HeaderValue::from_str(authority.as_str())?);
+                }
+            }
+            parts.uri = uri;
+            Ok(Intercepted::Forward(parts, Bytes::from(edited.body)))
+        }
+        Some(Resolution::Respond(response)) => {
+            emit_request(event_tx, id, &parts, &body);
+            let reply = reply(id, response, parts.version, 0, &Instant::now(), event_tx)?;
+            Ok(Intercepted::Respond(reply))
+        }
+        Some(Resolution::Drop) => {
+            emit_request(event_tx, id, &parts, &body);
+            let _ = event_tx.send(ProxyEvent::Error { id, error: Dropped.to_string() });
+            Err(Dropped.into())
+        }
+        Some(Resolution::ContinueResponse(_)) | None => Ok(Intercepted::Forward(parts, body)),
+    }
+}
+
+/// Pass the upstream response on to the client, after the rewrite rules, pausing it first if
+/// a breakpoint asks for it. The body is only buffered when one of them needs it.
+async fn intercept_response(
+    id: u64,
+    method: &Method,
+    uri: &Uri,
+    resp: Response<Incoming>,
+    start: Instant,
+    options: &ProxyOptions,
+    event_tx: std_mpsc::Sender<ProxyEvent>,
+) -> Result<Response<BoxBody>, BoxError> {
+    let (mut parts, body) = resp.into_parts();
+    options.rewrites.rewrite_response_headers(method, uri, &mut parts.headers);
+
+    let pauses = options.breakpoints.pauses_response(method.as_str(), uri);
+    if !pauses && !options.rewrites.rewrites_response_body(method, uri) {
+        emit_response_events(&event_tx, id, &parts, &start);
+        let body = measured_incoming(body, &parts.headers, id, start, event_tx);
+        return Ok(Response::from_parts(parts, body));
+    }
+
+    // Time to the response, not to whenever it was let go
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+    let body = body.collect().await?.to_bytes();
+    let body = options.rewrites.rewrite_response_body(method, uri, &parts.headers, body);
+    let original = InterceptedResponse {
+        status: parts.status.as_u16(),
+        headers: header_pairs(&parts.headers),
+        body: body.to_vec(),
+    };
+    if !pauses {
+        return reply(id, original, parts.version, elapsed_ms, &start, &event_tx);
+    }
+
+    let paused = Paused::Response {
+        method: method.to_string(),
+        url: uri.to_string(),
+        response: original.clone(),
+    };
+    let response = match options.breakpoints.pause(id, paused, &event_tx).await {
+        Some(Resolution::ContinueResponse(r) | Resolution::Respond(r)) => r,
+        Some(Resolution::Drop) => {
+            let _ = event_tx.send(ProxyEvent::Error { id, error: Dropped.to_string() });
+            return Err(Dropped.into());
+        }
+        Some(Resolution::ContinueRequest(_)) | None => original,
+    };
+    reply(id, response, parts.version, elapsed_ms, &start, &event_tx)
+}
+
+/// Answer with a file a rewrite rule mapped the request to.
+async fn serve_local(
+    id: u64,
+    file: LocalFile,
+    version: hyper::Version,
+    event_tx: &std_mpsc::Sender<ProxyEvent>,
+) -> Result<Response<BoxBody>, BoxError> {
+    let start = Instant::now();
+    let body = match tokio::fs::read(&file.path).await {
+        Ok(body) => body,
+        Err(e) => {
+            let error = format!("Failed to read {}: {e}", file.path.display());
+            let _ = event_tx.send(ProxyEvent::Error { id, error: error.clone() });
+            return Err(error.into());
+        }
+    };
+    let response = InterceptedResponse {
+        status: 200,
+        headers: vec![("content-type".to_string(), file.content_type)],
+        body,
+    };
+    reply(id, response, version, 0, &start, event_tx)
+}
+
+/// Answer the client with `response`, recording it as the exchange's response.
+fn reply(
+    id: u64,
+    response: InterceptedResponse,
+    version: hyper::Version,
+    elapsed_ms: u64,
+    start: &Instant,
+    event_tx: &std_mpsc::Sender<ProxyEvent>,
+) -> Result<Response<BoxBody>, BoxError> {
+    let size = response.body.len() as u64;
+    let body = if response.body.is_empty() { None } else { Some(response.body.clone()) };
+
+    let mut resp = Response::new(full_body(Bytes::from(response.body)));
+    *resp.status_mut() = StatusCode::from_u16(response.status)?;
+    set_headers(resp.headers_mut(), &response.headers)?;
+
+    let _ = event_tx.send(ProxyEvent::ResponseStart {
+        id,
+        status: response.status,
+        http_version: version_str(version),
+        elapsed_ms,
+    });
+    for (name, value) in response.headers {
+        let _ = event_tx.send(ProxyEvent::ResponseHeader { id, name, value });
+    }
+    // A buffered body never passes through a measured one, so its events are read here
+    if let Some(body) = body.as_ref().filter(|_| is_event_stream(resp.headers())) {
+        for event in EventStreamParser::new().feed(body) {
+            let _ = event_tx.send(ProxyEvent::ServerSentEvent { id, event });
+        }
+    }
+    let _ = event_tx.send(ProxyEvent::ResponseBodyComplete {
+        id,
+        body,
+        size,
+        elapsed_ms: start.elapsed().as_millis() as u64,
+    });
+    Ok(resp)
+}
+
 pub(crate) async fn handle_request(
     req: Request<Incoming>,
     event_tx: std_mpsc::Sender<ProxyEvent>,
-    ca: Arc<CertificateAuthority>,
-) -> Result<Response<BoxBody>, Infallible> {
+    options: ProxyOptions,
+) -> Result<Response<BoxBody>, BoxError> {
     let result = if req.method() == Method::CONNECT {
-        handle_connect(req, event_tx, ca).await
+        handle_connect(req, event_tx, options).await
     } else {
-        handle_http(req, event_tx).await
+        handle_http(req, event_tx, &options).await
     };
     match result {
         Ok(resp) => Ok(resp),
+        Err(e) if e.is::<Dropped>() => Err(e),
         Err(e) => {
             eprintln!("Proxy error: {e}");
             Ok(Response::builder()
@@ -113,50 +343,31 @@ pub(crate) async fn handle_request(
 async fn handle_http(
     req: Request<Incoming>,
     event_tx: std_mpsc::Sender<ProxyEvent>,
-) -> Result<Response<BoxBody>, Box<dyn std::error::Error + Send + Sync>> {
+    options: &ProxyOptions,
+) -> Result<Response<BoxBody>, BoxError> {
     let id = REQUEST_ID.fetch_add(1, Ordering::Relaxed);
-    let method = req.method().to_string();
-    let uri = req.uri().to_string();
-    let http_version = version_str(req.version());
-    let start = Instant::now();
-
-    let _ = event_tx.send(ProxyEvent::RequestStart { id, method, url: uri.clone(), http_version });
-
-    let client: Client<_, Full<Bytes>> = Client::builder(TokioExecutor::new()).build_http();
-
     let (parts, body) = req.into_parts();
-    let body_bytes = body.collect().await?.to_bytes();
-    let request_body = if body_bytes.is_empty() { None } else { Some(body_bytes.to_vec()) };
-    emit_request_events(&event_tx, id, &parts.headers, &request_body);
-
-    let outgoing_req = Request::from_parts(parts, Full::new(body_bytes));
-
-    match client.request(outgoing_req).await {
-        Ok(resp) => {
-            emit_response_events(&event_tx, id, &resp, &start);
-
-            let (parts, body) = resp.into_parts();
-            Ok(Response::from_parts(parts, measured_incoming(body, id, start, event_tx)))
-        }
-        Err(e) => {
-            let _ = event_tx.send(ProxyEvent::Error { id, error: e.to_string() });
-            Err(Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
-        }
-    }
+    let body = body.collect().await?.to_bytes();
+    forward(id, parts, body, options, event_tx).await
 }
 
 async fn handle_connect(
     req: Request<Incoming>,
     event_tx: std_mpsc::Sender<ProxyEvent>,
-    ca: Arc<CertificateAuthority>,
-) -> Result<Response<BoxBody>, Box<dyn std::error::Error + Send + Sync>> {
+    options: ProxyOptions,
+) -> Result<Response<BoxBody>, BoxError> {
     let authority = req.uri().authority().map(|a| a.to_string()).unwrap_or_default();
     let (host, port) = parse_host_port(&authority);
 
-    let server_config = ca.server_config(&host)?;
+    if !options.filter.intercepts(&host) {
+        return tunnel(req, host, port, options.upstream.clone());
+    }
+
+    let server_config = options.ca.server_config(&host)?;
     let acceptor = TlsAcceptor::from(server_config);
 
-    let target_addr = format!("{host}:{port}");
+    // What the tunneled requests are for, as it goes in their URLs
+    let authority = if port == 443 { host.clone() } else { format!("{host}:{port}") };
 
     tokio::spawn(async move {
         let upgraded = match hyper::upgrade::on(req).await {
@@ -176,7 +387,6 @@ async fn handle_connect(
         };
 
         let tx = event_tx.clone();
-        let host_for_requests = host.clone();
         let mut builder = auto::Builder::new(TokioExecutor::new());
         builder.http1().preserve_header_case(true).title_case_headers(true);
         if let Err(e) = builder
@@ -184,9 +394,9 @@ async fn handle_connect(
                 hyper_util::rt::TokioIo::new(tls_stream),
                 service_fn(move |req| {
                     let tx = tx.clone();
-                    let host = host_for_requests.clone();
-                    let target_addr = target_addr.clone();
-                    async move { handle_tunneled_request(req, tx, &host, &target_addr).await }
+                    let authority = authority.clone();
+                    let options = options.clone();
+                    async move { handle_tunneled_request(req, tx, &authority, &options).await }
                 }),
             )
             .await
@@ -198,15 +408,48 @@ async fn handle_connect(
     Ok(Response::new(full_body(Bytes::new())))
 }
 
+/// Pass the client's tunnel through to `host` untouched, TLS and all.
+fn tunnel(
+    req: Request<Incoming>,
+    host: String,
+    port: u16,
+    upstream: Arc<UpstreamProxy>,
+) -> Result<Response<BoxBody>, BoxError> {
+    tokio::spawn(async move {
+        let upgraded = match hyper::upgrade::on(req).await {
+            Ok(u) => u,
+            Err(e) => {
+                eprintln!("CONNECT upgrade failed: {e}");
+                return;
+            }
+        };
+        let mut upstream = match upstream.connect(&host, port, true).await {
+            Ok(Connected::Origin(s)) => s,
+            Ok(Connected::HttpProxy(..)) => unreachable!("TLS is always tunneled"),
+            Err(e) => {
+                eprintln!("Tunnel to {host}:{port} failed: {e}");
+                return;
+            }
+        };
+        let mut client = hyper_util::rt::TokioIo::new(upgraded);
+        if let Err(e) = tokio::io::copy_bidirectional(&mut client, &mut upstream).await {
+            eprintln!("Tunnel to {host}:{port} closed: {e}");
+        }
+    });
+
+    Ok(Response::new(full_body(Bytes::new())))
+}
+
 async fn handle_tunneled_request(
     req: Request<Incoming>,
     event_tx: std_mpsc::Sender<ProxyEvent>,
-    host: &str,
-    target_addr: &str,
-) -> Result<Response<BoxBody>, Infallible> {
-    let result = forward_https(req, event_tx, host, target_addr).await;
+    authority: &str,
+    options: &ProxyOptions,
+) -> Result<Response<BoxBody>, BoxError> {
+    let result = forward_https(req, event_tx, authority, options).await;
     match result {
         Ok(resp) => Ok(resp),
+        Err(e) if e.is::<Dropped>() => Err(e),
         Err(e) => {
             eprintln!("HTTPS forward error: {e:?}");
             Ok(Response::builder()
@@ -234,24 +477,35 @@ impl HttpSender {
     }
 }
 
-async fn forward_https(
-    req: Request<Incoming>,
-    event_tx: std_mpsc::Sender<ProxyEvent>,
-    host: &str,
-    target_addr: &str,
-) -> Result<Response<BoxBody>, Box<dyn std::error::Error + Send + Sync>> {
-    let id = REQUEST_ID.fetch_add(1, Ordering::Relaxed);
-    let method = req.method().to_string();
-    let http_version = version_str(req.version());
-    let path = req.uri().path_and_query().map(|pq| pq.to_string()).unwrap_or_else(|| "/".into());
-    let uri_str = format!("https://{host}{path}");
-    let start = Instant::now();
-
-    let _ =
-        event_tx.send(ProxyEvent::RequestStart { id, method, url: uri_str.clone(), http_version });
+/// Who is on the other end of an upstream connection.
+enum Peer {
+    Origin,
+    /// An HTTP proxy, which wants absolute URLs and possibly credentials.
+    Proxy(Option<HeaderValue>),
+}
 
-    // Connect to upstream with TLS
-    let tcp_stream = TcpStream::connect(target_addr).await?;
+/// Open a connection to the origin of `uri`, through the upstream proxy if there is one. That
+/// is normally where the client's tunnel went, unless a breakpoint sent the request elsewhere,
+/// possibly over plain HTTP. Upgrades need HTTP/1.1, so `http1_only` keeps HTTP/2 off the
+/// table.
+async fn connect_upstream(
+    uri: &Uri,
+    upstream: &UpstreamProxy,
+    http1_only: bool,
+) -> Result<(HttpSender, Peer), BoxError> {
+    let host = uri.host().ok_or("The request URL has no host")?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let tls = uri.scheme() != Some(&Scheme::HTTP);
+    let port = uri.port_u16().unwrap_or(if tls { 443 } else { 80 });
+
+    let (tcp_stream, peer) = match upstream.connect(host, port, tls).await? {
+        Connected::Origin(s) => (s, Peer::Origin),
+        Connected::HttpProxy(s, authorization) => (s, Peer::Proxy(authorization)),
+    };
+    if !tls {
+        let sender = handshake_h1(hyper_util::rt::TokioIo::new(tcp_stream)).await?;
+        return Ok((sender, peer));
+    }
 
     let mut root_store = rustls::RootCertStore::empty();
     for cert in rustls_native_certs::load_native_certs().certs {
@@ -263,7 +517,11 @@ async fn forward_https(
             .with_safe_default_protocol_versions()?
             .with_root_certificates(root_store)
             .with_no_client_auth();
-    tls_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    tls_config.alpn_protocols = if http1_only {
+        vec![b"http/1.1".to_vec()]
+    } else {
+        vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+    };
 
     let connector = tokio_rustls::TlsConnector::from(Arc::new(tls_config));
     let server_name = ServerName::try_from(host.to_string())?;
@@ -273,7 +531,7 @@ async fn forward_https(
 
     let io = hyper_util::rt::TokioIo::new(tls_stream);
 
-    let mut sender = if negotiated_h2 {
+    if negotiated_h2 {
         let (sender, conn) =
             hyper::client::conn::http2::Builder::new(TokioExecutor::new()).handshake(io).await?;
         tokio::spawn(async move {
@@ -281,50 +539,118 @@ async fn forward_https(
                 eprintln!("Upstream h2 connection error: {e}");
             }
         });
-        HttpSender::H2(sender)
+        Ok((HttpSender::H2(sender), peer))
     } else {
-        let (sender, conn) = hyper::client::conn::http1::Builder::new()
-            .preserve_header_case(true)
-            .title_case_headers(true)
-            .handshake(io)
-            .await?;
-        tokio::spawn(async move {
-            if let Err(e) = conn.await {
-                eprintln!("Upstream h1 connection error: {e}");
-            }
-        });
-        HttpSender::H1(sender)
-    };
+        Ok((handshake_h1(io).await?, peer))
+    }
+}
+
+async fn handshake_h1<I>(io: I) -> Result<HttpSender, BoxError>
+where
+    I: hyper::rt::Read + hyper::rt::Write + Unpin + Send + 'static,
+{
+    let (sender, conn) = hyper::client::conn::http1::Builder::new()
+        .preserve_header_case(true)
+        .title_case_headers(true)
+        .handshake(io)
+        .await?;
+    tokio::spawn(async move {
+        if let Err(e) = conn.with_upgrades().await {
+            eprintln!("Upstream h1 connection error: {e}");
+        }
+    });
+    Ok(HttpSender::H1(sender))
+}
+
+async fn forward_https(
+    req: Request<Incoming>,
+    event_tx: std_mpsc::Sender<ProxyEvent>,
+    authority: &str,
+    options: &ProxyOptions,
+) -> Result<Response<BoxBody>, BoxError> {
+    let id = REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+    let path = req.uri().path_and_query().map(|pq| pq.to_string()).unwrap_or_else(|| "/".into());
 
-    // Capture request metadata
     let (mut parts, body) = req.into_parts();
-    let body_bytes = body.collect().await?.to_bytes();
-    let request_body = if body_bytes.is_empty() { None } else { Some(body_bytes.to_vec()) };
-    emit_request_events(&event_tx, id, &parts.headers, &request_body);
+    let body = body.collect().await?.to_bytes();
+    // Absolute, so that rules see where the request is going
+    parts.uri = format!("https://{authority}{path}").parse::<Uri>()?;
+    if !parts.headers.contains_key(HOST) {
+        parts.headers.insert(HOST, authority.parse()?);
+    }
 
-    if negotiated_h2 {
-        // HTTP/2 requires absolute-form URI with scheme + authority
-        parts.uri = uri_str.parse::<Uri>()?;
-    } else {
-        parts.uri = path.parse::<Uri>()?;
+    forward(id, parts, body, options, event_tx).await
+}
+
+/// Send a request with an absolute URL upstream, once the rewrite rules and breakpoints have
+/// had their say, and pass the response back the same way.
+async fn forward(
+    id: u64,
+    mut parts: Parts,
+    mut body: Bytes,
+    options: &ProxyOptions,
+    event_tx: std_mpsc::Sender<ProxyEvent>,
+) -> Result<Response<BoxBody>, BoxError> {
+    if let Some(file) = options.rewrites.rewrite_request(&mut parts, &mut body) {
+        emit_request(&event_tx, id, &parts, &body);
+        return serve_local(id, file, parts.version, &event_tx).await;
     }
 
-    if !parts.headers.contains_key(hyper::header::HOST) {
-        parts.headers.insert(hyper::header::HOST, host.parse()?);
+    let intercepted = intercept_request(id, parts, body, &options.breakpoints, &event_tx).await?;
+    let (mut parts, body) = match intercepted {
+        Intercepted::Forward(parts, body) => (parts, body),
+        Intercepted::Respond(resp) => return Ok(resp),
+    };
+
+    let client_upgrade = websocket::take_upgrade(&mut parts);
+    let start = Instant::now();
+    emit_request(&event_tx, id, &parts, &body);
+
+    let connected = connect_upstream(&parts.uri, &options.upstream, client_upgrade.is_some());
+    let (mut sender, peer) = match connected.await {
+        Ok(connected) => connected,
+        Err(e) => {
+            let _ = event_tx.send(ProxyEvent::Error { id, error: e.to_string() });
+            return Err(e);
+        }
+    };
+
+    let (method, uri) = (parts.method.clone(), parts.uri.clone());
+    match (&sender, peer) {
+        // Proxies take the absolute URL, which is already there
+        (_, Peer::Proxy(authorization)) => {
+            if let Some(authorization) = authorization {
+                parts.headers.insert(PROXY_AUTHORIZATION, authorization);
+            }
+        }
+        // HTTP/2 requires absolute-form URI with scheme + authority; HTTP/1.1 wants the path
+        (HttpSender::H1(_), Peer::Origin) => {
+            let path = uri.path_and_query().map(|pq| pq.as_str()).unwrap_or("/");
+            parts.uri = path.parse::<Uri>()?;
+        }
+        (HttpSender::H2(_), Peer::Origin) => {}
     }
 
-    let outgoing = Request::from_parts(parts, Full::new(body_bytes));
+    let outgoing = Request::from_parts(parts, Full::new(body));
 
     match sender.send_request(outgoing).await {
-        Ok(resp) => {
-            emit_response_events(&event_tx, id, &resp, &start);
-
-            let (parts, body) = resp.into_parts();
-            Ok(Response::from_parts(parts, measured_incoming(body, id, start, event_tx)))
+        Ok(mut resp) => {
+            if let Some(client_upgrade) = client_upgrade
+                && resp.status() == StatusCode::SWITCHING_PROTOCOLS
+            {
+                let server_upgrade = hyper::upgrade::on(&mut resp);
+                tokio::spawn(websocket::relay(
+                    id,
+                    client_upgrade,
+                    server_upgrade,
+                    event_tx.clone(),
+                ));
+            }
+            intercept_response(id, &method, &uri, resp, start, options, event_tx).await
         }
         Err(e) => {
             let _ = event_tx.send(ProxyEvent::Error { id, error: e.to_string() });
-            Err(Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+            Err(Box::new(e) as BoxError)
         }
     }
 }
@@ -337,3 +663,31 @@ fn parse_host_port(authority: &str) -> (String, u16) {
     }
     (authority.to_string(), 443)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_the_events_of_a_buffered_event_stream() {
+        let (tx, rx) = std_mpsc::channel();
+        let response = InterceptedResponse {
+            status: 200,
+            headers: vec![("content-type".to_string(), "text/event-stream".to_string())],
+            body: b"event: ping\ndata: 1\n\ndata: 2\n\n".to_vec(),
+        };
+        reply(7, response, hyper::Version::HTTP_11, 0, &Instant::now(), &tx).unwrap();
+        drop(tx);
+
+        let events: Vec<_> = rx
+            .iter()
+            .filter_map(|e| match e {
+                ProxyEvent::ServerSentEvent { id: 7, event } => Some(event),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, "ping");
+        assert_eq!(events[1].data, "2");
+    }
+}
@@ -0,0 +1,398 @@
+//! Rules that change traffic in flight, without anyone stepping in.
+//!
+//! Rules apply in order, and each one is matched against the request as the client sent
+//! it, so one rule changing the URL does not decide whether the next applies. Requests are
+//! rewritten before breakpoints see them, responses before they pause.
+
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use bytes::Bytes;
+use http::request::Parts;
+use hyper::header::{CONTENT_LENGTH, HOST, HeaderMap, HeaderName, HeaderValue};
+use hyper::{Method, Uri};
+use regex::bytes::Regex;
+
+use crate::pattern::UrlPattern;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RewriteTarget {
+    Request,
+    Response,
+}
+
+#[derive(Debug, Clone)]
+pub enum RewriteAction {
+    /// Add a header, keeping any others with the same name.
+    AddHeader {
+        target: RewriteTarget,
+        name: String,
+        value: String,
+    },
+    /// Replace all headers with this name by a single one.
+    SetHeader {
+        target: RewriteTarget,
+        name: String,
+        value: String,
+    },
+    RemoveHeader {
+        target: RewriteTarget,
+        name: String,
+    },
+    /// Replace every match of a regex in the body. `replacement` may refer to groups as `$1`.
+    /// Compressed bodies are left alone.
+    ReplaceBody {
+        target: RewriteTarget,
+        pattern: String,
+        replacement: String,
+    },
+    /// Send the request to another origin, like `http://localhost:3000`. A path on it is put
+    /// in front of the request's own.
+    MapRemote {
+        url: String,
+    },
+    /// Answer with the contents of a local file instead of going upstream. The content type
+    /// is guessed from the extension when none is given.
+    MapLocal {
+        path: PathBuf,
+        content_type: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Rewrite {
+    pub pattern: UrlPattern,
+    pub action: RewriteAction,
+}
+
+impl Rewrite {
+    /// Check that the rule can be applied: valid header names and values, regex and URL.
+    pub fn validate(&self) -> Result<(), String> {
+        compile(&self.action).map(|_| ())
+    }
+}
+
+/// A local file to answer a request with.
+pub(crate) struct LocalFile {
+    pub path: PathBuf,
+    pub content_type: String,
+}
+
+/// The rewrite rules the proxy applies. Shared with whoever edits them, so changes apply to
+/// a running proxy.
+#[derive(Default)]
+pub struct Rewrites {
+    rules: RwLock<Vec<Compiled>>,
+}
+
+impl Rewrites {
+    /// Replace the rules. Invalid ones, which [`Rewrite::validate`] rejects, are skipped.
+    pub fn set_rules(&self, rules: Vec<Rewrite>) {
+        let compiled = rules
+            .into_iter()
+            .filter_map(|r| Some(Compiled { pattern: r.pattern, action: compile(&r.action).ok()? }))
+            .collect();
+        *self.rules.write().unwrap_or_else(|e| e.into_inner()) = compiled;
+    }
+
+    /// Rewrite a request before it goes upstream. Returns the file to answer with instead, if
+    /// a rule maps the request to one.
+    pub(crate) fn rewrite_request(&self, parts: &mut Parts, body: &mut Bytes) -> Option<LocalFile> {
+        let (method, uri) = (parts.method.clone(), parts.uri.clone());
+        for rule in self.matching(&method, &uri) {
+            match rule.action {
+                Action::Headers(RewriteTarget::Request, op) => op.apply(&mut parts.headers),
+                Action::Body(RewriteTarget::Request, ref pattern, ref replacement) => {
+                    if !parts.headers.contains_key(hyper::header::CONTENT_ENCODING) {
+                        *body = Bytes::from(
+                            pattern.replace_all(&body[..], &replacement[..]).into_owned(),
+                        );
+                        // The body is sent as is, so a length the client gave must follow it
+                        if parts.headers.contains_key(CONTENT_LENGTH) {
+                            parts.headers.insert(CONTENT_LENGTH, HeaderValue::from(body.len()));
+                        }
+                    }
+                }
+                Action::MapRemote(ref origin) => map_remote(parts, origin),
+                Action::MapLocal(ref path, ref content_type) => {
+                    return Some(LocalFile {
+                        path: path.clone(),
+                        content_type: content_type.clone(),
+                    });
+                }
+                Action::Headers(RewriteTarget::Response, _)
+                | Action::Body(RewriteTarget::Response, ..) => {}
+            }
+        }
+        None
+    }
+
+    /// Whether responses to this request have their body rewritten, so must be buffered.
+    pub(crate) fn rewrites_response_body(&self, method: &Method, uri: &Uri) -> bool {
+        self.matching(method, uri)
+            .iter()
+            .any(|r| matches!(r.action, Action::Body(RewriteTarget::Response, ..)))
+    }
+
+    pub(crate) fn rewrite_response_headers(
+        &self,
+        method: &Method,
+        uri: &Uri,
+        headers: &mut HeaderMap,
+    ) {
+        for rule in self.matching(method, uri) {
+            if let Action::Headers(RewriteTarget::Response, op) = rule.action {
+                op.apply(headers);
+            }
+        }
+    }
+
+    pub(crate) fn rewrite_response_body(
+        &self,
+        method: &Method,
+        uri: &Uri,
+        headers: &HeaderMap,
+        body: Bytes,
+    ) -> Bytes {
+        if headers.contains_key(hyper::header::CONTENT_ENCODING) {
+            return body;
+        }
+        let mut body = body;
+        for rule in self.matching(method, uri) {
+            if let Action::Body(RewriteTarget::Response, pattern, replacement) = &rule.action {
+                body = Bytes::from(pattern.replace_all(&body[..], &replacement[..]).into_owned());
+            }
+        }
+        body
+    }
+
+    fn matching(&self, method: &Method, uri: &Uri) -> Vec<Compiled> {
+        let rules = self.rules.read().unwrap_or_else(|e| e.into_inner());
+        rules.iter().filter(|r| r.pattern.matches(method.as_str(), uri)).cloned().collect()
+    }
+}
+
+#[derive(Clone)]
+struct Compiled {
+    pattern: UrlPattern,
+    action: Action,
+}
+
+#[derive(Clone)]
+enum Action {
+    Headers(RewriteTarget, HeaderOp),
+    Body(RewriteTarget, Regex, Vec<u8>),
+    MapRemote(Uri),
+    MapLocal(PathBuf, String),
+}
+
+#[derive(Clone)]
+enum HeaderOp {
+    Add(HeaderName, HeaderValue),
+    Set(HeaderName, HeaderValue),
+    Remove(HeaderName),
+}
+
+impl HeaderOp {
+    fn apply(self, headers: &mut HeaderMap) {
+        match self {
+            HeaderOp::Add(name, value) => {
+                headers.append(name, value);
+            }
+            HeaderOp::Set(name, value) => {
+                headers.insert(name, value);
+            }
+            HeaderOp::Remove(name) => {
+                headers.remove(name);
+            }
+        }
+    }
+}
+
+fn compile(action: &RewriteAction) -> Result<Action, String> {
+    let name = |n: &str| {
+        HeaderName::from_bytes(n.trim().as_bytes())
+            .map_err(|_| format!("Invalid header name {n:?}"))
+    };
+    let value =
+        |v: &str| HeaderValue::from_str(v).map_err(|_| format!("Invalid header value {v:?}"));
+    Ok(match action {
+        RewriteAction::AddHeader { target, name: n, value: v } => {
+            Action::Headers(*target, HeaderOp::Add(name(n)?, value(v)?))
+        }
+        RewriteAction::SetHeader { target, name: n, value: v } => {
+            Action::Headers(*target, HeaderOp::Set(name(n)?, value(v)?))
+        }
+        RewriteAction::RemoveHeader { target, name: n } => {
+            Action::Headers(*target, HeaderOp::Remove(name(n)?))
+        }
+        RewriteAction::ReplaceBody { target, pattern, replacement } => {
+            let regex = Regex::new(pattern).map_err(|e| format!("Invalid regex: {e}"))?;
+            Action::Body(*target, regex, replacement.as_bytes().to_vec())
+        }
+        RewriteAction::MapRemote { url } => {
+            let origin: Uri =
+                url.trim().parse().map_err(|e| format!("Invalid URL {url:?}: {e}"))?;
+            if origin.scheme().is_none() || origin.authority().is_none() {
+                return Err(format!("{url:?} needs a scheme and a host"));
+            }
+            Action::MapRemote(origin)
+        }
+        RewriteAction::MapLocal { path, content_type } => {
+            let content_type = match content_type.trim() {
+                "" => guess_content_type(path).to_string(),
+                t => t.to_string(),
+            };
+            value(&content_type)?;
+            Action::MapLocal(path.clone(), content_type)
+        }
+    })
+}
+
+fn map_remote(parts: &mut Parts, origin: &Uri) {
+    let prefix = origin.path().trim_end_matches('/');
+    let path = parts.uri.path_and_query().map(|pq| pq.as_str()).unwrap_or("/");
+    let mut uri = Uri::builder().path_and_query(format!("{prefix}{path}"));
+    if let (Some(scheme), Some(authority)) = (origin.scheme(), origin.authority()) {
+        uri = uri.scheme(scheme.clone()).authority(authority.clone());
+    }
+    if let Ok(uri) = uri.build() {
+        if let Some(authority) = uri.authority() {
+            if let Ok(host) = HeaderValue::from_str(authority.as_str()) {
+                parts.headers.insert(HOST, host);
+            }
+        }
+        parts.uri = uri;
+    }
+}
+
+fn guess_content_type(path: &std::path::Path) -> &'static str {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+    match extension.to_ascii_lowercase().as_str() {
+        "json" => "application/json",
+        "html" | "htm" => "text/html; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "xml" => "application/xml",
+        "txt" => "text/plain; charset=utf-8",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(url: &str) -> Parts {
+        let (mut parts, _) = hyper::Request::builder().uri(url).body(()).unwrap().into_parts();
+        parts.headers.insert(HOST, HeaderValue::from_static("api.example.com"));
+        parts
+    }
+
+    fn rule(host: &str, action: RewriteAction) -> Rewrite {
+        Rewrite { pattern: UrlPattern { host: host.into(), ..Default::default() }, action }
+    }
+
+    #[test]
+    fn rewrites_request_headers_and_body() {
+        let rewrites = Rewrites::default();
+        rewrites.set_rules(vec![
+            rule(
+                "",
+                RewriteAction::SetHeader {
+                    target: RewriteTarget::Request,
+                    name: "X-Env".into(),
+                    value: "test".into(),
+                },
+            ),
+            rule(
+                "other.example.com",
+                RewriteAction::RemoveHeader { target: RewriteTarget::Request, name: "host".into() },
+            ),
+            rule(
+                "",
+                RewriteAction::ReplaceBody {
+                    target: RewriteTarget::Request,
+                    pattern: r#""id":(\d+)"#.into(),
+                    replacement: r#""id":"$1""#.into(),
+                },
+            ),
+        ]);
+
+        let mut parts = request("https://api.example.com/users");
+        let mut body = Bytes::from_static(br#"{"id":42}"#);
+        assert!(rewrites.rewrite_request(&mut parts, &mut body).is_none());
+        assert_eq!(parts.headers["x-env"], "test");
+        assert_eq!(parts.headers[HOST], "api.example.com");
+        assert_eq!(body, Bytes::from_static(br#"{"id":"42"}"#));
+    }
+
+    #[test]
+    fn keeps_the_content_length_in_step_with_a_rewritten_body() {
+        let rewrites = Rewrites::default();
+        let replace = |pattern: &str, replacement: &str| {
+            rule(
+                "",
+                RewriteAction::ReplaceBody {
+                    target: RewriteTarget::Request,
+                    pattern: pattern.into(),
+                    replacement: replacement.into(),
+                },
+            )
+        };
+
+        rewrites.set_rules(vec![replace("short", "much longer")]);
+        let mut parts = request("https://api.example.com/users");
+        parts.headers.insert(CONTENT_LENGTH, HeaderValue::from_static("7"));
+        let mut body = Bytes::from_static(b"a short");
+        rewrites.rewrite_request(&mut parts, &mut body);
+        assert_eq!(body, Bytes::from_static(b"a much longer"));
+        assert_eq!(parts.headers[CONTENT_LENGTH], "13");
+
+        rewrites.set_rules(vec![replace("much longer", "tiny")]);
+        rewrites.rewrite_request(&mut parts, &mut body);
+        assert_eq!(body, Bytes::from_static(b"a tiny"));
+        assert_eq!(parts.headers[CONTENT_LENGTH], "6");
+
+        // A chunked body keeps being sent without a length
+        let mut parts = request("https://api.example.com/users");
+        rewrites.rewrite_request(&mut parts, &mut Bytes::from_static(b"much longer"));
+        assert!(!parts.headers.contains_key(CONTENT_LENGTH));
+    }
+
+    #[test]
+    fn maps_remote_and_local() {
+        let rewrites = Rewrites::default();
+        rewrites.set_rules(vec![rule(
+            "api.example.com",
+            RewriteAction::MapRemote { url: "http://localhost:3000/v2/".into() },
+        )]);
+        let mut parts = request("https://api.example.com/users?page=2");
+        rewrites.rewrite_request(&mut parts, &mut Bytes::new());
+        assert_eq!(parts.uri, "http://localhost:3000/v2/users?page=2");
+        assert_eq!(parts.headers[HOST], "localhost:3000");
+
+        rewrites.set_rules(vec![rule(
+            "",
+            RewriteAction::MapLocal { path: "fixtures/users.json".into(), content_type: "".into() },
+        )]);
+        let local = rewrites.rewrite_request(&mut request("https://a.test/"), &mut Bytes::new());
+        assert_eq!(local.unwrap().content_type, "application/json");
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        let bad_regex = RewriteAction::ReplaceBody {
+            target: RewriteTarget::Response,
+            pattern: "(".into(),
+            replacement: "".into(),
+        };
+        assert!(rule("", bad_regex).validate().is_err());
+        assert!(rule("", RewriteAction::MapRemote { url: "/relative".into() }).validate().is_err());
+    }
+}
@@ -0,0 +1,346 @@
+//! Reaching origins through another proxy, for networks that only let traffic out that way.
+//!
+//! HTTPS origins are tunneled through the HTTPS proxy with `CONNECT`, plain HTTP requests are
+//! sent to the HTTP proxy in absolute form, and the SOCKS proxy covers whichever of the two
+//! has no proxy of its own. Hosts on the bypass list are always reached directly.
+
+use std::net::IpAddr;
+use std::sync::RwLock;
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use hyper::header::HeaderValue;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The most a proxy's answer to `CONNECT` may take, headers and all.
+const MAX_CONNECT_RESPONSE: usize = 16 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpstreamAuth {
+    pub user: String,
+    pub password: String,
+}
+
+/// Where to send traffic on, in the shape of the client app's proxy setting. Empty addresses
+/// are not used; they may be `host:port` or URLs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UpstreamConfig {
+    pub http: String,
+    pub https: String,
+    /// `socks5://` resolves host names here, `socks5h://` (the default) on the proxy.
+    pub socks: String,
+    pub auth: Option<UpstreamAuth>,
+    /// Hosts to reach directly, separated by commas, as in `NO_PROXY`. `example.com` also
+    /// covers its subdomains, and `*` covers everything.
+    pub bypass: String,
+}
+
+impl UpstreamConfig {
+    /// Check that the proxy addresses can be used, so a bad one is refused when it is saved.
+    pub fn validate(&self) -> Result<(), String> {
+        for (address, default_scheme) in [
+            (&self.http, "http"),
+            (&self.https, "http"),
+            (&self.socks, "socks5h"),
+        ] {
+            if !address.trim().is_empty() {
+                proxy_address(address, default_scheme)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A connection on the way to an origin.
+pub(crate) enum Connected {
+    /// To the origin itself, directly or through a tunnel.
+    Origin(TcpStream),
+    /// To an HTTP proxy that takes plain HTTP requests in absolute form, with the
+    /// `Proxy-Authorization` they need.
+    HttpProxy(TcpStream, Option<HeaderValue>),
+}
+
+/// The upstream proxy, if any. Shared with whoever edits it, so changes apply to a running
+/// proxy from its next connection on.
+#[derive(Default)]
+pub struct UpstreamProxy {
+    config: RwLock<Option<UpstreamConfig>>,
+}
+
+impl UpstreamProxy {
+    pub fn set_config(&self, config: Option<UpstreamConfig>) {
+        *self.config.write().unwrap_or_else(|e| e.into_inner()) = config;
+    }
+
+    /// Connect towards `host:port`, through the proxy that covers it.
+    pub(crate) async fn connect(
+        &self,
+        host: &str,
+        port: u16,
+        tls: bool,
+    ) -> Result<Connected, BoxError> {
+        let config = self.config.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let Some(config) = config.filter(|c| !bypassed(&c.bypass, host)) else {
+            return Ok(Connected::Origin(TcpStream::connect((host, port)).await?));
+        };
+        let auth = config.auth.as_ref();
+
+        let http_proxy = if tls { &config.https } else { &config.http };
+        if !http_proxy.trim().is_empty() {
+            let (_, proxy_host, proxy_port) = proxy_address(http_proxy, "http")?;
+            let mut stream = TcpStream::connect((proxy_host.as_str(), proxy_port)).await?;
+            let authorization = auth.map(basic_auth).transpose()?;
+            if !tls {
+                return Ok(Connected::HttpProxy(stream, authorization));
+            }
+            http_connect(&mut stream, host, port, authorization.as_ref()).await?;
+            return Ok(Connected::Origin(stream));
+        }
+
+        if !config.socks.trim().is_empty() {
+            let (scheme, proxy_host, proxy_port) = proxy_address(&config.socks, "socks5h")?;
+            let mut stream = TcpStream::connect((proxy_host.as_str(), proxy_port)).await?;
+            socks5_connect(&mut stream, host, port, auth, scheme != "socks5").await?;
+            return Ok(Connected::Origin(stream));
+        }
+
+        Ok(Connected::Origin(TcpStream::connect((host, port)).await?))
+    }
+}
+
+/// The scheme, host and port of a proxy address like `proxy.corp:3128` or
+/// `socks5://bastion:1080`.
+fn proxy_address(address: &str, default_scheme: &str) -> Result<(String, String, u16), String> {
+    let address = address.trim();
+    let (scheme, rest) = match address.split_once("://") {
+        Some((scheme, rest)) => (scheme.to_ascii_lowercase(), rest),
+        None => (default_scheme.to_string(), address),
+    };
+    let default_port = match scheme.as_str() {
+        "http" => 80,
+        "socks5" | "socks5h" => 1080,
+        // The connection to the proxy itself is always plain TCP
+        "https" => {
+            return Err(format!(
+                "Proxies reached over HTTPS are not supported, use http:// instead in {address:?}"
+            ));
+        }
+        _ => return Err(format!("Unsupported proxy scheme in {address:?}")),
+    };
+
+    // Credentials go in the auth setting, and a trailing slash means nothing
+    let rest = rest.rsplit_once('@').map(|(_, r)| r).unwrap_or(rest).trim_end_matches('/');
+    let (host, port) = match rest.rsplit_once(':') {
+        Some((host, port)) if !host.ends_with(':') => {
+            let port = port.parse().map_err(|_| format!("Invalid proxy port in {address:?}"))?;
+            (host, port)
+        }
+        _ => (rest, default_port),
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        return Err(format!("No proxy host in {address:?}"));
+    }
+    Ok((scheme, host.to_string(), port))
+}
+
+fn bypassed(bypass: &str, host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']').to_ascii_lowercase();
+    bypass.split(',').map(|e| e.trim().to_ascii_lowercase()).filter(|e| !e.is_empty()).any(|e| {
+        if e == "*" {
+            return true;
+        }
+        if e.parse::<IpAddr>().is_ok() {
+            return e == host;
+        }
+        let domain = e.trim_start_matches('*').trim_start_matches('.');
+        host == domain || host.ends_with(&format!(".{domain}"))
+    })
+}
+
+fn basic_auth(auth: &UpstreamAuth) -> Result<HeaderValue, BoxError> {
+    let credentials = BASE64_STANDARD.encode(format!("{}:{}", auth.user, auth.password));
+    Ok(HeaderValue::from_str(&format!("Basic {credentials}"))?)
+}
+
+/// Ask an HTTP proxy for a tunnel to `host:port`.
+async fn http_connect(
+    stream: &mut TcpStream,
+    host: &str,
+    port: u16,
+    authorization: Option<&HeaderValue>,
+) -> Result<(), BoxError> {
+    let target =
+        if host.contains(':') { format!("[{host}]:{port}") } else { format!("{host}:{port}") };
+    let mut request = format!("CONNECT {target} HTTP/1.1\r\nHost: {target}\r\n");
+    if let Some(authorization) = authorization {
+        request.push_str(&format!("Proxy-Authorization: {}\r\n", authorization.to_str()?));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read a byte at a time, so nothing the origin sends after the headers is taken
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_CONNECT_RESPONSE {
+            return Err("The upstream proxy's response to CONNECT is too large".into());
+        }
+        let byte = stream.read_u8().await?;
+        response.push(byte);
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some("200") => Ok(()),
+        _ => Err(format!("The upstream proxy refused the tunnel: {status_line}").into()),
+    }
+}
+
+/// Open a SOCKS5 connection to `host:port`. With `remote_dns`, the proxy resolves the name.
+async fn socks5_connect(
+    stream: &mut TcpStream,
+    host: &str,
+    port: u16,
+    auth: Option<&UpstreamAuth>,
+    remote_dns: bool,
+) -> Result<(), BoxError> {
+    // Offer no authentication, and username/password when we have them
+    let methods: &[u8] = if auth.is_some() { &[0x00, 0x02] } else { &[0x00] };
+    stream.write_all(&[&[0x05, methods.len() as u8], methods].concat()).await?;
+    let mut choice = [0u8; 2];
+    stream.read_exact(&mut choice).await?;
+    match (choice[1], auth) {
+        (0x00, _) => {}
+        (0x02, Some(auth)) => {
+            let (user, password) = (auth.user.as_bytes(), auth.password.as_bytes());
+            if user.len() > 255 || password.len() > 255 {
+                return Err("SOCKS credentials are too long".into());
+            }
+            let mut request = vec![0x01, user.len() as u8];
+            request.extend_from_slice(user);
+            request.push(password.len() as u8);
+            request.extend_from_slice(password);
+            stream.write_all(&request).await?;
+            let mut status = [0u8; 2];
+            stream.read_exact(&mut status).await?;
+            if status[1] != 0x00 {
+                return Err("The SOCKS proxy rejected the credentials".into());
+            }
+        }
+        _ => return Err("The SOCKS proxy accepts none of our authentication methods".into()),
+    }
+
+    let mut request = vec![0x05, 0x01, 0x00];
+    let ip = match host.parse::<IpAddr>() {
+        Ok(ip) => Some(ip),
+        Err(_) if remote_dns => None,
+        Err(_) => {
+            let mut addrs = tokio::net::lookup_host((host, port)).await?;
+            Some(addrs.next().ok_or_else(|| format!("Failed to resolve {host}"))?.ip())
+        }
+    };
+    match ip {
+        Some(IpAddr::V4(ip)) => {
+            request.push(0x01);
+            request.extend_from_slice(&ip.octets());
+        }
+        Some(IpAddr::V6(ip)) => {
+            request.push(0x04);
+            request.extend_from_slice(&ip.octets());
+        }
+        None => {
+            if host.len() > 255 {
+                return Err("The host name is too long for SOCKS".into());
+            }
+            request.extend_from_slice(&[0x03, host.len() as u8]);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] != 0x00 {
+        return Err(format!("The SOCKS proxy failed to connect (code {})", reply[1]).into());
+    }
+    // Skip the address the proxy bound, which we have no use for
+    let address_len = match reply[3] {
+        0x01 => 4,
+        0x04 => 16,
+        0x03 => stream.read_u8().await? as usize,
+        _ => return Err("The SOCKS proxy sent an unknown address type".into()),
+    };
+    let mut bound = vec![0u8; address_len + 2];
+    stream.read_exact(&mut bound).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_proxy_addresses() {
+        let parsed = |a| proxy_address(a, "http");
+        assert_eq!(parsed("proxy.corp:3128"), Ok(("http".into(), "proxy.corp".into(), 3128)));
+        assert_eq!(parsed("http://user@proxy.corp/"), Ok(("http".into(), "proxy.corp".into(), 80)));
+        assert_eq!(parsed("socks5://[::1]:9050"), Ok(("socks5".into(), "::1".into(), 9050)));
+        assert_eq!(
+            proxy_address("bastion", "socks5h"),
+            Ok(("socks5h".into(), "bastion".into(), 1080))
+        );
+        assert!(parsed("ftp://proxy").is_err());
+        assert!(parsed("https://proxy.corp:3128").is_err());
+        assert!(parsed("proxy:port").is_err());
+    }
+
+    #[test]
+    fn bypasses_listed_hosts_and_their_subdomains() {
+        let bypass = "localhost, .internal.corp,*.dev.test, 10.0.0.1";
+        assert!(bypassed(bypass, "LOCALHOST"));
+        assert!(bypassed(bypass, "internal.corp"));
+        assert!(bypassed(bypass, "api.internal.corp"));
+        assert!(bypassed(bypass, "a.dev.test"));
+        assert!(bypassed(bypass, "10.0.0.1"));
+        assert!(!bypassed(bypass, "notinternal.corp"));
+        assert!(!bypassed(bypass, "10.0.0.2"));
+        assert!(bypassed("*", "example.com"));
+        assert!(!bypassed("", "example.com"));
+    }
+
+    #[tokio::test]
+    async fn tunnels_through_http_connect_with_auth() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 1024];
+            let n = socket.read(&mut buf).await.unwrap();
+            socket.write_all(b"HTTP/1.1 200 Connection established\r\n\r\nhello").await.unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_string()
+        });
+
+        let upstream = UpstreamProxy::default();
+        upstream.set_config(Some(UpstreamConfig {
+            https: addr.to_string(),
+            auth: Some(UpstreamAuth { user: "u".into(), password: "p".into() }),
+            ..Default::default()
+        }));
+        let Connected::Origin(mut stream) = upstream.connect("api.test", 443, true).await.unwrap()
+        else {
+            panic!("expected a tunnel");
+        };
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("CONNECT api.test:443 HTTP/1.1\r\n"));
+        assert!(request.contains("Proxy-Authorization: Basic dTpw\r\n"));
+        let mut hello = [0u8; 5];
+        stream.read_exact(&mut hello).await.unwrap();
+        assert_eq!(&hello, b"hello");
+    }
+}
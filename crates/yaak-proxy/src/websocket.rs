@@ -0,0 +1,239 @@
+//! Recording the frames of WebSocket connections that go through the proxy.
+//!
+//! Once the server agrees to the upgrade, bytes are relayed exactly as they arrive, and a
+//! parser picks the frames out of a copy of them. Recording never changes what either end
+//! sees. Compression is not offered to the server, so that payloads are recorded as sent.
+//! Frames over [`MAX_FRAME_LEN`] are relayed but not recorded, so they are never buffered.
+
+use std::sync::mpsc as std_mpsc;
+
+use http::request::Parts;
+use hyper::header::{CONNECTION, HeaderMap, SEC_WEBSOCKET_EXTENSIONS, UPGRADE};
+use hyper::upgrade::OnUpgrade;
+use hyper_util::rt::TokioIo;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::ProxyEvent;
+
+/// Take the client's side of a WebSocket upgrade out of `parts`, so that the connection can
+/// be relayed once the server switches protocols. `None` for every other request.
+pub(crate) fn take_upgrade(parts: &mut Parts) -> Option<OnUpgrade> {
+    if !is_websocket_upgrade(&parts.headers) {
+        return None;
+    }
+    parts.headers.remove(SEC_WEBSOCKET_EXTENSIONS);
+    parts.extensions.remove::<OnUpgrade>()
+}
+
+fn is_websocket_upgrade(headers: &HeaderMap) -> bool {
+    let has_token = |name, token: &str| {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .any(|t| t.trim().eq_ignore_ascii_case(token))
+    };
+    has_token(CONNECTION, "upgrade") && has_token(UPGRADE, "websocket")
+}
+
+/// Relay an upgraded connection between the client and the server until both sides close,
+/// recording each frame as it passes.
+pub(crate) async fn relay(
+    id: u64,
+    client: OnUpgrade,
+    server: OnUpgrade,
+    event_tx: std_mpsc::Sender<ProxyEvent>,
+) {
+    let (client, server) = match tokio::try_join!(client, server) {
+        Ok(upgraded) => upgraded,
+        Err(e) => {
+            eprintln!("WebSocket upgrade failed: {e}");
+            let _ = event_tx.send(ProxyEvent::WebSocketClosed { id });
+            return;
+        }
+    };
+
+    let (client_rx, client_tx) = tokio::io::split(TokioIo::new(client));
+    let (server_rx, server_tx) = tokio::io::split(TokioIo::new(server));
+    let result = tokio::try_join!(
+        pipe(id, false, client_rx, server_tx, &event_tx),
+        pipe(id, true, server_rx, client_tx, &event_tx),
+    );
+    if let Err(e) = result {
+        eprintln!("WebSocket relay closed: {e}");
+    }
+    let _ = event_tx.send(ProxyEvent::WebSocketClosed { id });
+}
+
+async fn pipe<R, W>(
+    id: u64,
+    is_server: bool,
+    mut from: R,
+    mut to: W,
+    event_tx: &std_mpsc::Sender<ProxyEvent>,
+) -> std::io::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut parser = FrameParser::default();
+    let mut buf = vec![0u8; 16 * 1024];
+    loop {
+        let n = from.read(&mut buf).await?;
+        if n == 0 {
+            return to.shutdown().await;
+        }
+        to.write_all(&buf[..n]).await?;
+        for frame in parser.feed(&buf[..n]) {
+            let _ = event_tx.send(ProxyEvent::WebSocketFrame {
+                id,
+                is_server,
+                opcode: frame.opcode,
+                payload: frame.payload,
+            });
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Frame {
+    opcode: u8,
+    /// Unmasked
+    payload: Vec<u8>,
+}
+
+/// The largest payload that is recorded.
+const MAX_FRAME_LEN: u64 = 16 * 1024 * 1024;
+
+/// Picks whole frames out of one direction of a connection, however the bytes are split.
+#[derive(Default)]
+struct FrameParser {
+    buf: Vec<u8>,
+    /// What is left of a frame that is too large to record
+    skip: u64,
+}
+
+impl FrameParser {
+    fn feed(&mut self, bytes: &[u8]) -> Vec<Frame> {
+        let skipped = usize::try_from(self.skip).unwrap_or(usize::MAX).min(bytes.len());
+        self.skip -= skipped as u64;
+        self.buf.extend_from_slice(&bytes[skipped..]);
+
+        let mut frames = Vec::new();
+        while let Some(parsed) = parse_frame(&self.buf) {
+            match parsed {
+                Parsed::Frame(frame, len) => {
+                    frames.push(frame);
+                    self.buf.drain(..len);
+                }
+                Parsed::TooLarge(len) => {
+                    let drained = usize::try_from(len).unwrap_or(usize::MAX).min(self.buf.len());
+                    self.buf.drain(..drained);
+                    self.skip = len - drained as u64;
+                }
+            }
+        }
+        frames
+    }
+}
+
+enum Parsed {
+    /// A frame and its length
+    Frame(Frame, usize),
+    /// The length of a frame whose payload is over [`MAX_FRAME_LEN`]
+    TooLarge(u64),
+}
+
+/// The frame at the start of `buf`, once all of it is there, or as soon as its header says
+/// it is too large.
+fn parse_frame(buf: &[u8]) -> Option<Parsed> {
+    let (first, second) = (*buf.first()?, *buf.get(1)?);
+    let opcode = first & 0x0f;
+    let (len, mut offset) = match second & 0x7f {
+        126 => (u16::from_be_bytes(buf.get(2..4)?.try_into().ok()?) as u64, 4),
+        127 => (u64::from_be_bytes(buf.get(2..10)?.try_into().ok()?), 10),
+        n => (n as u64, 2),
+    };
+    let mut mask: Option<[u8; 4]> = None;
+    if second & 0x80 != 0 {
+        mask = Some(buf.get(offset..offset + 4)?.try_into().ok()?);
+        offset += 4;
+    }
+    if len > MAX_FRAME_LEN {
+        return Some(Parsed::TooLarge(offset as u64 + len));
+    }
+
+    let end = offset.checked_add(usize::try_from(len).ok()?)?;
+    let mut payload = buf.get(offset..end)?.to_vec();
+    if let Some(mask) = mask {
+        for (i, b) in payload.iter_mut().enumerate() {
+            *b ^= mask[i % 4];
+        }
+    }
+    Some(Parsed::Frame(Frame { opcode, payload }, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_frames_split_across_reads() {
+        let mut parser = FrameParser::default();
+        // A masked "Hi" from a client, then an unmasked 300 byte binary frame from a server
+        let masked = [0x81, 0x82, 1, 2, 3, 4, b'H' ^ 1, b'i' ^ 2];
+        let mut long = vec![0x82, 126, 0x01, 0x2c];
+        long.extend(std::iter::repeat_n(7u8, 300));
+
+        assert_eq!(parser.feed(&masked[..3]), vec![]);
+        let mut rest = masked[3..].to_vec();
+        rest.extend_from_slice(&long[..100]);
+        assert_eq!(parser.feed(&rest), vec![Frame { opcode: 1, payload: b"Hi".to_vec() }]);
+        assert_eq!(parser.feed(&long[100..]), vec![Frame { opcode: 2, payload: vec![7; 300] }]);
+        assert!(parser.buf.is_empty());
+    }
+
+    #[test]
+    fn skips_frames_too_large_to_record() {
+        let mut parser = FrameParser::default();
+        let len = MAX_FRAME_LEN + 1;
+        let mut huge = vec![0x82, 127];
+        huge.extend_from_slice(&len.to_be_bytes());
+        huge.extend(std::iter::repeat_n(0u8, 1024));
+
+        assert_eq!(parser.feed(&huge), vec![]);
+        assert!(parser.buf.is_empty());
+        assert_eq!(parser.skip, len - 1024);
+
+        // The rest of it, then a frame that is recorded again
+        let chunk = vec![0u8; 64 * 1024];
+        let mut remaining = len - 1024;
+        while remaining > chunk.len() as u64 {
+            assert_eq!(parser.feed(&chunk), vec![]);
+            remaining -= chunk.len() as u64;
+        }
+        let mut rest = vec![0u8; remaining as usize];
+        rest.extend_from_slice(&[0x81, 0x02, b'o', b'k']);
+        assert_eq!(parser.feed(&rest), vec![Frame { opcode: 1, payload: b"ok".to_vec() }]);
+        assert_eq!(parser.skip, 0);
+    }
+
+    #[test]
+    fn only_takes_websocket_upgrades() {
+        let (mut parts, _) = http::Request::builder()
+            .header(CONNECTION, "keep-alive, Upgrade")
+            .header(UPGRADE, "websocket")
+            .header(SEC_WEBSOCKET_EXTENSIONS, "permessage-deflate")
+            .body(())
+            .unwrap()
+            .into_parts();
+        assert!(is_websocket_upgrade(&parts.headers));
+        take_upgrade(&mut parts);
+        assert!(!parts.headers.contains_key(SEC_WEBSOCKET_EXTENSIONS));
+
+        let (parts, _) =
+            http::Request::builder().header(UPGRADE, "h2c").body(()).unwrap().into_parts();
+        assert!(!is_websocket_upgrade(&parts.headers));
+    }
+}
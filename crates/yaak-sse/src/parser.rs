@@ -0,0 +1,204 @@
+//! Incremental `text/event-stream` parsing, following the WHATWG EventSource rules.
+//!
+//! Bytes can be fed in whatever chunks the network delivers: lines are only decoded once they
+//! are complete, so a multi-byte character or a CRLF split across two reads is still handled.
+
+use crate::sse::ServerSentEvent;
+
+#[derive(Debug, Default)]
+pub struct EventStreamParser {
+    /// Bytes of a line that has not ended yet
+    line: Vec<u8>,
+    /// The last chunk ended in CR, so an LF at the start of the next one belongs to it
+    pending_cr: bool,
+    started: bool,
+    event_type: String,
+    data: String,
+    retry: Option<u64>,
+    last_event_id: Option<String>,
+    reconnection_time: Option<u64>,
+}
+
+impl EventStreamParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse the next chunk of a stream and return the events it completed.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<ServerSentEvent> {
+        let mut events = Vec::new();
+        let mut bytes = bytes;
+        if self.pending_cr && !bytes.is_empty() {
+            self.pending_cr = false;
+            if bytes[0] == b'\n' {
+                bytes = &bytes[1..];
+            }
+        }
+
+        let mut start = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            let b = bytes[i];
+            if b != b'\r' && b != b'\n' {
+                i += 1;
+                continue;
+            }
+
+            self.line.extend_from_slice(&bytes[start..i]);
+            let line = std::mem::take(&mut self.line);
+            if let Some(event) = self.process_line(&line) {
+                events.push(event);
+            }
+
+            i += 1;
+            if b == b'\r' {
+                match bytes.get(i) {
+                    Some(b'\n') => i += 1,
+                    Some(_) => {}
+                    None => self.pending_cr = true,
+                }
+            }
+            start = i;
+        }
+        self.line.extend_from_slice(&bytes[start..]);
+
+        events
+    }
+
+    /// Start over on a new connection to the same stream. Half-read lines and events from the
+    /// old connection are dropped; the last event ID and reconnection time carry over.
+    pub fn restart(&mut self) {
+        *self = Self {
+            last_event_id: self.last_event_id.take(),
+            reconnection_time: self.reconnection_time,
+            ..Self::default()
+        };
+    }
+
+    /// The ID to send as `Last-Event-ID` when reconnecting, if the server has set one.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.last_event_id.as_deref().filter(|id| !id.is_empty())
+    }
+
+    /// How long the server asked clients to wait before reconnecting, in milliseconds.
+    pub fn reconnection_time(&self) -> Option<u64> {
+        self.reconnection_time
+    }
+
+    fn process_line(&mut self, line: &[u8]) -> Option<ServerSentEvent> {
+        let line = String::from_utf8_lossy(line);
+        let line = if self.started {
+            line.as_ref()
+        } else {
+            self.started = true;
+            line.strip_prefix('\u{feff}').unwrap_or(&line)
+        };
+
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event_type = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => self.last_event_id = Some(value.to_string()),
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                if let Ok(retry) = value.parse() {
+                    self.retry = Some(retry);
+                    self.reconnection_time = Some(retry);
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<ServerSentEvent> {
+        let event_type = std::mem::take(&mut self.event_type);
+        let retry = self.retry.take();
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let mut data = std::mem::take(&mut self.data);
+        data.pop();
+        Some(ServerSentEvent {
+            event_type: if event_type.is_empty() { "message".to_string() } else { event_type },
+            data,
+            id: self.last_event_id.clone().filter(|id| !id.is_empty()),
+            retry,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(chunks: &[&[u8]]) -> (EventStreamParser, Vec<ServerSentEvent>) {
+        let mut parser = EventStreamParser::new();
+        let events = chunks.iter().flat_map(|c| parser.feed(c)).collect();
+        (parser, events)
+    }
+
+    #[test]
+    fn parses_fields_and_multiline_data() {
+        let (parser, events) = parse(&[
+            b"\xEF\xBB\xBF: comment\nevent: delta\ndata: one\ndata:two\nid: 7\nretry: 250\n\n",
+        ]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, "delta");
+        assert_eq!(events[0].data, "one\ntwo");
+        assert_eq!(events[0].id.as_deref(), Some("7"));
+        assert_eq!(events[0].retry, Some(250));
+        assert_eq!(parser.last_event_id(), Some("7"));
+        assert_eq!(parser.reconnection_time(), Some(250));
+    }
+
+    #[test]
+    fn handles_chunk_boundaries_and_line_endings() {
+        let (_, events) = parse(&[
+            b"data: h\xC3",
+            b"\xA9llo\r",
+            b"\n\r",
+            b"\ndata: b\rid: 2\r\r",
+        ]);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].data, "h\u{e9}llo");
+        assert_eq!(events[0].event_type, "message");
+        assert_eq!(events[0].id, None);
+        assert_eq!(events[1].data, "b");
+        assert_eq!(events[1].id.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn keeps_the_last_id_and_drops_incomplete_events() {
+        let (parser, events) =
+            parse(&[b"id: 1\ndata: a\n\ndata: b\n\nevent: ping\n\nretry: x\ndata: partial"]);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].id.as_deref(), Some("1"));
+        assert_eq!(parser.last_event_id(), Some("1"));
+        assert_eq!(parser.reconnection_time(), None);
+    }
+
+    #[test]
+    fn restart_keeps_only_the_resume_state() {
+        let (mut parser, _) = parse(&[b"retry: 10\nid: 4\ndata: a\n\ndata: cut off"]);
+        parser.restart();
+        let events = parser.feed(b"data: b\n\n");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "b");
+        assert_eq!(events[0].id.as_deref(), Some("4"));
+        assert_eq!(parser.reconnection_time(), Some(10));
+    }
+}
@@ -209,8 +209,11 @@ impl TryFrom<AnyModel> for SyncModel {
             AnyModel::GrpcEvent(m) => return Err(UnknownModel(m.model)),
             AnyModel::HttpResponse(m) => return Err(UnknownModel(m.model)),
             AnyModel::HttpResponseEvent(m) => return Err(UnknownModel(m.model)),
+            AnyModel::HttpResponseSseEvent(m) => return Err(UnknownModel(m.model)),
             AnyModel::KeyValue(m) => return Err(UnknownModel(m.model)),
             AnyModel::Plugin(m) => return Err(UnknownModel(m.model)),
+            AnyModel::RunnerIteration(m) => return Err(UnknownModel(m.model)),
+            AnyModel::RunnerRun(m) => return Err(UnknownModel(m.model)),
             AnyModel::Settings(m) => return Err(UnknownModel(m.model)),
             AnyModel::WebsocketConnection(m) => return Err(UnknownModel(m.model)),
             AnyModel::WebsocketEvent(m) => return Err(UnknownModel(m.model)),
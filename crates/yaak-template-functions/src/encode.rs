@@ -0,0 +1,63 @@
+use base64::Engine;
+use base64::alphabet::STANDARD;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig, URL_SAFE_NO_PAD};
+use base64::engine::{DecodePaddingMode, general_purpose};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, percent_decode_str, utf8_percent_encode};
+use yaak_templates::error::Result;
+
+use crate::{Args, render_error};
+
+/// What `encodeURIComponent` leaves alone, besides letters and digits
+const URI_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'!')
+    .remove(b'~')
+    .remove(b'*')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')');
+
+/// Decodes with or without padding, like Node's `Buffer.from(value, "base64")`
+const LENIENT: GeneralPurpose = GeneralPurpose::new(
+    &STANDARD,
+    GeneralPurposeConfig::new()
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent)
+        .with_decode_allow_trailing_bits(true),
+);
+
+pub(crate) fn base64_encode(args: &Args) -> Result<String> {
+    let value = args.text("value");
+    Ok(match args.text_or("encoding", "base64").as_str() {
+        "base64url" => URL_SAFE_NO_PAD.encode(value),
+        _ => general_purpose::STANDARD.encode(value),
+    })
+}
+
+pub(crate) fn base64_decode(args: &Args) -> Result<String> {
+    // Node reads both alphabets and skips whitespace, so this does too
+    let value: String = args
+        .text("value")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '-' => '+',
+            '_' => '/',
+            c => c,
+        })
+        .collect();
+    let bytes = LENIENT
+        .decode(value.trim_end_matches('='))
+        .map_err(|e| render_error(format!("Invalid base64 value: {e}")))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+pub(crate) fn url_encode(args: &Args) -> Result<String> {
+    Ok(utf8_percent_encode(&args.text("value"), URI_COMPONENT).to_string())
+}
+
+pub(crate) fn url_decode(args: &Args) -> Result<String> {
+    let value = args.text("value");
+    Ok(percent_decode_str(&value).decode_utf8().map(|v| v.into_owned()).unwrap_or_default())
+}
@@ -0,0 +1,45 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use yaak_templates::error::Result;
+
+use crate::{Args, render_error};
+
+pub(crate) fn hash(algorithm: &str, args: &Args) -> Result<String> {
+    let digest = digest(algorithm, args.text("input").as_bytes());
+    encode(&digest, args)
+}
+
+pub(crate) fn hmac(algorithm: &str, args: &Args) -> Result<String> {
+    let key = args.text("key");
+    let input = args.text("input");
+    // md5 has no `Digest` implementation in the tree, so HMAC is done by hand (RFC 2104)
+    let block_size = if algorithm == "sha512" { 128 } else { 64 };
+    let mut key =
+        if key.len() > block_size { digest(algorithm, key.as_bytes()) } else { key.into_bytes() };
+    key.resize(block_size, 0);
+
+    let mut inner: Vec<u8> = key.iter().map(|b| b ^ 0x36).collect();
+    inner.extend_from_slice(input.as_bytes());
+    let mut outer: Vec<u8> = key.iter().map(|b| b ^ 0x5c).collect();
+    outer.extend(digest(algorithm, &inner));
+    encode(&digest(algorithm, &outer), args)
+}
+
+fn digest(algorithm: &str, data: &[u8]) -> Vec<u8> {
+    match algorithm {
+        "md5" => md5::compute(data).0.to_vec(),
+        "sha1" => Sha1::digest(data).to_vec(),
+        "sha256" => Sha256::digest(data).to_vec(),
+        _ => Sha512::digest(data).to_vec(),
+    }
+}
+
+fn encode(digest: &[u8], args: &Args) -> Result<String> {
+    match args.text_or("encoding", "base64").as_str() {
+        "base64" => Ok(STANDARD.encode(digest)),
+        "hex" => Ok(hex::encode(digest)),
+        encoding => Err(render_error(format!("Unknown encoding \"{encoding}\""))),
+    }
+}
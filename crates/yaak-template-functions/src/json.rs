@@ -0,0 +1,197 @@
+use serde_json::Value;
+use yaak_templates::error::Result;
+
+use crate::Args;
+
+/// Filter JSON text with a JSONPath query. Like the plugin, input or a query that can't be
+/// used renders as nothing rather than failing the whole template.
+pub(crate) fn jsonpath(args: &Args) -> Result<String> {
+    let Ok(json) = serde_json::from_str::<Value>(&args.text("input")) else {
+        return Ok(String::new());
+    };
+    let Some(path) = parse_path(&args.text("query")) else {
+        return Ok(String::new());
+    };
+    let items = select(&json, &path);
+    let formatted = args.flag("formatted");
+
+    Ok(match args.text_or("result", "first").as_str() {
+        "all" => to_text(&Value::Array(items.into_iter().cloned().collect()), formatted),
+        "join" => {
+            let join = args.get("join").unwrap_or_else(|| ", ".to_string());
+            items.iter().map(|v| to_text(v, false)).collect::<Vec<_>>().join(&join)
+        }
+        _ => items.first().map(|v| to_text(v, formatted)).unwrap_or_default(),
+    })
+}
+
+pub(crate) fn escape(args: &Args) -> Result<String> {
+    Ok(args.text("input").replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// The input without insignificant whitespace, or as it was when it isn't JSON
+pub(crate) fn minify(args: &Args) -> Result<String> {
+    let input = args.text("input");
+    Ok(serde_json::from_str::<Value>(&input).map(|v| v.to_string()).unwrap_or(input))
+}
+
+/// Strings as they are, other scalars as JSON, objects and arrays as JSON
+fn to_text(value: &Value, formatted: bool) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(_) | Value::Object(_) if formatted => {
+            serde_json::to_string_pretty(value).unwrap_or_default()
+        }
+        v => v.to_string(),
+    }
+}
+
+#[derive(Debug)]
+enum Selector {
+    Name(String),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>),
+    Wildcard,
+}
+
+#[derive(Debug)]
+struct Segment {
+    /// `..`, which applies the selectors to every value below as well
+    descendants: bool,
+    selectors: Vec<Selector>,
+}
+
+/// Parse the JSONPath subset without script expressions: `$`, `.name`, `..name`, `*`, and
+/// brackets holding names, indexes and `start:end` slices, separated by commas.
+fn parse_path(query: &str) -> Option<Vec<Segment>> {
+    let mut rest = query.trim();
+    rest = rest.strip_prefix('$').unwrap_or(rest);
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        let descendants = rest.starts_with("..");
+        if descendants {
+            rest = &rest[2..];
+        } else if let Some(r) = rest.strip_prefix('.') {
+            rest = r;
+        } else if !rest.starts_with('[') && !segments.is_empty() {
+            return None;
+        }
+
+        let selectors = if let Some(r) = rest.strip_prefix('[') {
+            let (inner, r) = split_bracket(r)?;
+            rest = r;
+            inner.into_iter().map(parse_selector).collect::<Option<Vec<_>>>()?
+        } else {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            let name = &rest[..end];
+            rest = &rest[end..];
+            match name {
+                "" => return None,
+                "*" => vec![Selector::Wildcard],
+                name => vec![Selector::Name(name.to_string())],
+            }
+        };
+        segments.push(Segment { descendants, selectors });
+    }
+    Some(segments)
+}
+
+/// The comma-separated parts of a bracket, and what follows its `]`
+fn split_bracket(s: &str) -> Option<(Vec<&str>, &str)> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quote: Option<char> = None;
+    let mut chars = s.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(_), '\\') => {
+                chars.next();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, ',') => {
+                parts.push(s[start..i].trim());
+                start = i + 1;
+            }
+            (None, ']') => {
+                parts.push(s[start..i].trim());
+                return Some((parts, &s[i + 1..]));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_selector(part: &str) -> Option<Selector> {
+    if part == "*" {
+        return Some(Selector::Wildcard);
+    }
+    for q in ['\'', '"'] {
+        if let Some(name) = part.strip_prefix(q).and_then(|p| p.strip_suffix(q)) {
+            return Some(Selector::Name(name.replace(&format!("\\{q}"), &q.to_string())));
+        }
+    }
+    if let Some((start, end)) = part.split_once(':') {
+        let bound = |b: &str| match b.trim() {
+            "" => Some(None),
+            b => b.parse().ok().map(Some),
+        };
+        return Some(Selector::Slice(bound(start)?, bound(end)?));
+    }
+    part.parse().ok().map(Selector::Index)
+}
+
+fn select<'a>(root: &'a Value, path: &[Segment]) -> Vec<&'a Value> {
+    let mut current = vec![root];
+    for segment in path {
+        let mut next = Vec::new();
+        for value in current {
+            let mut targets = vec![value];
+            if segment.descendants {
+                collect_descendants(value, &mut targets);
+            }
+            for target in targets {
+                for selector in &segment.selectors {
+                    apply(selector, target, &mut next);
+                }
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+fn collect_descendants<'a>(value: &'a Value, out: &mut Vec<&'a Value>) {
+    let children: Vec<&Value> = match value {
+        Value::Array(a) => a.iter().collect(),
+        Value::Object(o) => o.values().collect(),
+        _ => return,
+    };
+    for child in children {
+        out.push(child);
+        collect_descendants(child, out);
+    }
+}
+
+fn apply<'a>(selector: &Selector, value: &'a Value, out: &mut Vec<&'a Value>) {
+    match (selector, value) {
+        (Selector::Name(name), Value::Object(o)) => out.extend(o.get(name)),
+        (Selector::Index(i), Value::Array(a)) => {
+            let i = if *i < 0 { a.len() as i64 + i } else { *i };
+            out.extend(usize::try_from(i).ok().and_then(|i| a.get(i)));
+        }
+        (Selector::Slice(start, end), Value::Array(a)) => {
+            let len = a.len() as i64;
+            let bound = |b: i64| (if b < 0 { len + b } else { b }).clamp(0, len) as usize;
+            let (start, end) = (bound(start.unwrap_or(0)), bound(end.unwrap_or(len)));
+            if start < end {
+                out.extend(&a[start..end]);
+            }
+        }
+        (Selector::Wildcard, Value::Array(a)) => out.extend(a),
+        (Selector::Wildcard, Value::Object(o)) => out.extend(o.values()),
+        _ => {}
+    }
+}
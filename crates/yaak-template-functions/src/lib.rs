@@ -0,0 +1,208 @@
+//! Template functions implemented in Rust, so that common templates render on any host.
+//!
+//! The desktop and CLI also have plugins, but the browser and other plugin-less hosts do
+//! not. Every function here has the name and arguments of the bundled plugin it mirrors,
+//! so a template renders the same wherever it is sent from.
+
+mod encode;
+mod hash;
+mod json;
+mod random;
+mod regex;
+mod timestamp;
+mod uuid;
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+use yaak_templates::error::Error::RenderError;
+use yaak_templates::error::Result;
+
+/// Run the native function `name`, or `None` when there is no native function by that name.
+pub fn call(name: &str, args: &HashMap<String, Value>) -> Option<Result<String>> {
+    let args = Args(args);
+    let result = match name {
+        "base64.encode" => encode::base64_encode(&args),
+        "base64.decode" => encode::base64_decode(&args),
+        "url.encode" => encode::url_encode(&args),
+        "url.decode" => encode::url_decode(&args),
+        "hash.md5" | "hash.sha1" | "hash.sha256" | "hash.sha512" => {
+            hash::hash(&name["hash.".len()..], &args)
+        }
+        "hmac.md5" | "hmac.sha1" | "hmac.sha256" | "hmac.sha512" => {
+            hash::hmac(&name["hmac.".len()..], &args)
+        }
+        "json.jsonpath" => json::jsonpath(&args),
+        "json.escape" => json::escape(&args),
+        "json.minify" => json::minify(&args),
+        "random.range" => random::range(&args),
+        "regex.match" => regex::find(&args),
+        "regex.replace" => regex::replace(&args),
+        "timestamp.unix" => timestamp::unix(&args),
+        "timestamp.unixMillis" => timestamp::unix_millis(&args),
+        "timestamp.iso8601" => timestamp::iso8601(&args),
+        "timestamp.format" => timestamp::format(&args),
+        "timestamp.offset" => timestamp::offset(&args),
+        "uuid.v1" => uuid::v1(),
+        "uuid.v3" => uuid::v3(&args),
+        "uuid.v4" => uuid::v4(),
+        "uuid.v5" => uuid::v5(&args),
+        "uuid.v6" => uuid::v6(&args),
+        "uuid.v7" => uuid::v7(),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// The arguments of one call, read the way the plugins read theirs.
+struct Args<'a>(&'a HashMap<String, Value>);
+
+impl Args<'_> {
+    /// The argument as text, or `None` when it wasn't given.
+    fn get(&self, name: &str) -> Option<String> {
+        match self.0.get(name)? {
+            Value::Null => None,
+            Value::String(s) => Some(s.clone()),
+            v => Some(v.to_string()),
+        }
+    }
+
+    /// The argument as text, empty when it wasn't given.
+    fn text(&self, name: &str) -> String {
+        self.get(name).unwrap_or_default()
+    }
+
+    /// The argument as text, or `default` when it wasn't given or is empty.
+    fn text_or(&self, name: &str, default: &str) -> String {
+        self.get(name).filter(|v| !v.is_empty()).unwrap_or_else(|| default.to_string())
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        match self.0.get(name) {
+            Some(Value::Bool(b)) => *b,
+            Some(Value::String(s)) => s == "true",
+            _ => false,
+        }
+    }
+}
+
+fn render_error(message: impl Into<String>) -> yaak_templates::error::Error {
+    RenderError(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn run(name: &str, args: Value) -> Result<String> {
+        let args = serde_json::from_value(args).unwrap();
+        call(name, &args).expect("native function")
+    }
+
+    #[test]
+    fn unknown_functions_are_left_to_plugins() {
+        assert!(call("response.body.path", &HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn encodes_and_hashes() {
+        assert_eq!(run("base64.encode", json!({"value": "hi?>"})).unwrap(), "aGk/Pg==");
+        assert_eq!(
+            run("base64.encode", json!({"value": "hi?>", "encoding": "base64url"})).unwrap(),
+            "aGk_Pg"
+        );
+        assert_eq!(run("base64.decode", json!({"value": "aGk_Pg"})).unwrap(), "hi?>");
+        assert_eq!(run("url.encode", json!({"value": "a b&c/d!"})).unwrap(), "a%20b%26c%2Fd!");
+        assert_eq!(run("url.decode", json!({"value": "a%20b%26c"})).unwrap(), "a b&c");
+        assert_eq!(
+            run("hash.md5", json!({"input": "", "encoding": "hex"})).unwrap(),
+            "d41d8cd98f00b204e9800998ecf8427e"
+        );
+        let fox = "The quick brown fox jumps over the lazy dog";
+        assert_eq!(
+            run("hmac.sha256", json!({"input": fox, "key": "key", "encoding": "hex"})).unwrap(),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+        assert_eq!(
+            run("hmac.md5", json!({"input": fox, "key": "key", "encoding": "hex"})).unwrap(),
+            "80070713463e7749b90c2dc24911e275"
+        );
+    }
+
+    #[test]
+    fn matches_and_replaces() {
+        let input = "id=42, name=yaak";
+        assert_eq!(
+            run("regex.match", json!({"input": input, "regex": r"id=(\d+)"})).unwrap(),
+            "42"
+        );
+        assert_eq!(
+            run("regex.match", json!({"input": input, "regex": r"name=(?<n>\w+)"})).unwrap(),
+            "yaak"
+        );
+        assert_eq!(
+            run(
+                "regex.replace",
+                json!({"input": input, "regex": r"(\w+)=", "replacement": "$1: "})
+            )
+            .unwrap(),
+            "id: 42, name: yaak"
+        );
+        assert_eq!(
+            run(
+                "regex.replace",
+                json!({"input": "aAa", "regex": "a", "replacement": "[$&]", "flags": "i"})
+            )
+            .unwrap(),
+            "[a]Aa"
+        );
+    }
+
+    #[test]
+    fn selects_json() {
+        let input = r#"{"items": [{"id": 1, "tags": ["a"]}, {"id": 2, "tags": ["b", "c"]}]}"#;
+        let path = |query: &str, result: &str| {
+            run("json.jsonpath", json!({"input": input, "query": query, "result": result})).unwrap()
+        };
+        assert_eq!(path("$.items[1].id", "first"), "2");
+        assert_eq!(path("$.items[-1]['tags']", "first"), r#"["b","c"]"#);
+        assert_eq!(path("$..id", "all"), "[1,2]");
+        assert_eq!(path("$.items[*].tags[0]", "join"), "a, b");
+        assert_eq!(path("$.nope", "first"), "");
+        assert_eq!(
+            run("json.minify", json!({"input": "{ \"b\": 1,\n \"a\": [ 2 ] }"})).unwrap(),
+            r#"{"b":1,"a":[2]}"#
+        );
+    }
+
+    #[test]
+    fn formats_and_offsets_timestamps() {
+        let date = "2025-05-28T11:15:00.250Z";
+        assert_eq!(run("timestamp.unixMillis", json!({"date": date})).unwrap(), "1748430900250");
+        assert_eq!(run("timestamp.unix", json!({"date": "1748430900250"})).unwrap(), "1748430900");
+        assert_eq!(
+            run("timestamp.offset", json!({"date": date, "expression": "-1d +2h 30m"})).unwrap(),
+            "2025-05-27T13:45:00.250Z"
+        );
+        assert!(run("timestamp.offset", json!({"date": date, "expression": "2w"})).is_err());
+        assert!(run("timestamp.iso8601", json!({"date": "yesterday"})).is_err());
+    }
+
+    #[test]
+    fn generates_uuids() {
+        let namespace = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
+        assert_eq!(
+            run("uuid.v5", json!({"name": "yaak.app", "namespace": namespace})).unwrap(),
+            run("uuid.v5", json!({"name": "yaak.app", "namespace": namespace})).unwrap(),
+        );
+        for (name, version) in [
+            ("uuid.v1", '1'),
+            ("uuid.v4", '4'),
+            ("uuid.v6", '6'),
+            ("uuid.v7", '7'),
+        ] {
+            assert_eq!(run(name, json!({})).unwrap().chars().nth(14), Some(version));
+        }
+    }
+}
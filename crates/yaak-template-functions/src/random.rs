@@ -0,0 +1,38 @@
+use yaak_templates::error::Result;
+
+use crate::{Args, render_error};
+
+pub(crate) fn range(args: &Args) -> Result<String> {
+    let min = parse_int("min", &args.text_or("min", "0"))?;
+    let max = parse_int("max", &args.text_or("max", "1"))?;
+    let mut value = random_f64() * (max - min) + min;
+    if let Some(decimals) = args.get("decimals").filter(|d| !d.is_empty()) {
+        let scale = 10f64.powf(parse_int("decimals", &decimals)?);
+        value = (value * scale).round() / scale;
+    }
+    Ok(value.to_string())
+}
+
+/// The leading integer of `value`, like `parseInt(value, 10)`
+fn parse_int(name: &str, value: &str) -> Result<f64> {
+    let value = value.trim();
+    let end = value
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || (i == 0 && (c == '-' || c == '+'))))
+        .map_or(value.len(), |(i, _)| i);
+    value[..end]
+        .parse::<i64>()
+        .map(|v| v as f64)
+        .map_err(|_| render_error(format!("Invalid {name} \"{value}\"")))
+}
+
+/// A uniformly distributed number in `[0, 1)`
+fn random_f64() -> f64 {
+    (u64::from_le_bytes(random_bytes()) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+pub(crate) fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes).expect("system randomness");
+    bytes
+}
@@ -0,0 +1,91 @@
+use ::regex::{Regex, RegexBuilder};
+use yaak_templates::error::Result;
+
+use crate::{Args, render_error};
+
+/// The first named group of the first match, else its first group, else the whole match.
+pub(crate) fn find(args: &Args) -> Result<String> {
+    let input = args.text("input");
+    let regex = compile(&args.get("regex").unwrap_or_else(|| ".*".to_string()), "")?;
+    let Some(captures) = regex.captures(&input) else {
+        return Ok(String::new());
+    };
+
+    let group = match regex.capture_names().flatten().next() {
+        Some(name) => captures.name(name),
+        None => captures.get(1).or_else(|| captures.get(0)),
+    };
+    Ok(group.map(|m| m.as_str().to_string()).unwrap_or_default())
+}
+
+pub(crate) fn replace(args: &Args) -> Result<String> {
+    let input = args.text("input");
+    let pattern = args.text("regex");
+    if pattern.is_empty() {
+        return Ok(String::new());
+    }
+
+    let flags = args.get("flags").unwrap_or_else(|| "g".to_string());
+    let regex = compile(&pattern, &flags)?;
+    let replacement = replacement(&args.text("replacement"), regex.captures_len());
+    Ok(if flags.contains('g') {
+        regex.replace_all(&input, replacement.as_str()).into_owned()
+    } else {
+        regex.replace(&input, replacement.as_str()).into_owned()
+    })
+}
+
+/// Compile a JavaScript-style pattern with its flags. `g` is up to the caller, and flags
+/// that only change how JavaScript iterates (`u`, `v`, `y`, `d`) have nothing to do here.
+fn compile(pattern: &str, flags: &str) -> Result<Regex> {
+    let mut builder = RegexBuilder::new(pattern);
+    for flag in flags.chars() {
+        match flag {
+            'i' => builder.case_insensitive(true),
+            'm' => builder.multi_line(true),
+            's' => builder.dot_matches_new_line(true),
+            'g' | 'u' | 'v' | 'y' | 'd' => &mut builder,
+            f => return Err(render_error(format!("Invalid regular expression flag \"{f}\""))),
+        };
+    }
+    builder.build().map_err(|e| render_error(format!("Invalid regular expression: {e}")))
+}
+
+/// Translate a JavaScript replacement (`$1`, `$&`, `$<name>`, `$$`) to the `regex` crate's
+/// syntax. Anything else after a `$` is literal, as it is in JavaScript.
+fn replacement(js: &str, captures_len: usize) -> String {
+    let mut out = String::with_capacity(js.len());
+    let mut rest = js;
+    while let Some(i) = rest.find('$') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i + 1..];
+
+        let digits = rest.bytes().take(2).take_while(u8::is_ascii_digit).count();
+        // Two digits name a group only if there are that many; otherwise the second is text
+        let group = (1..=digits).rev().find_map(|n| {
+            rest[..n]
+                .parse::<usize>()
+                .ok()
+                .filter(|g| (1..captures_len).contains(g))
+                .map(|g| (g, n))
+        });
+
+        if let Some((group, len)) = group {
+            out.push_str(&format!("${{{group}}}"));
+            rest = &rest[len..];
+        } else if let Some(r) = rest.strip_prefix('&') {
+            out.push_str("${0}");
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix('$') {
+            out.push_str("$$");
+            rest = r;
+        } else if let Some((name, r)) = rest.strip_prefix('<').and_then(|r| r.split_once('>')) {
+            out.push_str(&format!("${{{name}}}"));
+            rest = r;
+        } else {
+            out.push_str("$$");
+        }
+    }
+    out.push_str(rest);
+    out
+}
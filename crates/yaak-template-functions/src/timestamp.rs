@@ -0,0 +1,236 @@
+use chrono::{
+    DateTime, Datelike, Days, Local, Months, NaiveDate, NaiveDateTime, Offset, TimeDelta, TimeZone,
+    Timelike, Utc,
+};
+use yaak_templates::error::Result;
+
+use crate::{Args, render_error};
+
+pub(crate) fn unix(args: &Args) -> Result<String> {
+    Ok(parse_date(&args.text("date"))?.timestamp().to_string())
+}
+
+pub(crate) fn unix_millis(args: &Args) -> Result<String> {
+    Ok(parse_date(&args.text("date"))?.timestamp_millis().to_string())
+}
+
+pub(crate) fn iso8601(args: &Args) -> Result<String> {
+    Ok(iso_string(parse_date(&args.text("date"))?))
+}
+
+pub(crate) fn format(args: &Args) -> Result<String> {
+    let date = parse_date(&args.text("date"))?.with_timezone(&Local);
+    format_date(&date, &args.text_or("format", "yyyy-MM-dd HH:mm:ss"))
+}
+
+/// Move the date by an expression like `-5d +2h 3m`, in the units y, M, d, h, m and s.
+/// Years, months and days are calendar ones, in local time.
+pub(crate) fn offset(args: &Args) -> Result<String> {
+    let mut date = parse_date(&args.text("date"))?.with_timezone(&Local);
+    for op in args.text("expression").split_whitespace() {
+        let invalid = || render_error(format!("Invalid date expression: {op}"));
+        let (negative, rest) = match op.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, op.strip_prefix('+').unwrap_or(op)),
+        };
+        let Some((split, _)) = rest.char_indices().last() else {
+            return Err(invalid());
+        };
+        let (amount, unit) = rest.split_at(split);
+        if amount.is_empty() || !amount.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let amount: u32 = amount.parse().map_err(|_| invalid())?;
+
+        let moved = match (unit, negative) {
+            ("y", false) => date.checked_add_months(Months::new(amount.saturating_mul(12))),
+            ("y", true) => date.checked_sub_months(Months::new(amount.saturating_mul(12))),
+            ("M", false) => date.checked_add_months(Months::new(amount)),
+            ("M", true) => date.checked_sub_months(Months::new(amount)),
+            ("d", false) => date.checked_add_days(Days::new(amount.into())),
+            ("d", true) => date.checked_sub_days(Days::new(amount.into())),
+            ("h" | "m" | "s", _) => {
+                let seconds = i64::from(amount)
+                    * match unit {
+                        "h" => 3600,
+                        "m" => 60,
+                        _ => 1,
+                    };
+                let delta = TimeDelta::seconds(if negative { -seconds } else { seconds });
+                date.checked_add_signed(delta)
+            }
+            _ => return Err(invalid()),
+        };
+        date = moved.ok_or_else(|| render_error(format!("Date out of range: {op}")))?;
+    }
+    Ok(iso_string(date.with_timezone(&Utc)))
+}
+
+/// Read a date the way the timestamp plugin does: now when empty, then ISO 8601 (a date or
+/// time without an offset is local), then milliseconds since the epoch.
+pub(crate) fn parse_date(date: &str) -> Result<DateTime<Utc>> {
+    let date = date.trim();
+    if date.is_empty() {
+        return Ok(Utc::now());
+    }
+
+    if let Ok(d) = DateTime::parse_from_rfc3339(date) {
+        return Ok(d.with_timezone(&Utc));
+    }
+    if let Ok(d) = DateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S%.f%z") {
+        return Ok(d.with_timezone(&Utc));
+    }
+
+    let naive = [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|f| NaiveDateTime::parse_from_str(date, f).ok())
+    .or_else(|| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0));
+    if let Some(d) = naive.and_then(|n| Local.from_local_datetime(&n).earliest()) {
+        return Ok(d.with_timezone(&Utc));
+    }
+
+    let (whole, fraction) = date.split_once('.').unwrap_or((date, "0"));
+    let is_millis = !whole.is_empty()
+        && !fraction.is_empty()
+        && whole.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit());
+    if let Some(d) = date
+        .parse::<f64>()
+        .ok()
+        .filter(|_| is_millis)
+        .and_then(|ms| DateTime::from_timestamp_millis(ms as i64))
+    {
+        return Ok(d);
+    }
+
+    Err(render_error(format!("Invalid date: {date}")))
+}
+
+/// Like JavaScript's `Date.prototype.toISOString()`
+fn iso_string(date: DateTime<Utc>) -> String {
+    date.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+/// Format with [date-fns tokens](https://date-fns.org/docs/format), which the timestamp
+/// plugin documents. Text in single quotes is literal; `''` is a quote.
+fn format_date(date: &DateTime<Local>, format: &str) -> Result<String> {
+    let chars: Vec<char> = format.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let len = chars[i..].iter().take_while(|&&n| n == c).count();
+
+        if c == '\'' {
+            if len >= 2 {
+                out.push('\'');
+                i += 2;
+                continue;
+            }
+            // A quoted run, in which `''` is a quote
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == '\'' {
+                    if chars.get(i + 1) == Some(&'\'') {
+                        out.push('\'');
+                        i += 2;
+                        continue;
+                    }
+                    i += 1;
+                    break;
+                }
+                out.push(chars[i]);
+                i += 1;
+            }
+            continue;
+        }
+
+        if !c.is_ascii_alphabetic() {
+            out.push(c);
+            i += 1;
+            continue;
+        }
+
+        let pad = |n: u32| format!("{n:0len$}");
+        let hour12 = match date.hour() % 12 {
+            0 => 12,
+            h => h,
+        };
+        let offset = date.offset().fix().local_minus_utc() / 60;
+        let token = match c {
+            'y' if len == 2 => format!("{:02}", date.year().rem_euclid(100)),
+            'y' => format!("{:0len$}", date.year()),
+            'M' | 'L' => match len {
+                1 | 2 => pad(date.month()),
+                3 => date.format("%b").to_string(),
+                4 => date.format("%B").to_string(),
+                _ => date.format("%B").to_string()[..1].to_string(),
+            },
+            'Q' => pad(date.month0() / 3 + 1),
+            'd' => pad(date.day()),
+            'E' => match len {
+                1..=3 => date.format("%a").to_string(),
+                4 => date.format("%A").to_string(),
+                _ => date.format("%A").to_string()[..1].to_string(),
+            },
+            'i' => pad(date.weekday().number_from_monday()),
+            'I' => pad(date.iso_week().week()),
+            'R' => format!("{:0len$}", date.iso_week().year()),
+            'a' => match len {
+                1 | 2 => date.format("%p").to_string(),
+                3 => date.format("%P").to_string(),
+                _ => if date.hour() < 12 { "a.m." } else { "p.m." }.to_string(),
+            },
+            'H' => pad(date.hour()),
+            'h' => pad(hour12),
+            'k' => pad(if date.hour() == 0 { 24 } else { date.hour() }),
+            'K' => pad(date.hour() % 12),
+            'm' => pad(date.minute()),
+            's' => pad(date.second()),
+            'S' => format!("{:09}", date.nanosecond() % 1_000_000_000)[..len.min(9)].to_string(),
+            'X' if offset == 0 => "Z".to_string(),
+            'X' | 'x' => format_offset(offset, len),
+            'T' => date.timestamp_millis().to_string(),
+            't' => date.timestamp().to_string(),
+            c => {
+                return Err(render_error(format!(
+                    "Format string contains an unescaped latin alphabet character `{c}`"
+                )));
+            }
+        };
+        out.push_str(&token);
+        i += len;
+    }
+    Ok(out)
+}
+
+/// `+05`, `+0530` (1), `+0530` (2), `+05:30` (3 and up)
+fn format_offset(minutes: i32, len: usize) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let (hours, minutes) = (minutes.abs() / 60, minutes.abs() % 60);
+    match len {
+        1 if minutes == 0 => format!("{sign}{hours:02}"),
+        1 | 2 => format!("{sign}{hours:02}{minutes:02}"),
+        _ => format!("{sign}{hours:02}:{minutes:02}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_date_fns_tokens() {
+        let date = Local.with_ymd_and_hms(2025, 3, 7, 14, 5, 9).unwrap();
+        let format = |f: &str| format_date(&date, f).unwrap();
+        assert_eq!(format("yyyy-MM-dd HH:mm:ss"), "2025-03-07 14:05:09");
+        assert_eq!(format("EEE, d MMM yy h:mm a"), "Fri, 7 Mar 25 2:05 PM");
+        assert_eq!(format("'Today is' EEEE, 'it''s' HH'h'"), "Today is Friday, it's 14h");
+        assert!(format_date(&date, "yyyy-MM-dd 'at' HH:mm:ss").is_ok());
+        assert!(format_date(&date, "YYYY-MM-DD").is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use ::uuid::Uuid;
+use ::uuid::timestamp::Timestamp;
+use ::uuid::timestamp::context::{ContextV1, NoContext};
+use chrono::{DateTime, Utc};
+use yaak_templates::error::Result;
+
+use crate::random::random_bytes;
+use crate::timestamp::parse_date;
+use crate::{Args, render_error};
+
+// Time comes from chrono rather than uuid's own clock, which has none in a browser
+
+pub(crate) fn v1() -> Result<String> {
+    Ok(Uuid::new_v1(clock_timestamp(Utc::now()), &random_bytes()).to_string())
+}
+
+pub(crate) fn v3(args: &Args) -> Result<String> {
+    Ok(Uuid::new_v3(&namespace(args)?, args.text("name").as_bytes()).to_string())
+}
+
+pub(crate) fn v4() -> Result<String> {
+    Ok(Uuid::new_v4().to_string())
+}
+
+pub(crate) fn v5(args: &Args) -> Result<String> {
+    Ok(Uuid::new_v5(&namespace(args)?, args.text("name").as_bytes()).to_string())
+}
+
+pub(crate) fn v6(args: &Args) -> Result<String> {
+    let date = parse_date(&args.text("timestamp"))?;
+    Ok(Uuid::new_v6(clock_timestamp(date), &random_bytes()).to_string())
+}
+
+pub(crate) fn v7() -> Result<String> {
+    let now = Utc::now();
+    let timestamp =
+        Timestamp::from_unix(NoContext, now.timestamp() as u64, now.timestamp_subsec_nanos());
+    Ok(Uuid::new_v7(timestamp).to_string())
+}
+
+/// A v1/v6 timestamp with a random clock sequence, as the `uuid` npm package makes them
+fn clock_timestamp(date: DateTime<Utc>) -> Timestamp {
+    let context = ContextV1::new(u16::from_le_bytes(random_bytes()));
+    Timestamp::from_unix(context, date.timestamp() as u64, date.timestamp_subsec_nanos())
+}
+
+fn namespace(args: &Args) -> Result<Uuid> {
+    let namespace = args.text("namespace");
+    Uuid::parse_str(namespace.trim())
+        .map_err(|_| render_error(format!("Invalid namespace UUID \"{namespace}\"")))
+}
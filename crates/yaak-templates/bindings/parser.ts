@@ -6,4 +6,4 @@ export type Token = { "type": "raw", text: string, } | { "type": "tag", val: Val
 
 export type Tokens = { tokens: Array<Token>, };
 
-export type Val = { "type": "str", text: string, } | { "type": "var", name: string, } | { "type": "bool", value: boolean, } | { "type": "fn", name: string, args: Array<FnArg>, } | { "type": "null" };
+export type Val = { "type": "str", text: string, } | { "type": "var", name: string, } | { "type": "bool", value: boolean, } | { "type": "num", value: number, } | { "type": "fn", name: string, args: Array<FnArg>, } | { "type": "filter", value: Val, name: string, } | { "type": "fallback", value: Val, fallback: Val, } | { "type": "null" };
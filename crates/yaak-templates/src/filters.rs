@@ -0,0 +1,48 @@
+use crate::error::Error::RenderError;
+use crate::error::Result;
+use base64::Engine;
+use base64::prelude::{BASE64_STANDARD, BASE64_URL_SAFE_NO_PAD};
+
+/// Apply a filter a value is piped through, as in `${[ my_var | trim | upper ]}`
+pub fn apply_filter(name: &str, value: &str) -> Result<String> {
+    let v = match name {
+        "base64" => BASE64_STANDARD.encode(value),
+        "base64url" => BASE64_URL_SAFE_NO_PAD.encode(value),
+        // A JSON string, quotes included, so the value can go into a JSON body as it is
+        "json" => serde_json::to_string(value).expect("strings serialize"),
+        "lower" => value.to_lowercase(),
+        "trim" => value.trim().to_string(),
+        "upper" => value.to_uppercase(),
+        "urlencode" => url_encode(value),
+        _ => return Err(RenderError(format!("Unknown filter \"{name}\""))),
+    };
+    Ok(v)
+}
+
+/// Percent-encode everything but what `encodeURIComponent` leaves alone
+fn url_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || b"-_.!~*'()".contains(&b) {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_filters() {
+        assert_eq!(apply_filter("upper", "Yaak").unwrap(), "YAAK");
+        assert_eq!(apply_filter("base64", "hi?>").unwrap(), "aGk/Pg==");
+        assert_eq!(apply_filter("base64url", "hi?>").unwrap(), "aGk_Pg");
+        assert_eq!(apply_filter("json", "say \"hi\"\n").unwrap(), r#""say \"hi\"\n""#);
+        assert_eq!(apply_filter("urlencode", "a b&ü").unwrap(), "a%20b%26%C3%BC");
+        assert!(apply_filter("reverse", "abc").is_err());
+    }
+}
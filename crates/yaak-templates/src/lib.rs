@@ -1,5 +1,6 @@
 pub mod error;
 pub mod escape;
+pub mod filters;
 pub mod format_json;
 pub mod parser;
 pub mod renderer;
@@ -38,10 +38,34 @@ impl Display for FnArg {
 #[serde(rename_all = "snake_case", tag = "type")]
 #[ts(export, export_to = "parser.ts")]
 pub enum Val {
-    Str { text: String },
-    Var { name: String },
-    Bool { value: bool },
-    Fn { name: String, args: Vec<FnArg> },
+    Str {
+        text: String,
+    },
+    Var {
+        name: String,
+    },
+    Bool {
+        value: bool,
+    },
+    /// A number literal, only parsed as a function argument. Elsewhere, digits like `2024` are
+    /// still a variable name, as they were before numbers existed.
+    Num {
+        value: f64,
+    },
+    Fn {
+        name: String,
+        args: Vec<FnArg>,
+    },
+    /// `value | name`, the value passed through a built-in filter
+    Filter {
+        value: Box<Val>,
+        name: String,
+    },
+    /// `value ?? fallback`, the fallback used when the value is a variable that isn't defined
+    Fallback {
+        value: Box<Val>,
+        fallback: Box<Val>,
+    },
     Null,
 }
 
@@ -57,6 +81,7 @@ impl Display for Val {
             }
             Val::Var { name } => name.to_string(),
             Val::Bool { value } => value.to_string(),
+            Val::Num { value } => value.to_string(),
             Val::Fn { name, args } => {
                 format!(
                     "{name}({})",
@@ -69,6 +94,14 @@ impl Display for Val {
                         .join(", ")
                 )
             }
+            Val::Filter { value, name } => match value.as_ref() {
+                Val::Fallback { .. } => format!("({value}) | {name}"),
+                _ => format!("{value} | {name}"),
+            },
+            Val::Fallback { value, fallback } => match value.as_ref() {
+                Val::Fallback { .. } => format!("({value}) ?? {fallback}"),
+                _ => format!("{value} ?? {fallback}"),
+            },
             Val::Null => "null".to_string(),
         };
         write!(f, "{}", str)
@@ -113,6 +146,13 @@ fn transform_val<T: TemplateCallback>(val: &Val, cb: &T) -> Result<Val> {
             }
             Val::Fn { name: fn_name.clone(), args: new_args }
         }
+        Val::Filter { value, name } => {
+            Val::Filter { value: Box::new(transform_val(value, cb)?), name: name.clone() }
+        }
+        Val::Fallback { value, fallback } => Val::Fallback {
+            value: Box::new(transform_val(value, cb)?),
+            fallback: Box::new(transform_val(fallback, cb)?),
+        },
         _ => val.clone(),
     };
     Ok(val)
@@ -139,6 +179,9 @@ pub fn transform_args<T: TemplateCallback>(tokens: Tokens, cb: &T) -> Result<Tok
 //  ${[ my_fn() ]}
 //  ${[ my_fn(my_var) ]}
 //  ${[ my_fn(my_var, "A String") ]}
+//  ${[ my_fn(count=3, ratio=-0.5) ]}
+//  ${[ my_var | trim | upper ]}
+//  ${[ my_var ?? other_var ?? 'default' ]}
 
 // default
 #[derive(Default)]
@@ -191,7 +234,7 @@ impl Parser {
         //    ${[ my_var...
         self.skip_whitespace();
 
-        let val = match self.parse_value()? {
+        let val = match self.parse_expr()? {
             Some(v) => v,
             None => return Ok(None),
         };
@@ -217,8 +260,66 @@ impl Parser {
         );
     }
 
+    /// A value with its filters, then any fallbacks, which bind loosest
+    ///    my_var | upper ?? 'none'
+    fn parse_expr(&mut self) -> Result<Option<Val>> {
+        let start_pos = self.pos;
+
+        let value = match self.parse_filtered()? {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        let end_pos = self.pos;
+        self.skip_whitespace();
+        if !self.match_str("??") {
+            self.pos = end_pos;
+            return Ok(Some(value));
+        }
+
+        self.skip_whitespace();
+        match self.parse_expr()? {
+            Some(fallback) => {
+                Ok(Some(Val::Fallback { value: Box::new(value), fallback: Box::new(fallback) }))
+            }
+            None => {
+                self.pos = start_pos;
+                Ok(None)
+            }
+        }
+    }
+
+    fn parse_filtered(&mut self) -> Result<Option<Val>> {
+        let start_pos = self.pos;
+
+        let mut value = match self.parse_value()? {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        loop {
+            let end_pos = self.pos;
+            self.skip_whitespace();
+            if !self.match_str("|") {
+                self.pos = end_pos;
+                return Ok(Some(value));
+            }
+
+            self.skip_whitespace();
+            match self.parse_ident() {
+                Some(name) => value = Val::Filter { value: Box::new(value), name },
+                None => {
+                    self.pos = start_pos;
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
     fn parse_value(&mut self) -> Result<Option<Val>> {
-        let v = if let Some((name, args)) = self.parse_fn()? {
+        let v = if let Some(v) = self.parse_group()? {
+            Some(v)
+        } else if let Some((name, args)) = self.parse_fn()? {
             Some(Val::Fn { name, args })
         } else if let Some(v) = self.parse_string()? {
             Some(Val::Str { text: v })
@@ -239,6 +340,26 @@ impl Parser {
         Ok(v)
     }
 
+    /// An expression in parentheses
+    ///    (my_var ?? 'none') | upper
+    fn parse_group(&mut self) -> Result<Option<Val>> {
+        let start_pos = self.pos;
+        if !self.match_str("(") {
+            return Ok(None);
+        }
+
+        self.skip_whitespace();
+        let value = self.parse_expr()?;
+        self.skip_whitespace();
+        match value {
+            Some(v) if self.match_str(")") => Ok(Some(v)),
+            _ => {
+                self.pos = start_pos;
+                Ok(None)
+            }
+        }
+    }
+
     fn parse_fn(&mut self) -> Result<Option<(String, Vec<FnArg>)>> {
         let start_pos = self.pos;
 
@@ -283,7 +404,10 @@ impl Parser {
             self.skip_whitespace();
             self.match_str("=");
             self.skip_whitespace();
-            let value = self.parse_value()?;
+            let value = match self.parse_number() {
+                Some(value) => Some(Val::Num { value }),
+                None => self.parse_expr()?,
+            };
             self.skip_whitespace();
 
             if let (Some(name), Some(value)) = (name.clone(), value.clone()) {
@@ -345,6 +469,42 @@ impl Parser {
         Some(text)
     }
 
+    /// A decimal number like `3`, `-1` or `0.25`, unless it starts an identifier like `2fa`.
+    /// Only tried for function argument values; see [`Val::Num`].
+    fn parse_number(&mut self) -> Option<f64> {
+        let start_pos = self.pos;
+
+        let mut text = String::new();
+        if self.match_str("-") {
+            text.push('-');
+        }
+        let mut seen_dot = false;
+        while self.pos < self.chars.len() {
+            let ch = self.peek_char();
+            if ch.is_ascii_digit() {
+                text.push(ch);
+            } else if ch == '.' && !seen_dot && text.ends_with(|c: char| c.is_ascii_digit()) {
+                seen_dot = true;
+                text.push(ch);
+            } else {
+                break;
+            }
+            self.pos += 1;
+        }
+
+        let ends_ident = self.pos < self.chars.len() && {
+            let ch = self.peek_char();
+            ch.is_alphanumeric() || ch == '_' || ch == '-' || ch == '.'
+        };
+        match text.parse::<f64>() {
+            Ok(v) if !ends_ident && !text.ends_with('.') => Some(v),
+            _ => {
+                self.pos = start_pos;
+                None
+            }
+        }
+    }
+
     fn parse_fn_name(&mut self) -> Option<String> {
         let start_pos = self.pos;
 
@@ -819,6 +979,100 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn fn_number_args() -> Result<()> {
+        let mut p = Parser::new(r#"${[ random.range(min=-1, max=2.5, seed=2fa) ]}"#);
+        assert_eq!(
+            p.parse()?.tokens,
+            vec![
+                Token::Tag {
+                    val: Val::Fn {
+                        name: "random.range".into(),
+                        args: vec![
+                            FnArg { name: "min".into(), value: Val::Num { value: -1.0 } },
+                            FnArg { name: "max".into(), value: Val::Num { value: 2.5 } },
+                            FnArg { name: "seed".into(), value: Val::Var { name: "2fa".into() } },
+                        ],
+                    }
+                },
+                Token::Eof
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn digits_are_a_variable_outside_fn_args() -> Result<()> {
+        let mut p = Parser::new(r#"${[ 2024 ]}${[ 2024_q1 ]}"#);
+        assert_eq!(
+            p.parse()?.tokens,
+            vec![
+                Token::Tag { val: Val::Var { name: "2024".into() } },
+                Token::Tag { val: Val::Var { name: "2024_q1".into() } },
+                Token::Eof
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn filters_and_fallbacks() -> Result<()> {
+        let mut p = Parser::new(r#"${[ token | trim | base64 ?? fallback_token ?? 'none' ]}"#);
+        let filtered = Val::Filter {
+            value: Box::new(Val::Filter {
+                value: Box::new(Val::Var { name: "token".into() }),
+                name: "trim".into(),
+            }),
+            name: "base64".into(),
+        };
+        assert_eq!(
+            p.parse()?.tokens,
+            vec![
+                Token::Tag {
+                    val: Val::Fallback {
+                        value: Box::new(filtered),
+                        fallback: Box::new(Val::Fallback {
+                            value: Box::new(Val::Var { name: "fallback_token".into() }),
+                            fallback: Box::new(Val::Str { text: "none".into() }),
+                        }),
+                    }
+                },
+                Token::Eof
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn filter_invalid() -> Result<()> {
+        let mut p = Parser::new(r#"${[ a || b ]}"#);
+        assert_eq!(
+            p.parse()?.tokens,
+            vec![Token::Raw { text: "${[ a || b ]}".into() }, Token::Eof]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn display_round_trips() -> Result<()> {
+        for template in [
+            r#"${[ (a ?? b) | upper ]}"#,
+            r#"${[ (a ?? b) ?? c ]}"#,
+            r#"${[ a ?? b | json ]}"#,
+            r#"${[ foo(n=3, f=-0.25, v=bar ?? 'x') | urlencode ]}"#,
+        ] {
+            let tokens = Parser::new(template).parse()?;
+            assert_eq!(tokens.to_string(), template);
+            assert_eq!(Parser::new(&tokens.to_string()).parse()?, tokens);
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn token_display_var() -> Result<()> {
         assert_eq!(Val::Var { name: "foo".to_string() }.to_string(), "foo");
@@ -1,5 +1,6 @@
 use crate::error::Error::{RenderStackExceededError, VariableNotFound};
 use crate::error::Result;
+use crate::filters::apply_filter;
 use crate::{Parser, Token, Tokens, Val};
 use log::warn;
 use serde_json::json;
@@ -156,6 +157,7 @@ async fn render_value<T: TemplateCallback>(
             for a in args {
                 let v = match a.value.clone() {
                     Val::Bool { value } => serde_json::Value::Bool(value),
+                    Val::Num { value } => num_to_json(value),
                     Val::Null => serde_json::Value::Null,
                     _ => serde_json::Value::String(
                         Box::pin(render_value(a.value, vars, cb, opt, depth + 1)).await?,
@@ -167,12 +169,34 @@ async fn render_value<T: TemplateCallback>(
             Box::pin(parse_and_render_at_depth(&result, vars, cb, opt, depth)).await?
         }
         Val::Bool { value } => value.to_string(),
+        Val::Num { value } => value.to_string(),
+        Val::Filter { value, name } => {
+            let v = Box::pin(render_value(*value, vars, cb, opt, depth + 1)).await?;
+            apply_filter(&name, &v)?
+        }
+        Val::Fallback { value, fallback } => {
+            match Box::pin(render_value(*value, vars, cb, opt, depth + 1)).await {
+                Err(VariableNotFound(_)) => {
+                    Box::pin(render_value(*fallback, vars, cb, opt, depth + 1)).await?
+                }
+                r => r?,
+            }
+        }
         Val::Null => "".into(),
     };
 
     Ok(v)
 }
 
+/// Whole numbers go to functions as integers, so that `3` doesn't arrive as `3.0`
+fn num_to_json(value: f64) -> serde_json::Value {
+    if value.fract() == 0.0 && value.abs() < 2f64.powi(53) {
+        json!(value as i64)
+    } else {
+        json!(value)
+    }
+}
+
 #[cfg(test)]
 mod parse_and_render_tests {
     use crate::error::Error::{RenderError, RenderStackExceededError, VariableNotFound};
@@ -329,6 +353,58 @@ mod parse_and_render_tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn render_filters_and_fallbacks() -> Result<()> {
+        let empty_cb = EmptyCB {};
+        let vars = HashMap::from([
+            ("name".to_string(), " yaak ".to_string()),
+            ("empty".to_string(), "".to_string()),
+        ]);
+        let opt = RenderOptions { error_behavior: RenderErrorBehavior::Throw };
+        let render = |t: &'static str| parse_and_render(t, &vars, &empty_cb, &opt);
+        assert_eq!(render("${[ name | trim | upper ]}").await?, "YAAK");
+        assert_eq!(render("${[ missing ?? name | trim ]}").await?, "yaak");
+        assert_eq!(render("${[ missing ?? other ?? 'default' ]}").await?, "default");
+        assert_eq!(render("${[ empty ?? 'default' ]}").await?, "");
+        // Digits outside function arguments are a variable name, not a number
+        let vars = HashMap::from([("2024".to_string(), "year".to_string())]);
+        assert_eq!(parse_and_render("${[ 2024 ]}", &vars, &empty_cb, &opt).await?, "year");
+        assert_eq!(
+            render("${[ name | reverse ]}").await,
+            Err(RenderError("Unknown filter \"reverse\"".to_string()))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn render_fn_number_args() -> Result<()> {
+        let vars = HashMap::new();
+        let template = r#"${[ add(a=2, b=0.5) ]}"#;
+        let opt = RenderOptions { error_behavior: RenderErrorBehavior::Throw };
+        struct CB {}
+        impl TemplateCallback for CB {
+            async fn run(
+                &self,
+                _fn_name: &str,
+                args: HashMap<String, serde_json::Value>,
+            ) -> Result<String> {
+                Ok(format!("{} {}", args["a"], args["b"]))
+            }
+
+            fn transform_arg(
+                &self,
+                _fn_name: &str,
+                _arg_name: &str,
+                arg_value: &str,
+            ) -> Result<String> {
+                Ok(arg_value.to_string())
+            }
+        }
+
+        assert_eq!(parse_and_render(template, &vars, &CB {}, &opt).await?, "2 0.5");
+        Ok(())
+    }
+
     #[tokio::test]
     async fn render_fn_arg() -> Result<()> {
         let vars = HashMap::new();
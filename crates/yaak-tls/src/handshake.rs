@@ -0,0 +1,404 @@
+//! Recording what a rustls handshake settled on.
+//!
+//! Whoever drives a connection usually keeps the rustls session to itself (reqwest does), so
+//! the details are collected from inside rustls instead:
+//!
+//! - the certificate verifier sees the chain the server sent,
+//! - the client certificate resolver is only asked when the server requests a certificate,
+//! - each cipher suite is wrapped to note when its keys are put to use, which also gives
+//!   the protocol version.
+//!
+//! These hooks are shared by every connection made from one config and cannot tell them
+//! apart, so they report to whichever [`HandshakeObserver`] is polling the handshake on the
+//! current thread. See [`HandshakeObserver::observe`].
+
+use rustls::client::ResolvesClientCert;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::cipher::{
+    AeadKey, Iv, KeyBlockShape, MessageDecrypter, MessageEncrypter, Tls12AeadAlgorithm,
+    Tls13AeadAlgorithm, UnsupportedOperationError,
+};
+use rustls::crypto::{CryptoProvider, ring};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::sign::CertifiedKey;
+use rustls::{
+    CipherSuite, CipherSuiteCommon, ConnectionTrafficSecrets, DigitallySignedStruct,
+    DistinguishedName, SignatureScheme, SupportedCipherSuite, Tls12CipherSuite, Tls13CipherSuite,
+};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll};
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<HandshakeObserver>>> = const { RefCell::new(None) };
+}
+
+/// What one handshake settled on, as far as it could be seen.
+#[derive(Debug, Clone, Default)]
+pub struct Handshake {
+    /// `TLSv1.2` or `TLSv1.3`
+    pub protocol_version: Option<String>,
+    /// The IANA name, like `TLS13_AES_128_GCM_SHA256`
+    pub cipher_suite: Option<String>,
+    /// The chain the server sent, leaf first. Empty for a resumed session, which rustls
+    /// does not verify again.
+    pub server_certificates: Vec<CertificateDer<'static>>,
+    pub client_certificate_requested: bool,
+    pub client_certificate_presented: bool,
+}
+
+impl Handshake {
+    /// Whether keys were agreed without the server proving itself, which only happens when
+    /// an earlier session is resumed.
+    pub fn resumed(&self) -> bool {
+        self.cipher_suite.is_some() && self.server_certificates.is_empty()
+    }
+}
+
+/// Collects the handshake of the connection it is observing.
+#[derive(Debug, Default)]
+pub struct HandshakeObserver {
+    handshake: Mutex<Option<Handshake>>,
+}
+
+impl HandshakeObserver {
+    /// The last handshake observed, if one got far enough to record anything.
+    pub fn last(&self) -> Option<Handshake> {
+        self.handshake.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Forget the last handshake, ahead of a new connection.
+    pub fn reset(&self) {
+        *self.handshake.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Run `future`, recording any handshake it drives on a config from
+    /// [`get_observed_tls_config`](crate::get_observed_tls_config).
+    pub fn observe<F: Future>(self: &Arc<Self>, future: F) -> Observed<F> {
+        Observed { observer: self.clone(), future: Box::pin(future) }
+    }
+
+    fn record(update: impl FnOnce(&mut Handshake)) {
+        CURRENT.with(|current| {
+            if let Some(observer) = current.borrow().as_ref() {
+                let mut handshake = observer.handshake.lock().unwrap_or_else(|e| e.into_inner());
+                update(handshake.get_or_insert_with(Handshake::default));
+            }
+        });
+    }
+}
+
+/// A future that makes its [`HandshakeObserver`] current while it is being polled.
+pub struct Observed<F> {
+    observer: Arc<HandshakeObserver>,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Observed<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let previous = CURRENT.with(|c| c.replace(Some(self.observer.clone())));
+        // Put the previous observer back even if the future panics
+        struct Restore(Option<Arc<HandshakeObserver>>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.with(|c| *c.borrow_mut() = self.0.take());
+            }
+        }
+        let _restore = Restore(previous);
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// The ring provider, with each cipher suite reporting when it is used.
+pub(crate) fn observed_provider() -> CryptoProvider {
+    static SUITES: OnceLock<Vec<SupportedCipherSuite>> = OnceLock::new();
+    let mut provider = ring::default_provider();
+    // Suites are `&'static`, so the wrapped ones are built once and kept for good
+    let suites = SUITES.get_or_init(|| provider.cipher_suites.iter().map(observed_suite).collect());
+    provider.cipher_suites = suites.clone();
+    provider
+}
+
+fn observed_suite(suite: &SupportedCipherSuite) -> SupportedCipherSuite {
+    fn common(common: &CipherSuiteCommon) -> CipherSuiteCommon {
+        CipherSuiteCommon {
+            suite: common.suite,
+            hash_provider: common.hash_provider,
+            confidentiality_limit: common.confidentiality_limit,
+        }
+    }
+
+    match suite {
+        SupportedCipherSuite::Tls13(inner) => {
+            let aead_alg = Box::leak(Box::new(ObservedAead {
+                inner: inner.aead_alg,
+                suite: inner.common.suite,
+            }));
+            SupportedCipherSuite::Tls13(Box::leak(Box::new(Tls13CipherSuite {
+                common: common(&inner.common),
+                hkdf_provider: inner.hkdf_provider,
+                aead_alg,
+                quic: inner.quic,
+            })))
+        }
+        SupportedCipherSuite::Tls12(inner) => {
+            let aead_alg = Box::leak(Box::new(ObservedAead {
+                inner: inner.aead_alg,
+                suite: inner.common.suite,
+            }));
+            SupportedCipherSuite::Tls12(Box::leak(Box::new(Tls12CipherSuite {
+                common: common(&inner.common),
+                prf_provider: inner.prf_provider,
+                kx: inner.kx,
+                sign: inner.sign,
+                aead_alg,
+            })))
+        }
+    }
+}
+
+/// Wraps a suite's AEAD algorithm. rustls asks it for an encrypter once the handshake has
+/// agreed on keys, so that is when the suite is known to be in use.
+struct ObservedAead<A: ?Sized + 'static> {
+    inner: &'static A,
+    suite: CipherSuite,
+}
+
+impl<A: ?Sized> ObservedAead<A> {
+    fn record(&self, protocol_version: &str) {
+        HandshakeObserver::record(|h| {
+            h.protocol_version = Some(protocol_version.to_string());
+            h.cipher_suite = Some(format!("{:?}", self.suite));
+        });
+    }
+}
+
+impl Tls13AeadAlgorithm for ObservedAead<dyn Tls13AeadAlgorithm> {
+    fn encrypter(&self, key: AeadKey, iv: Iv) -> Box<dyn MessageEncrypter> {
+        self.record("TLSv1.3");
+        self.inner.encrypter(key, iv)
+    }
+
+    fn decrypter(&self, key: AeadKey, iv: Iv) -> Box<dyn MessageDecrypter> {
+        self.inner.decrypter(key, iv)
+    }
+
+    fn key_len(&self) -> usize {
+        self.inner.key_len()
+    }
+
+    fn extract_keys(
+        &self,
+        key: AeadKey,
+        iv: Iv,
+    ) -> Result<ConnectionTrafficSecrets, UnsupportedOperationError> {
+        self.inner.extract_keys(key, iv)
+    }
+
+    fn fips(&self) -> bool {
+        self.inner.fips()
+    }
+}
+
+impl Tls12AeadAlgorithm for ObservedAead<dyn Tls12AeadAlgorithm> {
+    fn encrypter(&self, key: AeadKey, iv: &[u8], extra: &[u8]) -> Box<dyn MessageEncrypter> {
+        self.record("TLSv1.2");
+        self.inner.encrypter(key, iv, extra)
+    }
+
+    fn decrypter(&self, key: AeadKey, iv: &[u8]) -> Box<dyn MessageDecrypter> {
+        self.inner.decrypter(key, iv)
+    }
+
+    fn key_block_shape(&self) -> KeyBlockShape {
+        self.inner.key_block_shape()
+    }
+
+    fn extract_keys(
+        &self,
+        key: AeadKey,
+        iv: &[u8],
+        explicit: &[u8],
+    ) -> Result<ConnectionTrafficSecrets, UnsupportedOperationError> {
+        self.inner.extract_keys(key, iv, explicit)
+    }
+
+    fn fips(&self) -> bool {
+        self.inner.fips()
+    }
+}
+
+/// Records the server's chain before handing it to the real verifier, so it is there to
+/// look at when verification fails too.
+#[derive(Debug)]
+pub(crate) struct ObservedVerifier(pub(crate) Arc<dyn ServerCertVerifier>);
+
+impl ServerCertVerifier for ObservedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer,
+        intermediates: &[CertificateDer],
+        server_name: &ServerName,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        HandshakeObserver::record(|h| {
+            h.server_certificates = std::iter::once(end_entity)
+                .chain(intermediates)
+                .map(|c| c.clone().into_owned())
+                .collect();
+        });
+        self.0.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.supported_verify_schemes()
+    }
+
+    fn requires_raw_public_keys(&self) -> bool {
+        self.0.requires_raw_public_keys()
+    }
+
+    fn root_hint_subjects(&self) -> Option<&[DistinguishedName]> {
+        self.0.root_hint_subjects()
+    }
+}
+
+/// Records whether the server asked for a client certificate, and whether there was one
+/// to give it.
+#[derive(Debug)]
+pub(crate) struct ObservedCertResolver(pub(crate) Arc<dyn ResolvesClientCert>);
+
+impl ResolvesClientCert for ObservedCertResolver {
+    fn resolve(
+        &self,
+        root_hint_subjects: &[&[u8]],
+        sigschemes: &[SignatureScheme],
+    ) -> Option<Arc<CertifiedKey>> {
+        let key = self.0.resolve(root_hint_subjects, sigschemes);
+        HandshakeObserver::record(|h| {
+            h.client_certificate_requested = true;
+            h.client_certificate_presented = key.is_some();
+        });
+        key
+    }
+
+    fn only_raw_public_keys(&self) -> bool {
+        self.0.only_raw_public_keys()
+    }
+
+    fn has_certs(&self) -> bool {
+        self.0.has_certs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::certificate::tests::{SAN_CRT, SAN_KEY, der};
+    use crate::get_observed_tls_config;
+    use rustls::server::WebPkiClientVerifier;
+    use rustls::{ClientConnection, RootCertStore, ServerConfig, ServerConnection};
+    use std::task::Waker;
+
+    fn server_config(
+        versions: &[&'static rustls::SupportedProtocolVersion],
+        ask_for_client_cert: bool,
+    ) -> Arc<ServerConfig> {
+        let provider = Arc::new(ring::default_provider());
+        let builder = ServerConfig::builder_with_provider(provider.clone())
+            .with_protocol_versions(versions)
+            .unwrap();
+        let builder = if ask_for_client_cert {
+            let mut roots = RootCertStore::empty();
+            roots.add(der(SAN_CRT)).unwrap();
+            let verifier = WebPkiClientVerifier::builder_with_provider(roots.into(), provider)
+                .allow_unauthenticated()
+                .build()
+                .unwrap();
+            builder.with_client_cert_verifier(verifier)
+        } else {
+            builder.with_no_client_auth()
+        };
+        let key = rustls_pemfile::private_key(&mut SAN_KEY.as_bytes()).unwrap().unwrap();
+        Arc::new(builder.with_single_cert(vec![der(SAN_CRT)], key).unwrap())
+    }
+
+    /// Run a handshake to completion, passing records between the two ends in memory.
+    fn handshake(server: Arc<ServerConfig>) {
+        let config = Arc::new(get_observed_tls_config(false, true, None).unwrap());
+        let mut client = ClientConnection::new(config, "localhost".try_into().unwrap()).unwrap();
+        let mut server = ServerConnection::new(server).unwrap();
+        while client.is_handshaking() || server.is_handshaking() {
+            let mut records = Vec::new();
+            client.write_tls(&mut records).unwrap();
+            server.read_tls(&mut records.as_slice()).unwrap();
+            server.process_new_packets().unwrap();
+
+            records.clear();
+            server.write_tls(&mut records).unwrap();
+            client.read_tls(&mut records.as_slice()).unwrap();
+            client.process_new_packets().unwrap();
+        }
+    }
+
+    fn observed_handshake(server: Arc<ServerConfig>) -> Handshake {
+        let observer = Arc::new(HandshakeObserver::default());
+        let mut future = std::pin::pin!(observer.observe(async { handshake(server) }));
+        assert!(future.as_mut().poll(&mut Context::from_waker(Waker::noop())).is_ready());
+        observer.last().expect("nothing was recorded")
+    }
+
+    #[test]
+    fn records_a_tls13_handshake() {
+        let handshake = observed_handshake(server_config(&[&rustls::version::TLS13], false));
+        assert_eq!(handshake.protocol_version.as_deref(), Some("TLSv1.3"));
+        assert!(handshake.cipher_suite.unwrap().starts_with("TLS13_"));
+        assert_eq!(handshake.server_certificates, vec![der(SAN_CRT)]);
+        assert!(!handshake.client_certificate_requested);
+    }
+
+    #[test]
+    fn records_a_tls12_handshake() {
+        let handshake = observed_handshake(server_config(&[&rustls::version::TLS12], false));
+        assert_eq!(handshake.protocol_version.as_deref(), Some("TLSv1.2"));
+        assert!(handshake.cipher_suite.unwrap().starts_with("TLS_ECDHE_ECDSA_"));
+        assert_eq!(handshake.server_certificates.len(), 1);
+    }
+
+    #[test]
+    fn records_an_unanswered_client_certificate_request() {
+        let handshake = observed_handshake(server_config(&[&rustls::version::TLS13], true));
+        assert!(handshake.client_certificate_requested);
+        assert!(!handshake.client_certificate_presented);
+    }
+
+    #[test]
+    fn records_nothing_without_an_observer() {
+        let observer = HandshakeObserver::default();
+        handshake(server_config(&[&rustls::version::TLS13], false));
+        assert!(observer.last().is_none());
+    }
+}
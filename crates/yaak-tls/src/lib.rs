@@ -1,11 +1,12 @@
 use crate::error::Error::GenericError;
 use crate::error::Result;
+use crate::handshake::{ObservedCertResolver, ObservedVerifier, observed_provider};
 use log::debug;
 use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
 use rustls::crypto::ring;
 use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
 use rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
-use rustls_platform_verifier::BuilderVerifierExt;
+use rustls_platform_verifier::Verifier;
 use std::fs;
 use std::io::BufReader;
 use std::path::Path;
@@ -13,7 +14,9 @@ use std::str::FromStr;
 use std::sync::Arc;
 use yasna::models::ObjectIdentifier;
 
+pub mod certificate;
 pub mod error;
+pub mod handshake;
 
 const OID_RSA_ENCRYPTION: &[u64] = &[1, 2, 840, 113549, 1, 1, 1];
 const OID_EC_PUBLIC_KEY: &[u64] = &[1, 2, 840, 10045, 2, 1];
@@ -36,50 +39,57 @@ pub fn get_tls_config(
     with_alpn: bool,
     client_cert: Option<ClientCertificateConfig>,
 ) -> Result<ClientConfig> {
-    let maybe_client_cert = load_client_cert(client_cert)?;
-
-    let mut client = if validate_certificates {
-        build_with_validation(maybe_client_cert)
-    } else {
-        build_without_validation(maybe_client_cert)
-    }?;
-
-    if with_alpn {
-        client.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
-    }
+    build_tls_config(validate_certificates, with_alpn, client_cert, false)
+}
 
-    Ok(client)
+/// Like [`get_tls_config`], but reporting each handshake to the
+/// [`HandshakeObserver`](handshake::HandshakeObserver) that drives it.
+pub fn get_observed_tls_config(
+    validate_certificates: bool,
+    with_alpn: bool,
+    client_cert: Option<ClientCertificateConfig>,
+) -> Result<ClientConfig> {
+    build_tls_config(validate_certificates, with_alpn, client_cert, true)
 }
 
-fn build_with_validation(
-    client_cert: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
+fn build_tls_config(
+    validate_certificates: bool,
+    with_alpn: bool,
+    client_cert: Option<ClientCertificateConfig>,
+    observed: bool,
 ) -> Result<ClientConfig> {
-    let arc_crypto_provider = Arc::new(ring::default_provider());
-    let builder = ClientConfig::builder_with_provider(arc_crypto_provider)
-        .with_safe_default_protocol_versions()?
-        .with_platform_verifier()?;
+    let maybe_client_cert = load_client_cert(client_cert)?;
 
-    if let Some((certs, key)) = client_cert {
-        return Ok(builder.with_client_auth_cert(certs, key)?);
+    let provider = Arc::new(if observed { observed_provider() } else { ring::default_provider() });
+    let mut verifier: Arc<dyn ServerCertVerifier> = if validate_certificates {
+        Arc::new(Verifier::new(provider.clone())?)
+    } else {
+        Arc::new(NoVerifier)
+    };
+    if observed {
+        verifier = Arc::new(ObservedVerifier(verifier));
     }
 
-    Ok(builder.with_no_client_auth())
-}
-
-fn build_without_validation(
-    client_cert: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
-) -> Result<ClientConfig> {
-    let arc_crypto_provider = Arc::new(ring::default_provider());
-    let builder = ClientConfig::builder_with_provider(arc_crypto_provider)
+    let builder = ClientConfig::builder_with_provider(provider)
         .with_safe_default_protocol_versions()?
         .dangerous()
-        .with_custom_certificate_verifier(Arc::new(NoVerifier));
+        .with_custom_certificate_verifier(verifier);
 
-    if let Some((certs, key)) = client_cert {
-        return Ok(builder.with_client_auth_cert(certs, key)?);
+    let mut client = match maybe_client_cert {
+        Some((certs, key)) => builder.with_client_auth_cert(certs, key)?,
+        None => builder.with_no_client_auth(),
+    };
+
+    if observed {
+        client.client_auth_cert_resolver =
+            Arc::new(ObservedCertResolver(client.client_auth_cert_resolver.clone()));
     }
 
-    Ok(builder.with_no_client_auth())
+    if with_alpn {
+        client.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    }
+
+    Ok(client)
 }
 
 fn load_client_cert(
@@ -447,22 +447,24 @@ struct PreparedHttpSend {
     cookie_jar: Option<CookieJar>,
 }
 
-/// A template callback for a host with no plugins. Variables render; a function is a clear
-/// refusal naming the function, so the user knows what the request needs rather than seeing
-/// an empty string sent in its place.
+/// A template callback for a host with no plugins. Variables and the native functions render;
+/// any other function is a clear refusal naming the function, so the user knows what the
+/// request needs rather than seeing an empty string sent in its place.
 struct NoPluginsCallback;
 
 impl TemplateCallback for NoPluginsCallback {
     fn run(
         &self,
         fn_name: &str,
-        _args: HashMap<String, serde_json::Value>,
+        args: HashMap<String, serde_json::Value>,
     ) -> impl std::future::Future<Output = yaak_templates::error::Result<String>> + Send {
-        let message = format!(
-            "This request uses the template function \"{fn_name}\", which needs plugins. \
-             Plugins aren't available in the browser yet"
-        );
-        async move { Err(yaak_templates::error::Error::RenderError(message)) }
+        let result = yaak_template_functions::call(fn_name, &args).unwrap_or_else(|| {
+            Err(yaak_templates::error::Error::RenderError(format!(
+                "This request uses the template function \"{fn_name}\", which needs plugins. \
+                 Plugins aren't available in the browser yet"
+            )))
+        });
+        async move { result }
     }
 
     fn transform_arg(
@@ -481,7 +483,7 @@ impl TemplateCallback for NoPluginsCallback {
 /// posts to the Yaak server.
 ///
 /// Refuses, with a message the user can act on, when the request needs something this host
-/// doesn't have: an authentication plugin, or a template function.
+/// doesn't have: an authentication plugin, or a template function only a plugin provides.
 #[wasm_bindgen]
 pub async fn prepare_http_send(payload: JsValue) -> Result<JsValue> {
     let req: PrepareHttpSendReq = from_js(payload)?;
@@ -8,5 +8,6 @@ pub use manager::WebsocketManager;
 pub use render::render_websocket_request;
 
 // Re-export http types needed by consumers
-pub use http::HeaderMap;
+pub use http::{HeaderMap, HeaderName};
+pub use tokio_tungstenite::tungstenite::Message;
 pub use tokio_tungstenite::tungstenite::http::HeaderValue;
@@ -0,0 +1,314 @@
+//! Checking a finished response against the assertions on its request.
+//!
+//! Runs inside the send, after the body has fully arrived, so every host that sends — the
+//! desktop, the CLI, the hosted sender — records the same results on the same response row.
+//! The assertions passed in are expected to be rendered already; disabled ones are skipped.
+
+use regex::Regex;
+use serde_json::Value;
+use yaak_models::models::{
+    HttpAssertionKind, HttpAssertionResult, HttpRequestAssertion, HttpResponse,
+};
+
+/// Whether any enabled assertion needs the response body, so a send knows to keep a copy of it.
+pub fn assertions_read_body(assertions: &[HttpRequestAssertion]) -> bool {
+    assertions.iter().any(|a| {
+        a.enabled
+            && matches!(
+                a.kind,
+                HttpAssertionKind::JsonPathEquals
+                    | HttpAssertionKind::JsonPathContains
+                    | HttpAssertionKind::BodyMatches
+            )
+    })
+}
+
+/// Run every enabled assertion against a response and its decoded body.
+pub fn evaluate_assertions(
+    assertions: &[HttpRequestAssertion],
+    response: &HttpResponse,
+    body: &[u8],
+) -> Vec<HttpAssertionResult> {
+    // Parsed once, lazily, and shared by every JSON path assertion.
+    let mut json_body: Option<Result<Value, String>> = None;
+
+    assertions
+        .iter()
+        .filter(|a| a.enabled)
+        .map(|assertion| {
+            let outcome = match assertion.kind {
+                HttpAssertionKind::StatusEquals => check_status(response, &assertion.value),
+                HttpAssertionKind::HeaderMatches => {
+                    check_header(response, &assertion.target, &assertion.value)
+                }
+                HttpAssertionKind::JsonPathEquals | HttpAssertionKind::JsonPathContains => {
+                    let json = json_body.get_or_insert_with(|| {
+                        serde_json::from_slice::<Value>(body)
+                            .map_err(|e| format!("Response body is not JSON: {e}"))
+                    });
+                    match json {
+                        Ok(json) => check_json_path(
+                            json,
+                            &assertion.target,
+                            &assertion.value,
+                            matches!(assertion.kind, HttpAssertionKind::JsonPathContains),
+                        ),
+                        Err(e) => Err(e.clone()),
+                    }
+                }
+                HttpAssertionKind::BodyMatches => check_body(body, &assertion.value),
+                HttpAssertionKind::ElapsedUnder => check_elapsed(response, &assertion.value),
+            };
+
+            let (passed, actual, error) = match outcome {
+                Ok((passed, actual)) => (passed, actual, None),
+                Err(error) => (false, None, Some(error)),
+            };
+            HttpAssertionResult {
+                assertion_id: assertion.id.clone(),
+                kind: assertion.kind.clone(),
+                target: assertion.target.clone(),
+                expected: assertion.value.clone(),
+                actual,
+                passed,
+                error,
+            }
+        })
+        .collect()
+}
+
+/// `Ok((passed, actual))` when the check ran, `Err(reason)` when it could not.
+type CheckOutcome = Result<(bool, Option<String>), String>;
+
+fn check_status(response: &HttpResponse, expected: &str) -> CheckOutcome {
+    let expected = expected
+        .trim()
+        .parse::<i32>()
+        .map_err(|_| format!("Expected status \"{expected}\" is not a number"))?;
+    Ok((response.status == expected, Some(response.status.to_string())))
+}
+
+fn check_header(response: &HttpResponse, name: &str, pattern: &str) -> CheckOutcome {
+    let regex = compile(pattern)?;
+    let values = response
+        .headers
+        .iter()
+        .filter(|h| h.name.eq_ignore_ascii_case(name.trim()))
+        .map(|h| h.value.as_str())
+        .collect::<Vec<_>>();
+    if values.is_empty() {
+        return Ok((false, None));
+    }
+    let passed = values.iter().any(|v| regex.is_match(v));
+    Ok((passed, Some(values.join(", "))))
+}
+
+fn check_body(body: &[u8], pattern: &str) -> CheckOutcome {
+    let regex = compile(pattern)?;
+    let body = String::from_utf8_lossy(body);
+    Ok((regex.is_match(&body), None))
+}
+
+fn check_elapsed(response: &HttpResponse, limit: &str) -> CheckOutcome {
+    let limit = limit
+        .trim()
+        .parse::<i32>()
+        .map_err(|_| format!("Elapsed limit \"{limit}\" is not a number of milliseconds"))?;
+    Ok((response.elapsed < limit, Some(format!("{}ms", response.elapsed))))
+}
+
+fn check_json_path(json: &Value, path: &str, expected: &str, contains: bool) -> CheckOutcome {
+    let Some(actual) = select_json_path(json, path)? else {
+        return Ok((false, None));
+    };
+
+    // A bare word like `ok` is not JSON, but it is what people type when they mean the string.
+    let expected_value =
+        serde_json::from_str::<Value>(expected).unwrap_or_else(|_| Value::String(expected.into()));
+
+    let passed = if contains {
+        match actual {
+            Value::String(s) => s.contains(expected),
+            Value::Array(items) => items.contains(&expected_value),
+            Value::Object(map) => map.contains_key(expected),
+            _ => false,
+        }
+    } else {
+        match (actual, &expected_value) {
+            // `200` and `"200"` both read as two hundred to whoever wrote the assertion.
+            (Value::String(s), _) => s == expected,
+            (actual, expected_value) => actual == expected_value,
+        }
+    };
+
+    let actual = match actual {
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    };
+    Ok((passed, Some(actual)))
+}
+
+/// Walk a JSON path made of `$`, `.key`, `['key']`, and `[index]` segments.
+///
+/// This is the subset every assertion so far has needed; filters and wildcards would make a
+/// path match many values, and an assertion compares against one.
+pub(crate) fn select_json_path<'a>(
+    json: &'a Value,
+    path: &str,
+) -> Result<Option<&'a Value>, String> {
+    let path = path.trim();
+    let mut rest = path.strip_prefix('$').unwrap_or(path);
+    let mut current = json;
+
+    while !rest.is_empty() {
+        let (segment, remaining) = if let Some(after_dot) = rest.strip_prefix('.') {
+            let end = after_dot.find(['.', '[']).unwrap_or(after_dot.len());
+            (Segment::Key(&after_dot[..end]), &after_dot[end..])
+        } else if let Some(after_bracket) = rest.strip_prefix('[') {
+            let end = after_bracket
+                .find(']')
+                .ok_or_else(|| format!("Unclosed bracket in JSON path \"{path}\""))?;
+            let inner = after_bracket[..end].trim();
+            let segment = match inner.strip_prefix(['\'', '"']) {
+                Some(quoted) => Segment::Key(&quoted[..quoted.len().saturating_sub(1)]),
+                None => Segment::Index(
+                    inner
+                        .parse::<usize>()
+                        .map_err(|_| format!("Invalid index [{inner}] in JSON path \"{path}\""))?,
+                ),
+            };
+            (segment, &after_bracket[end + 1..])
+        } else if rest.len() == path.len() {
+            // Tolerate a path written without the leading `$.`, like `data.id`.
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            (Segment::Key(&rest[..end]), &rest[end..])
+        } else {
+            return Err(format!("Invalid JSON path \"{path}\""));
+        };
+
+        let next = match segment {
+            Segment::Key(key) => current.get(key),
+            Segment::Index(index) => current.get(index),
+        };
+        let Some(next) = next else {
+            return Ok(None);
+        };
+        current = next;
+        rest = remaining;
+    }
+
+    Ok(Some(current))
+}
+
+enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+fn compile(pattern: &str) -> Result<Regex, String> {
+    Regex::new(pattern).map_err(|e| format!("Invalid regex \"{pattern}\": {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yaak_models::models::HttpResponseHeader;
+
+    fn assertion(kind: HttpAssertionKind, target: &str, value: &str) -> HttpRequestAssertion {
+        HttpRequestAssertion {
+            kind,
+            target: target.to_string(),
+            value: value.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn response() -> HttpResponse {
+        HttpResponse {
+            status: 201,
+            elapsed: 42,
+            headers: vec![HttpResponseHeader {
+                name: "Content-Type".to_string(),
+                value: "application/json; charset=utf-8".to_string(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    const BODY: &[u8] = br#"{"data":{"id":"abc","count":3,"tags":["a","b"],"ok":true}}"#;
+
+    #[test]
+    fn passes_and_fails_each_kind() {
+        let results = evaluate_assertions(
+            &[
+                assertion(HttpAssertionKind::StatusEquals, "", "201"),
+                assertion(HttpAssertionKind::StatusEquals, "", "200"),
+                assertion(HttpAssertionKind::HeaderMatches, "content-type", "^application/json"),
+                assertion(HttpAssertionKind::HeaderMatches, "x-missing", ".*"),
+                assertion(HttpAssertionKind::JsonPathEquals, "$.data.id", "abc"),
+                assertion(HttpAssertionKind::JsonPathEquals, "$.data.count", "3"),
+                assertion(HttpAssertionKind::JsonPathEquals, "$['data'].ok", "false"),
+                assertion(HttpAssertionKind::JsonPathContains, "$.data.tags", "b"),
+                assertion(HttpAssertionKind::JsonPathContains, "data.tags[0]", "a"),
+                assertion(HttpAssertionKind::BodyMatches, "", r#""count":\d+"#),
+                assertion(HttpAssertionKind::ElapsedUnder, "", "10"),
+            ],
+            &response(),
+            BODY,
+        );
+
+        assert_eq!(
+            results.iter().map(|r| r.passed).collect::<Vec<_>>(),
+            vec![
+                true, false, true, false, true, true, false, true, true, true, false
+            ],
+        );
+        assert_eq!(results[1].actual.as_deref(), Some("201"));
+        assert_eq!(results[10].actual.as_deref(), Some("42ms"));
+    }
+
+    #[test]
+    fn skips_disabled_assertions() {
+        let results = evaluate_assertions(
+            &[HttpRequestAssertion {
+                enabled: false,
+                ..assertion(HttpAssertionKind::StatusEquals, "", "500")
+            }],
+            &response(),
+            BODY,
+        );
+        assert!(results.is_empty());
+    }
+
+    /// A check that cannot run fails with a reason, rather than passing by omission.
+    #[test]
+    fn reports_checks_that_cannot_run() {
+        let results = evaluate_assertions(
+            &[
+                assertion(HttpAssertionKind::BodyMatches, "", "("),
+                assertion(HttpAssertionKind::JsonPathEquals, "$.id", "1"),
+                assertion(HttpAssertionKind::StatusEquals, "", "ok"),
+            ],
+            &response(),
+            b"not json",
+        );
+
+        assert!(results.iter().all(|r| !r.passed && r.error.is_some()), "{results:?}");
+    }
+
+    #[test]
+    fn missing_json_path_fails_without_error() {
+        let results = evaluate_assertions(
+            &[assertion(
+                HttpAssertionKind::JsonPathEquals,
+                "$.data.nope[2]",
+                "x",
+            )],
+            &response(),
+            BODY,
+        );
+        assert!(!results[0].passed);
+        assert_eq!(results[0].error, None);
+        assert_eq!(results[0].actual, None);
+    }
+}
@@ -0,0 +1,428 @@
+//! Load checks: one request, rendered once and fired repeatedly.
+//!
+//! Templates, authentication, and cookies are resolved exactly once, the way a single send would
+//! resolve them, and every request after that reuses the result. That keeps template functions
+//! and auth plugins (which may prompt, or hit a token endpoint) out of the hot loop, so the
+//! numbers measure the server rather than Yaak. Requests go through the same cached client a
+//! normal send uses, so proxy, certificate, and DNS override settings all apply. Nothing is
+//! stored: no responses, no timeline, no cookie jar updates.
+
+use crate::error::Result;
+use crate::send::{
+    SendHttpRequestError, apply_plugin_authentication, layer_variables, load_cookie_jar,
+    render_proxy_credentials, resolve_send_inputs,
+};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use yaak_crypto::manager::EncryptionManager;
+use yaak_http::client::HttpConnectionOptions;
+use yaak_http::manager::HttpConnectionManager;
+use yaak_http::sender::ReqwestSender;
+use yaak_http::transaction::HttpTransaction;
+use yaak_http::types::{SendableBody, SendableHttpRequest, SendableHttpRequestOptions};
+use yaak_models::models::{
+    EnvironmentVariable, Http3Mode, HttpBenchErrorCount, HttpBenchLatency, HttpBenchReport,
+    HttpBenchStatusCount,
+};
+use yaak_models::query_manager::QueryManager;
+use yaak_models::render::render_http_request;
+use yaak_plugins::events::{PluginContext, RenderPurpose};
+use yaak_plugins::manager::PluginManager;
+use yaak_plugins::template_callback::PluginTemplateCallback;
+use yaak_templates::RenderOptions;
+use yaak_tls::find_client_certificate;
+
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    /// Requests in flight at once
+    pub concurrency: u32,
+    /// Requests per second across all workers. `None` sends as fast as responses come back.
+    pub rate: Option<f64>,
+    pub duration: Duration,
+    /// Stop after this many requests, even if time remains
+    pub max_requests: Option<u64>,
+}
+
+pub struct BenchHttpRequestParams<'a> {
+    pub query_manager: &'a QueryManager,
+    pub request_id: &'a str,
+    pub environment_id: Option<&'a str>,
+    pub cookie_jar_id: Option<&'a str>,
+    pub plugin_manager: Arc<PluginManager>,
+    pub encryption_manager: Arc<EncryptionManager>,
+    pub plugin_context: &'a PluginContext,
+    pub connection_manager: &'a HttpConnectionManager,
+    pub cancelled_rx: Option<watch::Receiver<bool>>,
+    pub options: BenchOptions,
+    /// Variables layered above every environment for this bench only
+    pub variables: Vec<EnvironmentVariable>,
+}
+
+/// The rendered request every worker sends a copy of.
+struct BenchTemplate {
+    url: String,
+    method: String,
+    headers: Vec<(String, String)>,
+    body: Option<SendableBody>,
+    options: SendableHttpRequestOptions,
+}
+
+impl BenchTemplate {
+    fn sendable(&self) -> SendableHttpRequest {
+        SendableHttpRequest {
+            url: self.url.clone(),
+            method: self.method.clone(),
+            headers: self.headers.clone(),
+            body: match &self.body {
+                Some(SendableBody::Bytes(bytes)) => Some(SendableBody::Bytes(bytes.clone())),
+                _ => None,
+            },
+            options: self.options.clone(),
+        }
+    }
+}
+
+/// What happened to one request.
+#[derive(Debug, Clone, PartialEq)]
+enum Sample {
+    Completed { status: u16, latency: Duration },
+    Failed { error: String },
+}
+
+/// Hands out send slots: one per request, paced by the rate and bounded by the deadline.
+struct Schedule {
+    started_at: Instant,
+    deadline: Instant,
+    rate: Option<f64>,
+    max_requests: Option<u64>,
+    issued: AtomicU64,
+}
+
+impl Schedule {
+    /// Wait for this worker's next slot, or return false when the run is over.
+    async fn next(&self) -> bool {
+        let n = self.issued.fetch_add(1, Ordering::Relaxed);
+        if self.max_requests.is_some_and(|max| n >= max) {
+            return false;
+        }
+        if let Some(rate) = self.rate.filter(|r| *r > 0.0) {
+            let due = self.started_at + Duration::from_secs_f64(n as f64 / rate);
+            if due >= self.deadline {
+                return false;
+            }
+            tokio::time::sleep_until(due).await;
+        }
+        Instant::now() < self.deadline
+    }
+}
+
+/// Render a request once, then send it at the configured concurrency and rate until the
+/// duration (or request limit) runs out, and report what came back.
+pub async fn bench_http_request_by_id(
+    params: BenchHttpRequestParams<'_>,
+) -> Result<HttpBenchReport> {
+    let request = params
+        .query_manager
+        .connect()
+        .get_http_request(params.request_id)
+        .map_err(SendHttpRequestError::LoadRequest)?;
+    let cookie_jar = load_cookie_jar(params.query_manager, params.cookie_jar_id)?;
+    let mut inputs = resolve_send_inputs(
+        params.query_manager,
+        &request,
+        params.environment_id,
+        cookie_jar.map(|jar| jar.cookies),
+    )?;
+    layer_variables(&mut inputs.environment_chain, &request.workspace_id, &params.variables);
+    let (resolved_request, auth_context_id) =
+        (inputs.request.request().clone(), inputs.request.auth_context_id().to_string());
+
+    let template_callback = PluginTemplateCallback::new(
+        params.plugin_manager.clone(),
+        params.encryption_manager.clone(),
+        params.plugin_context,
+        RenderPurpose::Send,
+    );
+    render_proxy_credentials(
+        &mut inputs.runtime_config,
+        &inputs.environment_chain,
+        &template_callback,
+    )
+    .await?;
+    let rendered_request = render_http_request(
+        &resolved_request,
+        inputs.environment_chain,
+        &template_callback,
+        &RenderOptions::throw(),
+    )
+    .await
+    .map_err(SendHttpRequestError::RenderRequest)?;
+
+    let mut sendable_request = SendableHttpRequest::from_http_request(
+        &rendered_request,
+        inputs.runtime_config.send_options(),
+    )
+    .await
+    .map_err(SendHttpRequestError::BuildSendableRequest)?;
+    apply_plugin_authentication(
+        &mut sendable_request,
+        &rendered_request,
+        &auth_context_id,
+        &params.plugin_manager,
+        params.plugin_context,
+    )
+    .await
+    .map_err(SendHttpRequestError::PrepareSendableRequest)?;
+
+    // A streamed body (a file or multipart upload) can only be read once, so read it up front
+    // and send the same bytes every time.
+    let body = match sendable_request.body.take() {
+        Some(SendableBody::Stream { mut data, .. }) => {
+            let mut bytes = Vec::new();
+            data.read_to_end(&mut bytes).await?;
+            Some(SendableBody::Bytes(bytes.into()))
+        }
+        body => body,
+    };
+    let template = Arc::new(BenchTemplate {
+        url: sendable_request.url,
+        method: sendable_request.method,
+        headers: sendable_request.headers,
+        body,
+        options: sendable_request.options,
+    });
+
+    let runtime_config = &inputs.runtime_config;
+    let cached_client = params
+        .connection_manager
+        .get_client(&HttpConnectionOptions {
+            id: params.plugin_context.id.clone(),
+            validate_certificates: runtime_config.settings.validate_certificates.value,
+            proxy: runtime_config.proxy.clone(),
+            client_certificate: find_client_certificate(
+                &template.url,
+                &runtime_config.client_certificates,
+            ),
+            dns_overrides: runtime_config.dns_overrides.clone(),
+            address_filter: None,
+            http3: runtime_config.settings.http3.value != Http3Mode::Off,
+        })
+        .await
+        .map_err(SendHttpRequestError::CreateHttpClient)?;
+
+    // Cookies from the jar go out with every request, but what comes back is not kept: each
+    // request should look like the first, and the jar should look like nothing happened.
+    let send_cookies = runtime_config.settings.send_cookies.value;
+    let cookie_store = inputs.cookie_store;
+    let (_cancel_tx, default_cancelled_rx) = watch::channel(false);
+    let cancelled_rx = params.cancelled_rx.clone().unwrap_or(default_cancelled_rx);
+
+    let options = params.options;
+    let started_at = Instant::now();
+    let schedule = Arc::new(Schedule {
+        started_at,
+        deadline: started_at + options.duration,
+        rate: options.rate,
+        max_requests: options.max_requests,
+        issued: AtomicU64::new(0),
+    });
+
+    let mut workers = JoinSet::new();
+    for _ in 0..options.concurrency.max(1) {
+        let sender = ReqwestSender::with_client(cached_client.client.clone());
+        let transaction = match cookie_store.clone() {
+            Some(store) => {
+                HttpTransaction::with_cookie_behavior(sender, store, send_cookies, false)
+            }
+            None => HttpTransaction::new(sender),
+        };
+        let template = template.clone();
+        let schedule = schedule.clone();
+        let cancelled_rx = cancelled_rx.clone();
+        workers.spawn(async move {
+            let mut samples = Vec::new();
+            loop {
+                // Checked on its own so the borrow is not held across the wait below.
+                if *cancelled_rx.borrow() {
+                    break;
+                }
+                if !schedule.next().await {
+                    break;
+                }
+                // Nobody reads the timeline here, and a closed channel just drops the events.
+                let (event_tx, _) = mpsc::channel(1);
+                let sent_at = Instant::now();
+                let sample = match transaction
+                    .execute_with_cancellation(template.sendable(), cancelled_rx.clone(), event_tx)
+                    .await
+                {
+                    Ok(response) => {
+                        let status = response.status;
+                        match response.drain().await {
+                            Ok(()) => Sample::Completed { status, latency: sent_at.elapsed() },
+                            Err(err) => Sample::Failed { error: err.to_string() },
+                        }
+                    }
+                    Err(_) if *cancelled_rx.borrow() => break,
+                    Err(err) => Sample::Failed { error: err.to_string() },
+                };
+                samples.push(sample);
+            }
+            samples
+        });
+    }
+
+    let mut samples = Vec::new();
+    while let Some(result) = workers.join_next().await {
+        if let Ok(mut worker_samples) = result {
+            samples.append(&mut worker_samples);
+        }
+    }
+
+    let mut report = summarize(&samples, started_at.elapsed());
+    report.request_id = request.id;
+    report.method = template.method.clone();
+    report.url = template.url.clone();
+    report.concurrency = i32::try_from(options.concurrency.max(1)).unwrap_or(i32::MAX);
+    report.rate = options.rate;
+    report.cancelled = *cancelled_rx.borrow();
+    Ok(report)
+}
+
+fn summarize(samples: &[Sample], elapsed: Duration) -> HttpBenchReport {
+    let mut latencies = Vec::new();
+    let mut status_codes: BTreeMap<u16, i32> = BTreeMap::new();
+    let mut errors: BTreeMap<&str, i32> = BTreeMap::new();
+    for sample in samples {
+        match sample {
+            Sample::Completed { status, latency } => {
+                latencies.push(latency.as_nanos() as f64 / 1_000_000.0);
+                *status_codes.entry(*status).or_default() += 1;
+            }
+            Sample::Failed { error } => *errors.entry(error).or_default() += 1,
+        }
+    }
+    latencies.sort_by(f64::total_cmp);
+
+    let completed = latencies.len();
+    let seconds = elapsed.as_secs_f64();
+    let mut errors = errors
+        .into_iter()
+        .map(|(error, count)| HttpBenchErrorCount { error: error.to_string(), count })
+        .collect::<Vec<_>>();
+    errors.sort_by(|a, b| b.count.cmp(&a.count));
+
+    HttpBenchReport {
+        elapsed: i32::try_from(elapsed.as_millis()).unwrap_or(i32::MAX),
+        total: samples.len() as i32,
+        completed: completed as i32,
+        failed: (samples.len() - completed) as i32,
+        throughput: if seconds > 0.0 { completed as f64 / seconds } else { 0.0 },
+        latency: HttpBenchLatency {
+            min: latencies.first().copied().unwrap_or_default(),
+            mean: if completed > 0 {
+                latencies.iter().sum::<f64>() / completed as f64
+            } else {
+                0.0
+            },
+            p50: percentile(&latencies, 50.0),
+            p90: percentile(&latencies, 90.0),
+            p95: percentile(&latencies, 95.0),
+            p99: percentile(&latencies, 99.0),
+            max: latencies.last().copied().unwrap_or_default(),
+        },
+        status_codes: status_codes
+            .into_iter()
+            .map(|(status, count)| HttpBenchStatusCount { status: status as i32, count })
+            .collect(),
+        errors,
+        ..Default::default()
+    }
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completed(status: u16, ms: u64) -> Sample {
+        Sample::Completed { status, latency: Duration::from_millis(ms) }
+    }
+
+    fn failed(error: &str) -> Sample {
+        Sample::Failed { error: error.to_string() }
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let values = (1..=100).map(f64::from).collect::<Vec<_>>();
+        assert_eq!(percentile(&values, 50.0), 50.0);
+        assert_eq!(percentile(&values, 99.0), 99.0);
+        assert_eq!(percentile(&values, 100.0), 100.0);
+        assert_eq!(percentile(&[7.0], 90.0), 7.0);
+        assert_eq!(percentile(&[], 90.0), 0.0);
+    }
+
+    #[test]
+    fn summarizes_latency_statuses_and_errors() {
+        let samples = [
+            completed(200, 30),
+            completed(200, 10),
+            completed(503, 20),
+            failed("connection refused"),
+            failed("timed out"),
+            failed("connection refused"),
+        ];
+        let report = summarize(&samples, Duration::from_secs(2));
+
+        assert_eq!((report.total, report.completed, report.failed), (6, 3, 3));
+        assert_eq!(report.throughput, 1.5);
+        assert_eq!(report.latency.min, 10.0);
+        assert_eq!(report.latency.mean, 20.0);
+        assert_eq!(report.latency.p50, 20.0);
+        assert_eq!(report.latency.max, 30.0);
+        assert_eq!(
+            report.status_codes,
+            vec![
+                HttpBenchStatusCount { status: 200, count: 2 },
+                HttpBenchStatusCount { status: 503, count: 1 },
+            ]
+        );
+        assert_eq!(
+            report.errors,
+            vec![
+                HttpBenchErrorCount { error: "connection refused".to_string(), count: 2 },
+                HttpBenchErrorCount { error: "timed out".to_string(), count: 1 },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn schedule_stops_at_the_request_limit() {
+        let started_at = Instant::now();
+        let schedule = Schedule {
+            started_at,
+            deadline: started_at + Duration::from_secs(60),
+            rate: None,
+            max_requests: Some(2),
+            issued: AtomicU64::new(0),
+        };
+        assert!(schedule.next().await);
+        assert!(schedule.next().await);
+        assert!(!schedule.next().await);
+    }
+}
@@ -0,0 +1,211 @@
+//! `text/event-stream` responses, parsed while they stream.
+//!
+//! Every event is stored as its own [`HttpResponseSseEvent`] the moment it completes, so a
+//! long-lived stream can be read event by event while it is still open. The body file is
+//! written as usual; the events are a view of it, not a replacement.
+//!
+//! A stream that drops mid-way is reconnected the way `EventSource` would, with
+//! `Last-Event-ID`, but only once the server has sent an ID. Without one there is nothing to
+//! resume from, and re-sending (often a POST that starts an expensive generation) would repeat
+//! work rather than continue it. A stream the server closes cleanly is finished and is never
+//! reconnected.
+
+use crate::send::{CookieBehavior, SendRequestExecutor};
+use log::warn;
+use std::time::Duration;
+use tokio::io::AsyncRead;
+use tokio::sync::{mpsc, watch};
+use yaak_http::sender::HttpResponseEvent as SenderHttpResponseEvent;
+use yaak_http::types::SendableHttpRequest;
+use yaak_models::models::HttpResponseSseEvent;
+use yaak_models::query_manager::QueryManager;
+use yaak_models::util::UpdateSource;
+use yaak_sse::parser::EventStreamParser;
+
+/// Reconnects allowed for one response before the drop is reported as an error.
+pub const MAX_RECONNECTS: u32 = 3;
+
+/// Used until the server sends a `retry:` field of its own.
+const DEFAULT_RECONNECTION_TIME: Duration = Duration::from_secs(3);
+
+pub fn is_event_stream(headers: &[(String, String)]) -> bool {
+    headers.iter().any(|(name, value)| {
+        name.eq_ignore_ascii_case("content-type")
+            && value
+                .split(';')
+                .next()
+                .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("text/event-stream"))
+    })
+}
+
+pub struct EventStreamRecorder {
+    parser: EventStreamParser,
+    response_id: String,
+    workspace_id: String,
+    next_index: i32,
+    reconnects: u32,
+}
+
+impl EventStreamRecorder {
+    pub fn new(response_id: &str, workspace_id: &str) -> Self {
+        Self {
+            parser: EventStreamParser::new(),
+            response_id: response_id.to_string(),
+            workspace_id: workspace_id.to_string(),
+            next_index: 0,
+            reconnects: 0,
+        }
+    }
+
+    /// Parse the next body chunk and store the events it completes, when there is a database
+    /// to store them in.
+    pub fn record(
+        &mut self,
+        chunk: &[u8],
+        store: Option<(&QueryManager, &UpdateSource)>,
+    ) -> Vec<HttpResponseSseEvent> {
+        let events = self
+            .parser
+            .feed(chunk)
+            .into_iter()
+            .map(|event| {
+                let index = self.next_index;
+                self.next_index += 1;
+                HttpResponseSseEvent {
+                    workspace_id: self.workspace_id.clone(),
+                    response_id: self.response_id.clone(),
+                    index,
+                    event_type: event.event_type,
+                    data: event.data,
+                    event_id: event.id,
+                    retry: event.retry.map(|r| i32::try_from(r).unwrap_or(i32::MAX)),
+                    ..Default::default()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let Some((query_manager, update_source)) = store else {
+            return events;
+        };
+        let db = query_manager.connect();
+        events
+            .into_iter()
+            .map(|event| match db.upsert_http_response_sse_event(&event, update_source) {
+                Ok(stored) => stored,
+                Err(err) => {
+                    warn!("Failed to persist server-sent event: {err}");
+                    event
+                }
+            })
+            .collect()
+    }
+
+    /// Re-send a dropped stream's request with `Last-Event-ID`, and return the new body to
+    /// keep reading from. `None` means the stream is over and the drop stands.
+    pub async fn reconnect(
+        &mut self,
+        executor: &dyn SendRequestExecutor,
+        request: &SendableHttpRequest,
+        event_tx: &mpsc::Sender<SenderHttpResponseEvent>,
+        cookie_behavior: &CookieBehavior,
+        cancelled_rx: Option<watch::Receiver<bool>>,
+        error: &std::io::Error,
+    ) -> Option<Box<dyn AsyncRead + Unpin + Send>> {
+        let last_event_id = self.parser.last_event_id()?.to_string();
+        let mut reason = error.to_string();
+
+        while self.reconnects < MAX_RECONNECTS {
+            self.reconnects += 1;
+            let delay = self
+                .parser
+                .reconnection_time()
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_RECONNECTION_TIME);
+            let _ = event_tx.try_send(SenderHttpResponseEvent::Info(format!(
+                "Event stream dropped ({reason}), reconnecting in {}ms with Last-Event-ID {} \
+                 (attempt {}/{MAX_RECONNECTS})",
+                delay.as_millis(),
+                last_event_id,
+                self.reconnects,
+            )));
+            if !wait(delay, cancelled_rx.clone()).await {
+                return None;
+            }
+
+            let mut request = request.try_clone()?;
+            request.insert_header(("Last-Event-ID".to_string(), last_event_id.clone()));
+            let mut response =
+                match executor.send(request, event_tx.clone(), cookie_behavior.clone()).await {
+                    Ok(response) => response,
+                    Err(err) => {
+                        reason = err.to_string();
+                        continue;
+                    }
+                };
+
+            // Anything but a fresh event stream (a 204 in particular) is the server saying
+            // there is nothing more to resume.
+            if response.status != 200 || !is_event_stream(&response.headers) {
+                let _ = event_tx.try_send(SenderHttpResponseEvent::Info(format!(
+                    "Server answered the reconnect with {}, not resuming",
+                    response.status
+                )));
+                return None;
+            }
+
+            self.parser.restart();
+            return response.into_body_stream().ok();
+        }
+
+        None
+    }
+}
+
+/// Sleep before a reconnect. Returns false when the send was cancelled instead.
+async fn wait(delay: Duration, cancelled_rx: Option<watch::Receiver<bool>>) -> bool {
+    match cancelled_rx {
+        Some(mut cancelled_rx) => {
+            if *cancelled_rx.borrow() {
+                return false;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => true,
+                _ = cancelled_rx.wait_for(|cancelled| *cancelled) => false,
+            }
+        }
+        None => {
+            tokio::time::sleep(delay).await;
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(content_type: &str) -> Vec<(String, String)> {
+        vec![("Content-Type".to_string(), content_type.to_string())]
+    }
+
+    #[test]
+    fn detects_event_streams() {
+        assert!(is_event_stream(&headers("text/event-stream")));
+        assert!(is_event_stream(&headers("Text/Event-Stream; charset=utf-8")));
+        assert!(!is_event_stream(&headers("application/json")));
+        assert!(!is_event_stream(&[]));
+    }
+
+    #[test]
+    fn numbers_events_across_chunks() {
+        let mut recorder = EventStreamRecorder::new("rs_1", "wk_1");
+        let first = recorder.record(b"id: a\ndata: one\n\ndata: tw", None);
+        let second = recorder.record(b"o\n\n", None);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].index, 0);
+        assert_eq!(first[0].event_id.as_deref(), Some("a"));
+        assert_eq!(second[0].index, 1);
+        assert_eq!(second[0].data, "two");
+        assert_eq!(second[0].response_id, "rs_1");
+    }
+}
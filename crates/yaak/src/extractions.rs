@@ -0,0 +1,266 @@
+//! Writing values from a finished response into environment variables.
+//!
+//! This is what makes a login-then-call flow deterministic: the login response writes its token
+//! into an environment once, and every later request reads a plain variable instead of
+//! re-reading or re-sending the login at render time. Like assertions, it runs inside the send,
+//! so the desktop and `yaak send` behave the same.
+
+use crate::assertions::select_json_path;
+use log::{debug, warn};
+use regex::Regex;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use yaak_models::models::{
+    Environment, EnvironmentVariable, HttpExtractionSource, HttpRequestExtraction, HttpResponse,
+};
+use yaak_models::query_manager::QueryManager;
+use yaak_models::util::UpdateSource;
+
+/// One value ready to be written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedValue {
+    /// `None` writes to the environment the request was sent with.
+    pub environment_id: Option<String>,
+    pub variable: String,
+    pub value: String,
+}
+
+/// Whether any enabled extraction reads the response body, so a send knows to keep a copy of it.
+pub fn extractions_read_body(extractions: &[HttpRequestExtraction]) -> bool {
+    extractions.iter().any(|e| {
+        e.enabled
+            && matches!(
+                e.source,
+                HttpExtractionSource::JsonPath
+                    | HttpExtractionSource::Xpath
+                    | HttpExtractionSource::BodyRegex
+            )
+    })
+}
+
+/// Only a response that arrived whole and did not report a failing status feeds its values
+/// forward; a 401 from a login must not overwrite the token that is already there.
+pub fn response_is_extractable(response: &HttpResponse) -> bool {
+    response.error.is_none() && (200..400).contains(&response.status)
+}
+
+/// Run every enabled extraction against a response and its decoded body.
+///
+/// An extraction that finds nothing writes nothing, so the variable keeps its previous value.
+pub fn evaluate_extractions(
+    extractions: &[HttpRequestExtraction],
+    response: &HttpResponse,
+    body: &[u8],
+) -> Vec<ExtractedValue> {
+    extractions
+        .iter()
+        .filter(|e| e.enabled && !e.variable.trim().is_empty())
+        .filter_map(|extraction| {
+            let value = match extraction.source {
+                HttpExtractionSource::JsonPath => extract_json_path(body, &extraction.expression),
+                HttpExtractionSource::Xpath => extract_xpath(body, &extraction.expression),
+                HttpExtractionSource::Header => {
+                    Ok(extract_header(response, &extraction.expression))
+                }
+                HttpExtractionSource::BodyRegex => extract_regex(body, &extraction.expression),
+                HttpExtractionSource::Cookie => {
+                    Ok(extract_cookie(response, &extraction.expression))
+                }
+            };
+            match value {
+                Ok(Some(value)) => Some(ExtractedValue {
+                    environment_id: extraction.environment_id.clone(),
+                    variable: extraction.variable.trim().to_string(),
+                    value,
+                }),
+                Ok(None) => {
+                    debug!("Extraction for {} found nothing", extraction.variable);
+                    None
+                }
+                Err(e) => {
+                    warn!("Extraction for {} failed: {e}", extraction.variable);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Write extracted values into their environments, one upsert per environment.
+///
+/// An existing variable of the same name is updated in place (and re-enabled); otherwise the
+/// variable is appended.
+pub fn write_extracted_values(
+    query_manager: &QueryManager,
+    values: &[ExtractedValue],
+    default_environment_id: Option<&str>,
+) -> yaak_models::error::Result<Vec<Environment>> {
+    let mut by_environment: BTreeMap<&str, Vec<&ExtractedValue>> = BTreeMap::new();
+    for value in values {
+        let Some(environment_id) = value.environment_id.as_deref().or(default_environment_id)
+        else {
+            warn!("No environment to write extracted {} into", value.variable);
+            continue;
+        };
+        by_environment.entry(environment_id).or_default().push(value);
+    }
+
+    let db = query_manager.connect();
+    let mut updated = Vec::new();
+    for (environment_id, values) in by_environment {
+        let mut environment = db.get_environment(environment_id)?;
+        for value in values {
+            match environment.variables.iter_mut().find(|v| v.name == value.variable) {
+                Some(variable) => {
+                    variable.value = value.value.clone();
+                    variable.enabled = true;
+                }
+                None => environment.variables.push(EnvironmentVariable {
+                    enabled: true,
+                    name: value.variable.clone(),
+                    value: value.value.clone(),
+                    id: None,
+                }),
+            }
+        }
+        updated.push(db.upsert_environment(&environment, &UpdateSource::Extraction)?);
+    }
+    Ok(updated)
+}
+
+fn extract_json_path(body: &[u8], path: &str) -> Result<Option<String>, String> {
+    let json = serde_json::from_slice::<Value>(body)
+        .map_err(|e| format!("Response body is not JSON: {e}"))?;
+    Ok(select_json_path(&json, path)?.map(|value| match value {
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    }))
+}
+
+fn extract_xpath(body: &[u8], expression: &str) -> Result<Option<String>, String> {
+    let body = std::str::from_utf8(body).map_err(|e| format!("Response body is not UTF-8: {e}"))?;
+    let package =
+        sxd_document::parser::parse(body).map_err(|e| format!("Response body is not XML: {e}"))?;
+    let document = package.as_document();
+    let value = sxd_xpath::evaluate_xpath(&document, expression)
+        .map_err(|e| format!("Invalid XPath \"{expression}\": {e}"))?;
+    Ok(match value {
+        sxd_xpath::Value::Nodeset(nodes) if nodes.size() == 0 => None,
+        value => Some(value.string()),
+    })
+}
+
+fn extract_header(response: &HttpResponse, name: &str) -> Option<String> {
+    response
+        .headers
+        .iter()
+        .find(|h| h.name.eq_ignore_ascii_case(name.trim()))
+        .map(|h| h.value.clone())
+}
+
+fn extract_regex(body: &[u8], pattern: &str) -> Result<Option<String>, String> {
+    let regex = Regex::new(pattern).map_err(|e| format!("Invalid regex \"{pattern}\": {e}"))?;
+    let body = String::from_utf8_lossy(body);
+    Ok(regex.captures(&body).and_then(|captures| {
+        captures.get(1).or_else(|| captures.get(0)).map(|m| m.as_str().to_string())
+    }))
+}
+
+fn extract_cookie(response: &HttpResponse, name: &str) -> Option<String> {
+    // The last Set-Cookie for a name wins, the same as it would in a cookie jar.
+    response
+        .headers
+        .iter()
+        .filter(|h| h.name.eq_ignore_ascii_case("set-cookie"))
+        .filter_map(|h| {
+            let pair = h.value.split(';').next()?;
+            let (cookie_name, value) = pair.split_once('=')?;
+            (cookie_name.trim() == name.trim()).then(|| value.trim().to_string())
+        })
+        .last()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yaak_models::models::HttpResponseHeader;
+
+    fn extraction(source: HttpExtractionSource, expression: &str) -> HttpRequestExtraction {
+        HttpRequestExtraction {
+            source,
+            expression: expression.to_string(),
+            variable: "out".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn response() -> HttpResponse {
+        HttpResponse {
+            status: 200,
+            headers: vec![
+                HttpResponseHeader { name: "X-Request-Id".to_string(), value: "req-1".to_string() },
+                HttpResponseHeader {
+                    name: "Set-Cookie".to_string(),
+                    value: "session=abc123; Path=/; HttpOnly".to_string(),
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn values(extractions: &[HttpRequestExtraction], body: &[u8]) -> Vec<String> {
+        evaluate_extractions(extractions, &response(), body).into_iter().map(|v| v.value).collect()
+    }
+
+    #[test]
+    fn extracts_from_each_source() {
+        let json = br#"{"data":{"token":"t0k","count":3}}"#;
+        assert_eq!(
+            values(
+                &[
+                    extraction(HttpExtractionSource::JsonPath, "$.data.token"),
+                    extraction(HttpExtractionSource::JsonPath, "$.data.count"),
+                    extraction(HttpExtractionSource::Header, "x-request-id"),
+                    extraction(HttpExtractionSource::BodyRegex, r#""token":"(\w+)""#),
+                    extraction(HttpExtractionSource::Cookie, "session"),
+                ],
+                json,
+            ),
+            vec!["t0k", "3", "req-1", "t0k", "abc123"],
+        );
+
+        let xml = b"<login><token>x-t0k</token></login>";
+        assert_eq!(
+            values(&[extraction(HttpExtractionSource::Xpath, "/login/token")], xml),
+            vec!["x-t0k"],
+        );
+    }
+
+    #[test]
+    fn skips_misses_failures_and_disabled() {
+        let extractions = [
+            extraction(HttpExtractionSource::JsonPath, "$.missing"),
+            extraction(HttpExtractionSource::BodyRegex, "("),
+            extraction(HttpExtractionSource::Cookie, "nope"),
+            HttpRequestExtraction {
+                enabled: false,
+                ..extraction(HttpExtractionSource::Header, "x-request-id")
+            },
+            HttpRequestExtraction {
+                variable: " ".to_string(),
+                ..extraction(HttpExtractionSource::Header, "x-request-id")
+            },
+        ];
+        assert!(values(&extractions, b"{}").is_empty());
+    }
+
+    #[test]
+    fn only_successful_responses_are_extractable() {
+        assert!(response_is_extractable(&response()));
+        assert!(!response_is_extractable(&HttpResponse { status: 401, ..response() }));
+        assert!(!response_is_extractable(&HttpResponse {
+            error: Some("boom".to_string()),
+            ..response()
+        }));
+    }
+}
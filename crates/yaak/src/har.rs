@@ -0,0 +1,240 @@
+//! HAR export of response history, and HAR import as workspace requests.
+//!
+//! Exported entries carry what the send recorded: the request as it went out, both bodies,
+//! and timings from the response's events. Imported entries only keep their requests, since
+//! responses belong to sends made from this app.
+
+use crate::Result;
+use std::fs::File;
+use std::net::SocketAddr;
+use std::path::Path;
+use yaak_har::{
+    Content, Creator, Entry, Har, Header, PostData, Request, Response, Timings, format_timestamp,
+    query_string,
+};
+use yaak_models::blob_manager::BlobManager;
+use yaak_models::captured::{CapturedHttpRequest, captured_to_resources};
+use yaak_models::models::{HttpResponse, HttpResponseEventData, HttpResponseHeader};
+use yaak_models::query_manager::QueryManager;
+use yaak_plugins::events::ImportResources;
+
+pub struct ExportHarParams<'a> {
+    pub query_manager: &'a QueryManager,
+    pub blob_manager: &'a BlobManager,
+    pub yaak_version: &'a str,
+    pub export_path: &'a Path,
+    pub response_ids: Vec<&'a str>,
+}
+
+pub fn export_har(params: ExportHarParams<'_>) -> Result<()> {
+    let db = params.query_manager.connect();
+    let blobs = params.blob_manager.connect();
+
+    let mut entries = Vec::new();
+    for id in params.response_ids {
+        let response = db.get_http_response(id)?;
+        let events: Vec<HttpResponseEventData> =
+            db.list_http_response_events(id)?.into_iter().map(|e| e.event).collect();
+
+        let method = events
+            .iter()
+            .rev()
+            .find_map(|e| match e {
+                HttpResponseEventData::SendUrl { method, .. } => Some(method.clone()),
+                _ => None,
+            })
+            .or_else(|| db.get_http_request(&response.request_id).ok().map(|r| r.method))
+            .unwrap_or_else(|| "GET".to_string());
+
+        let chunks = blobs.get_chunks(&format!("{id}.request"))?;
+        let request_body = match chunks.is_empty() {
+            true => None,
+            false => Some(chunks.into_iter().flat_map(|c| c.data).collect::<Vec<u8>>()),
+        };
+        let response_body = match &response.body_path {
+            Some(path) => std::fs::read(path)?,
+            None => Vec::new(),
+        };
+
+        entries.push(to_entry(&response, &events, method, request_body, response_body));
+    }
+    entries.sort_by(|a, b| a.started_date_time.cmp(&b.started_date_time));
+
+    let creator = Creator { name: "Yaak".to_string(), version: params.yaak_version.to_string() };
+    let file = File::options().create(true).truncate(true).write(true).open(params.export_path)?;
+    serde_json::to_writer_pretty(&file, &Har::new(creator, entries))?;
+    file.sync_all()?;
+
+    Ok(())
+}
+
+/// The requests in `har`, as resources to import into the current workspace. Requests are
+/// grouped in a folder per host, in the order they were made.
+pub fn to_import_resources(har: &Har) -> ImportResources {
+    let mut entries: Vec<&Entry> = har.log.entries.iter().collect();
+    entries.sort_by(|a, b| a.started_date_time.cmp(&b.started_date_time));
+
+    let requests = entries
+        .into_iter()
+        .map(|e| CapturedHttpRequest {
+            method: e.request.method.clone(),
+            url: e.request.url.clone(),
+            headers: e.request.headers.iter().map(|h| (h.name.clone(), h.value.clone())).collect(),
+            body: e.request.body(),
+        })
+        .collect();
+
+    let resources = captured_to_resources(requests, false);
+    ImportResources {
+        workspaces: resources.workspaces,
+        environments: resources.environments,
+        folders: resources.folders,
+        http_requests: resources.http_requests,
+        grpc_requests: resources.grpc_requests,
+        websocket_requests: resources.websocket_requests,
+    }
+}
+
+fn to_entry(
+    response: &HttpResponse,
+    events: &[HttpResponseEventData],
+    method: String,
+    request_body: Option<Vec<u8>>,
+    response_body: Vec<u8>,
+) -> Entry {
+    let http_version = response.version.clone().unwrap_or_else(|| "HTTP/1.1".to_string());
+    let request_content_type = find_header(&response.request_headers, "content-type");
+    let response_content_type = find_header(&response.headers, "content-type");
+
+    let mut content = Content::from_bytes(response_content_type, &response_body);
+    if let (Some(length), Some(compressed)) =
+        (response.content_length, response.content_length_compressed)
+    {
+        content.compression = Some((length - compressed).max(0) as i64);
+    }
+
+    let timings = to_timings(response, events);
+    Entry {
+        started_date_time: format_timestamp(response.created_at),
+        time: match timings.total() {
+            t if t > 0.0 => t,
+            _ => response.elapsed as f64,
+        },
+        request: Request {
+            method,
+            url: response.url.clone(),
+            http_version: http_version.clone(),
+            headers: to_headers(&response.request_headers),
+            query_string: query_string(&response.url),
+            body_size: request_body.as_ref().map(|b| b.len() as i64).unwrap_or(0),
+            post_data: request_body.map(|b| PostData::from_bytes(request_content_type, &b)),
+            ..Default::default()
+        },
+        response: Response {
+            status: response.status as i64,
+            status_text: response.status_reason.clone().unwrap_or_default(),
+            http_version,
+            headers: to_headers(&response.headers),
+            redirect_url: find_header(&response.headers, "location").to_string(),
+            body_size: response
+                .content_length_compressed
+                .or(response.content_length)
+                .map(|l| l as i64)
+                .unwrap_or(-1),
+            content,
+            ..Default::default()
+        },
+        timings,
+        server_ip_address: response.remote_addr.as_deref().map(|a| {
+            a.parse::<SocketAddr>().map(|s| s.ip().to_string()).unwrap_or_else(|_| a.to_string())
+        }),
+        comment: response.error.clone(),
+        ..Default::default()
+    }
+}
+
+/// Timings from the response's events, falling back to its elapsed fields for sends that
+/// predate them.
+fn to_timings(response: &HttpResponse, events: &[HttpResponseEventData]) -> Timings {
+    let (mut dns, mut tcp, mut tls, mut wait, mut receive) = (None, None, None, None, None);
+    for event in events {
+        match event {
+            HttpResponseEventData::DnsResolved { duration, .. } => dns = Some(*duration as f64),
+            HttpResponseEventData::TcpConnected { duration } => tcp = Some(*duration as f64),
+            HttpResponseEventData::TlsHandshakeCompleted { duration } => {
+                tls = Some(*duration as f64)
+            }
+            HttpResponseEventData::FirstByteReceived { duration } => wait = Some(*duration as f64),
+            HttpResponseEventData::DownloadCompleted { duration } => {
+                receive = Some(*duration as f64)
+            }
+            _ => {}
+        }
+    }
+
+    let dns = dns.unwrap_or(response.elapsed_dns as f64);
+    let ssl = tls.unwrap_or(response.elapsed_tls as f64);
+    let connect = match tcp {
+        Some(tcp) => tcp + tls.unwrap_or(0.0),
+        None => response.elapsed_connect as f64,
+    };
+    Timings {
+        dns: if dns > 0.0 { dns } else { -1.0 },
+        connect: if connect > 0.0 { connect } else { -1.0 },
+        ssl: if ssl > 0.0 { ssl } else { -1.0 },
+        wait: wait.unwrap_or(response.elapsed_ttfb as f64),
+        receive: receive.unwrap_or((response.elapsed - response.elapsed_headers).max(0) as f64),
+        ..Default::default()
+    }
+}
+
+fn to_headers(headers: &[HttpResponseHeader]) -> Vec<Header> {
+    headers.iter().map(|h| Header { name: h.name.clone(), value: h.value.clone() }).collect()
+}
+
+fn find_header<'a>(headers: &'a [HttpResponseHeader], name: &str) -> &'a str {
+    headers
+        .iter()
+        .find(|h| h.name.eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str())
+        .unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_entries_from_responses_and_events() {
+        let response = HttpResponse {
+            url: "https://api.test/users?page=2".to_string(),
+            status: 200,
+            version: Some("HTTP/2.0".to_string()),
+            remote_addr: Some("[::1]:443".to_string()),
+            headers: vec![HttpResponseHeader {
+                name: "Content-Type".to_string(),
+                value: "application/json".to_string(),
+            }],
+            content_length: Some(100),
+            content_length_compressed: Some(40),
+            elapsed: 50,
+            elapsed_headers: 30,
+            elapsed_ttfb: 20,
+            ..Default::default()
+        };
+        let events = vec![
+            HttpResponseEventData::TcpConnected { duration: 3 },
+            HttpResponseEventData::TlsHandshakeCompleted { duration: 4 },
+        ];
+
+        let entry = to_entry(&response, &events, "POST".to_string(), None, b"{}".to_vec());
+        assert_eq!(entry.request.method, "POST");
+        assert_eq!(entry.request.query_string[0].value, "2");
+        assert_eq!(entry.server_ip_address.as_deref(), Some("::1"));
+        assert_eq!(entry.response.content.compression, Some(60));
+        assert_eq!(entry.response.content.text.as_deref(), Some("{}"));
+        assert_eq!((entry.timings.connect, entry.timings.ssl, entry.timings.dns), (7.0, 4.0, -1.0));
+        assert_eq!((entry.timings.wait, entry.timings.receive), (20.0, 20.0));
+        assert_eq!(entry.time, 47.0);
+    }
+}
@@ -1,7 +1,9 @@
 use crate::Result;
+use crate::har;
 use log::info;
 use std::collections::BTreeMap;
 use yaak_core::WorkspaceContext;
+use yaak_har::Har;
 use yaak_models::models::{
     Environment, Folder, GrpcRequest, HttpRequest, WebsocketRequest, Workspace,
 };
@@ -19,10 +21,23 @@ pub struct ImportDataParams<'a> {
 }
 
 pub async fn import_data(params: ImportDataParams<'_>) -> Result<BatchUpsertResult> {
-    let import_result =
-        params.plugin_manager.import_data(params.plugin_context, params.contents).await?;
+    let resources =
+        parse_resources(params.plugin_manager, params.plugin_context, params.contents).await?;
 
-    import_resources(params.query_manager, params.workspace_context, import_result.resources)
+    import_resources(params.query_manager, params.workspace_context, resources)
+}
+
+/// The resources in an import file. HAR files are read here, and anything else is handed to
+/// the importer plugins.
+pub async fn parse_resources(
+    plugin_manager: &PluginManager,
+    plugin_context: &PluginContext,
+    contents: &str,
+) -> Result<ImportResources> {
+    if let Some(har) = Har::parse(contents) {
+        return Ok(har::to_import_resources(&har));
+    }
+    Ok(plugin_manager.import_data(plugin_context, contents).await?.resources)
 }
 
 pub fn import_resources(
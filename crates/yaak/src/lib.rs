@@ -1,8 +1,14 @@
+pub mod assertions;
+pub mod bench;
 pub mod error;
+pub mod event_stream;
 pub mod export;
+pub mod extractions;
+pub mod har;
 pub mod import;
 pub mod plugin_events;
 pub mod response_body;
+pub mod runner;
 pub mod send;
 
 pub use error::Error;
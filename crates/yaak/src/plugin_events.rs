@@ -11,11 +11,12 @@ use yaak_plugins::events::{
     GetHttpRequestByIdResponse, GetHttpResponseBodyInfoRequest, GetHttpResponseBodyInfoResponse,
     GetKeyValueRequest, GetKeyValueResponse, InternalEventPayload, ListCookieNamesRequest,
     ListFoldersRequest, ListFoldersResponse, ListHttpRequestsRequest, ListHttpRequestsResponse,
-    ListOpenWorkspacesRequest, OpenExternalUrlRequest, OpenWindowRequest, PromptFormRequest,
-    PromptTextRequest, ReadHttpResponseBodyChunkRequest, ReadHttpResponseBodyChunkResponse,
-    ReloadResponse, RenderGrpcRequestRequest, RenderHttpRequestRequest, SendHttpRequestRequest,
-    SetKeyValueRequest, ShowToastRequest, TemplateRenderRequest, UpsertModelRequest,
-    UpsertModelResponse, WindowInfoRequest,
+    ListHttpResponseSseEventsRequest, ListHttpResponseSseEventsResponse, ListOpenWorkspacesRequest,
+    OpenExternalUrlRequest, OpenWindowRequest, PromptFormRequest, PromptTextRequest,
+    ReadHttpResponseBodyChunkRequest, ReadHttpResponseBodyChunkResponse, ReloadResponse,
+    RenderGrpcRequestRequest, RenderHttpRequestRequest, SendHttpRequestRequest, SetKeyValueRequest,
+    ShowToastRequest, TemplateRenderRequest, UpsertModelRequest, UpsertModelResponse,
+    WindowInfoRequest,
 };
 
 pub struct SharedPluginEventContext<'a> {
@@ -45,6 +46,7 @@ pub enum SharedRequest<'a> {
     ListFolders(&'a ListFoldersRequest),
     ListHttpRequests(&'a ListHttpRequestsRequest),
     FindHttpResponses(&'a FindHttpResponsesRequest),
+    ListHttpResponseSseEvents(&'a ListHttpResponseSseEventsRequest),
     GetHttpResponseBodyInfo(&'a GetHttpResponseBodyInfoRequest),
     ReadHttpResponseBodyChunk(&'a ReadHttpResponseBodyChunkRequest),
     UpsertModel(&'a UpsertModelRequest),
@@ -143,6 +145,9 @@ impl<'a> From<&'a InternalEventPayload> for GroupedPluginRequest<'a> {
             InternalEventPayload::FindHttpResponsesRequest(req) => {
                 GroupedPluginRequest::Shared(SharedRequest::FindHttpResponses(req))
             }
+            InternalEventPayload::ListHttpResponseSseEventsRequest(req) => {
+                GroupedPluginRequest::Shared(SharedRequest::ListHttpResponseSseEvents(req))
+            }
             InternalEventPayload::GetHttpResponseBodyInfoRequest(req) => {
                 GroupedPluginRequest::Shared(SharedRequest::GetHttpResponseBodyInfo(req))
             }
@@ -301,6 +306,16 @@ fn build_shared_reply(
                 http_responses,
             })
         }
+        SharedRequest::ListHttpResponseSseEvents(req) => {
+            match query_manager.connect().list_http_response_sse_events(&req.response_id) {
+                Ok(events) => InternalEventPayload::ListHttpResponseSseEventsResponse(
+                    ListHttpResponseSseEventsResponse { events },
+                ),
+                Err(err) => InternalEventPayload::ErrorResponse(ErrorResponse {
+                    error: format!("Failed to list events of response {}: {err}", req.response_id),
+                }),
+            }
+        }
         SharedRequest::GetHttpResponseBodyInfo(req) => match body_store.info(&req.response_id) {
             Ok(info) => InternalEventPayload::GetHttpResponseBodyInfoResponse(
                 GetHttpResponseBodyInfoResponse {
@@ -634,6 +649,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn list_http_response_sse_events_is_shared_handled() {
+        let (query_manager, _temp_dir) = seed_query_manager();
+        let payload = InternalEventPayload::ListHttpResponseSseEventsRequest(
+            ListHttpResponseSseEventsRequest { response_id: "rs_missing".to_string() },
+        );
+
+        let result = dispatch(
+            &query_manager,
+            &payload,
+            SharedPluginEventContext { plugin_name: "@yaak/test", workspace_id: Some("wk_test") },
+        );
+
+        match result {
+            GroupedPluginEvent::Handled(Some(
+                InternalEventPayload::ListHttpResponseSseEventsResponse(resp),
+            )) => {
+                assert!(resp.events.is_empty());
+            }
+            other => panic!("unexpected list events result: {other:?}"),
+        }
+    }
+
     /// A store that answers from memory, standing in for whatever holds the
     /// bytes — the point being that the dispatch below never learns which.
     struct FakeBodyStore {
@@ -0,0 +1,332 @@
+//! Collection runs: an ordered list of requests, sent once per row of an optional data file.
+//!
+//! Each step goes through [`send_http_request_by_id_with_plugins`], so a run sends exactly what a
+//! single send would, with the row's values layered above every environment. The run and one
+//! [`RunnerIteration`] per row are stored as they finish, which is what lets the app and the CLI
+//! both show run history, including for a run that is still going.
+
+use crate::error::Error::DataFile;
+use crate::error::Result;
+use crate::send::{SendHttpRequestByIdWithPluginsParams, send_http_request_by_id_with_plugins};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use yaak_crypto::manager::EncryptionManager;
+use yaak_http::manager::HttpConnectionManager;
+use yaak_models::blob_manager::BlobManager;
+use yaak_models::models::{
+    EnvironmentVariable, RunnerIteration, RunnerRun, RunnerRunState, RunnerStep, RunnerStepResult,
+};
+use yaak_models::query_manager::QueryManager;
+use yaak_models::util::UpdateSource;
+use yaak_plugins::events::PluginContext;
+use yaak_plugins::manager::PluginManager;
+
+pub struct RunCollectionParams<'a> {
+    pub query_manager: &'a QueryManager,
+    pub blob_manager: &'a BlobManager,
+    pub workspace_id: &'a str,
+    /// The id to store the run under, so a caller can refer to it, e.g. to cancel it, before
+    /// it finishes. Generated when absent.
+    pub run_id: Option<&'a str>,
+    pub name: String,
+    pub steps: Vec<RunnerStep>,
+    /// A CSV file with a header row, or a JSON array of objects. One iteration per row.
+    pub data_file: Option<&'a Path>,
+    pub environment_id: Option<&'a str>,
+    pub update_source: UpdateSource,
+    pub cookie_jar_id: Option<String>,
+    pub response_dir: &'a Path,
+    pub plugin_manager: Arc<PluginManager>,
+    pub encryption_manager: Arc<EncryptionManager>,
+    pub plugin_context: &'a PluginContext,
+    pub cancelled_rx: Option<watch::Receiver<bool>>,
+    pub connection_manager: &'a HttpConnectionManager,
+    /// Variables layered above every environment for the whole run. A data row's values win
+    /// over these when both set the same name.
+    pub variables: Vec<EnvironmentVariable>,
+}
+
+/// Run every step for every data row, in order, and return the finished run.
+///
+/// A failing step does not stop the run: the point is a complete record of which rows fail
+/// where. Cancelling stops before the next step and marks the run cancelled.
+pub async fn run_collection(params: RunCollectionParams<'_>) -> Result<RunnerRun> {
+    let rows = match params.data_file {
+        Some(path) => read_data_file(path)?,
+        None => vec![BTreeMap::new()],
+    };
+
+    let started_at = Instant::now();
+    let mut run = params.query_manager.connect().upsert_runner_run(
+        &RunnerRun {
+            id: params.run_id.unwrap_or_default().to_string(),
+            workspace_id: params.workspace_id.to_string(),
+            name: params.name.clone(),
+            steps: params.steps.clone(),
+            data_file: params.data_file.map(|p| p.to_string_lossy().to_string()),
+            iteration_count: rows.len() as i32,
+            state: RunnerRunState::Running,
+            ..Default::default()
+        },
+        &params.update_source,
+    )?;
+
+    let mut cancelled = false;
+    for (index, row) in rows.into_iter().enumerate() {
+        let iteration_started_at = Instant::now();
+        // Later entries win, so the row's values come after the run's own variables
+        let variables = params
+            .variables
+            .iter()
+            .cloned()
+            .chain(row.iter().map(|(name, value)| EnvironmentVariable {
+                enabled: true,
+                name: name.clone(),
+                value: value.clone(),
+                id: None,
+            }))
+            .collect::<Vec<_>>();
+
+        let mut results = Vec::new();
+        for step in &params.steps {
+            if !wait(step.delay_ms, params.cancelled_rx.clone()).await {
+                cancelled = true;
+                break;
+            }
+            results.push(run_step(&params, step, &variables).await);
+            if is_cancelled(&params.cancelled_rx) {
+                cancelled = true;
+                break;
+            }
+        }
+
+        let passed = results.len() == params.steps.len() && results.iter().all(|r| r.passed);
+        params.query_manager.connect().upsert_runner_iteration(
+            &RunnerIteration {
+                workspace_id: params.workspace_id.to_string(),
+                run_id: run.id.clone(),
+                index: index as i32,
+                variables: row,
+                results,
+                passed,
+                elapsed: duration_to_i32(iteration_started_at.elapsed()),
+                ..Default::default()
+            },
+            &params.update_source,
+        )?;
+
+        if passed {
+            run.passed_count += 1;
+        } else {
+            run.failed_count += 1;
+        }
+        run.elapsed = duration_to_i32(started_at.elapsed());
+        run = params.query_manager.connect().upsert_runner_run(&run, &params.update_source)?;
+
+        if cancelled {
+            break;
+        }
+    }
+
+    run.state = if cancelled { RunnerRunState::Cancelled } else { RunnerRunState::Completed };
+    run.elapsed = duration_to_i32(started_at.elapsed());
+    Ok(params.query_manager.connect().upsert_runner_run(&run, &params.update_source)?)
+}
+
+/// Send one step, retrying while it fails and attempts remain.
+async fn run_step(
+    params: &RunCollectionParams<'_>,
+    step: &RunnerStep,
+    variables: &[EnvironmentVariable],
+) -> RunnerStepResult {
+    let mut result = RunnerStepResult { request_id: step.request_id.clone(), ..Default::default() };
+    let max_attempts = step.retries.max(0) + 1;
+
+    while result.attempts < max_attempts {
+        result.attempts += 1;
+        let sent = send_http_request_by_id_with_plugins(SendHttpRequestByIdWithPluginsParams {
+            query_manager: params.query_manager,
+            blob_manager: params.blob_manager,
+            request_id: &step.request_id,
+            environment_id: params.environment_id,
+            update_source: params.update_source.clone(),
+            cookie_jar_id: params.cookie_jar_id.clone(),
+            response_dir: params.response_dir,
+            emit_events_to: None,
+            emit_response_body_chunks_to: None,
+            plugin_manager: params.plugin_manager.clone(),
+            encryption_manager: params.encryption_manager.clone(),
+            plugin_context: params.plugin_context,
+            cancelled_rx: params.cancelled_rx.clone(),
+            connection_manager: params.connection_manager,
+            variables: variables.to_vec(),
+        })
+        .await;
+
+        match sent {
+            Ok(sent) => {
+                let response = sent.response;
+                let failed_assertions =
+                    response.assertion_results.iter().filter(|r| !r.passed).count();
+                result.response_id = Some(response.id);
+                result.status = response.status;
+                result.elapsed = response.elapsed;
+                result.error = match (response.error, failed_assertions) {
+                    (Some(error), _) => Some(error),
+                    (None, 0) => None,
+                    (None, n) => Some(format!(
+                        "{n} of {} assertions failed",
+                        response.assertion_results.len()
+                    )),
+                };
+            }
+            Err(err) => {
+                result.response_id = None;
+                result.status = 0;
+                result.elapsed = 0;
+                result.error = Some(err.to_string());
+            }
+        }
+
+        result.passed = result.error.is_none();
+        if result.passed || is_cancelled(&params.cancelled_rx) {
+            break;
+        }
+    }
+
+    result
+}
+
+/// Sleep for a step's delay. Returns false when the run was cancelled instead.
+async fn wait(delay_ms: i32, cancelled_rx: Option<watch::Receiver<bool>>) -> bool {
+    if is_cancelled(&cancelled_rx) {
+        return false;
+    }
+    if delay_ms <= 0 {
+        return true;
+    }
+
+    let sleep = tokio::time::sleep(Duration::from_millis(delay_ms as u64));
+    match cancelled_rx {
+        Some(mut cancelled_rx) => {
+            tokio::select! {
+                _ = sleep => true,
+                _ = cancelled_rx.wait_for(|cancelled| *cancelled) => false,
+            }
+        }
+        None => {
+            sleep.await;
+            true
+        }
+    }
+}
+
+fn is_cancelled(cancelled_rx: &Option<watch::Receiver<bool>>) -> bool {
+    cancelled_rx.as_ref().is_some_and(|rx| *rx.borrow())
+}
+
+/// Read the rows of a data file: a CSV with a header row, or a JSON array of objects.
+///
+/// Every value becomes a string, because that is what a template variable holds; JSON values
+/// that are not strings are written as JSON, and `null` as an empty string.
+pub fn read_data_file(path: &Path) -> Result<Vec<BTreeMap<String, String>>> {
+    let contents = std::fs::read_to_string(path)?;
+    let is_json = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json"));
+    let rows = if is_json { parse_json_rows(&contents)? } else { parse_csv_rows(&contents)? };
+    if rows.is_empty() {
+        return Err(DataFile(format!("{} has no rows", path.display())));
+    }
+    Ok(rows)
+}
+
+fn parse_json_rows(contents: &str) -> Result<Vec<BTreeMap<String, String>>> {
+    let Value::Array(items) = serde_json::from_str::<Value>(contents)? else {
+        return Err(DataFile("JSON data must be an array of objects".to_string()));
+    };
+
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let Value::Object(object) = item else {
+                return Err(DataFile(format!("JSON data row {i} is not an object")));
+            };
+            Ok(object
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(s) => s,
+                        Value::Null => String::new(),
+                        v => v.to_string(),
+                    };
+                    (key, value)
+                })
+                .collect())
+        })
+        .collect()
+}
+
+fn parse_csv_rows(contents: &str) -> Result<Vec<BTreeMap<String, String>>> {
+    let mut reader =
+        csv::ReaderBuilder::new().trim(csv::Trim::Headers).from_reader(contents.as_bytes());
+    let headers = reader.headers().map_err(|e| DataFile(e.to_string()))?.clone();
+
+    reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(|e| DataFile(e.to_string()))?;
+            Ok(headers
+                .iter()
+                .zip(record.iter())
+                .map(|(h, v)| (h.to_string(), v.to_string()))
+                .collect())
+        })
+        .collect()
+}
+
+fn duration_to_i32(duration: Duration) -> i32 {
+    i32::try_from(duration.as_millis()).unwrap_or(i32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn parses_csv_rows() {
+        let rows = parse_csv_rows("user, id\nalice,1\n\"bob, jr\",2\n").expect("valid CSV");
+        assert_eq!(
+            rows,
+            vec![
+                row(&[("user", "alice"), ("id", "1")]),
+                row(&[("user", "bob, jr"), ("id", "2")])
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_json_rows() {
+        let rows = parse_json_rows(r#"[{"user":"alice","id":1,"admin":true,"note":null}]"#)
+            .expect("valid JSON");
+        assert_eq!(
+            rows,
+            vec![row(&[
+                ("admin", "true"),
+                ("id", "1"),
+                ("note", ""),
+                ("user", "alice")
+            ])]
+        );
+
+        assert!(parse_json_rows(r#"{"user":"alice"}"#).is_err());
+        assert!(parse_json_rows(r#"["alice"]"#).is_err());
+    }
+}
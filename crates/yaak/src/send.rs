@@ -1,3 +1,8 @@
+use crate::assertions::{assertions_read_body, evaluate_assertions};
+use crate::event_stream::{EventStreamRecorder, is_event_stream};
+use crate::extractions::{
+    evaluate_extractions, extractions_read_body, response_is_extractable, write_extracted_values,
+};
 use async_trait::async_trait;
 use log::warn;
 use std::path::{Path, PathBuf};
@@ -23,19 +28,19 @@ use yaak_http::types::{
 };
 use yaak_models::blob_manager::{BlobManager, BodyChunk};
 use yaak_models::models::{
-    ClientCertificate, Cookie, CookieJar, DnsOverride, Environment, HttpRequest, HttpResponse,
-    HttpResponseEvent, HttpResponseEventData, HttpResponseHeader, HttpResponseState, ProxySetting,
-    ProxySettingAuth, ResolvedHttpRequestSettings,
+    ClientCertificate, Cookie, CookieJar, DnsOverride, Environment, EnvironmentVariable, Http3Mode,
+    HttpRequest, HttpResponse, HttpResponseEvent, HttpResponseEventData, HttpResponseHeader,
+    HttpResponseState, ProxySetting, ProxySettingAuth, ResolvedHttpRequestSettings,
 };
 use yaak_models::query_manager::QueryManager;
-use yaak_models::render::render_http_request;
+use yaak_models::render::{make_vars_hashmap, render_http_request};
 use yaak_models::util::{UpdateSource, generate_prefixed_id};
 use yaak_plugins::events::{
     CallHttpAuthenticationRequest, HttpHeader, PluginContext, RenderPurpose,
 };
 use yaak_plugins::manager::PluginManager;
 use yaak_plugins::template_callback::PluginTemplateCallback;
-use yaak_templates::{RenderOptions, TemplateCallback};
+use yaak_templates::{RenderOptions, TemplateCallback, parse_and_render};
 use yaak_tls::find_client_certificate;
 
 const HTTP_EVENT_CHANNEL_CAPACITY: usize = 100;
@@ -194,6 +199,7 @@ impl SendRequestExecutor for ConnectionManagerSendRequestExecutor<'_> {
                 client_certificate,
                 dns_overrides: runtime_config.dns_overrides.clone(),
                 address_filter: None,
+                http3: runtime_config.settings.http3.value != Http3Mode::Off,
             })
             .await?;
 
@@ -321,6 +327,9 @@ pub struct SendHttpRequestWithPluginsParams<'a> {
     pub plugin_context: &'a PluginContext,
     pub cancelled_rx: Option<watch::Receiver<bool>>,
     pub connection_manager: &'a HttpConnectionManager,
+    /// Variables layered above every environment for this send only, such as one row of a
+    /// collection run's data file.
+    pub variables: Vec<EnvironmentVariable>,
 }
 
 pub struct SendHttpRequestByIdWithPluginsParams<'a> {
@@ -338,6 +347,9 @@ pub struct SendHttpRequestByIdWithPluginsParams<'a> {
     pub plugin_context: &'a PluginContext,
     pub cancelled_rx: Option<watch::Receiver<bool>>,
     pub connection_manager: &'a HttpConnectionManager,
+    /// Variables layered above every environment for this send only, such as one row of a
+    /// collection run's data file.
+    pub variables: Vec<EnvironmentVariable>,
 }
 
 /// Where a send left the response body, so the caller knows where to get it.
@@ -397,6 +409,7 @@ impl HttpSendRuntimeConfig {
             } else {
                 None
             },
+            http3: self.settings.http3.value,
         }
     }
 }
@@ -427,8 +440,8 @@ pub fn resolve_send_inputs(
         request: resolved_request,
         environment_chain,
         runtime_config: HttpSendRuntimeConfig {
+            proxy: proxy_setting_from_settings(resolved_settings.proxy.value.clone()),
             settings: resolved_settings,
-            proxy: proxy_setting_from_settings(settings.proxy),
             dns_overrides: workspace.setting_dns_overrides,
             client_certificates: settings.client_certificates,
         },
@@ -455,6 +468,26 @@ pub fn resolve_inherited_request(
     })
 }
 
+/// Put a send's own variables first in an environment chain, so they win over every
+/// environment without being written to one.
+pub fn layer_variables(
+    environment_chain: &mut Vec<Environment>,
+    workspace_id: &str,
+    variables: &[EnvironmentVariable],
+) {
+    if variables.is_empty() {
+        return;
+    }
+    environment_chain.insert(
+        0,
+        Environment {
+            workspace_id: workspace_id.to_string(),
+            variables: variables.to_vec(),
+            ..Default::default()
+        },
+    );
+}
+
 pub async fn send_http_request_by_id_with_plugins(
     params: SendHttpRequestByIdWithPluginsParams<'_>,
 ) -> Result<SendHttpRequestResult> {
@@ -480,6 +513,7 @@ pub async fn send_http_request_by_id_with_plugins(
         plugin_context: params.plugin_context,
         cancelled_rx: params.cancelled_rx,
         connection_manager: params.connection_manager,
+        variables: params.variables,
     })
     .await
 }
@@ -488,12 +522,13 @@ pub async fn send_http_request_with_plugins(
     params: SendHttpRequestWithPluginsParams<'_>,
 ) -> Result<SendHttpRequestResult> {
     let mut cookie_jar = load_cookie_jar(params.query_manager, params.cookie_jar_id.as_deref())?;
-    let inputs = resolve_send_inputs(
+    let mut inputs = resolve_send_inputs(
         params.query_manager,
         &params.request,
         params.environment_id,
         cookie_jar.as_ref().map(|jar| jar.cookies.clone()),
     )?;
+    layer_variables(&mut inputs.environment_chain, &params.request.workspace_id, &params.variables);
 
     let template_callback = PluginTemplateCallback::new(
         params.plugin_manager.clone(),
@@ -501,6 +536,12 @@ pub async fn send_http_request_with_plugins(
         params.plugin_context,
         RenderPurpose::Send,
     );
+    render_proxy_credentials(
+        &mut inputs.runtime_config,
+        &inputs.environment_chain,
+        &template_callback,
+    )
+    .await?;
     let auth_hook = PluginPrepareSendableRequest {
         plugin_manager: params.plugin_manager,
         plugin_context: params.plugin_context.clone(),
@@ -591,6 +632,10 @@ pub async fn send_http_request<T: TemplateCallback>(
         store_cookies: resolved_settings.store_cookies.value,
     };
 
+    // Extractions without an environment of their own write to the workspace environment the
+    // request was sent with: the active sub environment, or the base one.
+    let extraction_environment_id =
+        environment_chain.iter().find(|e| e.parent_model == "workspace").map(|e| e.id.clone());
     let rendered_request = render_http_request(
         &request,
         environment_chain,
@@ -638,6 +683,10 @@ pub async fn send_http_request<T: TemplateCallback>(
     response.elapsed = 0;
     response.elapsed_headers = 0;
     response.elapsed_dns = 0;
+    response.elapsed_connect = 0;
+    response.elapsed_tls = 0;
+    response.elapsed_ttfb = 0;
+    response.assertion_results = Vec::new();
     // Responses with no request behind them are ephemeral: they belong to whoever called this
     // function and never reach the model store.
     let store = storage.as_ref().filter(|_| !response.request_id.is_empty());
@@ -685,13 +734,11 @@ pub async fn send_http_request<T: TemplateCallback>(
     let event_response_id = response.id.clone();
     let event_workspace_id = request.workspace_id.clone();
     let emit_events_to = params.emit_events_to.clone();
-    let dns_elapsed = Arc::new(AtomicI32::new(0));
-    let event_dns_elapsed = dns_elapsed.clone();
+    let phase_timings = Arc::new(PhaseTimings::default());
+    let event_phase_timings = phase_timings.clone();
     let event_handle = tokio::spawn(async move {
         while let Some(event) = event_rx.recv().await {
-            if let SenderHttpResponseEvent::DnsResolved { duration, .. } = &event {
-                event_dns_elapsed.store(u64_to_i32(*duration), Ordering::Relaxed);
-            }
+            event_phase_timings.observe(&event);
 
             if let Some((query_manager, update_source)) = event_store.as_ref() {
                 let db_event = HttpResponseEvent::new(
@@ -735,10 +782,14 @@ pub async fn send_http_request<T: TemplateCallback>(
         }
     }
 
+    // Kept in case the response is an event stream that drops and has to be re-sent. A
+    // streamed body can only go out once, so those requests are never reconnected.
+    let reconnect = sendable_request.try_clone().map(|request| (request, event_tx.clone()));
     let mut http_response =
         match executor.send(sendable_request, event_tx, cookie_behavior.clone()).await {
             Ok(response) => response,
             Err(err) => {
+                drop(reconnect);
                 if let Some(store) = store {
                     let _ = persist_response_error(
                         store,
@@ -772,7 +823,7 @@ pub async fn send_http_request<T: TemplateCallback>(
         None => None,
     };
     let response_body_path = body_path.as_ref().map(|p| p.to_string_lossy().to_string());
-    let connected_response = HttpResponse {
+    let mut connected_response = HttpResponse {
         state: HttpResponseState::Connected,
         elapsed_headers: headers_elapsed,
         status: i32::from(http_response.status),
@@ -780,7 +831,6 @@ pub async fn send_http_request<T: TemplateCallback>(
         url: http_response.url.clone(),
         remote_addr: http_response.remote_addr.clone(),
         version: http_response.version.clone(),
-        elapsed_dns: dns_elapsed.load(Ordering::Relaxed),
         body_path: response_body_path.clone(),
         content_length: http_response.content_length.map(u64_to_i32),
         headers: http_response
@@ -795,6 +845,7 @@ pub async fn send_http_request<T: TemplateCallback>(
             .collect(),
         ..response
     };
+    let _ = phase_timings.apply(&mut connected_response);
     if let Some(store) = store {
         response = store
             .query_manager
@@ -815,6 +866,8 @@ pub async fn send_http_request<T: TemplateCallback>(
         }
         None => None,
     };
+    let mut event_stream = is_event_stream(&http_response.headers)
+        .then(|| EventStreamRecorder::new(&response.id, &request.workspace_id));
     let mut body_stream =
         http_response.into_body_stream().map_err(SendHttpRequestError::ReadResponseBody)?;
     let mut read_buf = vec![0; 64 * 1024];
@@ -827,6 +880,12 @@ pub async fn send_http_request<T: TemplateCallback>(
     } else {
         ResponseBody::Returned(Vec::new())
     };
+    // Assertions and extractions that read the body need a copy of it when it is not already
+    // being returned.
+    let reads_body = assertions_read_body(&rendered_request.assertions)
+        || extractions_read_body(&rendered_request.extractions);
+    let mut captured_body =
+        (reads_body && !matches!(response_body, ResponseBody::Returned(_))).then(Vec::new);
     let mut body_read_error = None;
     let mut written_bytes: usize = 0;
     let mut last_progress_update = started_at;
@@ -870,18 +929,24 @@ pub async fn send_http_request<T: TemplateCallback>(
                 } else if let ResponseBody::Returned(body) = &mut response_body {
                     body.extend_from_slice(chunk);
                 }
+                if let Some(body) = captured_body.as_mut() {
+                    body.extend_from_slice(chunk);
+                }
+                if let Some(recorder) = event_stream.as_mut() {
+                    recorder.record(chunk, store.map(|s| (s.query_manager, &s.update_source)));
+                }
 
                 let now = Instant::now();
                 let should_update = now.duration_since(last_progress_update).as_millis()
                     >= RESPONSE_PROGRESS_UPDATE_INTERVAL_MS;
                 if should_update {
                     let elapsed = duration_to_i32(started_at.elapsed());
-                    let progress_response = HttpResponse {
+                    let mut progress_response = HttpResponse {
                         elapsed,
                         content_length: Some(usize_to_i32(written_bytes)),
-                        elapsed_dns: dns_elapsed.load(Ordering::Relaxed),
                         ..response.clone()
                     };
+                    let _ = phase_timings.apply(&mut progress_response);
                     if let Some(store) = store {
                         response = store
                             .query_manager
@@ -899,6 +964,22 @@ pub async fn send_http_request<T: TemplateCallback>(
                 }
             }
             Err(err) => {
+                if let (Some(recorder), Some((request, event_tx))) =
+                    (event_stream.as_mut(), reconnect.as_ref())
+                    && let Some(stream) = recorder
+                        .reconnect(
+                            executor,
+                            request,
+                            event_tx,
+                            &cookie_behavior,
+                            params.cancelled_rx.clone(),
+                            &err,
+                        )
+                        .await
+                {
+                    body_stream = stream;
+                    continue;
+                }
                 body_read_error = Some(SendHttpRequestError::ReadResponseBody(
                     yaak_http::error::Error::BodyReadError(err.to_string()),
                 ));
@@ -907,6 +988,9 @@ pub async fn send_http_request<T: TemplateCallback>(
         }
     }
 
+    // The timeline task finishes once every event sender is gone.
+    drop(reconnect);
+
     if let Some((file, path)) = body_file.as_mut() {
         file.flush().await.map_err(|source| SendHttpRequestError::WriteResponseBody {
             path: path.clone(),
@@ -946,16 +1030,24 @@ pub async fn send_http_request<T: TemplateCallback>(
     }
 
     let compressed_length = http_response.content_length.unwrap_or(written_bytes as u64);
-    let final_response = HttpResponse {
+    let mut final_response = HttpResponse {
         body_path: response_body_path,
         content_length: Some(usize_to_i32(written_bytes)),
         content_length_compressed: Some(u64_to_i32(compressed_length)),
         elapsed: duration_to_i32(started_at.elapsed()),
         elapsed_headers: headers_elapsed,
-        elapsed_dns: dns_elapsed.load(Ordering::Relaxed),
         state: HttpResponseState::Closed,
         ..response
     };
+    let _ = phase_timings.apply(&mut final_response);
+    let body: &[u8] = match (&captured_body, &response_body) {
+        (Some(body), _) | (None, ResponseBody::Returned(body)) => body.as_slice(),
+        (None, _) => &[],
+    };
+    if !rendered_request.assertions.is_empty() {
+        final_response.assertion_results =
+            evaluate_assertions(&rendered_request.assertions, &final_response, body);
+    }
     if let Some(store) = store {
         response = store
             .query_manager
@@ -966,6 +1058,29 @@ pub async fn send_http_request<T: TemplateCallback>(
         response = final_response;
     }
 
+    // Extracted values have nowhere to go without a database behind the send.
+    if let Some(store) = store
+        && !rendered_request.extractions.is_empty()
+        && response_is_extractable(&response)
+    {
+        let values = evaluate_extractions(&rendered_request.extractions, &response, body);
+        if let Err(err) = write_extracted_values(
+            store.query_manager,
+            &values,
+            extraction_environment_id.as_deref(),
+        ) {
+            response.error = Some(append_error_message(
+                response.error.take(),
+                format!("Request succeeded but failed to write extracted values: {err}"),
+            ));
+            response = store
+                .query_manager
+                .connect()
+                .upsert_http_response(&response, &store.update_source, store.blob_manager)
+                .map_err(SendHttpRequestError::PersistResponse)?;
+        }
+    }
+
     // Request-body history can be much larger than the response. It should not keep the
     // response in a loading state after the network/response-body work has completed.
     if let Some(task) = request_body_capture_task.take() {
@@ -1009,6 +1124,17 @@ pub async fn send_http_request<T: TemplateCallback>(
         warn!("Failed to join response event task: {}", join_err);
     }
 
+    // Phase events can still be queued behind the body when the response closes
+    if phase_timings.apply(&mut response)
+        && let Some(store) = store
+    {
+        response = store
+            .query_manager
+            .connect()
+            .upsert_http_response(&response, &store.update_source, store.blob_manager)
+            .map_err(SendHttpRequestError::PersistResponse)?;
+    }
+
     Ok(SendHttpRequestResult {
         rendered_request,
         response,
@@ -1123,7 +1249,7 @@ fn proxy_setting_from_settings(proxy: Option<ProxySetting>) -> HttpConnectionPro
     match proxy {
         None => HttpConnectionProxySetting::System,
         Some(ProxySetting::Disabled) => HttpConnectionProxySetting::Disabled,
-        Some(ProxySetting::Enabled { http, https, auth, bypass, disabled }) => {
+        Some(ProxySetting::Enabled { http, https, auth, bypass, disabled, socks }) => {
             if disabled {
                 HttpConnectionProxySetting::System
             } else {
@@ -1131,6 +1257,7 @@ fn proxy_setting_from_settings(proxy: Option<ProxySetting>) -> HttpConnectionPro
                     http,
                     https,
                     bypass,
+                    socks,
                     auth: auth.map(|ProxySettingAuth { user, password }| {
                         HttpConnectionProxySettingAuth { user, password }
                     }),
@@ -1140,6 +1267,32 @@ fn proxy_setting_from_settings(proxy: Option<ProxySetting>) -> HttpConnectionPro
     }
 }
 
+/// Render the credentials of a workspace or folder proxy. Those models are synced and exported,
+/// so the credentials are templates there, normally kept encrypted with `secure()`.
+pub async fn render_proxy_credentials<T: TemplateCallback>(
+    runtime_config: &mut HttpSendRuntimeConfig,
+    environment_chain: &[Environment],
+    template_callback: &T,
+) -> Result<()> {
+    if !matches!(runtime_config.settings.proxy.source_model.as_str(), "workspace" | "folder") {
+        return Ok(());
+    }
+    let HttpConnectionProxySetting::Enabled { auth: Some(auth), .. } = &mut runtime_config.proxy
+    else {
+        return Ok(());
+    };
+
+    let vars = make_vars_hashmap(environment_chain.to_vec());
+    let options = RenderOptions::throw();
+    auth.user = parse_and_render(&auth.user, &vars, template_callback, &options)
+        .await
+        .map_err(SendHttpRequestError::RenderRequest)?;
+    auth.password = parse_and_render(&auth.password, &vars, template_callback, &options)
+        .await
+        .map_err(SendHttpRequestError::RenderRequest)?;
+    Ok(())
+}
+
 pub async fn apply_plugin_authentication(
     sendable_request: &mut SendableHttpRequest,
     request: &HttpRequest,
@@ -1238,6 +1391,50 @@ fn sendable_body_length(body: Option<&SendableBody>) -> Option<i32> {
     }
 }
 
+/// Connection phase durations, taken from timeline events as they arrive. After a redirect,
+/// the phases of the last connection win.
+#[derive(Default)]
+struct PhaseTimings {
+    dns: AtomicI32,
+    connect: AtomicI32,
+    tls: AtomicI32,
+    ttfb: AtomicI32,
+}
+
+impl PhaseTimings {
+    fn observe(&self, event: &SenderHttpResponseEvent) {
+        let (phase, duration) = match event {
+            SenderHttpResponseEvent::DnsResolved { duration, .. } => (&self.dns, duration),
+            SenderHttpResponseEvent::TcpConnected { duration } => (&self.connect, duration),
+            SenderHttpResponseEvent::TlsHandshakeCompleted { duration } => (&self.tls, duration),
+            SenderHttpResponseEvent::FirstByteReceived { duration } => (&self.ttfb, duration),
+            _ => return,
+        };
+        phase.store(u64_to_i32(*duration), Ordering::Relaxed);
+    }
+
+    /// Copy the phases seen so far onto the response. Returns whether any of them changed.
+    fn apply(&self, response: &mut HttpResponse) -> bool {
+        let before = (
+            response.elapsed_dns,
+            response.elapsed_connect,
+            response.elapsed_tls,
+            response.elapsed_ttfb,
+        );
+        response.elapsed_dns = self.dns.load(Ordering::Relaxed);
+        response.elapsed_connect = self.connect.load(Ordering::Relaxed);
+        response.elapsed_tls = self.tls.load(Ordering::Relaxed);
+        response.elapsed_ttfb = self.ttfb.load(Ordering::Relaxed);
+        before
+            != (
+                response.elapsed_dns,
+                response.elapsed_connect,
+                response.elapsed_tls,
+                response.elapsed_ttfb,
+            )
+    }
+}
+
 fn duration_to_i32(duration: std::time::Duration) -> i32 {
     u128_to_i32(duration.as_millis())
 }
@@ -1262,7 +1459,10 @@ mod tests {
     use tempfile::TempDir;
     use tokio::io::AsyncRead;
     use yaak_http::decompress::ContentEncoding;
-    use yaak_models::models::{CookieDomain, CookieExpires, Workspace};
+    use yaak_models::models::{
+        CookieDomain, CookieExpires, HttpAssertionKind, HttpExtractionSource, HttpRequestAssertion,
+        HttpRequestExtraction, Workspace,
+    };
 
     struct NoopTemplateCallback;
 
@@ -1297,6 +1497,10 @@ mod tests {
             event_tx: mpsc::Sender<SenderHttpResponseEvent>,
             _cookie_behavior: CookieBehavior,
         ) -> yaak_http::error::Result<yaak_http::sender::HttpResponse> {
+            let _ = event_tx.try_send(SenderHttpResponseEvent::TcpConnected { duration: 3 });
+            let _ =
+                event_tx.try_send(SenderHttpResponseEvent::TlsHandshakeCompleted { duration: 7 });
+            let _ = event_tx.try_send(SenderHttpResponseEvent::FirstByteReceived { duration: 12 });
             let _ = event_tx.try_send(SenderHttpResponseEvent::HeaderDown(
                 "content-type".to_string(),
                 "text/plain".to_string(),
@@ -1388,6 +1592,46 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn records_connection_phases_on_the_response() {
+        let executor = StubExecutor { body: b"hello world" };
+
+        let result = send_http_request(SendHttpRequestParams {
+            inputs: HttpSendInputs {
+                request: ResolvedHttpRequest::assume_resolved(
+                    HttpRequest {
+                        workspace_id: "wk_test".to_string(),
+                        url: "https://localhost/test".to_string(),
+                        ..Default::default()
+                    },
+                    String::new(),
+                ),
+                environment_chain: Vec::new(),
+                runtime_config: HttpSendRuntimeConfig {
+                    settings: ResolvedHttpRequestSettings::default(),
+                    proxy: HttpConnectionProxySetting::System,
+                    dns_overrides: Vec::new(),
+                    client_certificates: Vec::new(),
+                },
+                cookie_store: None,
+            },
+            template_callback: &NoopTemplateCallback,
+            storage: None,
+            emit_events_to: None,
+            emit_response_body_chunks_to: None,
+            cancelled_rx: None,
+            existing_response: None,
+            prepare_sendable_request: None,
+            executor: &executor,
+        })
+        .await
+        .expect("send should succeed");
+
+        assert_eq!(result.response.elapsed_connect, 3);
+        assert_eq!(result.response.elapsed_tls, 7);
+        assert_eq!(result.response.elapsed_ttfb, 12);
+    }
+
     /// A response nothing stores has to hand its body back, because no later
     /// read can find it: there is no row to look up and no id to read it by.
     /// GraphQL introspection is the caller that depends on this.
@@ -1434,6 +1678,408 @@ mod tests {
         assert!(result.response.request_id.is_empty(), "an unsaved response has no request");
     }
 
+    /// Assertions run on the finished response, including ones that need a body the caller is
+    /// streaming away rather than getting back.
+    #[tokio::test]
+    async fn records_assertion_results_on_the_response() {
+        let (chunk_tx, _chunk_rx) = mpsc::unbounded_channel();
+        let executor = StubExecutor { body: b"hello world" };
+        let assertion = |kind, value: &str| HttpRequestAssertion {
+            kind,
+            value: value.to_string(),
+            ..Default::default()
+        };
+
+        let result = send_http_request(SendHttpRequestParams {
+            inputs: HttpSendInputs {
+                request: ResolvedHttpRequest::assume_resolved(
+                    HttpRequest {
+                        workspace_id: "wk_test".to_string(),
+                        url: "http://localhost/test".to_string(),
+                        assertions: vec![
+                            assertion(HttpAssertionKind::StatusEquals, "200"),
+                            assertion(HttpAssertionKind::BodyMatches, "^hello"),
+                            assertion(HttpAssertionKind::BodyMatches, "goodbye"),
+                            HttpRequestAssertion {
+                                enabled: false,
+                                ..assertion(HttpAssertionKind::StatusEquals, "500")
+                            },
+                        ],
+                        ..Default::default()
+                    },
+                    String::new(),
+                ),
+                environment_chain: Vec::new(),
+                runtime_config: HttpSendRuntimeConfig {
+                    settings: ResolvedHttpRequestSettings::default(),
+                    proxy: HttpConnectionProxySetting::System,
+                    dns_overrides: Vec::new(),
+                    client_certificates: Vec::new(),
+                },
+                cookie_store: None,
+            },
+            template_callback: &NoopTemplateCallback,
+            storage: None,
+            emit_events_to: None,
+            emit_response_body_chunks_to: Some(chunk_tx),
+            cancelled_rx: None,
+            existing_response: None,
+            prepare_sendable_request: None,
+            executor: &executor,
+        })
+        .await
+        .expect("send should succeed");
+
+        assert_eq!(
+            result.response.assertion_results.iter().map(|r| r.passed).collect::<Vec<_>>(),
+            vec![true, true, false],
+        );
+    }
+
+    /// A successful send writes what its extractions found into the environment it was sent
+    /// with, which for a request with no sub environment selected is the base one.
+    #[tokio::test]
+    async fn writes_extracted_values_into_the_environment() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let (query_manager, blob_manager, _rx) = yaak_models::init_standalone(
+            &temp_dir.path().join("db.sqlite"),
+            &temp_dir.path().join("blobs.sqlite"),
+        )
+        .expect("Failed to initialize DB");
+        let db = query_manager.connect();
+        db.upsert_workspace(
+            &Workspace { id: "wk_test".to_string(), ..Default::default() },
+            &UpdateSource::Sync,
+        )
+        .expect("Failed to seed workspace");
+        let request = db
+            .upsert_http_request(
+                &HttpRequest {
+                    id: "rq_test".to_string(),
+                    workspace_id: "wk_test".to_string(),
+                    url: "http://localhost/test".to_string(),
+                    extractions: vec![HttpRequestExtraction {
+                        source: HttpExtractionSource::BodyRegex,
+                        expression: r"hello (\w+)".to_string(),
+                        variable: "greeting".to_string(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                &UpdateSource::Sync,
+            )
+            .expect("Failed to seed request");
+        let environment_chain =
+            db.resolve_environments("wk_test", None, None).expect("Failed to resolve environments");
+        let base_environment_id = environment_chain[0].id.clone();
+        drop(db);
+
+        let (chunk_tx, _chunk_rx) = mpsc::unbounded_channel();
+        send_http_request(SendHttpRequestParams {
+            inputs: HttpSendInputs {
+                request: ResolvedHttpRequest::assume_resolved(request, String::new()),
+                environment_chain,
+                runtime_config: HttpSendRuntimeConfig {
+                    settings: ResolvedHttpRequestSettings::default(),
+                    proxy: HttpConnectionProxySetting::System,
+                    dns_overrides: Vec::new(),
+                    client_certificates: Vec::new(),
+                },
+                cookie_store: None,
+            },
+            template_callback: &NoopTemplateCallback,
+            storage: Some(ResponseStorage {
+                query_manager: &query_manager,
+                blob_manager: &blob_manager,
+                update_source: UpdateSource::Sync,
+                response_dir: temp_dir.path(),
+            }),
+            emit_events_to: None,
+            emit_response_body_chunks_to: Some(chunk_tx),
+            cancelled_rx: None,
+            existing_response: None,
+            prepare_sendable_request: None,
+            executor: &StubExecutor { body: b"hello world" },
+        })
+        .await
+        .expect("send should succeed");
+
+        let environment = query_manager
+            .connect()
+            .get_environment(&base_environment_id)
+            .expect("Failed to load environment");
+        assert_eq!(
+            environment
+                .variables
+                .iter()
+                .map(|v| (v.name.as_str(), v.value.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("greeting", "world")],
+        );
+    }
+
+    /// An extraction that cannot be written fails on the response, where the user sees it,
+    /// and leaves the send itself successful.
+    #[tokio::test]
+    async fn records_extraction_write_failures_on_the_response() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let (query_manager, blob_manager, _rx) = yaak_models::init_standalone(
+            &temp_dir.path().join("db.sqlite"),
+            &temp_dir.path().join("blobs.sqlite"),
+        )
+        .expect("Failed to initialize DB");
+        let db = query_manager.connect();
+        db.upsert_workspace(
+            &Workspace { id: "wk_test".to_string(), ..Default::default() },
+            &UpdateSource::Sync,
+        )
+        .expect("Failed to seed workspace");
+        let request = db
+            .upsert_http_request(
+                &HttpRequest {
+                    id: "rq_test".to_string(),
+                    workspace_id: "wk_test".to_string(),
+                    url: "http://localhost/test".to_string(),
+                    extractions: vec![HttpRequestExtraction {
+                        source: HttpExtractionSource::BodyRegex,
+                        expression: r"hello (\w+)".to_string(),
+                        variable: "greeting".to_string(),
+                        environment_id: Some("ev_missing".to_string()),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                &UpdateSource::Sync,
+            )
+            .expect("Failed to seed request");
+        drop(db);
+
+        let (chunk_tx, _chunk_rx) = mpsc::unbounded_channel();
+        let result = send_http_request(SendHttpRequestParams {
+            inputs: HttpSendInputs {
+                request: ResolvedHttpRequest::assume_resolved(request, String::new()),
+                environment_chain: Vec::new(),
+                runtime_config: HttpSendRuntimeConfig {
+                    settings: ResolvedHttpRequestSettings::default(),
+                    proxy: HttpConnectionProxySetting::System,
+                    dns_overrides: Vec::new(),
+                    client_certificates: Vec::new(),
+                },
+                cookie_store: None,
+            },
+            template_callback: &NoopTemplateCallback,
+            storage: Some(ResponseStorage {
+                query_manager: &query_manager,
+                blob_manager: &blob_manager,
+                update_source: UpdateSource::Sync,
+                response_dir: temp_dir.path(),
+            }),
+            emit_events_to: None,
+            emit_response_body_chunks_to: Some(chunk_tx),
+            cancelled_rx: None,
+            existing_response: None,
+            prepare_sendable_request: None,
+            executor: &StubExecutor { body: b"hello world" },
+        })
+        .await
+        .expect("send should succeed");
+
+        let error = result.response.error.expect("response should record the failure");
+        assert!(error.contains("failed to write extracted values"), "{error}");
+        let stored = query_manager
+            .connect()
+            .get_http_response(&result.response.id)
+            .expect("Failed to load response");
+        assert_eq!(stored.error, Some(error));
+    }
+
+    /// Replies with an event stream whose first connection drops after one event, and records
+    /// the `Last-Event-ID` each connection was opened with.
+    struct DroppingEventStreamExecutor {
+        last_event_ids: std::sync::Mutex<Vec<Option<String>>>,
+    }
+
+    struct ConnectionReset;
+
+    impl AsyncRead for ConnectionReset {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            _buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Err(std::io::ErrorKind::ConnectionReset.into()))
+        }
+    }
+
+    #[async_trait]
+    impl SendRequestExecutor for DroppingEventStreamExecutor {
+        async fn send(
+            &self,
+            sendable_request: SendableHttpRequest,
+            _event_tx: mpsc::Sender<SenderHttpResponseEvent>,
+            _cookie_behavior: CookieBehavior,
+        ) -> yaak_http::error::Result<yaak_http::sender::HttpResponse> {
+            let last_event_id = sendable_request
+                .headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("last-event-id"))
+                .map(|(_, value)| value.clone());
+            let mut last_event_ids = self.last_event_ids.lock().unwrap();
+            last_event_ids.push(last_event_id);
+            let body: Pin<Box<dyn AsyncRead + Send>> = if last_event_ids.len() == 1 {
+                Box::pin(
+                    std::io::Cursor::new(b"retry: 1\nid: 1\ndata: first\n\ndata: lo".to_vec())
+                        .chain(ConnectionReset),
+                )
+            } else {
+                Box::pin(std::io::Cursor::new(b"id: 2\ndata: second\n\n".to_vec()))
+            };
+            Ok(yaak_http::sender::HttpResponse::new(
+                200,
+                Some("OK".to_string()),
+                vec![("content-type".to_string(), "text/event-stream".to_string())],
+                Vec::new(),
+                None,
+                sendable_request.url.clone(),
+                None,
+                Some("HTTP/1.1".to_string()),
+                body,
+                ContentEncoding::Identity,
+            ))
+        }
+    }
+
+    /// Events are stored as they arrive, and a stream that drops after the server sent an ID
+    /// picks up where it left off instead of failing the send.
+    #[tokio::test]
+    async fn stores_events_and_resumes_a_dropped_event_stream() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let (query_manager, blob_manager, _rx) = yaak_models::init_standalone(
+            &temp_dir.path().join("db.sqlite"),
+            &temp_dir.path().join("blobs.sqlite"),
+        )
+        .expect("Failed to initialize DB");
+        let db = query_manager.connect();
+        db.upsert_workspace(
+            &Workspace { id: "wk_test".to_string(), ..Default::default() },
+            &UpdateSource::Sync,
+        )
+        .expect("Failed to seed workspace");
+        let request = db
+            .upsert_http_request(
+                &HttpRequest {
+                    id: "rq_test".to_string(),
+                    workspace_id: "wk_test".to_string(),
+                    url: "http://localhost/stream".to_string(),
+                    ..Default::default()
+                },
+                &UpdateSource::Sync,
+            )
+            .expect("Failed to seed request");
+        drop(db);
+
+        let executor = DroppingEventStreamExecutor { last_event_ids: Default::default() };
+        let result = send_http_request(SendHttpRequestParams {
+            inputs: HttpSendInputs {
+                request: ResolvedHttpRequest::assume_resolved(request, String::new()),
+                environment_chain: Vec::new(),
+                runtime_config: HttpSendRuntimeConfig {
+                    settings: ResolvedHttpRequestSettings::default(),
+                    proxy: HttpConnectionProxySetting::System,
+                    dns_overrides: Vec::new(),
+                    client_certificates: Vec::new(),
+                },
+                cookie_store: None,
+            },
+            template_callback: &NoopTemplateCallback,
+            storage: Some(ResponseStorage {
+                query_manager: &query_manager,
+                blob_manager: &blob_manager,
+                update_source: UpdateSource::Sync,
+                response_dir: temp_dir.path(),
+            }),
+            emit_events_to: None,
+            emit_response_body_chunks_to: None,
+            cancelled_rx: None,
+            existing_response: None,
+            prepare_sendable_request: None,
+            executor: &executor,
+        })
+        .await
+        .expect("send should resume the stream");
+
+        assert_eq!(*executor.last_event_ids.lock().unwrap(), vec![None, Some("1".to_string())],);
+        let events = query_manager
+            .connect()
+            .list_http_response_sse_events(&result.response.id)
+            .expect("Failed to list events");
+        assert_eq!(
+            events
+                .iter()
+                .map(|e| (e.index, e.data.as_str(), e.event_id.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![(0, "first", Some("1")), (1, "second", Some("2"))],
+        );
+    }
+
+    #[tokio::test]
+    async fn renders_the_credentials_of_a_workspace_proxy() {
+        let runtime_config = |source_model: &str| {
+            let mut settings = ResolvedHttpRequestSettings::default();
+            settings.proxy.source_model = source_model.to_string();
+            HttpSendRuntimeConfig {
+                settings,
+                proxy: HttpConnectionProxySetting::Enabled {
+                    http: "localhost:9090".to_string(),
+                    https: String::new(),
+                    auth: Some(HttpConnectionProxySettingAuth {
+                        user: "${[ proxy_user ]}".to_string(),
+                        password: "${[ proxy_password ]}".to_string(),
+                    }),
+                    bypass: String::new(),
+                    socks: String::new(),
+                },
+                dns_overrides: Vec::new(),
+                client_certificates: Vec::new(),
+            }
+        };
+        let variable = |name: &str, value: &str| EnvironmentVariable {
+            enabled: true,
+            name: name.to_string(),
+            value: value.to_string(),
+            id: None,
+        };
+        let environment_chain = vec![Environment {
+            variables: vec![
+                variable("proxy_user", "alice"),
+                variable("proxy_password", "s3cret"),
+            ],
+            ..Default::default()
+        }];
+        let credentials = |config: HttpSendRuntimeConfig| match config.proxy {
+            HttpConnectionProxySetting::Enabled { auth: Some(auth), .. } => {
+                (auth.user, auth.password)
+            }
+            _ => panic!("proxy should keep its credentials"),
+        };
+
+        let mut workspace_config = runtime_config("workspace");
+        render_proxy_credentials(&mut workspace_config, &environment_chain, &NoopTemplateCallback)
+            .await
+            .expect("credentials should render");
+        assert_eq!(credentials(workspace_config), ("alice".to_string(), "s3cret".to_string()));
+
+        // The global settings aren't synced, so their credentials are used as written
+        let mut settings_config = runtime_config("default");
+        render_proxy_credentials(&mut settings_config, &environment_chain, &NoopTemplateCallback)
+            .await
+            .expect("nothing to render");
+        assert_eq!(
+            credentials(settings_config),
+            ("${[ proxy_user ]}".to_string(), "${[ proxy_password ]}".to_string())
+        );
+    }
+
     fn seed_cookie_jar() -> (QueryManager, CookieJar, TempDir) {
         let temp_dir = TempDir::new().expect("Failed to create temp dir");
         let (query_manager, _blob_manager, _rx) = yaak_models::init_standalone(
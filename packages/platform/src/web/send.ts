@@ -218,6 +218,9 @@ function headOf(frame: Extract<Frame, { type: "response" }>): ResponsePatch {
     contentLength: frame.contentLength,
     elapsedHeaders: frame.elapsedHeaders,
     elapsedDns: frame.elapsedDns,
+    elapsedConnect: frame.elapsedConnect,
+    elapsedTls: frame.elapsedTls,
+    elapsedTtfb: frame.elapsedTtfb,
   };
 }
 
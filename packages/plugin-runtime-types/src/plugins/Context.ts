@@ -13,6 +13,8 @@ import type {
   ListFoldersResponse,
   ListHttpRequestsRequest,
   ListHttpRequestsResponse,
+  ListHttpResponseSseEventsRequest,
+  ListHttpResponseSseEventsResponse,
   OpenWindowRequest,
   PromptFormRequest,
   PromptFormResponse,
@@ -222,6 +224,14 @@ export interface Context {
      * up, so its body arrives with the send that made it instead.
      */
     body(args: GetHttpResponseBodyInfoRequest): Promise<HttpResponseBody>;
+    /**
+     * Server-sent events parsed from a `text/event-stream` response, oldest
+     * first. Events are stored as they arrive, so this can be read while the
+     * stream is still open.
+     */
+    serverSentEvents(
+      args: ListHttpResponseSseEventsRequest,
+    ): Promise<ListHttpResponseSseEventsResponse["events"]>;
   };
   templates: {
     render<T extends JsonValue>(args: TemplateRenderRequest & { data: T }): Promise<T>;
@@ -35,6 +35,7 @@ import type {
   ListFoldersResponse,
   ListHttpRequestsRequest,
   ListHttpRequestsResponse,
+  ListHttpResponseSseEventsResponse,
   ListOpenWorkspacesResponse,
   PluginContext,
   PromptFormResponse,
@@ -811,6 +812,17 @@ export class PluginInstance {
           return httpResponses.map(forPlugin);
         },
         body: ({ responseId }) => storedBody(responseId),
+        serverSentEvents: async (args) => {
+          const payload = {
+            type: "list_http_response_sse_events_request",
+            ...args,
+          } as const;
+          const { events } = await this.#sendForReply<ListHttpResponseSseEventsResponse>(
+            context,
+            payload,
+          );
+          return events;
+        },
       },
       grpcRequest: {
         render: async (args) => {